@@ -285,6 +285,19 @@ impl CallArgs<'_> {
     pub fn empty() -> Self {
         Self::Unnamed(Box::default())
     }
+
+    /// Returns the number of arguments.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Unnamed(args) => args.len(),
+            Self::Named(args) => args.len(),
+        }
+    }
+
+    /// Returns `true` if there are no arguments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 /// A named argument: `name: value`.
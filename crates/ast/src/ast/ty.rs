@@ -133,8 +133,8 @@ impl ElementaryType {
             Self::Bool => "bool".into(),
             Self::String => "string".into(),
             Self::Bytes => "bytes".into(),
-            Self::Fixed(_size, _fixed) => "fixed".into(),
-            Self::UFixed(_size, _fixed) => "ufixed".into(),
+            Self::Fixed(size, fixed) => format!("fixed{}x{}", size.bits(), fixed.get()).into(),
+            Self::UFixed(size, fixed) => format!("ufixed{}x{}", size.bits(), fixed.get()).into(),
             Self::Int(size) => format!("int{}", size.bits()).into(),
             Self::UInt(size) => format!("uint{}", size.bits()).into(),
             Self::FixedBytes(size) => format!("bytes{}", size.bytes()).into(),
@@ -148,8 +148,8 @@ impl ElementaryType {
             Self::Bool => "bool",
             Self::String => "string",
             Self::Bytes => "bytes",
-            Self::Fixed(_size, _fixed) => "fixed",
-            Self::UFixed(_size, _fixed) => "ufixed",
+            Self::Fixed(size, fixed) => return write!(f, "fixed{}x{}", size.bits(), fixed.get()),
+            Self::UFixed(size, fixed) => return write!(f, "ufixed{}x{}", size.bits(), fixed.get()),
             Self::Int(size) => return write!(f, "int{}", size.bits()),
             Self::UInt(size) => return write!(f, "uint{}", size.bits()),
             Self::FixedBytes(size) => return write!(f, "bytes{}", size.bytes()),
@@ -1,7 +1,8 @@
 //! Yul AST.
 
-use super::{AstPath, Box, DocComments, Lit, StrLit};
+use super::{AstPath, Box, CommentKind, DocComment, DocComments, Lit, LitKind, StrKind, StrLit};
 use solar_interface::{Ident, Span};
+use std::fmt;
 
 /// A block of Yul statements: `{ ... }`.
 ///
@@ -189,3 +190,195 @@ pub struct ExprCall<'ast> {
     pub name: Ident,
     pub arguments: Box<'ast, [Expr<'ast>]>,
 }
+
+impl fmt::Display for Object<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Printer::new(f).object(self)
+    }
+}
+
+impl fmt::Display for Stmt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Printer::new(f).stmt(self)
+    }
+}
+
+impl fmt::Display for Expr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Printer::new(f).expr(self)
+    }
+}
+
+/// Pretty-prints Yul code, with one statement per line and blocks indented by four spaces.
+struct Printer<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    indent: usize,
+}
+
+impl<'a, 'b> Printer<'a, 'b> {
+    fn new(f: &'a mut fmt::Formatter<'b>) -> Self {
+        Self { f, indent: 0 }
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        writeln!(self.f)?;
+        write!(self.f, "{:1$}", "", self.indent * 4)
+    }
+
+    fn docs(&mut self, docs: &[DocComment]) -> fmt::Result {
+        for doc in docs {
+            match doc.kind {
+                CommentKind::Line => write!(self.f, "///{}", doc.symbol)?,
+                CommentKind::Block => write!(self.f, "/**{}*/", doc.symbol)?,
+            }
+            self.newline()?;
+        }
+        Ok(())
+    }
+
+    fn object(&mut self, object: &Object<'_>) -> fmt::Result {
+        self.docs(object.docs)?;
+        write!(self.f, "object \"{}\" {{", object.name.value)?;
+        self.indent += 1;
+        self.newline()?;
+        self.f.write_str("code ")?;
+        self.block(object.code.code)?;
+        for child in object.children.iter() {
+            self.newline()?;
+            self.object(child)?;
+        }
+        for data in object.data.iter() {
+            self.newline()?;
+            write!(self.f, "data \"{}\" ", data.name.value)?;
+            self.lit(data.data)?;
+        }
+        self.indent -= 1;
+        self.newline()?;
+        self.f.write_str("}")
+    }
+
+    fn block(&mut self, block: &[Stmt<'_>]) -> fmt::Result {
+        if block.is_empty() {
+            return self.f.write_str("{ }");
+        }
+        self.f.write_str("{")?;
+        self.indent += 1;
+        for stmt in block {
+            self.newline()?;
+            self.stmt(stmt)?;
+        }
+        self.indent -= 1;
+        self.newline()?;
+        self.f.write_str("}")
+    }
+
+    fn stmt(&mut self, stmt: &Stmt<'_>) -> fmt::Result {
+        self.docs(stmt.docs)?;
+        match &stmt.kind {
+            StmtKind::Block(block) => self.block(block),
+            StmtKind::AssignSingle(path, expr) => {
+                write!(self.f, "{path} := ")?;
+                self.expr(expr)
+            }
+            StmtKind::AssignMulti(paths, call) => {
+                self.list(paths.iter(), |this, path| write!(this.f, "{path}"))?;
+                self.f.write_str(" := ")?;
+                self.call(call)
+            }
+            StmtKind::Expr(call) => self.call(call),
+            StmtKind::If(cond, block) => {
+                self.f.write_str("if ")?;
+                self.expr(cond)?;
+                self.f.write_str(" ")?;
+                self.block(block)
+            }
+            StmtKind::For { init, cond, step, body } => {
+                self.f.write_str("for ")?;
+                self.block(init)?;
+                self.f.write_str(" ")?;
+                self.expr(cond)?;
+                self.f.write_str(" ")?;
+                self.block(step)?;
+                self.f.write_str(" ")?;
+                self.block(body)
+            }
+            StmtKind::Switch(StmtSwitch { selector, branches, default_case }) => {
+                self.f.write_str("switch ")?;
+                self.expr(selector)?;
+                for case in branches.iter() {
+                    self.newline()?;
+                    self.f.write_str("case ")?;
+                    self.lit(case.constant)?;
+                    self.f.write_str(" ")?;
+                    self.block(case.body)?;
+                }
+                if let Some(default_case) = default_case {
+                    self.newline()?;
+                    self.f.write_str("default ")?;
+                    self.block(default_case)?;
+                }
+                Ok(())
+            }
+            StmtKind::Leave => self.f.write_str("leave"),
+            StmtKind::Break => self.f.write_str("break"),
+            StmtKind::Continue => self.f.write_str("continue"),
+            StmtKind::FunctionDef(Function { name, parameters, returns, body }) => {
+                write!(self.f, "function {name}(")?;
+                self.list(parameters.iter(), |this, param| write!(this.f, "{param}"))?;
+                self.f.write_str(")")?;
+                if !returns.is_empty() {
+                    self.f.write_str(" -> ")?;
+                    self.list(returns.iter(), |this, ret| write!(this.f, "{ret}"))?;
+                }
+                self.f.write_str(" ")?;
+                self.block(body)
+            }
+            StmtKind::VarDecl(names, value) => {
+                self.f.write_str("let ")?;
+                self.list(names.iter(), |this, name| write!(this.f, "{name}"))?;
+                if let Some(value) = value {
+                    self.f.write_str(" := ")?;
+                    self.expr(value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr<'_>) -> fmt::Result {
+        match &expr.kind {
+            ExprKind::Path(path) => write!(self.f, "{path}"),
+            ExprKind::Call(call) => self.call(call),
+            ExprKind::Lit(lit) => self.lit(lit),
+        }
+    }
+
+    fn call(&mut self, call: &ExprCall<'_>) -> fmt::Result {
+        write!(self.f, "{}(", call.name)?;
+        self.list(call.arguments.iter(), Self::expr)?;
+        self.f.write_str(")")
+    }
+
+    fn lit(&mut self, lit: &Lit) -> fmt::Result {
+        match &lit.kind {
+            LitKind::Str(StrKind::Str, _) => write!(self.f, "\"{}\"", lit.symbol),
+            LitKind::Str(StrKind::Unicode, _) => write!(self.f, "unicode\"{}\"", lit.symbol),
+            LitKind::Str(StrKind::Hex, _) => write!(self.f, "hex\"{}\"", lit.symbol),
+            _ => write!(self.f, "{}", lit.symbol),
+        }
+    }
+
+    fn list<T>(
+        &mut self,
+        items: impl Iterator<Item = T>,
+        mut f: impl FnMut(&mut Self, T) -> fmt::Result,
+    ) -> fmt::Result {
+        for (i, item) in items.enumerate() {
+            if i > 0 {
+                self.f.write_str(", ")?;
+            }
+            f(self, item)?;
+        }
+        Ok(())
+    }
+}
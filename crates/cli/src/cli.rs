@@ -43,6 +43,13 @@ pub struct Args {
     /// Comma separated list of types of output for the compiler to emit.
     #[arg(long, value_delimiter = ',')]
     pub emit: Vec<CompilerOutput>,
+    /// Checks that the storage layouts of the contracts are compatible with previously emitted
+    /// ones, so that they can be used to upgrade existing deployments.
+    ///
+    /// Accepts the output of `--emit storage-layout`. Contracts are matched by their fully
+    /// qualified name, or by their name if it is unique.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub storage_layout_base: Option<PathBuf>,
 
     /// Coloring.
     #[arg(help_heading = "Display options", long, value_enum, default_value = "auto")]
@@ -8,6 +8,7 @@
 use clap::Parser as _;
 use cli::Args;
 use solar_interface::{
+    config::CompilerOutput,
     diagnostics::{DiagCtxt, DynEmitter, HumanEmitter, JsonEmitter},
     Result, Session, SourceMap,
 };
@@ -60,6 +61,13 @@ impl Compiler {
         if sess.language.is_yul() && !args.unstable.parse_yul {
             return Err(sess.dcx.err("Yul is not supported yet").emit());
         }
+        // Standalone Yul objects are only analyzed, so none of the outputs would be written.
+        if sess.language.is_yul() {
+            if let Some(emit) = sess.emit.first() {
+                let msg = format!("`--emit {emit}` is not supported for Yul input yet");
+                return Err(sess.dcx.err(msg).emit());
+            }
+        }
 
         // Partition arguments into three categories:
         // - `stdin`: `-`, occurrences after the first are ignored
@@ -91,7 +99,16 @@ impl Compiler {
         }
         pcx.load_files(paths)?;
 
-        pcx.parse_and_resolve()?;
+        pcx.parse_and_resolve_with(|gcx| {
+            if gcx.sess.do_emit(CompilerOutput::Ir) {
+                solar_codegen::emit_ir(gcx)?;
+            }
+            if gcx.sess.do_emit(CompilerOutput::Bin) || gcx.sess.do_emit(CompilerOutput::BinRuntime)
+            {
+                solar_codegen::emit_bytecode(gcx)?;
+            }
+            Ok(())
+        })?;
 
         Ok(())
     }
@@ -155,6 +172,7 @@ fn run_compiler_with(args: Args, f: impl FnOnce(&Compiler) -> Result + Send) ->
         };
         sess.out_dir = args.out_dir.clone();
         sess.pretty_json = args.pretty_json;
+        sess.storage_layout_base = args.storage_layout_base.clone();
 
         let compiler = Compiler { sess, args };
         compiler.sess.enter(|| {
@@ -0,0 +1,306 @@
+//! Construction of Yul AST nodes.
+
+use alloy_primitives::{ruint::UintTryFrom, U256};
+use num_bigint::{BigInt, Sign};
+use solar_ast::ast::{
+    self, yul, CommentKind, DocComment, Lit, LitKind, PathSlice, StrKind, StrLit,
+};
+use solar_data_structures::BumpExt;
+use solar_interface::{Ident, Span, Symbol};
+
+/// Builds Yul AST nodes in an AST arena.
+///
+/// All the nodes have dummy spans, since they do not correspond to any source code.
+#[derive(Clone, Copy)]
+pub struct YulBuilder<'ast> {
+    arena: &'ast ast::Arena,
+}
+
+/// A name of a Yul identifier.
+pub trait IntoSymbol {
+    /// Converts the name into a symbol.
+    fn into_symbol(self) -> Symbol;
+}
+
+impl IntoSymbol for Symbol {
+    fn into_symbol(self) -> Symbol {
+        self
+    }
+}
+
+impl IntoSymbol for &str {
+    fn into_symbol(self) -> Symbol {
+        Symbol::intern(self)
+    }
+}
+
+impl IntoSymbol for &String {
+    fn into_symbol(self) -> Symbol {
+        Symbol::intern(self)
+    }
+}
+
+impl IntoSymbol for String {
+    fn into_symbol(self) -> Symbol {
+        Symbol::intern(&self)
+    }
+}
+
+impl<'ast> YulBuilder<'ast> {
+    /// Creates a new builder that allocates in the given arena.
+    pub fn new(arena: &'ast ast::Arena) -> Self {
+        Self { arena }
+    }
+
+    /// Creates an identifier.
+    pub fn ident(self, name: impl IntoSymbol) -> Ident {
+        Ident::new(name.into_symbol(), Span::DUMMY)
+    }
+
+    fn idents(self, names: impl IntoIterator<Item = Symbol>) -> &'ast mut [Ident] {
+        self.arena.alloc_from_iter(names.into_iter().map(|name| self.ident(name)))
+    }
+
+    fn ast_path(self, name: impl IntoSymbol) -> ast::AstPath<'ast> {
+        PathSlice::from_mut_slice(self.arena.alloc_as_slice(self.ident(name)))
+    }
+
+    fn expr(self, kind: yul::ExprKind<'ast>) -> yul::Expr<'ast> {
+        yul::Expr { span: Span::DUMMY, kind }
+    }
+
+    fn stmt(self, kind: yul::StmtKind<'ast>) -> yul::Stmt<'ast> {
+        yul::Stmt { docs: Default::default(), span: Span::DUMMY, kind }
+    }
+
+    /// Allocates a block of statements.
+    pub fn block(self, stmts: Vec<yul::Stmt<'ast>>) -> yul::Block<'ast> {
+        self.arena.alloc_vec(stmts)
+    }
+
+    /// Creates a reference to a variable: `x`.
+    pub fn path(self, name: impl IntoSymbol) -> yul::Expr<'ast> {
+        self.expr(yul::ExprKind::Path(self.ast_path(name)))
+    }
+
+    fn number_lit(self, value: U256, text: String) -> &'ast mut Lit {
+        let value = BigInt::from_bytes_be(Sign::Plus, &value.to_be_bytes::<32>());
+        self.arena.literals.alloc(Lit {
+            span: Span::DUMMY,
+            symbol: Symbol::intern(&text),
+            kind: LitKind::Number(value),
+        })
+    }
+
+    /// Creates a number literal. Small numbers are written in decimal, and the others in
+    /// hexadecimal.
+    pub fn num<T>(self, value: T) -> yul::Expr<'ast>
+    where
+        U256: UintTryFrom<T>,
+    {
+        let value: U256 = U256::from(value);
+        let text = if value < U256::from_limbs([1024, 0, 0, 0]) {
+            value.to_string()
+        } else {
+            format!("{value:#x}")
+        };
+        self.expr(yul::ExprKind::Lit(self.number_lit(value, text)))
+    }
+
+    /// Creates a number literal written in hexadecimal.
+    pub fn hex<T>(self, value: T) -> yul::Expr<'ast>
+    where
+        U256: UintTryFrom<T>,
+    {
+        let value = U256::from(value);
+        self.expr(yul::ExprKind::Lit(self.number_lit(value, format!("{value:#x}"))))
+    }
+
+    /// Creates a string literal. The string must not contain characters that need escaping.
+    pub fn str(self, value: &str) -> yul::Expr<'ast> {
+        let lit = self.arena.literals.alloc(Lit {
+            span: Span::DUMMY,
+            symbol: Symbol::intern(value),
+            kind: LitKind::Str(StrKind::Str, value.as_bytes().into()),
+        });
+        self.expr(yul::ExprKind::Lit(lit))
+    }
+
+    fn call_(
+        self,
+        name: impl IntoSymbol,
+        args: impl IntoIterator<Item = yul::Expr<'ast>>,
+    ) -> yul::ExprCall<'ast> {
+        let arguments = self.arena.alloc_vec(args.into_iter().collect());
+        yul::ExprCall { name: self.ident(name), arguments }
+    }
+
+    /// Creates a function call: `f(a, b)`.
+    pub fn call(
+        self,
+        name: impl IntoSymbol,
+        args: impl IntoIterator<Item = yul::Expr<'ast>>,
+    ) -> yul::Expr<'ast> {
+        self.expr(yul::ExprKind::Call(self.call_(name, args)))
+    }
+
+    /// Creates a function call statement: `f(a, b)`.
+    pub fn call_stmt(
+        self,
+        name: impl IntoSymbol,
+        args: impl IntoIterator<Item = yul::Expr<'ast>>,
+    ) -> yul::Stmt<'ast> {
+        self.stmt(yul::StmtKind::Expr(self.call_(name, args)))
+    }
+
+    /// Creates an expression statement. The expression must be a function call.
+    pub fn expr_stmt(self, expr: yul::Expr<'ast>) -> yul::Stmt<'ast> {
+        match expr.kind {
+            yul::ExprKind::Call(call) => self.stmt(yul::StmtKind::Expr(call)),
+            _ => panic!("expression statements must be function calls"),
+        }
+    }
+
+    /// Creates a variable declaration: `let a, b := value`.
+    pub fn let_(
+        self,
+        names: impl IntoIterator<Item = Symbol>,
+        value: Option<yul::Expr<'ast>>,
+    ) -> yul::Stmt<'ast> {
+        self.stmt(yul::StmtKind::VarDecl(self.idents(names), value))
+    }
+
+    /// Creates a declaration of a single variable: `let a := value`.
+    pub fn let1(self, name: impl IntoSymbol, value: yul::Expr<'ast>) -> yul::Stmt<'ast> {
+        self.let_([name.into_symbol()], Some(value))
+    }
+
+    /// Creates an assignment: `a, b := value`.
+    ///
+    /// Assignments to multiple variables require a function call on the right-hand side.
+    pub fn assign(
+        self,
+        names: impl IntoIterator<Item = Symbol>,
+        value: yul::Expr<'ast>,
+    ) -> yul::Stmt<'ast> {
+        let names = names.into_iter().collect::<Vec<_>>();
+        if let [name] = names[..] {
+            return self.stmt(yul::StmtKind::AssignSingle(self.ast_path(name), value));
+        }
+        let yul::ExprKind::Call(call) = value.kind else {
+            panic!("multi-assignments require a function call");
+        };
+        let paths = self.arena.alloc_from_iter(names.into_iter().map(|name| self.ast_path(name)));
+        self.stmt(yul::StmtKind::AssignMulti(paths, call))
+    }
+
+    /// Creates an assignment to a single variable: `a := value`.
+    pub fn assign1(self, name: impl IntoSymbol, value: yul::Expr<'ast>) -> yul::Stmt<'ast> {
+        self.assign([name.into_symbol()], value)
+    }
+
+    /// Creates a blocked scope: `{ ... }`.
+    pub fn block_stmt(self, stmts: Vec<yul::Stmt<'ast>>) -> yul::Stmt<'ast> {
+        self.stmt(yul::StmtKind::Block(self.block(stmts)))
+    }
+
+    /// Creates an if statement: `if cond { ... }`.
+    pub fn if_(self, cond: yul::Expr<'ast>, body: Vec<yul::Stmt<'ast>>) -> yul::Stmt<'ast> {
+        self.stmt(yul::StmtKind::If(cond, self.block(body)))
+    }
+
+    /// Creates a for loop: `for { init } cond { step } { body }`.
+    pub fn for_(
+        self,
+        init: Vec<yul::Stmt<'ast>>,
+        cond: yul::Expr<'ast>,
+        step: Vec<yul::Stmt<'ast>>,
+        body: Vec<yul::Stmt<'ast>>,
+    ) -> yul::Stmt<'ast> {
+        self.stmt(yul::StmtKind::For {
+            init: self.block(init),
+            cond,
+            step: self.block(step),
+            body: self.block(body),
+        })
+    }
+
+    /// Creates a switch statement.
+    pub fn switch(
+        self,
+        selector: yul::Expr<'ast>,
+        cases: impl IntoIterator<Item = (yul::Expr<'ast>, Vec<yul::Stmt<'ast>>)>,
+        default_case: Option<Vec<yul::Stmt<'ast>>>,
+    ) -> yul::Stmt<'ast> {
+        let branches = cases
+            .into_iter()
+            .map(|(constant, body)| {
+                let yul::ExprKind::Lit(constant) = constant.kind else {
+                    panic!("switch cases must be literals");
+                };
+                yul::StmtSwitchCase { constant, body: self.block(body) }
+            })
+            .collect();
+        self.stmt(yul::StmtKind::Switch(yul::StmtSwitch {
+            selector,
+            branches: self.arena.alloc_vec(branches),
+            default_case: default_case.map(|body| self.block(body)),
+        }))
+    }
+
+    /// Creates a `leave` statement.
+    pub fn leave(self) -> yul::Stmt<'ast> {
+        self.stmt(yul::StmtKind::Leave)
+    }
+
+    /// Creates a `break` statement.
+    pub fn break_(self) -> yul::Stmt<'ast> {
+        self.stmt(yul::StmtKind::Break)
+    }
+
+    /// Creates a `continue` statement.
+    pub fn continue_(self) -> yul::Stmt<'ast> {
+        self.stmt(yul::StmtKind::Continue)
+    }
+
+    /// Creates a function definition: `function name(params) -> returns { body }`.
+    pub fn function(
+        self,
+        name: impl IntoSymbol,
+        parameters: impl IntoIterator<Item = Symbol>,
+        returns: impl IntoIterator<Item = Symbol>,
+        body: Vec<yul::Stmt<'ast>>,
+    ) -> yul::Stmt<'ast> {
+        self.stmt(yul::StmtKind::FunctionDef(yul::Function {
+            name: self.ident(name),
+            parameters: self.idents(parameters),
+            returns: self.idents(returns),
+            body: self.block(body),
+        }))
+    }
+
+    /// Creates an object with the given code and sub-objects.
+    ///
+    /// Each of the `docs` is written as a `///` comment.
+    pub fn object(
+        self,
+        name: &str,
+        docs: &[String],
+        code: Vec<yul::Stmt<'ast>>,
+        children: Vec<yul::Object<'ast>>,
+    ) -> yul::Object<'ast> {
+        let docs = docs.iter().map(|doc| DocComment {
+            kind: CommentKind::Line,
+            span: Span::DUMMY,
+            symbol: Symbol::intern(&format!(" {doc}")),
+        });
+        yul::Object {
+            docs: self.arena.alloc_from_iter(docs),
+            span: Span::DUMMY,
+            name: StrLit { span: Span::DUMMY, value: Symbol::intern(name) },
+            code: yul::CodeBlock { span: Span::DUMMY, code: self.block(code) },
+            children: self.arena.alloc_vec(children),
+            data: Default::default(),
+        }
+    }
+}
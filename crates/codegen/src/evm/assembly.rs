@@ -0,0 +1,473 @@
+//! EVM assembly.
+
+use super::Opcode;
+use alloy_primitives::U256;
+use solar_data_structures::{
+    index::{Idx, IndexVec},
+    map::FxIndexMap,
+    newtype_index,
+};
+use solar_interface::{config::EvmVersion, Symbol};
+use std::fmt;
+
+newtype_index! {
+    /// A jump destination in an [`Assembly`].
+    pub struct Tag;
+
+    /// A sub-assembly of an [`Assembly`].
+    pub struct SubId;
+
+    /// A data section of an [`Assembly`].
+    pub struct DataId;
+}
+
+/// An item of an [`Assembly`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AssemblyItem {
+    /// An opcode without immediate bytes. Constants are pushed with [`Push`](Self::Push) instead.
+    Op(Opcode),
+    /// Pushes a constant with the smallest `PUSH` opcode that fits it.
+    Push(U256),
+    /// Marks a jump destination.
+    Tag(Tag),
+    /// Pushes the offset of a jump destination.
+    PushTag(Tag),
+    /// Pushes the size of the bytecode of a sub-assembly.
+    PushSubSize(SubId),
+    /// Pushes the offset of the bytecode of a sub-assembly.
+    PushSubOffset(SubId),
+    /// Pushes the offset of a data section.
+    PushDataOffset(DataId),
+    /// Pushes the size of the whole bytecode, including the sub-assemblies and data sections.
+    PushProgramSize,
+    /// Pushes the value of an immutable variable.
+    ///
+    /// The value is a 32-byte placeholder in the bytecode, which is filled in by
+    /// [`AssignImmutable`](Self::AssignImmutable) in the creation code.
+    PushImmutable(Symbol),
+    /// Pops a memory offset and a value, and writes the value to every placeholder of the
+    /// immutable variable in the copy of the sub-assembly bytecode that starts at that offset.
+    AssignImmutable(Symbol),
+}
+
+impl fmt::Display for AssemblyItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Op(opcode) => write!(f, "{opcode}"),
+            Self::Push(value) => write!(f, "PUSH {value:#x}"),
+            Self::Tag(tag) => write!(f, "tag_{}:", tag.get()),
+            Self::PushTag(tag) => write!(f, "PUSH [tag] {}", tag.get()),
+            Self::PushSubSize(id) => write!(f, "PUSH #[$] sub_{}", id.get()),
+            Self::PushSubOffset(id) => write!(f, "PUSH [$] sub_{}", id.get()),
+            Self::PushDataOffset(id) => write!(f, "PUSH [$] data_{}", id.get()),
+            Self::PushProgramSize => write!(f, "PUSHSIZE"),
+            Self::PushImmutable(name) => write!(f, "PUSHIMMUTABLE {name}"),
+            Self::AssignImmutable(name) => write!(f, "ASSIGNIMMUTABLE {name}"),
+        }
+    }
+}
+
+/// An error that occurred while assembling EVM bytecode.
+#[derive(Debug, thiserror::Error)]
+pub enum AssemblyError {
+    #[error("opcode `{0}` is not available in EVM version `{1}`")]
+    UnavailableOpcode(Opcode, EvmVersion),
+}
+
+/// A sequence of EVM instructions with symbolic jump destinations, together with the
+/// sub-assemblies and data sections that are appended to its bytecode.
+///
+/// Creation code is an assembly that has the runtime code as its sub-assembly.
+#[derive(Clone, Debug)]
+pub struct Assembly {
+    evm_version: EvmVersion,
+    items: Vec<AssemblyItem>,
+    tags: usize,
+    subs: IndexVec<SubId, Self>,
+    data: IndexVec<DataId, Vec<u8>>,
+}
+
+impl Assembly {
+    /// Creates a new, empty assembly targeting the given EVM version.
+    pub fn new(evm_version: EvmVersion) -> Self {
+        Self {
+            evm_version,
+            items: Vec::new(),
+            tags: 0,
+            subs: IndexVec::new(),
+            data: IndexVec::new(),
+        }
+    }
+
+    /// Returns the EVM version that this assembly targets.
+    pub fn evm_version(&self) -> EvmVersion {
+        self.evm_version
+    }
+
+    /// Returns the items of the assembly.
+    pub fn items(&self) -> &[AssemblyItem] {
+        &self.items
+    }
+
+    /// Returns the sub-assemblies.
+    pub fn subs(&self) -> &IndexVec<SubId, Self> {
+        &self.subs
+    }
+
+    /// Returns the data sections.
+    pub fn data(&self) -> &IndexVec<DataId, Vec<u8>> {
+        &self.data
+    }
+
+    /// Appends an item.
+    pub fn append(&mut self, item: AssemblyItem) {
+        self.items.push(item);
+    }
+
+    /// Appends an opcode.
+    pub fn op(&mut self, opcode: Opcode) {
+        debug_assert!(opcode.immediate_size() == 0, "use `push` to push constants");
+        self.append(AssemblyItem::Op(opcode));
+    }
+
+    /// Appends a push of a constant.
+    pub fn push(&mut self, value: U256) {
+        self.append(AssemblyItem::Push(value));
+    }
+
+    /// Creates a new jump destination. It must be placed with [`place_tag`](Self::place_tag).
+    pub fn new_tag(&mut self) -> Tag {
+        let tag = Tag::from_usize(self.tags);
+        self.tags += 1;
+        tag
+    }
+
+    /// Marks the current position as the given jump destination.
+    pub fn place_tag(&mut self, tag: Tag) {
+        self.append(AssemblyItem::Tag(tag));
+    }
+
+    /// Appends a push of the offset of a jump destination.
+    pub fn push_tag(&mut self, tag: Tag) {
+        self.append(AssemblyItem::PushTag(tag));
+    }
+
+    /// Adds a sub-assembly, whose bytecode is appended to the bytecode of this assembly.
+    pub fn add_sub(&mut self, sub: Self) -> SubId {
+        self.subs.push(sub)
+    }
+
+    /// Adds a data section, which is appended to the bytecode after the sub-assemblies.
+    pub fn add_data(&mut self, data: Vec<u8>) -> DataId {
+        self.data.push(data)
+    }
+
+    /// Assembles the bytecode.
+    ///
+    /// Constants are pushed with the smallest `PUSH` opcode that fits them, and `PUSH0` is used
+    /// for zero if it is available. Offsets all use the same number of bytes, which is the smallest
+    /// that fits the size of the whole bytecode.
+    pub fn assemble(&self) -> Result<Bytecode, AssemblyError> {
+        for item in &self.items {
+            if let AssemblyItem::Op(opcode) = *item {
+                if !opcode.is_available(self.evm_version) {
+                    return Err(AssemblyError::UnavailableOpcode(opcode, self.evm_version));
+                }
+            }
+        }
+
+        let subs = self.subs.iter().map(Self::assemble).collect::<Result<IndexVec<_, _>, _>>()?;
+        let mut sub_immutables = FxIndexMap::<Symbol, Vec<usize>>::default();
+        for sub in &subs {
+            for (&name, refs) in &sub.immutable_references {
+                sub_immutables.entry(name).or_default().extend_from_slice(refs);
+            }
+        }
+
+        let mut width = 1;
+        let layout = loop {
+            let layout = self.layout(&subs, &sub_immutables, width);
+            let required = bytes_len(U256::from(layout.total_size));
+            if required <= width {
+                break layout;
+            }
+            width = required;
+        };
+
+        let mut code = Vec::with_capacity(layout.total_size);
+        let mut immutable_references = FxIndexMap::<Symbol, Vec<usize>>::default();
+        for item in &self.items {
+            match *item {
+                AssemblyItem::Op(opcode) => code.push(opcode as u8),
+                AssemblyItem::Push(value) => self.write_push(&mut code, value),
+                AssemblyItem::Tag(_) => code.push(Opcode::JUMPDEST as u8),
+                AssemblyItem::PushTag(tag) => {
+                    let offset = layout.tags[tag.index()].expect("jump to an unplaced tag");
+                    write_push_n(&mut code, U256::from(offset), width);
+                }
+                AssemblyItem::PushSubSize(id) => {
+                    self.write_push(&mut code, U256::from(subs[id].code.len()));
+                }
+                AssemblyItem::PushSubOffset(id) => {
+                    write_push_n(&mut code, U256::from(layout.sub_offsets[id.index()]), width);
+                }
+                AssemblyItem::PushDataOffset(id) => {
+                    write_push_n(&mut code, U256::from(layout.data_offsets[id.index()]), width);
+                }
+                AssemblyItem::PushProgramSize => {
+                    write_push_n(&mut code, U256::from(layout.total_size), width);
+                }
+                AssemblyItem::PushImmutable(name) => {
+                    immutable_references.entry(name).or_default().push(code.len() + 1);
+                    write_push_n(&mut code, U256::ZERO, 32);
+                }
+                AssemblyItem::AssignImmutable(name) => {
+                    let refs = sub_immutables.get(&name).map(Vec::as_slice).unwrap_or_default();
+                    for &position in refs {
+                        code.extend([Opcode::DUP2 as u8, Opcode::DUP2 as u8]);
+                        self.write_push(&mut code, U256::from(position));
+                        code.extend([Opcode::ADD as u8, Opcode::MSTORE as u8]);
+                    }
+                    code.extend([Opcode::POP as u8, Opcode::POP as u8]);
+                }
+            }
+        }
+        debug_assert_eq!(code.len(), layout.code_size);
+        for sub in &subs {
+            code.extend_from_slice(&sub.code);
+        }
+        for data in &self.data {
+            code.extend_from_slice(data);
+        }
+        debug_assert_eq!(code.len(), layout.total_size);
+
+        Ok(Bytecode { code, immutable_references, subs })
+    }
+
+    /// Computes the offsets of the tags, sub-assemblies and data sections, assuming that offsets
+    /// are pushed with `width` bytes.
+    fn layout(
+        &self,
+        subs: &IndexVec<SubId, Bytecode>,
+        sub_immutables: &FxIndexMap<Symbol, Vec<usize>>,
+        width: usize,
+    ) -> Layout {
+        let mut tags = vec![None; self.tags];
+        let mut size = 0;
+        for item in &self.items {
+            size += match *item {
+                AssemblyItem::Op(_) => 1,
+                AssemblyItem::Push(value) => self.push_size(value),
+                AssemblyItem::Tag(tag) => {
+                    tags[tag.index()] = Some(size);
+                    1
+                }
+                AssemblyItem::PushTag(_)
+                | AssemblyItem::PushSubOffset(_)
+                | AssemblyItem::PushDataOffset(_)
+                | AssemblyItem::PushProgramSize => 1 + width,
+                AssemblyItem::PushSubSize(id) => self.push_size(U256::from(subs[id].code.len())),
+                AssemblyItem::PushImmutable(_) => 33,
+                AssemblyItem::AssignImmutable(name) => {
+                    let refs = sub_immutables.get(&name).map(Vec::as_slice).unwrap_or_default();
+                    let stores: usize =
+                        refs.iter().map(|&p| 4 + self.push_size(U256::from(p))).sum();
+                    stores + 2
+                }
+            };
+        }
+        let code_size = size;
+        let sub_offsets = subs
+            .iter()
+            .map(|sub| {
+                let offset = size;
+                size += sub.code.len();
+                offset
+            })
+            .collect();
+        let data_offsets = self
+            .data
+            .iter()
+            .map(|data| {
+                let offset = size;
+                size += data.len();
+                offset
+            })
+            .collect();
+        Layout { tags, code_size, sub_offsets, data_offsets, total_size: size }
+    }
+
+    fn has_push0(&self) -> bool {
+        Opcode::PUSH0.is_available(self.evm_version)
+    }
+
+    /// Returns the size of the instruction that pushes `value`.
+    fn push_size(&self, value: U256) -> usize {
+        if value.is_zero() && self.has_push0() {
+            1
+        } else {
+            1 + bytes_len(value)
+        }
+    }
+
+    fn write_push(&self, code: &mut Vec<u8>, value: U256) {
+        if value.is_zero() && self.has_push0() {
+            code.push(Opcode::PUSH0 as u8);
+        } else {
+            write_push_n(code, value, bytes_len(value));
+        }
+    }
+}
+
+impl fmt::Display for Assembly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+impl Assembly {
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        for item in &self.items {
+            let extra = if matches!(item, AssemblyItem::Tag(_)) { 2 } else { 4 };
+            writeln!(f, "{:indent$}{item}", "", indent = indent + extra)?;
+        }
+        for (id, sub) in self.subs.iter_enumerated() {
+            writeln!(f, "{:indent$}sub_{}: assembly {{", "", id.get())?;
+            sub.fmt_indented(f, indent + 4)?;
+            writeln!(f, "{:indent$}}}", "")?;
+        }
+        for (id, data) in self.data.iter_enumerated() {
+            writeln!(
+                f,
+                "{:indent$}data_{}: {}",
+                "",
+                id.get(),
+                alloy_primitives::hex::encode(data)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Assembled EVM bytecode.
+#[derive(Clone, Debug, Default)]
+pub struct Bytecode {
+    /// The bytecode, followed by the bytecode of the sub-assemblies and the data sections.
+    pub code: Vec<u8>,
+    /// The positions of the placeholders of each immutable variable in `code`.
+    pub immutable_references: FxIndexMap<Symbol, Vec<usize>>,
+    /// The assembled sub-assemblies.
+    pub subs: IndexVec<SubId, Self>,
+}
+
+struct Layout {
+    tags: Vec<Option<usize>>,
+    code_size: usize,
+    sub_offsets: Vec<usize>,
+    data_offsets: Vec<usize>,
+    total_size: usize,
+}
+
+/// Returns the number of bytes needed to represent `value`, which is at least 1.
+fn bytes_len(value: U256) -> usize {
+    value.byte_len().max(1)
+}
+
+fn write_push_n(code: &mut Vec<u8>, value: U256, n: usize) {
+    debug_assert!(bytes_len(value) <= n);
+    code.push(Opcode::push(n) as u8);
+    code.extend_from_slice(&value.to_be_bytes::<32>()[32 - n..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    fn assemble(asm: &Assembly) -> String {
+        hex::encode(asm.assemble().unwrap().code)
+    }
+
+    #[test]
+    fn push_sizes() {
+        let mut asm = Assembly::new(EvmVersion::Shanghai);
+        asm.push(U256::ZERO);
+        asm.push(U256::from(0x80));
+        asm.push(U256::from(0x1234));
+        asm.push(U256::MAX);
+        assert_eq!(assemble(&asm), format!("5f6080611234{}", "7f".to_string() + &"ff".repeat(32)));
+
+        let mut asm = Assembly::new(EvmVersion::Paris);
+        asm.push(U256::ZERO);
+        assert_eq!(assemble(&asm), "6000");
+    }
+
+    #[test]
+    fn jumps() {
+        let mut asm = Assembly::new(EvmVersion::Cancun);
+        let tag = asm.new_tag();
+        asm.place_tag(tag);
+        asm.push_tag(tag);
+        asm.op(Opcode::JUMP);
+        assert_eq!(assemble(&asm), "5b600056");
+
+        // Offsets need two bytes once the code is larger than 255 bytes.
+        let mut asm = Assembly::new(EvmVersion::Cancun);
+        let tag = asm.new_tag();
+        asm.push_tag(tag);
+        asm.op(Opcode::JUMP);
+        for _ in 0..300 {
+            asm.op(Opcode::INVALID);
+        }
+        asm.place_tag(tag);
+        let code = asm.assemble().unwrap().code;
+        assert_eq!(code.len(), 305);
+        assert_eq!(code[..4], [0x61, 0x01, 0x30, 0x56]);
+        assert_eq!(code[0x130], Opcode::JUMPDEST as u8);
+    }
+
+    #[test]
+    fn subs_and_data() {
+        solar_interface::enter(|| {
+            let x = Symbol::intern("x");
+            let mut runtime = Assembly::new(EvmVersion::Cancun);
+            runtime.append(AssemblyItem::PushImmutable(x));
+            runtime.op(Opcode::STOP);
+
+            let mut asm = Assembly::new(EvmVersion::Cancun);
+            let sub = asm.add_sub(runtime);
+            let data = asm.add_data(vec![0xaa, 0xbb]);
+            // `codecopy(0, dataoffset(sub), datasize(sub))`
+            asm.append(AssemblyItem::PushSubSize(sub));
+            asm.append(AssemblyItem::PushSubOffset(sub));
+            asm.push(U256::ZERO);
+            asm.op(Opcode::CODECOPY);
+            // `setimmutable(0, "x", 42)`
+            asm.push(U256::from(42));
+            asm.push(U256::ZERO);
+            asm.append(AssemblyItem::AssignImmutable(x));
+            asm.append(AssemblyItem::PushDataOffset(data));
+            asm.op(Opcode::STOP);
+
+            let bytecode = asm.assemble().unwrap();
+            let runtime = &bytecode.subs[sub];
+            assert_eq!(runtime.code.len(), 34);
+            assert_eq!(runtime.immutable_references[&x], [1]);
+            assert_eq!(
+                hex::encode(&bytecode.code),
+                format!(
+                    "602260145f39602a5f8181600101525050603600{}aabb",
+                    hex::encode(&runtime.code)
+                )
+            );
+        });
+    }
+
+    #[test]
+    fn unavailable_opcode() {
+        let mut asm = Assembly::new(EvmVersion::London);
+        asm.op(Opcode::TSTORE);
+        let err = asm.assemble().unwrap_err();
+        assert_eq!(err.to_string(), "opcode `TSTORE` is not available in EVM version `london`");
+    }
+}
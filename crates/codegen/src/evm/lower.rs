@@ -0,0 +1,1030 @@
+//! Lowering of Yul objects into EVM assembly.
+//!
+//! Variables live on the stack, in the order in which they are declared, and are accessed with
+//! `DUP` and `SWAP`. Since these can only reach the top 16 stack slots, variables that are too deep
+//! inside the stack cannot be accessed, in which case a "stack too deep" error is emitted.
+//!
+//! To keep the stack shallow, the slot of a variable is freed after the last statement of its block
+//! that uses it. Free slots on top of the stack are popped, and the others are reused by the next
+//! variable declarations of the block.
+//!
+//! Functions are called by pushing a return label followed by the arguments, with the first
+//! argument on top, and jumping to the function. The callee pushes its return variables, and
+//! before returning rearranges the stack so that only the return values are left, with the first
+//! one at the bottom, followed by the return label.
+
+use super::{Assembly, AssemblyItem, DataId, Opcode, SubId, Tag};
+use alloy_primitives::U256;
+use solar_ast::ast::{yul, LitKind, StrKind};
+use solar_data_structures::map::{FxHashMap, FxHashSet};
+use solar_interface::{
+    config::EvmVersion,
+    diagnostics::{DiagCtxt, ErrorGuaranteed},
+    Ident, Result, Span, Symbol,
+};
+
+/// The maximum depth of a stack slot that can be reached by `DUP` and `SWAP`.
+const MAX_DEPTH: usize = 16;
+
+/// Lowers a Yul object into EVM assembly.
+///
+/// The sub-objects and data segments of the object are added as sub-assemblies and data
+/// sections, which can be referenced with the `datasize`, `dataoffset` and `datacopy` builtins.
+/// The object can also reference itself, in which case its whole bytecode is used.
+#[instrument(level = "debug", skip_all, fields(object = %object.name.value))]
+pub fn lower_object(
+    dcx: &DiagCtxt,
+    evm_version: EvmVersion,
+    object: &yul::Object<'_>,
+) -> Result<Assembly> {
+    let mut asm = Assembly::new(evm_version);
+    let mut guar = None;
+    let mut references = FxHashMap::default();
+    for child in object.children.iter() {
+        // Keep failed children referenceable to avoid reporting them as unknown.
+        let sub = lower_object(dcx, evm_version, child).unwrap_or_else(|e| {
+            guar = Some(e);
+            Assembly::new(evm_version)
+        });
+        references.insert(child.name.value, DataRef::Sub(asm.add_sub(sub)));
+    }
+    for data in object.data.iter() {
+        let LitKind::Str(_, bytes) = &data.data.kind else { continue };
+        let id = asm.add_data(bytes.to_vec());
+        references.insert(data.name.value, DataRef::Data(id, bytes.len()));
+    }
+
+    let mut lcx = LoweringCx {
+        dcx,
+        object: object.name.value,
+        asm,
+        references,
+        scopes: Vec::new(),
+        height: 0,
+        reachable: true,
+        function: None,
+        loops: Vec::new(),
+        too_deep: FxHashSet::default(),
+        guar,
+    };
+    lcx.block(object.code.code);
+    if lcx.reachable {
+        lcx.op(Opcode::STOP);
+    }
+    match lcx.guar {
+        Some(guar) => Err(guar),
+        None => Ok(lcx.asm),
+    }
+}
+
+/// A reference to a sub-object or a data segment.
+#[derive(Clone, Copy)]
+enum DataRef {
+    Sub(SubId),
+    Data(DataId, usize),
+}
+
+#[derive(Default)]
+struct Scope {
+    variables: FxHashMap<Symbol, Variable>,
+    functions: FxHashMap<Symbol, Function>,
+    /// The slots of the variables of this scope that are no longer used.
+    free: Vec<usize>,
+    /// Whether this is the outermost scope of a function body, which contains the parameters and
+    /// return variables. The variables of the enclosing scopes are not visible from here.
+    function_boundary: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Variable {
+    /// The position of the variable on the stack, counted from the bottom of the current frame.
+    slot: usize,
+    name: Ident,
+}
+
+#[derive(Clone, Copy)]
+struct Function {
+    tag: Tag,
+    parameters: usize,
+    returns: usize,
+}
+
+struct FunctionCx {
+    name: Ident,
+    /// Jumped to by `leave`.
+    exit: Tag,
+    /// The stack height after the return variables have been pushed.
+    height: usize,
+}
+
+struct LoopCx {
+    /// Jumped to by `continue`.
+    post: Tag,
+    /// Jumped to by `break`.
+    end: Tag,
+    /// The stack height at the start of the loop body.
+    height: usize,
+}
+
+/// A slot in the stack frame of a function.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FrameSlot {
+    ReturnLabel,
+    Parameter(usize),
+    Return(usize),
+}
+
+struct LoweringCx<'a> {
+    dcx: &'a DiagCtxt,
+    /// The name of the object.
+    object: Symbol,
+    asm: Assembly,
+    references: FxHashMap<Symbol, DataRef>,
+    scopes: Vec<Scope>,
+    /// The number of stack slots in the current frame.
+    height: usize,
+    /// Whether the next item can be reached by the execution.
+    reachable: bool,
+    function: Option<FunctionCx>,
+    loops: Vec<LoopCx>,
+    /// The variables that were reported as being too deep inside the stack.
+    too_deep: FxHashSet<(Symbol, Span)>,
+    guar: Option<ErrorGuaranteed>,
+}
+
+impl LoweringCx<'_> {
+    fn op(&mut self, opcode: Opcode) {
+        self.height = self.height - opcode.inputs() + opcode.outputs();
+        if opcode.is_terminating() {
+            self.reachable = false;
+        }
+        self.asm.op(opcode);
+    }
+
+    fn push(&mut self, value: U256) {
+        self.height += 1;
+        self.asm.push(value);
+    }
+
+    fn push_item(&mut self, item: AssemblyItem) {
+        self.height += 1;
+        self.asm.append(item);
+    }
+
+    fn push_tag(&mut self, tag: Tag) {
+        self.push_item(AssemblyItem::PushTag(tag));
+    }
+
+    fn place_tag(&mut self, tag: Tag) {
+        self.reachable = true;
+        self.asm.place_tag(tag);
+    }
+
+    fn jump(&mut self, tag: Tag) {
+        self.push_tag(tag);
+        self.op(Opcode::JUMP);
+    }
+
+    fn pop(&mut self, n: usize) {
+        for _ in 0..n {
+            self.op(Opcode::POP);
+        }
+    }
+
+    fn err(&mut self, span: Span, msg: impl Into<String>) {
+        self.guar = Some(self.dcx.err(msg.into()).span(span).emit());
+    }
+
+    /// Reports that a variable cannot be reached with `DUP` or `SWAP`. Each variable is only
+    /// reported once.
+    fn stack_too_deep(&mut self, span: Span, var: Ident, depth: usize) {
+        self.stack_too_deep_inner(span, var, depth, format!("variable `{var}`"));
+    }
+
+    fn stack_too_deep_inner(&mut self, span: Span, var: Ident, depth: usize, what: String) {
+        if !self.too_deep.insert((var.name, var.span)) {
+            return;
+        }
+        let n = depth - MAX_DEPTH;
+        let s = if n == 1 { "" } else { "s" };
+        let msg = format!("stack too deep: {what} is {n} slot{s} too deep inside the stack");
+        let mut diag = self.dcx.err(msg);
+        // Generated code has dummy spans.
+        if !span.is_dummy() {
+            diag = diag.span(span);
+        }
+        if !var.span.is_dummy() && var.span != span {
+            diag = diag.span_note(var.span, "variable declared here");
+        }
+        let location = match &self.function {
+            Some(f) => format!("in function `{}` of object `{}`", f.name, self.object),
+            None => format!("in object `{}`", self.object),
+        };
+        self.guar = Some(
+            diag.note(location)
+                .help("try removing local variables, or moving them into memory")
+                .emit(),
+        );
+    }
+
+    fn variable(&self, name: Symbol) -> Option<Variable> {
+        for scope in self.scopes.iter().rev() {
+            if let Some(&var) = scope.variables.get(&name) {
+                return Some(var);
+            }
+            if scope.function_boundary {
+                break;
+            }
+        }
+        None
+    }
+
+    fn function(&self, name: Symbol) -> Option<Function> {
+        self.scopes.iter().rev().find_map(|scope| scope.functions.get(&name).copied())
+    }
+
+    fn declare(&mut self, name: Ident, slot: usize) {
+        let var = Variable { slot, name };
+        self.scopes.last_mut().unwrap().variables.insert(name.name, var);
+    }
+
+    fn block(&mut self, block: &[yul::Stmt<'_>]) {
+        let height = self.height;
+        self.scopes.push(Scope::default());
+        self.block_inner(block, true);
+        self.scopes.pop();
+        if self.reachable {
+            self.pop(self.height - height);
+        }
+        self.height = height;
+    }
+
+    /// Lowers the statements of a block in the current scope.
+    ///
+    /// If `free_slots` is set, the slots of the variables declared in the block are freed after
+    /// their last use. This must not be set if the variables are used after the block.
+    fn block_inner(&mut self, block: &[yul::Stmt<'_>], free_slots: bool) {
+        // Functions can be called before they are defined.
+        for stmt in block {
+            if let yul::StmtKind::FunctionDef(f) = &stmt.kind {
+                let function = Function {
+                    tag: self.asm.new_tag(),
+                    parameters: f.parameters.len(),
+                    returns: f.returns.len(),
+                };
+                self.scopes.last_mut().unwrap().functions.insert(f.name.name, function);
+            }
+        }
+
+        let last_uses = if free_slots { last_uses(block) } else { FxHashMap::default() };
+        let mut skip = None;
+        for (i, stmt) in block.iter().enumerate() {
+            if let yul::StmtKind::FunctionDef(f) = &stmt.kind {
+                // Jump over consecutive function definitions.
+                if skip.is_none() && self.reachable {
+                    let tag = self.asm.new_tag();
+                    self.jump(tag);
+                    skip = Some(tag);
+                }
+                self.function_def(f);
+                continue;
+            }
+            if let Some(tag) = skip.take() {
+                self.place_tag(tag);
+            }
+            self.stmt(stmt);
+            if free_slots && self.reachable {
+                self.free_slots(|name| last_uses.get(&name).is_none_or(|&last| last <= i));
+            }
+        }
+        if let Some(tag) = skip {
+            self.place_tag(tag);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &yul::Stmt<'_>) {
+        match &stmt.kind {
+            yul::StmtKind::Block(block) => self.block(block),
+            yul::StmtKind::AssignSingle(path, expr) => {
+                self.expr(expr);
+                self.assign(path_name(path), path.span());
+            }
+            yul::StmtKind::AssignMulti(paths, call) => {
+                self.call(call, stmt.span, Some(paths.len()));
+                for path in paths.iter().rev() {
+                    self.assign(path_name(path), path.span());
+                }
+            }
+            yul::StmtKind::Expr(call) => {
+                let returns = self.call(call, stmt.span, None);
+                self.pop(returns);
+            }
+            yul::StmtKind::If(cond, body) => {
+                let end = self.asm.new_tag();
+                self.expr(cond);
+                self.op(Opcode::ISZERO);
+                self.push_tag(end);
+                self.op(Opcode::JUMPI);
+                self.block(body);
+                self.place_tag(end);
+            }
+            yul::StmtKind::For { init, cond, step, body } => self.for_loop(init, cond, step, body),
+            yul::StmtKind::Switch(switch) => self.switch(switch),
+            yul::StmtKind::Leave => {
+                let Some(function) = &self.function else {
+                    return self.err(stmt.span, "`leave` outside of a function");
+                };
+                let (exit, height) = (function.exit, function.height);
+                let current = self.height;
+                self.pop(current - height);
+                self.jump(exit);
+                self.height = current;
+            }
+            yul::StmtKind::Break | yul::StmtKind::Continue => {
+                let Some(cx) = self.loops.last() else {
+                    return self.err(stmt.span, "`break` or `continue` outside of a loop");
+                };
+                let tag = if matches!(stmt.kind, yul::StmtKind::Break) { cx.end } else { cx.post };
+                let height = cx.height;
+                let current = self.height;
+                self.pop(current - height);
+                self.jump(tag);
+                self.height = current;
+            }
+            yul::StmtKind::FunctionDef(f) => self.function_def(f),
+            yul::StmtKind::VarDecl(names, value) => {
+                match value {
+                    Some(expr) => match &expr.kind {
+                        yul::ExprKind::Call(call) => {
+                            self.call(call, expr.span, Some(names.len()));
+                        }
+                        _ => self.expr(expr),
+                    },
+                    None => {
+                        for _ in names.iter() {
+                            self.push(U256::ZERO);
+                        }
+                    }
+                }
+                if let [name] = **names {
+                    if let Some(slot) = self.take_free_slot() {
+                        self.op(Opcode::swap(self.height - 1 - slot));
+                        self.op(Opcode::POP);
+                        return self.declare(name, slot);
+                    }
+                }
+                let base = self.height - names.len();
+                for (i, &name) in names.iter().enumerate() {
+                    self.declare(name, base + i);
+                }
+            }
+        }
+    }
+
+    /// Frees the slots of the variables of the current scope that are dead, and pops the free
+    /// slots that are on top of the stack.
+    fn free_slots(&mut self, is_dead: impl Fn(Symbol) -> bool) {
+        let scope = self.scopes.last_mut().unwrap();
+        scope.variables.retain(|&name, var| {
+            let dead = is_dead(name);
+            if dead {
+                scope.free.push(var.slot);
+            }
+            !dead
+        });
+        loop {
+            let free = &mut self.scopes.last_mut().unwrap().free;
+            let Some(i) = free.iter().position(|&slot| slot + 1 == self.height) else { break };
+            free.swap_remove(i);
+            self.op(Opcode::POP);
+        }
+    }
+
+    /// Takes the free slot of the current scope that is closest to the top of the stack, if it
+    /// can be reached with `SWAP`.
+    fn take_free_slot(&mut self) -> Option<usize> {
+        let free = &mut self.scopes.last_mut().unwrap().free;
+        let (i, &slot) = free.iter().enumerate().max_by_key(|&(_, &slot)| slot)?;
+        if self.height - 1 - slot > MAX_DEPTH {
+            return None;
+        }
+        free.swap_remove(i);
+        Some(slot)
+    }
+
+    fn for_loop(
+        &mut self,
+        init: &[yul::Stmt<'_>],
+        cond: &yul::Expr<'_>,
+        step: &[yul::Stmt<'_>],
+        body: &[yul::Stmt<'_>],
+    ) {
+        // The variables declared in `init` are visible in the rest of the loop.
+        let height = self.height;
+        self.scopes.push(Scope::default());
+        self.block_inner(init, false);
+
+        let start = self.asm.new_tag();
+        let post = self.asm.new_tag();
+        let end = self.asm.new_tag();
+        self.place_tag(start);
+        self.expr(cond);
+        self.op(Opcode::ISZERO);
+        self.push_tag(end);
+        self.op(Opcode::JUMPI);
+
+        self.loops.push(LoopCx { post, end, height: self.height });
+        self.block(body);
+        self.loops.pop();
+
+        self.place_tag(post);
+        self.block(step);
+        self.jump(start);
+        self.place_tag(end);
+
+        self.scopes.pop();
+        self.pop(self.height - height);
+    }
+
+    fn switch(&mut self, switch: &yul::StmtSwitch<'_>) {
+        self.expr(&switch.selector);
+        let height = self.height;
+        let end = self.asm.new_tag();
+        let mut cases = Vec::with_capacity(switch.branches.len());
+        for case in switch.branches.iter() {
+            let tag = self.asm.new_tag();
+            let Some(value) = self.lit(case.constant) else { continue };
+            self.op(Opcode::dup(1));
+            self.push(value);
+            self.op(Opcode::EQ);
+            self.push_tag(tag);
+            self.op(Opcode::JUMPI);
+            cases.push((tag, &case.body));
+        }
+
+        // The default case follows the comparisons.
+        self.op(Opcode::POP);
+        if let Some(body) = &switch.default_case {
+            self.block(body);
+        }
+        for (tag, body) in cases {
+            if self.reachable {
+                self.jump(end);
+            }
+            self.height = height;
+            self.place_tag(tag);
+            self.op(Opcode::POP);
+            self.block(body);
+        }
+        self.place_tag(end);
+        self.height = height - 1;
+    }
+
+    fn function_def(&mut self, f: &yul::Function<'_>) {
+        let Some(function) = self.scopes.last().and_then(|s| s.functions.get(&f.name.name)) else {
+            unreachable!("function `{}` was not declared", f.name)
+        };
+        let Function { tag, parameters, .. } = *function;
+
+        let height = std::mem::replace(&mut self.height, 0);
+        let loops = std::mem::take(&mut self.loops);
+        let exit = self.asm.new_tag();
+
+        // The caller pushed the return label and the arguments, with the first one on top.
+        self.place_tag(tag);
+        self.height = 1 + parameters;
+        let mut scope = Scope { function_boundary: true, ..Default::default() };
+        for (i, &name) in f.parameters.iter().enumerate() {
+            scope.variables.insert(name.name, Variable { slot: parameters - i, name });
+        }
+        self.scopes.push(scope);
+        for (i, &name) in f.returns.iter().enumerate() {
+            self.push(U256::ZERO);
+            self.declare(name, 1 + parameters + i);
+        }
+        let function = FunctionCx { name: f.name, exit, height: self.height };
+        let outer = self.function.replace(function);
+
+        self.block(f.body);
+        self.place_tag(exit);
+        self.function_epilogue(f);
+
+        self.scopes.pop();
+        self.function = outer;
+        self.loops = loops;
+        self.height = height;
+    }
+
+    /// Rearranges the stack frame of the function so that only the return values are left,
+    /// followed by the return label, and returns to the caller.
+    fn function_epilogue(&mut self, f: &yul::Function<'_>) {
+        let parameters = f.parameters.len();
+        let returns = f.returns.len();
+        let mut stack = Vec::with_capacity(1 + parameters + returns);
+        stack.push(FrameSlot::ReturnLabel);
+        stack.extend((0..parameters).rev().map(FrameSlot::Parameter));
+        stack.extend((0..returns).map(FrameSlot::Return));
+        let target =
+            (0..returns).map(FrameSlot::Return).chain([FrameSlot::ReturnLabel]).collect::<Vec<_>>();
+
+        'shuffle: for (i, &wanted) in target.iter().enumerate() {
+            if stack[i] == wanted {
+                continue;
+            }
+            let j = stack.iter().position(|&slot| slot == wanted).unwrap();
+            let top = stack.len() - 1;
+            for depth in [top - j, top - i] {
+                if depth == 0 {
+                    continue;
+                }
+                if depth > MAX_DEPTH {
+                    let (var, what) = match stack[top - depth] {
+                        FrameSlot::ReturnLabel => {
+                            (f.name, format!("the return address of function `{}`", f.name))
+                        }
+                        FrameSlot::Parameter(i) => {
+                            (f.parameters[i], format!("variable `{}`", f.parameters[i]))
+                        }
+                        FrameSlot::Return(i) => {
+                            (f.returns[i], format!("variable `{}`", f.returns[i]))
+                        }
+                    };
+                    self.stack_too_deep_inner(f.name.span, var, depth, what);
+                    break 'shuffle;
+                }
+                self.op(Opcode::swap(depth));
+                stack.swap(top, top - depth);
+            }
+        }
+        self.pop(stack.len() - target.len());
+        self.op(Opcode::JUMP);
+    }
+
+    /// Assigns the value on top of the stack to a variable, and pops it.
+    fn assign(&mut self, name: Symbol, span: Span) {
+        let Some(var) = self.variable(name) else {
+            self.err(span, format!("cannot find variable `{name}` in this scope"));
+            return self.op(Opcode::POP);
+        };
+        let depth = self.height - 1 - var.slot;
+        if depth > MAX_DEPTH {
+            self.stack_too_deep(span, var.name, depth);
+        } else if depth > 0 {
+            self.op(Opcode::swap(depth));
+        }
+        self.op(Opcode::POP);
+    }
+
+    fn expr(&mut self, expr: &yul::Expr<'_>) {
+        match &expr.kind {
+            yul::ExprKind::Path(path) => {
+                let name = path_name(path);
+                let Some(var) = self.variable(name) else {
+                    self.err(expr.span, format!("cannot find variable `{name}` in this scope"));
+                    return self.push(U256::ZERO);
+                };
+                let depth = self.height - var.slot;
+                if depth > MAX_DEPTH {
+                    self.stack_too_deep(expr.span, var.name, depth);
+                    return self.push(U256::ZERO);
+                }
+                self.op(Opcode::dup(depth));
+            }
+            yul::ExprKind::Call(call) => {
+                self.call(call, expr.span, Some(1));
+            }
+            yul::ExprKind::Lit(lit) => {
+                let value = self.lit(lit).unwrap_or_default();
+                self.push(value);
+            }
+        }
+    }
+
+    /// Lowers a function call, and returns the number of values it pushed.
+    ///
+    /// If `expected` is set, the call must return exactly that many values.
+    fn call(&mut self, call: &yul::ExprCall<'_>, span: Span, expected: Option<usize>) -> usize {
+        let name = call.name.name;
+        let returns = if let Some(function) = self.function(name) {
+            self.check_arity(call, span, function.parameters);
+            let height = self.height;
+            let ret = self.asm.new_tag();
+            self.push_tag(ret);
+            for arg in call.arguments.iter().rev() {
+                self.expr(arg);
+            }
+            self.jump(function.tag);
+            self.place_tag(ret);
+            self.height = height + function.returns;
+            function.returns
+        } else if let Some(returns) = self.builtin(call, span) {
+            returns
+        } else {
+            self.err(call.name.span, format!("cannot find function `{name}` in this scope"));
+            0
+        };
+        match expected {
+            Some(expected) if expected != returns => {
+                let s = if returns == 1 { "" } else { "s" };
+                let msg =
+                    format!("function `{name}` returns {returns} value{s}, expected {expected}");
+                self.err(span, msg);
+                // Keep the stack height consistent.
+                if returns > expected {
+                    self.pop(returns - expected);
+                } else {
+                    for _ in returns..expected {
+                        self.push(U256::ZERO);
+                    }
+                }
+                expected
+            }
+            _ => returns,
+        }
+    }
+
+    /// Lowers a call to a builtin function. Returns `None` if the function is not a builtin.
+    fn builtin(&mut self, call: &yul::ExprCall<'_>, span: Span) -> Option<usize> {
+        let name = call.name.name;
+        let args = &call.arguments[..];
+        match name.as_str() {
+            "datasize" | "dataoffset" => {
+                self.check_arity(call, span, 1);
+                let (value, name_span) = self.string_arg(args.first(), span);
+                let reference = self.references.get(&value).copied();
+                let item = match (reference, name.as_str()) {
+                    // The object itself starts at the beginning of the bytecode.
+                    (None, "datasize") if value == self.object => AssemblyItem::PushProgramSize,
+                    (None, _) if value == self.object => AssemblyItem::Push(U256::ZERO),
+                    (Some(DataRef::Sub(id)), "datasize") => AssemblyItem::PushSubSize(id),
+                    (Some(DataRef::Sub(id)), _) => AssemblyItem::PushSubOffset(id),
+                    (Some(DataRef::Data(_, len)), "datasize") => {
+                        AssemblyItem::Push(U256::from(len))
+                    }
+                    (Some(DataRef::Data(id, _)), _) => AssemblyItem::PushDataOffset(id),
+                    (None, _) => {
+                        self.err(name_span, format!("unknown object or data `{value}`"));
+                        AssemblyItem::Push(U256::ZERO)
+                    }
+                };
+                self.push_item(item);
+                return Some(1);
+            }
+            "datacopy" => {
+                self.check_arity(call, span, 3);
+                self.args(args);
+                self.op(Opcode::CODECOPY);
+                return Some(0);
+            }
+            "loadimmutable" => {
+                self.check_arity(call, span, 1);
+                let (value, _) = self.string_arg(args.first(), span);
+                self.push_item(AssemblyItem::PushImmutable(value));
+                return Some(1);
+            }
+            "setimmutable" => {
+                self.check_arity(call, span, 3);
+                let [offset, name_arg, value] = args else { return Some(0) };
+                let (name_value, _) = self.string_arg(Some(name_arg), span);
+                self.expr(value);
+                self.expr(offset);
+                self.height -= 2;
+                self.asm.append(AssemblyItem::AssignImmutable(name_value));
+                return Some(0);
+            }
+            "memoryguard" => {
+                self.check_arity(call, span, 1);
+                let value = match args.first().map(|arg| &arg.kind) {
+                    Some(yul::ExprKind::Lit(lit)) => self.lit(lit).unwrap_or_default(),
+                    _ => {
+                        self.err(span, "the argument of `memoryguard` must be a literal");
+                        U256::ZERO
+                    }
+                };
+                self.push(value);
+                return Some(1);
+            }
+            "linkersymbol" | "verbatim" => {
+                self.err(span, format!("the `{name}` builtin is not supported yet"));
+                return Some(0);
+            }
+            _ => {}
+        }
+
+        let opcode = builtin_opcode(name.as_str())?;
+        if !opcode.is_available(self.asm.evm_version()) {
+            let msg = format!(
+                "the `{name}` builtin is not available in EVM version `{}`",
+                self.asm.evm_version()
+            );
+            self.err(call.name.span, msg);
+        }
+        self.check_arity(call, span, opcode.inputs());
+        self.args(args);
+        // Keep the stack height consistent if the arity was wrong.
+        self.height = self.height.max(opcode.inputs());
+        self.op(opcode);
+        Some(opcode.outputs())
+    }
+
+    /// Pushes the arguments of a call, in reverse order, so that the first one is on top.
+    fn args(&mut self, args: &[yul::Expr<'_>]) {
+        for arg in args.iter().rev() {
+            self.expr(arg);
+        }
+    }
+
+    fn check_arity(&mut self, call: &yul::ExprCall<'_>, span: Span, expected: usize) {
+        let found = call.arguments.len();
+        if found != expected {
+            let s = if expected == 1 { "" } else { "s" };
+            let msg = format!(
+                "function `{}` takes {expected} argument{s}, but {found} were supplied",
+                call.name
+            );
+            self.err(span, msg);
+        }
+    }
+
+    fn string_arg(&mut self, arg: Option<&yul::Expr<'_>>, span: Span) -> (Symbol, Span) {
+        if let Some(yul::Expr { kind: yul::ExprKind::Lit(lit), span }) = arg {
+            if let LitKind::Str(StrKind::Str, bytes) = &lit.kind {
+                if let Ok(s) = std::str::from_utf8(bytes) {
+                    return (Symbol::intern(s), *span);
+                }
+            }
+        }
+        let span = arg.map_or(span, |arg| arg.span);
+        self.err(span, "expected a string literal");
+        (Symbol::DUMMY, span)
+    }
+
+    /// Returns the value of a literal.
+    fn lit(&mut self, lit: &solar_ast::ast::Lit) -> Option<U256> {
+        let value = match &lit.kind {
+            LitKind::Number(n) => {
+                let (sign, bytes) = n.to_bytes_be();
+                if sign == num_bigint::Sign::Minus {
+                    None
+                } else {
+                    U256::try_from_be_slice(&bytes)
+                }
+            }
+            LitKind::Str(_, bytes) if bytes.len() <= 32 => {
+                let mut word = [0u8; 32];
+                word[..bytes.len()].copy_from_slice(bytes);
+                Some(U256::from_be_bytes(word))
+            }
+            LitKind::Bool(b) => Some(U256::from(*b as u8)),
+            LitKind::Err(_) => return None,
+            _ => None,
+        };
+        if value.is_none() {
+            self.err(lit.span, "literal does not fit in 32 bytes");
+        }
+        value
+    }
+}
+
+/// Returns the opcode of an EVM builtin function.
+fn builtin_opcode(name: &str) -> Option<Opcode> {
+    let opcode = match name {
+        "difficulty" => Opcode::PREVRANDAO,
+        "keccak256" => Opcode::KECCAK256,
+        _ => *Opcode::ALL.iter().find(|opcode| opcode.name().eq_ignore_ascii_case(name))?,
+    };
+    let not_builtin = opcode.is_push()
+        || (Opcode::DUP1..=Opcode::SWAP16).contains(&opcode)
+        || matches!(opcode, Opcode::JUMP | Opcode::JUMPI | Opcode::JUMPDEST | Opcode::PC);
+    (!not_builtin).then_some(opcode)
+}
+
+/// Returns the index of the last statement of the block that uses each variable, either by reading
+/// or by assigning it.
+///
+/// Functions cannot access the variables of the enclosing blocks, so their bodies are skipped.
+fn last_uses(block: &[yul::Stmt<'_>]) -> FxHashMap<Symbol, usize> {
+    let mut last_uses = FxHashMap::default();
+    for (i, stmt) in block.iter().enumerate() {
+        stmt_uses(stmt, &mut |name| {
+            last_uses.insert(name, i);
+        });
+    }
+    last_uses
+}
+
+fn stmt_uses(stmt: &yul::Stmt<'_>, f: &mut impl FnMut(Symbol)) {
+    let block_uses = |block: &[yul::Stmt<'_>], f: &mut _| {
+        for stmt in block {
+            stmt_uses(stmt, f);
+        }
+    };
+    match &stmt.kind {
+        yul::StmtKind::Block(block) => block_uses(block, f),
+        yul::StmtKind::AssignSingle(path, expr) => {
+            f(path_name(path));
+            expr_uses(expr, f);
+        }
+        yul::StmtKind::AssignMulti(paths, call) => {
+            for path in paths.iter() {
+                f(path_name(path));
+            }
+            call.arguments.iter().for_each(|arg| expr_uses(arg, f));
+        }
+        yul::StmtKind::Expr(call) => call.arguments.iter().for_each(|arg| expr_uses(arg, f)),
+        yul::StmtKind::If(cond, body) => {
+            expr_uses(cond, f);
+            block_uses(body, f);
+        }
+        yul::StmtKind::For { init, cond, step, body } => {
+            block_uses(init, f);
+            expr_uses(cond, f);
+            block_uses(step, f);
+            block_uses(body, f);
+        }
+        yul::StmtKind::Switch(switch) => {
+            expr_uses(&switch.selector, f);
+            for case in switch.branches.iter() {
+                block_uses(case.body, f);
+            }
+            if let Some(body) = &switch.default_case {
+                block_uses(body, f);
+            }
+        }
+        yul::StmtKind::VarDecl(_, value) => {
+            if let Some(value) = value {
+                expr_uses(value, f);
+            }
+        }
+        yul::StmtKind::Leave
+        | yul::StmtKind::Break
+        | yul::StmtKind::Continue
+        | yul::StmtKind::FunctionDef(_) => {}
+    }
+}
+
+fn expr_uses(expr: &yul::Expr<'_>, f: &mut impl FnMut(Symbol)) {
+    match &expr.kind {
+        yul::ExprKind::Path(path) => f(path_name(path)),
+        yul::ExprKind::Call(call) => call.arguments.iter().for_each(|arg| expr_uses(arg, f)),
+        yul::ExprKind::Lit(_) => {}
+    }
+}
+
+/// Returns the name of a Yul path. Paths with multiple segments are joined with `.`.
+fn path_name(path: &solar_ast::ast::AstPath<'_>) -> Symbol {
+    match path.segments() {
+        [ident] => ident.name,
+        segments => {
+            let names = segments.iter().map(|ident| ident.as_str()).collect::<Vec<_>>();
+            Symbol::intern(&names.join("."))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+    use solar_ast::ast::Arena;
+    use solar_interface::{source_map::FileName, ColorChoice, Session};
+    use solar_parse::Parser;
+
+    /// Lowers and assembles the given Yul object, returning the bytecode or the emitted
+    /// diagnostics.
+    fn compile(evm_version: EvmVersion, src: &str) -> Result<String, String> {
+        let sess = Session::builder().with_buffer_emitter(ColorChoice::Never).build();
+        sess.enter(|| {
+            let arena = Arena::new();
+            let mut parser = Parser::from_source_code(
+                &sess,
+                &arena,
+                FileName::Custom("test".into()),
+                src.into(),
+            )
+            .unwrap();
+            let object = parser.parse_yul_file_object().map_err(|e| e.emit()).unwrap();
+            let code = lower_object(&sess.dcx, evm_version, &object)
+                .map(|asm| hex::encode(asm.assemble().unwrap().code));
+            match sess.emitted_diagnostics().unwrap() {
+                Ok(()) => Ok(code.unwrap()),
+                Err(diags) => Err(diags.to_string()),
+            }
+        })
+    }
+
+    #[test]
+    fn builtins() {
+        let src = r#"object "a" { code { sstore(0, add(1, 2)) } }"#;
+        assert_eq!(compile(EvmVersion::Shanghai, src).unwrap(), "60026001015f5500");
+        assert_eq!(compile(EvmVersion::Paris, src).unwrap(), "600260010160005500");
+
+        let src = r#"object "a" { code { tstore(0, 1) } }"#;
+        let err = compile(EvmVersion::Shanghai, src).unwrap_err();
+        assert!(
+            err.contains("the `tstore` builtin is not available in EVM version `shanghai`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn functions() {
+        let src = r#"
+            object "a" {
+                code {
+                    mstore(0, f(1, 2))
+                    return(0, 32)
+                    function f(a, b) -> r { r := sub(b, a) }
+                }
+            }
+        "#;
+        let expected = "6009600260016010565b5f5260205ff35b5f81830390505b9291505056";
+        assert_eq!(compile(EvmVersion::Cancun, src).unwrap(), expected);
+    }
+
+    #[test]
+    fn sub_objects() {
+        let src = r#"
+            object "a" {
+                code {
+                    datacopy(0, dataoffset("b"), datasize("b"))
+                    return(0, datasize("b"))
+                }
+                object "b" { code { stop() } }
+            }
+        "#;
+        assert_eq!(compile(EvmVersion::Cancun, src).unwrap(), "6001600a5f3960015ff300");
+    }
+
+    #[test]
+    fn own_object() {
+        // Copies the constructor arguments that are appended to the creation code.
+        let src = r#"
+            object "a" {
+                code {
+                    let size := datasize("a")
+                    codecopy(dataoffset("a"), size, sub(codesize(), size))
+                    sstore(0, mload(0))
+                    datacopy(0, dataoffset("b"), datasize("b"))
+                    return(0, datasize("b"))
+                }
+                object "b" { code { stop() } }
+            }
+        "#;
+        assert_eq!(
+            compile(EvmVersion::Cancun, src).unwrap(),
+            "6018803803815f39505f515f55600160175f3960015ff300"
+        );
+    }
+
+    #[test]
+    fn slot_reuse() {
+        // Each variable is only used by the next one, so at most two of them are live at once.
+        let src = r#"
+            object "a" {
+                code {
+                    function f(x0) -> r {
+                        let x1 := add(x0, 1)
+                        let x2 := add(x1, 2)
+                        let x3 := add(x2, 3)
+                        let x4 := add(x3, 4)
+                        let x5 := add(x4, 5)
+                        let x6 := add(x5, 6)
+                        let x7 := add(x6, 7)
+                        let x8 := add(x7, 8)
+                        let x9 := add(x8, 9)
+                        let x10 := add(x9, 10)
+                        let x11 := add(x10, 11)
+                        let x12 := add(x11, 12)
+                        let x13 := add(x12, 13)
+                        let x14 := add(x13, 14)
+                        let x15 := add(x14, 15)
+                        let x16 := add(x15, 16)
+                        let x17 := add(x16, 17)
+                        let x18 := add(x17, 18)
+                        let x19 := add(x18, 19)
+                        let x20 := add(x19, 20)
+                        r := x20
+                    }
+                    sstore(0, f(0))
+                }
+            }
+        "#;
+        compile(EvmVersion::Cancun, src).unwrap();
+    }
+
+    #[test]
+    fn stack_too_deep() {
+        let src = r#"
+            object "a" {
+                code {
+                    function f(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q) -> r {
+                        r := add(a, q)
+                    }
+                }
+            }
+        "#;
+        let err = compile(EvmVersion::Cancun, src).unwrap_err();
+        assert!(err.contains("stack too deep: variable `q` is 2 slots too deep"), "{err}");
+    }
+}
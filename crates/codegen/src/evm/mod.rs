@@ -0,0 +1,10 @@
+//! EVM bytecode generation.
+
+mod assembly;
+pub use assembly::{Assembly, AssemblyError, AssemblyItem, Bytecode, DataId, SubId, Tag};
+
+mod lower;
+pub use lower::lower_object;
+
+mod opcode;
+pub use opcode::Opcode;
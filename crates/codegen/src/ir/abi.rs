@@ -0,0 +1,322 @@
+//! ABI encoding and decoding.
+//!
+//! Only value types and `bytes` and `string` are supported for now.
+
+use super::{
+    helpers::{is_byte_array, sym, type_id, type_ids, Repr},
+    ObjectCx,
+};
+use solar_ast::ast::yul;
+use solar_interface::{Result, Span, Symbol};
+use solar_sema::ty::{Ty, TyKind};
+
+/// The kind of an ABI-encodable type.
+#[derive(Clone, Copy)]
+enum AbiKind {
+    Value(Repr),
+    ByteArray,
+}
+
+impl<'ast, 'gcx> ObjectCx<'ast, 'gcx> {
+    fn abi_kind(&self, span: Span, ty: Ty<'gcx>) -> Result<AbiKind> {
+        if is_byte_array(ty) {
+            return Ok(AbiKind::ByteArray);
+        }
+        match Repr::of(ty) {
+            Some(repr) => Ok(AbiKind::Value(repr)),
+            None => {
+                Err(self.unsupported(span, &format!("ABI coding of `{}`", ty.display(self.gcx))))
+            }
+        }
+    }
+
+    /// `abi_encode_tuple_<tys>__to_<tys>__fromStack(headStart, value0, ...) -> tail`: encodes
+    /// the values at `headStart`, returning the end of the encoded data.
+    ///
+    /// Byte arrays must be in memory.
+    pub(crate) fn abi_encode(&mut self, span: Span, tys: &[Ty<'gcx>]) -> Result<Symbol> {
+        let kinds = tys.iter().map(|&ty| self.abi_kind(span, ty)).collect::<Result<Vec<_>>>()?;
+        let ids = type_ids(self.gcx, tys, "_");
+        let name = format!("abi_encode_tuple_{ids}__to_{ids}__fromStack");
+        Ok(self.helper(name, |cx, name| {
+            let b = cx.b;
+            let values = (0..tys.len()).map(|i| sym(&format!("value{i}"))).collect::<Vec<_>>();
+            let mut body = vec![
+                b.assign1("tail", b.call("add", [b.path("headStart"), b.num(32 * tys.len())]))
+            ];
+            for (i, (kind, &value)) in kinds.iter().zip(&values).enumerate() {
+                let head = b.call("add", [b.path("headStart"), b.num(32 * i)]);
+                match *kind {
+                    AbiKind::Value(repr) => {
+                        let value = cx.cleanup(repr, b.path(value));
+                        body.push(b.call_stmt("mstore", [head, value]));
+                    }
+                    AbiKind::ByteArray => {
+                        let encode = cx.abi_encode_byte_array();
+                        body.push(b.call_stmt(
+                            "mstore",
+                            [head, b.call("sub", [b.path("tail"), b.path("headStart")])],
+                        ));
+                        body.push(
+                            b.assign1("tail", b.call(encode, [b.path(value), b.path("tail")])),
+                        );
+                    }
+                }
+            }
+            let params = std::iter::once(sym("headStart")).chain(values.iter().copied());
+            b.function(name, params, [sym("tail")], body)
+        }))
+    }
+
+    /// `abi_encode_t_bytes_memory_ptr(value, pos) -> end`: encodes the length and the padded
+    /// contents of a byte array.
+    fn abi_encode_byte_array(&mut self) -> Symbol {
+        self.helper("abi_encode_t_bytes_memory_ptr", |cx, name| {
+            let b = cx.b;
+            let copy = cx.copy_memory_to_memory();
+            let round_up = cx.round_up_to_mul_of_32();
+            b.function(
+                name,
+                [sym("value"), sym("pos")],
+                [sym("end")],
+                vec![
+                    b.let1("length", b.call("mload", [b.path("value")])),
+                    b.call_stmt("mstore", [b.path("pos"), b.path("length")]),
+                    b.assign1("pos", b.call("add", [b.path("pos"), b.hex(0x20)])),
+                    b.call_stmt(
+                        copy,
+                        [
+                            b.call("add", [b.path("value"), b.hex(0x20)]),
+                            b.path("pos"),
+                            b.path("length"),
+                        ],
+                    ),
+                    b.assign1(
+                        "end",
+                        b.call("add", [b.path("pos"), b.call(round_up, [b.path("length")])]),
+                    ),
+                ],
+            )
+        })
+    }
+
+    /// `abi_encode_tuple_packed_<tys>__nonPadded_inplace_fromStack(pos, value0, ...) -> end`:
+    /// encodes the values without padding.
+    pub(crate) fn abi_encode_packed(&mut self, span: Span, tys: &[Ty<'gcx>]) -> Result<Symbol> {
+        let kinds = tys.iter().map(|&ty| self.abi_kind(span, ty)).collect::<Result<Vec<_>>>()?;
+        let ids = type_ids(self.gcx, tys, "_");
+        let name = format!("abi_encode_tuple_packed_{ids}__nonPadded_inplace_fromStack");
+        Ok(self.helper(name, |cx, name| {
+            let b = cx.b;
+            let values = (0..tys.len()).map(|i| sym(&format!("value{i}"))).collect::<Vec<_>>();
+            let mut body = vec![];
+            for (kind, &value) in kinds.iter().zip(&values) {
+                match *kind {
+                    AbiKind::Value(repr) => {
+                        let mut value = cx.cleanup(repr, b.path(value));
+                        let bytes = repr.bytes();
+                        if bytes < 32 && !matches!(repr, Repr::FixedBytes(_)) {
+                            value = b.call("shl", [b.num(256 - 8 * bytes as u32), value]);
+                        }
+                        body.push(b.call_stmt("mstore", [b.path("pos"), value]));
+                        body.push(b.assign1("pos", b.call("add", [b.path("pos"), b.num(bytes)])));
+                    }
+                    AbiKind::ByteArray => {
+                        let copy = cx.copy_memory_to_memory();
+                        body.push(
+                            b.let1(format!("{value}_length"), b.call("mload", [b.path(value)])),
+                        );
+                        body.push(b.call_stmt(
+                            copy,
+                            [
+                                b.call("add", [b.path(value), b.hex(0x20)]),
+                                b.path("pos"),
+                                b.path(format!("{value}_length")),
+                            ],
+                        ));
+                        body.push(b.assign1(
+                            "pos",
+                            b.call("add", [b.path("pos"), b.path(format!("{value}_length"))]),
+                        ));
+                    }
+                }
+            }
+            body.push(b.assign1("end", b.path("pos")));
+            let params = std::iter::once(sym("pos")).chain(values.iter().copied());
+            b.function(name, params, [sym("end")], body)
+        }))
+    }
+
+    /// `abi_decode_tuple_<tys>[_fromMemory](headStart, dataEnd) -> value0, ...`: decodes and
+    /// validates the values from calldata or memory.
+    ///
+    /// Byte arrays are decoded into memory.
+    pub(crate) fn abi_decode(
+        &mut self,
+        span: Span,
+        tys: &[Ty<'gcx>],
+        from_memory: bool,
+    ) -> Result<Symbol> {
+        let kinds = tys.iter().map(|&ty| self.abi_kind(span, ty)).collect::<Result<Vec<_>>>()?;
+        let suffix = if from_memory { "_fromMemory" } else { "" };
+        let name = format!("abi_decode_tuple_{}{suffix}", type_ids(self.gcx, tys, ""));
+        let load = if from_memory { "mload" } else { "calldataload" };
+        Ok(self.helper(name, |cx, name| {
+            let b = cx.b;
+            let values = (0..tys.len()).map(|i| sym(&format!("value{i}"))).collect::<Vec<_>>();
+            let revert = cx.revert_empty();
+            let mut body = vec![b.if_(
+                b.call(
+                    "slt",
+                    [
+                        b.call("sub", [b.path("dataEnd"), b.path("headStart")]),
+                        b.num(32 * tys.len()),
+                    ],
+                ),
+                vec![revert],
+            )];
+            for (i, (kind, &value)) in kinds.iter().zip(&values).enumerate() {
+                let head = b.call("add", [b.path("headStart"), b.num(32 * i)]);
+                let stmts = match *kind {
+                    AbiKind::Value(repr) => {
+                        let validate = cx.validator(repr, tys[i]);
+                        vec![
+                            b.assign1(value, b.call(load, [head])),
+                            b.call_stmt(validate, [b.path(value)]),
+                        ]
+                    }
+                    AbiKind::ByteArray => {
+                        let decode = cx.abi_decode_byte_array(from_memory);
+                        let revert = cx.revert_empty();
+                        vec![
+                            b.let1("offset", b.call(load, [head])),
+                            b.if_(b.call("gt", [b.path("offset"), b.hex(u64::MAX)]), vec![revert]),
+                            b.assign1(
+                                value,
+                                b.call(
+                                    decode,
+                                    [
+                                        b.call("add", [b.path("headStart"), b.path("offset")]),
+                                        b.path("dataEnd"),
+                                    ],
+                                ),
+                            ),
+                        ]
+                    }
+                };
+                body.push(b.block_stmt(stmts));
+            }
+            b.function(name, [sym("headStart"), sym("dataEnd")], values, body)
+        }))
+    }
+
+    /// `validator_revert_<ty>(value)`: reverts if the value is not valid for its type.
+    fn validator(&mut self, repr: Repr, ty: Ty<'gcx>) -> Symbol {
+        let name = format!("validator_revert_{}", type_id(self.gcx, ty));
+        self.helper(name, |cx, name| {
+            let b = cx.b;
+            let valid = match ty.kind {
+                TyKind::Enum(id) => {
+                    let n = cx.gcx.hir.enumm(id).variants.len();
+                    b.call("lt", [b.path("value"), b.num(n)])
+                }
+                _ => b.call("eq", [b.path("value"), cx.cleanup(repr, b.path("value"))]),
+            };
+            let revert = cx.revert_empty();
+            b.function(
+                name,
+                [sym("value")],
+                [],
+                vec![b.if_(b.call("iszero", [valid]), vec![revert])],
+            )
+        })
+    }
+
+    /// `abi_decode_t_bytes_memory_ptr[_fromMemory](offset, end) -> array`: decodes a byte array
+    /// into memory.
+    fn abi_decode_byte_array(&mut self, from_memory: bool) -> Symbol {
+        let suffix = if from_memory { "_fromMemory" } else { "" };
+        let name = format!("abi_decode_t_bytes_memory_ptr{suffix}");
+        let load = if from_memory { "mload" } else { "calldataload" };
+        self.helper(name, |cx, name| {
+            let b = cx.b;
+            let allocate = cx.allocate_byte_array();
+            let copy = if from_memory {
+                b.call_stmt(
+                    cx.copy_memory_to_memory(),
+                    [b.path("src"), b.path("dst"), b.path("length")],
+                )
+            } else {
+                b.call_stmt("calldatacopy", [b.path("dst"), b.path("src"), b.path("length")])
+            };
+            let revert = cx.revert_empty();
+            b.function(
+                name,
+                [sym("offset"), sym("end")],
+                [sym("array")],
+                vec![
+                    b.if_(
+                        b.call(
+                            "iszero",
+                            [b.call(
+                                "slt",
+                                [b.call("add", [b.path("offset"), b.hex(0x1f)]), b.path("end")],
+                            )],
+                        ),
+                        vec![cx.revert_empty()],
+                    ),
+                    b.let1("length", b.call(load, [b.path("offset")])),
+                    b.let1("src", b.call("add", [b.path("offset"), b.hex(0x20)])),
+                    b.if_(
+                        b.call(
+                            "gt",
+                            [b.call("add", [b.path("src"), b.path("length")]), b.path("end")],
+                        ),
+                        vec![revert],
+                    ),
+                    b.assign1("array", b.call(allocate, [b.path("length")])),
+                    b.let1("dst", b.call("add", [b.path("array"), b.hex(0x20)])),
+                    copy,
+                    b.call_stmt(
+                        "mstore",
+                        [b.call("add", [b.path("dst"), b.path("length")]), b.num(0)],
+                    ),
+                ],
+            )
+        })
+    }
+
+    /// Returns statements that ABI encode the values into a new byte array in memory, optionally
+    /// prefixed with a selector, and the name of the variable that holds the byte array.
+    pub(crate) fn encode_to_memory(
+        &mut self,
+        span: Span,
+        selector: Option<yul::Expr<'ast>>,
+        tys: &[Ty<'gcx>],
+        values: Vec<yul::Expr<'ast>>,
+        packed: bool,
+    ) -> Result<(Vec<yul::Stmt<'ast>>, Symbol)> {
+        let b = self.b;
+        let encode =
+            if packed { self.abi_encode_packed(span, tys)? } else { self.abi_encode(span, tys)? };
+        let allocate = self.allocate_unbounded();
+        let finalize = self.finalize_allocation();
+        let mem = self.fresh("memPtr");
+        let data = self.fresh("dataStart");
+        let end = self.fresh("dataEnd");
+        let mut stmts = vec![
+            b.let1(mem, b.call(allocate, [])),
+            b.let1(data, b.call("add", [b.path(mem), b.num(32)])),
+        ];
+        let mut start = b.path(data);
+        if let Some(selector) = selector {
+            stmts.push(b.call_stmt("mstore", [b.path(data), selector]));
+            start = b.call("add", [b.path(data), b.num(4)]);
+        }
+        stmts.push(b.let1(end, b.call(encode, std::iter::once(start).chain(values))));
+        stmts
+            .push(b.call_stmt("mstore", [b.path(mem), b.call("sub", [b.path(end), b.path(data)])]));
+        stmts.push(b.call_stmt(finalize, [b.path(mem), b.call("sub", [b.path(end), b.path(mem)])]));
+        Ok((stmts, mem))
+    }
+}
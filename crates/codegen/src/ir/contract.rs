@@ -0,0 +1,367 @@
+//! Lowering of contracts into creation and runtime objects.
+
+use super::{
+    helpers::sym, object_name, stmt::FnCx, storage::Offset, variable_name, ObjectCx, ObjectKind,
+    Value,
+};
+use crate::builder::YulBuilder;
+use alloy_primitives::U256;
+use solar_ast::ast::{self, yul, ContractKind, DataLocation, StateMutability, VarMut, Visibility};
+use solar_interface::{Result, Symbol};
+use solar_sema::{
+    hir::{self, ItemId},
+    ty::{Gcx, Ty},
+};
+
+/// Lowers a deployable contract into a Yul object.
+pub(crate) fn lower_contract<'ast>(
+    gcx: Gcx<'_>,
+    arena: &'ast ast::Arena,
+    id: hir::ContractId,
+) -> Result<yul::Object<'ast>> {
+    let b = YulBuilder::new(arena);
+    let c = gcx.hir.contract(id);
+    let name = object_name(gcx, id);
+    let runtime_name = format!("{name}_deployed");
+    let source = gcx.hir.source(c.source);
+    let docs = [format!("@use-src {}:\"{}\"", c.source.get(), source.file.name.display())];
+
+    let creation = creation_code(gcx, b, id, &name, &runtime_name);
+    let runtime = runtime_code(gcx, b, id);
+    let (creation, runtime) = (creation?, runtime?);
+    let runtime = b.object(&runtime_name, &docs, runtime, vec![]);
+    Ok(b.object(&name, &docs, creation, vec![runtime]))
+}
+
+/// Returns the code of the creation object, which runs the constructors and returns the runtime
+/// object.
+fn creation_code<'ast>(
+    gcx: Gcx<'_>,
+    b: YulBuilder<'ast>,
+    id: hir::ContractId,
+    name: &str,
+    runtime_name: &str,
+) -> Result<Vec<yul::Stmt<'ast>>> {
+    let mut cx = ObjectCx::new(gcx, b, id, ObjectKind::Creation);
+    let c = gcx.hir.contract(id);
+    let mut code =
+        vec![b.call_stmt("mstore", [b.num(64), b.call("memoryguard", [b.hex(cx.memory_start())])])];
+    let ctor = c.ctor.map(|ctor| gcx.hir.function(ctor));
+    if !ctor.is_some_and(|ctor| ctor.state_mutability == StateMutability::Payable) {
+        code.push(b.if_(b.call("callvalue", []), vec![cx.revert_empty()]));
+    }
+    let params = ctor.map_or(&[][..], |ctor| ctor.parameters);
+    let param_names = params.iter().map(|&p| variable_name(gcx, p)).collect::<Vec<_>>();
+    if !params.is_empty() {
+        let copy = copy_arguments_for_constructor(&mut cx, c.ctor.unwrap(), name);
+        if let Some(copy) = cx.record_err(copy) {
+            code.push(b.let_(param_names.iter().copied(), Some(b.call(copy, []))));
+        }
+    }
+    let constructor = constructor(&mut cx, 0);
+    if let Some(constructor) = cx.record_err(constructor) {
+        code.push(b.call_stmt(constructor, param_names.iter().map(|&p| b.path(p))));
+    }
+
+    let allocate = cx.allocate_unbounded();
+    code.push(b.let1("_1", b.call(allocate, [])));
+    code.push(b.call_stmt(
+        "codecopy",
+        [
+            b.path("_1"),
+            b.call("dataoffset", [b.str(runtime_name)]),
+            b.call("datasize", [b.str(runtime_name)]),
+        ],
+    ));
+    let immutables = c
+        .linearized_bases
+        .iter()
+        .rev()
+        .flat_map(|&base| gcx.hir.contract(base).variables())
+        .filter(|&var| gcx.hir.variable(var).mutability == Some(VarMut::Immutable));
+    for var in immutables {
+        code.push(b.call_stmt(
+            "setimmutable",
+            [
+                b.path("_1"),
+                b.str(&var.get().to_string()),
+                b.call("mload", [b.num(cx.immutable_address(var))]),
+            ],
+        ));
+    }
+    code.push(b.call_stmt("return", [b.path("_1"), b.call("datasize", [b.str(runtime_name)])]));
+    code.extend(cx.finish()?);
+    Ok(code)
+}
+
+/// `copy_arguments_for_constructor_<id>_object_<name>() -> ret_param_0, ...`: decodes the
+/// constructor arguments, which are appended to the creation code.
+fn copy_arguments_for_constructor(
+    cx: &mut ObjectCx<'_, '_>,
+    ctor: hir::FunctionId,
+    object: &str,
+) -> Result<Symbol> {
+    let gcx = cx.gcx;
+    let f = gcx.hir.function(ctor);
+    let tys = memory_tys(gcx, gcx.item_parameter_types(ctor.into()));
+    let name = format!("copy_arguments_for_constructor_{}_object_{object}", ctor.get());
+    cx.try_helper(name, |cx, name| {
+        let b = cx.b;
+        let decode = cx.abi_decode(f.span, &tys, true)?;
+        let allocate = cx.allocate_memory();
+        let rets = (0..tys.len()).map(|i| sym(&format!("ret_param_{i}"))).collect::<Vec<_>>();
+        let body = vec![
+            b.let1("programSize", b.call("datasize", [b.str(object)])),
+            b.let1("argSize", b.call("sub", [b.call("codesize", []), b.path("programSize")])),
+            b.let1("memoryDataOffset", b.call(allocate, [b.path("argSize")])),
+            b.call_stmt(
+                "codecopy",
+                [b.path("memoryDataOffset"), b.path("programSize"), b.path("argSize")],
+            ),
+            b.assign(
+                rets.iter().copied(),
+                b.call(
+                    decode,
+                    [
+                        b.path("memoryDataOffset"),
+                        b.call("add", [b.path("memoryDataOffset"), b.path("argSize")]),
+                    ],
+                ),
+            ),
+        ];
+        Ok(b.function(name, [], rets, body))
+    })
+}
+
+/// Returns the contract that specifies the arguments of the constructor of the base at `index`
+/// in the linearization, and the arguments.
+fn base_args<'hir>(
+    gcx: Gcx<'hir>,
+    bases: &[hir::ContractId],
+    index: usize,
+) -> Option<(usize, &'hir hir::CallArgs<'hir>)> {
+    let base = bases[index];
+    bases[..index].iter().enumerate().find_map(|(i, &derived)| {
+        let c = gcx.hir.contract(derived);
+        let ctor_modifiers = c.ctor.map_or(&[][..], |ctor| gcx.hir.function(ctor).modifiers);
+        c.bases_args
+            .iter()
+            .chain(ctor_modifiers)
+            .find(|m| m.id == ItemId::Contract(base) && !m.args.is_empty())
+            .map(|m| (i, &m.args))
+    })
+}
+
+/// Returns the constructor parameters of a contract.
+fn ctor_params(gcx: Gcx<'_>, base: hir::ContractId) -> &[hir::VariableId] {
+    gcx.hir.contract(base).ctor.map_or(&[][..], |ctor| gcx.hir.function(ctor).parameters)
+}
+
+/// Returns the parameters of the constructor function of the base at `index`: its own
+/// constructor parameters, followed by the arguments of the more basic constructors that were
+/// evaluated by more derived contracts.
+fn constructor_params(gcx: Gcx<'_>, bases: &[hir::ContractId], index: usize) -> Vec<Symbol> {
+    let own = ctor_params(gcx, bases[index]).iter();
+    let pending = (index + 1..bases.len())
+        .filter(|&i| base_args(gcx, bases, i).is_some_and(|(by, _)| by < index))
+        .flat_map(|i| ctor_params(gcx, bases[i]));
+    own.chain(pending).map(|&p| variable_name(gcx, p)).collect()
+}
+
+/// `constructor_<name>_<id>(params...)`: evaluates the arguments of the base constructors, runs
+/// the constructor of the next base, then initializes the state variables and runs the
+/// constructor of the base at `index` in the linearization.
+fn constructor(cx: &mut ObjectCx<'_, '_>, index: usize) -> Result<Symbol> {
+    let gcx = cx.gcx;
+    let bases = gcx.hir.contract(cx.contract).linearized_bases;
+    let base = bases[index];
+    let c = gcx.hir.contract(base);
+    let name = format!("constructor_{}_{}", c.name, base.get());
+    cx.try_helper(name, |cx, name| {
+        let b = cx.b;
+        let params = constructor_params(gcx, bases, index);
+        let next = if index + 1 < bases.len() { Some(constructor(cx, index + 1)?) } else { None };
+        let mut fcx = FnCx::new(cx, c.ctor);
+
+        // Evaluate the arguments of the base constructors that this contract specifies.
+        for i in index + 1..bases.len() {
+            let Some((by, args)) = base_args(gcx, bases, i) else { continue };
+            if by != index {
+                continue;
+            }
+            let ctor = gcx.hir.contract(bases[i]).ctor.expect("arguments without constructor");
+            let f = gcx.hir.function(ctor);
+            let args = super::stmt::ordered_args(gcx, args, f.parameters);
+            for (arg, &param) in args.into_iter().zip(f.parameters) {
+                let ty = gcx.type_of_item(param.into());
+                let value = fcx.expr_to(arg, ty)?[0];
+                let value = fcx.cx.val(value);
+                fcx.push(b.let1(variable_name(gcx, param), value));
+            }
+        }
+
+        if let Some(next) = next {
+            let args = constructor_params(gcx, bases, index + 1);
+            fcx.push(b.call_stmt(next, args.into_iter().map(|arg| b.path(arg))));
+        }
+
+        // Initialize the state variables.
+        for var_id in c.variables() {
+            let var = gcx.hir.variable(var_id);
+            let Some(init) = var.initializer else { continue };
+            let ty = gcx.type_of_item(var_id.into());
+            match var.mutability {
+                Some(VarMut::Constant) => continue,
+                Some(VarMut::Immutable) => {
+                    let value = fcx.expr_to(init, ty)?[0];
+                    let addr = b.num(fcx.cx.immutable_address(var_id));
+                    let value = fcx.cx.val(value);
+                    fcx.push(b.call_stmt("mstore", [addr, value]));
+                }
+                None => {
+                    let loc = fcx.cx.storage_location(var_id).expect("state variable without slot");
+                    let value = fcx.expr_to(init, fcx.memory_ty(ty))?[0];
+                    let stmt = fcx.cx.write_storage(
+                        var.span,
+                        ty,
+                        Value::Lit(loc.slot),
+                        Offset::Static(loc.offset),
+                        loc.transient,
+                        value,
+                    )?;
+                    fcx.push(stmt);
+                }
+            }
+        }
+
+        if let Some(ctor) = c.ctor {
+            let f = fcx.cx.function(ctor);
+            let args = ctor_params(gcx, base).iter().map(|&p| b.path(variable_name(gcx, p)));
+            fcx.push(b.call_stmt(f, args));
+        }
+        let body = fcx.stmts;
+        Ok(b.function(name, params, [], body))
+    })
+}
+
+/// Returns the code of the runtime object, which dispatches calls to the external functions.
+fn runtime_code<'ast>(
+    gcx: Gcx<'_>,
+    b: YulBuilder<'ast>,
+    id: hir::ContractId,
+) -> Result<Vec<yul::Stmt<'ast>>> {
+    let mut cx = ObjectCx::new(gcx, b, id, ObjectKind::Runtime);
+    let c = gcx.hir.contract(id);
+    let mut code = vec![b.call_stmt("mstore", [b.num(64), b.call("memoryguard", [b.hex(0x80)])])];
+
+    let mut functions = gcx.interface_functions(id).all().to_vec();
+    functions.sort_by_key(|f| f.selector);
+    if c.kind == ContractKind::Library && !functions.is_empty() {
+        let span = gcx.hir.function(functions[0].id).span;
+        cx.record_err(Err::<(), _>(cx.unsupported(span, "external library functions")));
+        functions.clear();
+    }
+    if !functions.is_empty() {
+        let mut cases = Vec::with_capacity(functions.len());
+        for f in &functions {
+            let wrapper = external_function(&mut cx, f.id);
+            let Some(wrapper) = cx.record_err(wrapper) else { continue };
+            let selector = U256::from(u32::from_be_bytes(f.selector.0));
+            cases.push((b.hex(selector), vec![b.call_stmt(wrapper, [])]));
+        }
+        code.push(b.if_(
+            b.call("iszero", [b.call("lt", [b.call("calldatasize", []), b.num(4)])]),
+            vec![
+                b.let1("selector", b.call("shr", [b.num(224), b.call("calldataload", [b.num(0)])])),
+                b.switch(b.path("selector"), cases, Some(vec![])),
+            ],
+        ));
+    }
+    if let Some(receive) = c.receive {
+        let f = cx.function(receive);
+        code.push(b.if_(
+            b.call("iszero", [b.call("calldatasize", [])]),
+            vec![b.call_stmt(f, []), b.call_stmt("stop", [])],
+        ));
+    }
+    match c.fallback {
+        Some(fallback) => {
+            let f = gcx.hir.function(fallback);
+            if !f.parameters.is_empty() {
+                cx.record_err(Err::<(), _>(
+                    cx.unsupported(f.span, "fallback functions with parameters"),
+                ));
+            }
+            if f.state_mutability != StateMutability::Payable {
+                code.push(b.if_(b.call("callvalue", []), vec![cx.revert_empty()]));
+            }
+            let name = cx.function(fallback);
+            code.push(b.call_stmt(name, []));
+            code.push(b.call_stmt("stop", []));
+        }
+        None => code.push(cx.revert_empty()),
+    }
+    code.extend(cx.finish()?);
+    Ok(code)
+}
+
+/// `external_fun_<name>_<id>()`: decodes the arguments from calldata, calls the function, and
+/// returns its encoded results.
+fn external_function(cx: &mut ObjectCx<'_, '_>, id: hir::FunctionId) -> Result<Symbol> {
+    let gcx = cx.gcx;
+    let f = gcx.hir.function(id);
+    debug_assert!(f.visibility >= Visibility::Public);
+    let name = format!("external_{}", super::function_name(gcx, id));
+    cx.try_helper(name, |cx, name| {
+        let b = cx.b;
+        let param_tys = memory_tys(gcx, gcx.item_parameter_types(id.into()));
+        let ret_tys = f.returns.iter().map(|&ret| gcx.type_of_item(ret.into())).collect::<Vec<_>>();
+        let ret_tys = memory_tys(gcx, &ret_tys);
+        let decode = cx.abi_decode(f.span, &param_tys, false)?;
+        let encode = cx.abi_encode(f.span, &ret_tys)?;
+        let function = cx.function(id);
+        let allocate = cx.allocate_unbounded();
+
+        let mut body = Vec::new();
+        if f.state_mutability != StateMutability::Payable {
+            body.push(b.if_(b.call("callvalue", []), vec![cx.revert_empty()]));
+        }
+        let params = (0..param_tys.len()).map(|i| sym(&format!("param_{i}"))).collect::<Vec<_>>();
+        let rets = (0..ret_tys.len()).map(|i| sym(&format!("ret_{i}"))).collect::<Vec<_>>();
+        let decode = b.call(decode, [b.num(4), b.call("calldatasize", [])]);
+        if params.is_empty() {
+            body.push(b.expr_stmt(decode));
+        } else {
+            body.push(b.let_(params.iter().copied(), Some(decode)));
+        }
+        let call = b.call(function, params.iter().map(|&p| b.path(p)));
+        if rets.is_empty() {
+            body.push(b.expr_stmt(call));
+        } else {
+            body.push(b.let_(rets.iter().copied(), Some(call)));
+        }
+        body.push(b.let1("memPos", b.call(allocate, [])));
+        body.push(b.let1(
+            "memEnd",
+            b.call(
+                encode,
+                std::iter::once(b.path("memPos")).chain(rets.iter().map(|&r| b.path(r))),
+            ),
+        ));
+        body.push(b.call_stmt(
+            "return",
+            [b.path("memPos"), b.call("sub", [b.path("memEnd"), b.path("memPos")])],
+        ));
+        Ok(b.function(name, [], [], body))
+    })
+}
+
+/// Returns the types with byte arrays in calldata replaced by byte arrays in memory, which is
+/// where they are decoded to.
+fn memory_tys<'gcx>(gcx: Gcx<'gcx>, tys: &[Ty<'gcx>]) -> Vec<Ty<'gcx>> {
+    tys.iter()
+        .map(
+            |&ty| if ty.is_ref() { ty.peel_refs().with_loc(gcx, DataLocation::Memory) } else { ty },
+        )
+        .collect()
+}
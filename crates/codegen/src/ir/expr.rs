@@ -0,0 +1,1745 @@
+//! Lowering of expressions.
+
+use super::{
+    helpers::{
+        const_word, is_byte_array, is_literal, mobile_type, stack_size, sym, type_id, Repr, Values,
+    },
+    stmt::{ordered_args, FnCx},
+    storage::Offset,
+    variable_name, ObjectKind, Value,
+};
+use alloy_primitives::U256;
+use solar_ast::ast::{
+    yul, ContractKind, DataLocation, ElementaryType, StateMutability, VarMut, Visibility,
+};
+use solar_data_structures::smallvec::smallvec;
+use solar_interface::{Result, Span, Symbol};
+use solar_sema::{
+    builtins::Builtin,
+    eval::{ConstValue, ConstantEvaluator},
+    hir::{self, BinOpKind, CallArgs, ExprKind, ItemId, Res, UnOpKind},
+    ty::{Ty, TyKind},
+};
+
+/// A place that can be assigned to.
+pub(crate) enum LValue<'gcx> {
+    /// A local variable or parameter.
+    Var(Symbol),
+    /// A value in storage.
+    Storage { slot: Value, offset: Offset, ty: Ty<'gcx>, transient: bool },
+    /// An immutable state variable.
+    Immutable(hir::VariableId),
+    /// A byte of a byte array in memory, at the given address.
+    MemoryByte(Value),
+    /// The components of a tuple. Empty components are skipped.
+    Tuple(Vec<Option<Self>>),
+}
+
+impl<'ast, 'gcx> FnCx<'_, 'ast, 'gcx> {
+    /// Returns the type of a type-checked expression.
+    pub(crate) fn ty(&self, expr: &hir::Expr<'_>) -> Ty<'gcx> {
+        self.gcx.type_of_expr(expr.id).expect("expression was not type checked")
+    }
+
+    /// Lowers an expression, returning the values of its type. Literals are converted to their
+    /// mobile type.
+    pub(crate) fn expr(&mut self, expr: &hir::Expr<'_>) -> Result<Values> {
+        let ty = self.ty(expr);
+        if is_literal(ty) {
+            return self.expr_to(expr, mobile_type(self.gcx, ty));
+        }
+        self.expr_conv(expr, ty, false)
+    }
+
+    /// Lowers an expression, implicitly converting it to the given type.
+    pub(crate) fn expr_to(&mut self, expr: &hir::Expr<'_>, to: Ty<'gcx>) -> Result<Values> {
+        self.expr_conv(expr, to, false)
+    }
+
+    /// Lowers an expression that must produce exactly one value.
+    fn expr1(&mut self, expr: &hir::Expr<'_>) -> Result<Value> {
+        Ok(self.expr(expr)?[0])
+    }
+
+    /// Lowers an expression that must produce exactly one value, converting it to `to`.
+    fn expr1_to(&mut self, expr: &hir::Expr<'_>, to: Ty<'gcx>) -> Result<Value> {
+        Ok(self.expr_to(expr, to)?[0])
+    }
+
+    fn expr_conv(&mut self, expr: &hir::Expr<'_>, to: Ty<'gcx>, explicit: bool) -> Result<Values> {
+        let expr = expr.peel_parens();
+        if let (ExprKind::Tuple(components), TyKind::Tuple(tys)) = (&expr.kind, &to.kind) {
+            if components.len() == tys.len() && components.iter().all(Option::is_some) {
+                let mut values = Values::new();
+                for (component, &ty) in components.iter().zip(tys.iter()) {
+                    values.extend(self.expr_conv(component.unwrap(), ty, explicit)?);
+                }
+                return Ok(values);
+            }
+        }
+        if let Some(value) = self.try_const(expr) {
+            return self.const_value(expr.span, value, to, explicit);
+        }
+        let from = self.ty(expr);
+        let values = self.expr_inner(expr, from)?;
+        self.convert(expr.span, values, from, to)
+    }
+
+    /// Evaluates an expression at compile time if it is a literal or a constant.
+    fn try_const(&self, expr: &hir::Expr<'_>) -> Option<ConstValue> {
+        let is_const = is_literal(self.ty(expr))
+            || match expr.kind {
+                ExprKind::Lit(_) => true,
+                ExprKind::Binary(lhs, _, rhs) => {
+                    is_literal(self.ty(lhs)) && is_literal(self.ty(rhs))
+                }
+                _ => match self.gcx.res_of_expr(expr.id) {
+                    Some(Res::Item(ItemId::Variable(id))) => {
+                        self.gcx.hir.variable(id).mutability == Some(VarMut::Constant)
+                    }
+                    Some(Res::Builtin(Builtin::TypeMin | Builtin::TypeMax)) => true,
+                    _ => false,
+                },
+            };
+        if !is_const {
+            return None;
+        }
+        ConstantEvaluator::new(self.gcx).try_eval(expr).ok()
+    }
+
+    /// Returns the values of a constant converted to the given type.
+    fn const_value(
+        &mut self,
+        span: Span,
+        value: ConstValue,
+        to: Ty<'gcx>,
+        explicit: bool,
+    ) -> Result<Values> {
+        match value {
+            ConstValue::Tuple(values) => {
+                let TyKind::Tuple(tys) = to.kind else {
+                    return Err(self.cx.unsupported(span, "this constant"));
+                };
+                let mut result = Values::new();
+                for (value, &ty) in values.into_vec().into_iter().zip(tys) {
+                    result.extend(self.const_value(span, value, ty, explicit)?);
+                }
+                Ok(result)
+            }
+            ConstValue::String(bytes) if is_byte_array(to) => {
+                let copy = self.cx.copy_literal_to_memory(&bytes);
+                Ok(smallvec![self.define("expr", self.b.call(copy, []))])
+            }
+            value => {
+                let converted =
+                    value.clone().convert(to, explicit).or_else(|_| value.convert(to, true));
+                match converted.ok().as_ref().and_then(const_word) {
+                    Some(word) => Ok(smallvec![Value::Lit(word)]),
+                    None => Err(self.cx.unsupported(span, "this constant")),
+                }
+            }
+        }
+    }
+
+    fn expr_inner(&mut self, expr: &hir::Expr<'_>, ty: Ty<'gcx>) -> Result<Values> {
+        let b = self.b;
+        match expr.kind {
+            ExprKind::Lit(_) => Err(self.cx.unsupported(expr.span, "this literal")),
+            ExprKind::Ident(_) => self.ident(expr, ty),
+            ExprKind::Member(base, name) => self.member(expr, ty, base, name.name),
+            ExprKind::Index(base, _) => {
+                if let TyKind::Elementary(ElementaryType::FixedBytes(size)) = self.ty(base).kind {
+                    return self.fixed_bytes_index(expr, size.bytes());
+                }
+                let lvalue = self.lvalue(expr)?;
+                self.load(expr.span, &lvalue, ty)
+            }
+            ExprKind::Assign(lhs, op, rhs) => self.assign(expr, lhs, op.map(|op| op.kind), rhs),
+            ExprKind::Binary(lhs, op, rhs) => self.binary(expr, ty, lhs, op.kind, rhs),
+            ExprKind::Unary(op, operand) => self.unary(expr, ty, op.kind, operand),
+            ExprKind::Call(callee, ref args) => self.call(expr, callee, args),
+            ExprKind::Ternary(cond, then, else_) => {
+                let cond = self.expr1_to(cond, self.gcx.types.bool)?;
+                let vars = (0..stack_size(ty)).map(|_| self.cx.fresh("expr")).collect::<Vec<_>>();
+                if !vars.is_empty() {
+                    self.push(b.let_(vars.iter().copied(), None));
+                }
+                let branch = |this: &mut Self, e: &hir::Expr<'_>| {
+                    this.nested(|this| {
+                        let values = this.expr_to(e, ty)?;
+                        for (&var, value) in vars.iter().zip(values) {
+                            this.push(b.assign1(var, this.cx.val(value)));
+                        }
+                        Ok(())
+                    })
+                    .map(|(stmts, ())| stmts)
+                };
+                let then = branch(self, then)?;
+                let else_ = branch(self, else_)?;
+                self.push(b.switch(self.cx.val(cond), [(b.num(0), else_)], Some(then)));
+                Ok(vars.into_iter().map(Value::Var).collect())
+            }
+            ExprKind::Tuple(components) => {
+                let mut values = Values::new();
+                for component in components {
+                    match component {
+                        Some(component) => values.extend(self.expr(component)?),
+                        None => {
+                            return Err(self.cx.unsupported(expr.span, "empty tuple components"))
+                        }
+                    }
+                }
+                Ok(values)
+            }
+            ExprKind::Payable(inner) => {
+                let from = self.ty(inner);
+                let values = self.expr(inner)?;
+                self.convert(expr.span, values, from, ty)
+            }
+            ExprKind::Delete(inner) => {
+                self.delete(inner)?;
+                Ok(Values::new())
+            }
+            ExprKind::Type(_) | ExprKind::TypeCall(_) => Ok(Values::new()),
+            ExprKind::New(_) => Err(self.cx.unsupported(expr.span, "`new` expressions")),
+            ExprKind::Array(_) => Err(self.cx.unsupported(expr.span, "array literals")),
+            ExprKind::Slice(..) => Err(self.cx.unsupported(expr.span, "slices")),
+            ExprKind::CallOptions(..) => Err(self.cx.unsupported(expr.span, "call options")),
+            ExprKind::Err(guar) => Err(guar),
+        }
+    }
+
+    fn ident(&mut self, expr: &hir::Expr<'_>, ty: Ty<'gcx>) -> Result<Values> {
+        let res = match (self.gcx.res_of_expr(expr.id), &expr.kind) {
+            (Some(res), _) => res,
+            (None, ExprKind::Ident([res])) => *res,
+            (None, _) => unreachable!("unresolved identifier"),
+        };
+        match res {
+            Res::Item(ItemId::Variable(id)) => self.variable(expr.span, id, ty),
+            Res::Item(ItemId::Function(_)) => {
+                Err(self.cx.unsupported(expr.span, "function pointers"))
+            }
+            Res::Builtin(Builtin::This) => {
+                Ok(smallvec![self.define("expr", self.b.call("address", []))])
+            }
+            Res::Builtin(_) | Res::Item(_) | Res::Namespace(_) => Ok(Values::new()),
+            Res::Err(guar) => Err(guar),
+        }
+    }
+
+    /// Reads a variable.
+    fn variable(&mut self, span: Span, id: hir::VariableId, ty: Ty<'gcx>) -> Result<Values> {
+        let var = self.gcx.hir.variable(id);
+        if var.mutability == Some(VarMut::Constant) {
+            // Constants that could not be evaluated at compile time are inlined.
+            let init = var.initializer.expect("constant without initializer");
+            return self.expr_to(init, self.memory_ty(ty));
+        }
+        let lvalue = self.variable_lvalue(span, id, ty)?;
+        self.load(span, &lvalue, ty)
+    }
+
+    fn variable_lvalue(
+        &mut self,
+        span: Span,
+        id: hir::VariableId,
+        ty: Ty<'gcx>,
+    ) -> Result<LValue<'gcx>> {
+        let var = self.gcx.hir.variable(id);
+        if !var.is_state_variable() {
+            return Ok(LValue::Var(variable_name(self.gcx, id)));
+        }
+        match var.mutability {
+            Some(VarMut::Immutable) => Ok(LValue::Immutable(id)),
+            Some(VarMut::Constant) => Err(self.cx.unsupported(span, "assignments to constants")),
+            None => {
+                let loc = self.cx.storage_location(id).expect("state variable without slot");
+                if loc.transient && !ty.is_value_type() {
+                    return Err(self.cx.unsupported(span, "transient reference types"));
+                }
+                Ok(LValue::Storage {
+                    slot: Value::Lit(loc.slot),
+                    offset: Offset::Static(loc.offset),
+                    ty,
+                    transient: loc.transient,
+                })
+            }
+        }
+    }
+
+    fn member(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        ty: Ty<'gcx>,
+        base: &hir::Expr<'_>,
+        name: Symbol,
+    ) -> Result<Values> {
+        match self.gcx.res_of_expr(expr.id) {
+            Some(Res::Builtin(builtin)) => return self.builtin_member(expr, builtin, base),
+            Some(Res::Item(ItemId::Variable(id))) => return self.variable(expr.span, id, ty),
+            Some(Res::Item(ItemId::Function(_))) => {
+                return Err(self.cx.unsupported(expr.span, "function pointers"));
+            }
+            _ => {}
+        }
+        let base_ty = self.ty(base);
+        if let TyKind::Type(inner) = base_ty.kind {
+            if let TyKind::Enum(id) = inner.kind {
+                let variants = self.gcx.hir.enumm(id).variants;
+                let index =
+                    variants.iter().position(|v| v.name == name).expect("unknown enum variant");
+                return Ok(smallvec![Value::Lit(U256::from(index))]);
+            }
+        }
+        if name == sym("length") && is_storage(base_ty) {
+            let slot = self.expr1(base)?;
+            let length = self.cx.storage_array_length(base_ty, slot);
+            return Ok(smallvec![self.define("expr", length)]);
+        }
+        let lvalue = self.lvalue(expr)?;
+        self.load(expr.span, &lvalue, ty)
+    }
+
+    fn builtin_member(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        builtin: Builtin,
+        base: &hir::Expr<'_>,
+    ) -> Result<Values> {
+        let b = self.b;
+        let evm = self.gcx.sess.evm_version;
+        let opcode = |name: &str| Some(b.call(name, []));
+        let value = match builtin {
+            Builtin::MsgSender => opcode("caller"),
+            Builtin::MsgValue => opcode("callvalue"),
+            Builtin::MsgGas => opcode("gas"),
+            Builtin::MsgSig => Some(b.call(
+                "and",
+                [b.call("calldataload", [b.num(0)]), b.hex(U256::from(u32::MAX) << 224)],
+            )),
+            Builtin::TxOrigin => opcode("origin"),
+            Builtin::TxGasPrice => opcode("gasprice"),
+            Builtin::BlockCoinbase => opcode("coinbase"),
+            Builtin::BlockTimestamp => opcode("timestamp"),
+            Builtin::BlockNumber => opcode("number"),
+            Builtin::BlockGaslimit => opcode("gaslimit"),
+            Builtin::BlockChainid => opcode("chainid"),
+            Builtin::BlockBasefee => opcode("basefee"),
+            Builtin::BlockBlobbasefee => opcode("blobbasefee"),
+            Builtin::BlockDifficulty | Builtin::BlockPrevrandao => {
+                opcode(if evm.has_prev_randao() { "prevrandao" } else { "difficulty" })
+            }
+            Builtin::AddressBalance | Builtin::AddressCodehash => {
+                let address = self.expr1(base)?;
+                let op = if builtin == Builtin::AddressBalance { "balance" } else { "extcodehash" };
+                Some(b.call(op, [self.cx.val(address)]))
+            }
+            Builtin::ArrayLength => {
+                let base = base.peel_parens();
+                if let ExprKind::Member(address, _) = base.kind {
+                    if self.gcx.res_of_expr(base.id) == Some(Res::Builtin(Builtin::AddressCode)) {
+                        let address = self.expr1(address)?;
+                        let size = b.call("extcodesize", [self.cx.val(address)]);
+                        return Ok(smallvec![self.define("expr", size)]);
+                    }
+                }
+                let array = self.expr1(base)?;
+                Some(b.call("mload", [self.cx.val(array)]))
+            }
+            Builtin::FixedBytesLength => {
+                let TyKind::Elementary(ElementaryType::FixedBytes(size)) = self.ty(base).kind
+                else {
+                    unreachable!()
+                };
+                return Ok(smallvec![Value::Lit(U256::from(size.bytes()))]);
+            }
+            Builtin::ContractName => {
+                let TyKind::Meta(contract) = self.ty(base).kind else { unreachable!() };
+                let TyKind::Contract(id) = contract.kind else { unreachable!() };
+                let name = self.gcx.hir.contract(id).name.as_str().to_string();
+                let copy = self.cx.copy_literal_to_memory(name.as_bytes());
+                Some(b.call(copy, []))
+            }
+            Builtin::InterfaceId => {
+                let TyKind::Meta(contract) = self.ty(base).kind else { unreachable!() };
+                let TyKind::Contract(id) = contract.kind else { unreachable!() };
+                let id = self.gcx.interface_id(id);
+                return Ok(smallvec![Value::Lit(selector_word(id.0))]);
+            }
+            Builtin::FunctionSelector | Builtin::ErrorSelector => {
+                match self.gcx.res_of_expr(base.peel_parens().id) {
+                    Some(Res::Item(id @ (ItemId::Function(_) | ItemId::Error(_)))) => {
+                        let selector = self.gcx.function_selector(id);
+                        return Ok(smallvec![Value::Lit(selector_word(selector.0))]);
+                    }
+                    _ => None,
+                }
+            }
+            Builtin::EventSelector => match self.gcx.res_of_expr(base.peel_parens().id) {
+                Some(Res::Item(ItemId::Event(id))) => {
+                    let selector = self.gcx.event_selector(id);
+                    return Ok(smallvec![Value::Lit(U256::from_be_bytes(selector.0))]);
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        match value {
+            Some(value) => Ok(smallvec![self.define("expr", value)]),
+            None => {
+                let what = format!("`{}`", builtin.name());
+                Err(self.cx.unsupported(expr.span, &what))
+            }
+        }
+    }
+
+    fn fixed_bytes_index(&mut self, expr: &hir::Expr<'_>, size: u8) -> Result<Values> {
+        let b = self.b;
+        let ExprKind::Index(base, Some(index)) = expr.kind else { unreachable!() };
+        let value = self.expr1(base)?;
+        let index = self.expr1_to(index, self.gcx.types.uint(256))?;
+        let panic = self.cx.panic_stmt(0x32);
+        let in_bounds = b.call("lt", [self.cx.val(index), b.num(size)]);
+        self.push(b.if_(b.call("iszero", [in_bounds]), vec![panic]));
+        let byte = b.call("byte", [self.cx.val(index), self.cx.val(value)]);
+        Ok(smallvec![self.define("expr", b.call("shl", [b.num(248), byte]))])
+    }
+
+    /// Lowers an expression that can be assigned to.
+    pub(crate) fn lvalue(&mut self, expr: &hir::Expr<'_>) -> Result<LValue<'gcx>> {
+        let b = self.b;
+        let expr = expr.peel_parens();
+        let ty = self.ty(expr);
+        match expr.kind {
+            ExprKind::Ident(_) | ExprKind::Member(..)
+                if matches!(
+                    self.gcx.res_of_expr(expr.id),
+                    Some(Res::Item(ItemId::Variable(_)))
+                ) =>
+            {
+                let Some(Res::Item(ItemId::Variable(id))) = self.gcx.res_of_expr(expr.id) else {
+                    unreachable!()
+                };
+                self.variable_lvalue(expr.span, id, ty)
+            }
+            ExprKind::Ident([Res::Item(ItemId::Variable(id))]) => {
+                self.variable_lvalue(expr.span, *id, ty)
+            }
+            ExprKind::Member(base, name) => {
+                let base_ty = self.ty(base);
+                match base_ty.kind {
+                    TyKind::Ref(inner, loc @ (DataLocation::Storage | DataLocation::Transient)) => {
+                        let TyKind::Struct(id) = inner.kind else { unreachable!() };
+                        let slot = self.expr1(base)?;
+                        let layout = self.gcx.struct_storage_layout(id);
+                        let field = self
+                            .gcx
+                            .hir
+                            .strukt(id)
+                            .fields
+                            .iter()
+                            .copied()
+                            .find(|&f| self.gcx.item_name(f).name == name.name)
+                            .expect("unknown struct field");
+                        let item = layout.members.iter().find(|m| m.variable == field).unwrap();
+                        let slot = self.slot_add(slot, item.slot);
+                        Ok(LValue::Storage {
+                            slot,
+                            offset: Offset::Static(item.offset),
+                            ty,
+                            transient: loc == DataLocation::Transient,
+                        })
+                    }
+                    _ => Err(self.cx.unsupported(expr.span, "structs outside of storage")),
+                }
+            }
+            ExprKind::Index(base, index) => {
+                let Some(index) = index else {
+                    return Err(self.cx.unsupported(expr.span, "index expressions without index"));
+                };
+                let base_ty = self.ty(base);
+                match base_ty.peel_refs().kind {
+                    TyKind::Mapping(key, _) => {
+                        let slot = self.expr1(base)?;
+                        let key = self.expr1_to(index, self.memory_ty(key))?;
+                        let slot_expr = self.cx.mapping_slot(base_ty, slot, key);
+                        let slot = self.define("slot", slot_expr);
+                        Ok(LValue::Storage {
+                            slot,
+                            offset: Offset::Static(0),
+                            ty,
+                            transient: false,
+                        })
+                    }
+                    TyKind::Array(..) | TyKind::DynArray(_) if is_storage(base_ty) => {
+                        let slot = self.expr1(base)?;
+                        let index = self.expr1_to(index, self.gcx.types.uint(256))?;
+                        let access = self.cx.storage_array_index_access(base_ty);
+                        let (s, o) = (self.cx.fresh("slot"), self.cx.fresh("offset"));
+                        let call = b.call(access, [self.cx.val(slot), self.cx.val(index)]);
+                        self.push(b.let_([s, o], Some(call)));
+                        Ok(LValue::Storage {
+                            slot: Value::Var(s),
+                            offset: Offset::Dynamic(o),
+                            ty,
+                            transient: false,
+                        })
+                    }
+                    TyKind::Elementary(ElementaryType::Bytes) if !is_storage(base_ty) => {
+                        let array = self.expr1(base)?;
+                        let index = self.expr1_to(index, self.gcx.types.uint(256))?;
+                        let panic = self.cx.panic_stmt(0x32);
+                        let length = b.call("mload", [self.cx.val(array)]);
+                        let in_bounds = b.call("lt", [self.cx.val(index), length]);
+                        self.push(b.if_(b.call("iszero", [in_bounds]), vec![panic]));
+                        let data = b.call("add", [self.cx.val(array), b.num(32)]);
+                        let addr = self.define("addr", b.call("add", [data, self.cx.val(index)]));
+                        Ok(LValue::MemoryByte(addr))
+                    }
+                    _ => Err(self.cx.unsupported(
+                        expr.span,
+                        &format!("indexing `{}`", base_ty.display(self.gcx)),
+                    )),
+                }
+            }
+            // `array.push() = value`.
+            ExprKind::Call(callee, CallArgs::Unnamed([])) => {
+                let ExprKind::Member(base, _) = callee.peel_parens().kind else {
+                    return Err(self.cx.unsupported(expr.span, "assignments to this expression"));
+                };
+                let base_ty = self.ty(base);
+                let TyKind::DynArray(elem) = base_ty.peel_refs().kind else { unreachable!() };
+                let array = self.expr1(base)?;
+                Ok(self.array_push_zero(base_ty, array, elem))
+            }
+            ExprKind::Tuple(components) => components
+                .iter()
+                .map(|c| c.map(|c| self.lvalue(c)).transpose())
+                .collect::<Result<_>>()
+                .map(LValue::Tuple),
+            _ => Err(self.cx.unsupported(expr.span, "assignments to this expression")),
+        }
+    }
+
+    /// Reads the value of an lvalue.
+    fn load(&mut self, span: Span, lvalue: &LValue<'gcx>, ty: Ty<'gcx>) -> Result<Values> {
+        let b = self.b;
+        let value = match *lvalue {
+            LValue::Var(name) => b.path(name),
+            LValue::Storage { slot, offset, ty, transient } => {
+                if Repr::of(ty).is_none() {
+                    // References to storage are represented by their slot.
+                    return Ok(smallvec![slot]);
+                }
+                self.cx.read_storage(span, ty, slot, offset, transient)?
+            }
+            LValue::Immutable(id) => self.load_immutable(id),
+            LValue::MemoryByte(addr) => {
+                let mask = Repr::FixedBytes(1).mask();
+                b.call("and", [b.call("mload", [self.cx.val(addr)]), b.hex(mask)])
+            }
+            LValue::Tuple(_) => {
+                let what = format!("reading tuples of type `{}`", ty.display(self.gcx));
+                return Err(self.cx.unsupported(span, &what));
+            }
+        };
+        Ok(smallvec![self.define("expr", value)])
+    }
+
+    /// Writes a value to an lvalue. Byte arrays must be in memory.
+    fn store(&mut self, span: Span, lvalue: &LValue<'gcx>, value: Value) -> Result<()> {
+        let b = self.b;
+        let stmt = match *lvalue {
+            LValue::Var(name) => b.assign1(name, self.cx.val(value)),
+            LValue::Storage { slot, offset, ty, transient } => {
+                if Repr::of(ty).is_none() && !is_byte_array(ty) {
+                    let what = format!("copying `{}` to storage", ty.display(self.gcx));
+                    return Err(self.cx.unsupported(span, &what));
+                }
+                self.cx.write_storage(span, ty, slot, offset, transient, value)?
+            }
+            LValue::Immutable(id) => {
+                if self.cx.kind != ObjectKind::Creation {
+                    return Err(self.cx.unsupported(span, "assignments to immutables at runtime"));
+                }
+                let addr = b.num(self.cx.immutable_address(id));
+                b.call_stmt("mstore", [addr, self.cx.val(value)])
+            }
+            LValue::MemoryByte(addr) => b.call_stmt(
+                "mstore8",
+                [self.cx.val(addr), b.call("byte", [b.num(0), self.cx.val(value)])],
+            ),
+            LValue::Tuple(_) => unreachable!("tuples are assigned component-wise"),
+        };
+        self.push(stmt);
+        Ok(())
+    }
+
+    fn assign(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        lhs: &hir::Expr<'_>,
+        op: Option<BinOpKind>,
+        rhs: &hir::Expr<'_>,
+    ) -> Result<Values> {
+        let lhs_ty = self.ty(lhs);
+        let Some(op) = op else {
+            if let ExprKind::Tuple(components) = lhs.peel_parens().kind {
+                let rhs_ty = self.ty(rhs);
+                let values = self.expr(rhs)?;
+                let TyKind::Tuple(rhs_tys) = mobile_type(self.gcx, rhs_ty).kind else {
+                    unreachable!()
+                };
+                let LValue::Tuple(lvalues) = self.lvalue(lhs)? else { unreachable!() };
+                let mut values = values.into_iter();
+                for ((component, lvalue), &from) in components.iter().zip(&lvalues).zip(rhs_tys) {
+                    let component_values: Values = values.by_ref().take(stack_size(from)).collect();
+                    let (Some(component), Some(lvalue)) = (component, lvalue) else { continue };
+                    let to = self.memory_ty(self.ty(component));
+                    let value = self.convert(component.span, component_values, from, to)?[0];
+                    self.store(component.span, lvalue, value)?;
+                }
+                return Ok(Values::new());
+            }
+            let value = self.expr1_to(rhs, self.memory_ty(lhs_ty))?;
+            let lvalue = self.lvalue(lhs)?;
+            self.store(expr.span, &lvalue, value)?;
+            return Ok(smallvec![value]);
+        };
+        let lvalue = self.lvalue(lhs)?;
+        let old = self.load(lhs.span, &lvalue, lhs_ty)?[0];
+        let rhs_value = if matches!(op, BinOpKind::Shl | BinOpKind::Shr | BinOpKind::Sar) {
+            self.expr1(rhs)?
+        } else {
+            self.expr1_to(rhs, lhs_ty)?
+        };
+        let rhs_ty = if is_literal(self.ty(rhs)) {
+            mobile_type(self.gcx, self.ty(rhs))
+        } else {
+            self.ty(rhs)
+        };
+        let new = self.binary_op(expr.span, op, lhs_ty, old, rhs_value, rhs_ty)?;
+        self.store(expr.span, &lvalue, new)?;
+        Ok(smallvec![new])
+    }
+
+    fn delete(&mut self, expr: &hir::Expr<'_>) -> Result<()> {
+        let ty = self.ty(expr);
+        let lvalue = self.lvalue(expr)?;
+        let zero = match lvalue {
+            LValue::Storage { ty, .. } if is_byte_array(ty) => Value::Lit(U256::from(0x60)),
+            LValue::Storage { .. } | LValue::Immutable(_) | LValue::MemoryByte(_) => Value::ZERO,
+            LValue::Var(_) => self.zero_value(expr.span, ty)?,
+            LValue::Tuple(_) => return Err(self.cx.unsupported(expr.span, "deleting tuples")),
+        };
+        self.store(expr.span, &lvalue, zero)
+    }
+
+    fn binary(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        ty: Ty<'gcx>,
+        lhs: &hir::Expr<'_>,
+        op: BinOpKind,
+        rhs: &hir::Expr<'_>,
+    ) -> Result<Values> {
+        let b = self.b;
+        if let Some(Res::Item(ItemId::Function(id))) = self.gcx.res_of_expr(expr.id) {
+            return self.call_internal(expr.span, id, &[lhs, rhs]);
+        }
+        match op {
+            BinOpKind::And | BinOpKind::Or => {
+                let bool_ty = self.gcx.types.bool;
+                let lhs = self.expr1_to(lhs, bool_ty)?;
+                let result = self.cx.fresh("expr");
+                self.push(b.let1(result, self.cx.val(lhs)));
+                let (mut stmts, rhs) = self.nested(|this| this.expr1_to(rhs, bool_ty))?;
+                stmts.push(b.assign1(result, self.cx.val(rhs)));
+                let cond = if op == BinOpKind::And {
+                    b.path(result)
+                } else {
+                    b.call("iszero", [b.path(result)])
+                };
+                self.push(b.if_(cond, stmts));
+                Ok(smallvec![Value::Var(result)])
+            }
+            BinOpKind::Lt
+            | BinOpKind::Le
+            | BinOpKind::Gt
+            | BinOpKind::Ge
+            | BinOpKind::Eq
+            | BinOpKind::Ne => {
+                let common = self.common_type(lhs, rhs);
+                let Some(repr) = Repr::of(common) else {
+                    let what = format!("comparisons of `{}`", common.display(self.gcx));
+                    return Err(self.cx.unsupported(expr.span, &what));
+                };
+                let lhs = self.expr1_to(lhs, common)?;
+                let rhs = self.expr1_to(rhs, common)?;
+                let (l, r) = (self.cx.val(lhs), self.cx.val(rhs));
+                let (lt, gt) = if repr.is_signed() { ("slt", "sgt") } else { ("lt", "gt") };
+                let value = match op {
+                    BinOpKind::Lt => b.call(lt, [l, r]),
+                    BinOpKind::Gt => b.call(gt, [l, r]),
+                    BinOpKind::Le => b.call("iszero", [b.call(gt, [l, r])]),
+                    BinOpKind::Ge => b.call("iszero", [b.call(lt, [l, r])]),
+                    BinOpKind::Eq => b.call("eq", [l, r]),
+                    BinOpKind::Ne => b.call("iszero", [b.call("eq", [l, r])]),
+                    _ => unreachable!(),
+                };
+                Ok(smallvec![self.define("expr", value)])
+            }
+            BinOpKind::Shl | BinOpKind::Shr | BinOpKind::Sar | BinOpKind::Pow => {
+                let lhs = self.expr1_to(lhs, ty)?;
+                let rhs_ty = mobile_type(self.gcx, self.ty(rhs));
+                let rhs = self.expr1_to(rhs, rhs_ty)?;
+                Ok(smallvec![self.binary_op(expr.span, op, ty, lhs, rhs, rhs_ty)?])
+            }
+            _ => {
+                let lhs = self.expr1_to(lhs, ty)?;
+                let rhs = self.expr1_to(rhs, ty)?;
+                Ok(smallvec![self.binary_op(expr.span, op, ty, lhs, rhs, ty)?])
+            }
+        }
+    }
+
+    /// Returns the type to which both operands of a comparison are converted.
+    fn common_type(&self, lhs: &hir::Expr<'_>, rhs: &hir::Expr<'_>) -> Ty<'gcx> {
+        let (l, r) = (self.ty(lhs), self.ty(rhs));
+        match (is_literal(l), is_literal(r)) {
+            (true, false) => r,
+            (false, true) => l,
+            (true, true) => mobile_type(self.gcx, l),
+            (false, false) => {
+                if l.is_implicitly_convertible_to(self.gcx, r).is_ok() {
+                    r
+                } else {
+                    l
+                }
+            }
+        }
+    }
+
+    /// Applies an arithmetic, bitwise, or shift operator to values of the operand type `ty`. The
+    /// right-hand side of shifts and exponentiation has type `rhs_ty`.
+    fn binary_op(
+        &mut self,
+        span: Span,
+        op: BinOpKind,
+        ty: Ty<'gcx>,
+        lhs: Value,
+        rhs: Value,
+        rhs_ty: Ty<'gcx>,
+    ) -> Result<Value> {
+        let b = self.b;
+        let Some(repr) = Repr::of(ty) else {
+            let what = format!("operators on `{}`", ty.display(self.gcx));
+            return Err(self.cx.unsupported(span, &what));
+        };
+        let (l, r) = (self.cx.val(lhs), self.cx.val(rhs));
+        let value = match op {
+            BinOpKind::BitAnd => b.call("and", [l, r]),
+            BinOpKind::BitOr => b.call("or", [l, r]),
+            BinOpKind::BitXor => b.call("xor", [l, r]),
+            BinOpKind::Shl => self.cx.cleanup(repr, b.call("shl", [r, l])),
+            BinOpKind::Shr | BinOpKind::Sar => {
+                let op = if repr.is_signed() { "sar" } else { "shr" };
+                let value = b.call(op, [r, l]);
+                if matches!(repr, Repr::FixedBytes(_)) {
+                    self.cx.cleanup(repr, value)
+                } else {
+                    value
+                }
+            }
+            BinOpKind::Add | BinOpKind::Sub | BinOpKind::Mul if self.unchecked => {
+                let op = match op {
+                    BinOpKind::Add => "add",
+                    BinOpKind::Sub => "sub",
+                    _ => "mul",
+                };
+                self.cx.cleanup(repr, b.call(op, [l, r]))
+            }
+            BinOpKind::Add | BinOpKind::Sub | BinOpKind::Mul | BinOpKind::Div | BinOpKind::Rem => {
+                let f = self.checked_arithmetic(op, repr, ty);
+                b.call(f, [l, r])
+            }
+            BinOpKind::Pow => {
+                let Some(exp_repr) = Repr::of(rhs_ty) else { unreachable!() };
+                if exp_repr.is_signed() {
+                    return Err(self.cx.unsupported(span, "signed exponents"));
+                }
+                let f = self.exp(repr, ty, rhs_ty);
+                b.call(f, [l, r])
+            }
+            _ => unreachable!("not an arithmetic operator: {op:?}"),
+        };
+        Ok(self.define("expr", value))
+    }
+
+    /// `checked_<op>_<ty>(x, y) -> r`, `wrapping_div_<ty>(x, y) -> r`, or `mod_<ty>(x, y) -> r`.
+    fn checked_arithmetic(&mut self, op: BinOpKind, repr: Repr, ty: Ty<'gcx>) -> Symbol {
+        let tid = type_id(self.gcx, ty);
+        let name = match op {
+            BinOpKind::Add => format!("checked_add_{tid}"),
+            BinOpKind::Sub => format!("checked_sub_{tid}"),
+            BinOpKind::Mul => format!("checked_mul_{tid}"),
+            BinOpKind::Div if self.unchecked => format!("wrapping_div_{tid}"),
+            BinOpKind::Div => format!("checked_div_{tid}"),
+            BinOpKind::Rem => format!("mod_{tid}"),
+            _ => unreachable!(),
+        };
+        let unchecked = self.unchecked;
+        self.cx.helper(name, |cx, name| {
+            let b = cx.b;
+            let (x, y) = (b.path("x"), b.path("y"));
+            let overflow = cx.panic_stmt(0x11);
+            let signed = repr.is_signed();
+            let bits = match repr {
+                Repr::Uint(bits) | Repr::Int(bits) => bits,
+                _ => 256,
+            };
+            let mut body = Vec::new();
+            match op {
+                BinOpKind::Add | BinOpKind::Sub if bits < 256 => {
+                    let op = if op == BinOpKind::Add { "add" } else { "sub" };
+                    body.push(b.assign1("sum", b.call(op, [x, y])));
+                    let out_of_range = if signed {
+                        b.call(
+                            "or",
+                            [
+                                b.call("sgt", [b.path("sum"), b.hex(repr.max())]),
+                                b.call("slt", [b.path("sum"), b.hex(repr.min())]),
+                            ],
+                        )
+                    } else {
+                        b.call("gt", [b.path("sum"), b.hex(repr.max())])
+                    };
+                    body.push(b.if_(out_of_range, vec![overflow]));
+                }
+                BinOpKind::Add => {
+                    body.push(b.assign1("sum", b.call("add", [x, y])));
+                    let overflow_cond = if signed {
+                        // Overflow iff `y >= 0` and `sum < x`, or `y < 0` and `sum >= x`.
+                        b.call(
+                            "xor",
+                            [
+                                b.call("slt", [b.path("sum"), b.path("x")]),
+                                b.call("slt", [b.path("y"), b.num(0)]),
+                            ],
+                        )
+                    } else {
+                        b.call("gt", [b.path("x"), b.path("sum")])
+                    };
+                    body.push(b.if_(overflow_cond, vec![overflow]));
+                }
+                BinOpKind::Sub => {
+                    body.push(b.assign1("sum", b.call("sub", [x, y])));
+                    let overflow_cond = if signed {
+                        b.call(
+                            "xor",
+                            [
+                                b.call("sgt", [b.path("sum"), b.path("x")]),
+                                b.call("slt", [b.path("y"), b.num(0)]),
+                            ],
+                        )
+                    } else {
+                        b.call("gt", [b.path("sum"), b.path("x")])
+                    };
+                    body.push(b.if_(overflow_cond, vec![overflow]));
+                }
+                BinOpKind::Mul => {
+                    body.push(b.assign1("product", b.call("mul", [x, y])));
+                    let overflow_cond = if bits < 256 {
+                        if signed {
+                            b.call(
+                                "or",
+                                [
+                                    b.call("sgt", [b.path("product"), b.hex(repr.max())]),
+                                    b.call("slt", [b.path("product"), b.hex(repr.min())]),
+                                ],
+                            )
+                        } else {
+                            b.call("gt", [b.path("product"), b.hex(repr.max())])
+                        }
+                    } else {
+                        let div = if signed { "sdiv" } else { "div" };
+                        let wrong = b.call(
+                            "iszero",
+                            [b.call(
+                                "or",
+                                [
+                                    b.call("iszero", [b.path("x")]),
+                                    b.call(
+                                        "eq",
+                                        [
+                                            b.path("y"),
+                                            b.call(div, [b.path("product"), b.path("x")]),
+                                        ],
+                                    ),
+                                ],
+                            )],
+                        );
+                        if signed {
+                            // `-1 * MIN` overflows, but `MIN / -1 == MIN` does not detect it.
+                            let min_times_minus_one = b.call(
+                                "and",
+                                [
+                                    b.call("slt", [b.path("x"), b.num(0)]),
+                                    b.call("eq", [b.path("y"), b.hex(repr.min())]),
+                                ],
+                            );
+                            b.call("or", [wrong, min_times_minus_one])
+                        } else {
+                            wrong
+                        }
+                    };
+                    body.push(b.if_(overflow_cond, vec![overflow]));
+                }
+                BinOpKind::Div | BinOpKind::Rem => {
+                    let div_by_zero = cx.panic_stmt(0x12);
+                    body.push(b.if_(b.call("iszero", [b.path("y")]), vec![div_by_zero]));
+                    if op == BinOpKind::Div && signed && !unchecked {
+                        let min_div_minus_one = b.call(
+                            "and",
+                            [
+                                b.call("eq", [b.path("x"), b.hex(repr.min())]),
+                                b.call("eq", [b.path("y"), b.hex(U256::MAX)]),
+                            ],
+                        );
+                        body.push(b.if_(min_div_minus_one, vec![overflow]));
+                    }
+                    let op = match (op, signed) {
+                        (BinOpKind::Div, false) => "div",
+                        (BinOpKind::Div, true) => "sdiv",
+                        (_, false) => "mod",
+                        (_, true) => "smod",
+                    };
+                    body.push(
+                        b.assign1("r", cx.cleanup(repr, b.call(op, [b.path("x"), b.path("y")]))),
+                    );
+                }
+                _ => unreachable!(),
+            }
+            let ret = if matches!(op, BinOpKind::Div | BinOpKind::Rem) {
+                "r"
+            } else if op == BinOpKind::Mul {
+                "product"
+            } else {
+                "sum"
+            };
+            b.function(name, [sym("x"), sym("y")], [sym(ret)], body)
+        })
+    }
+
+    /// `checked_exp_<base>_<exponent>(base, exponent) -> power`: exponentiation by squaring.
+    fn exp(&mut self, repr: Repr, ty: Ty<'gcx>, exp_ty: Ty<'gcx>) -> Symbol {
+        let prefix = if self.unchecked { "wrapping" } else { "checked" };
+        let name = format!("{prefix}_exp_{}_{}", type_id(self.gcx, ty), type_id(self.gcx, exp_ty));
+        let unchecked = self.unchecked;
+        let mul =
+            if unchecked { None } else { Some(self.checked_arithmetic(BinOpKind::Mul, repr, ty)) };
+        self.cx.helper(name, |cx, name| {
+            let b = cx.b;
+            let mul = |x: yul::Expr<'ast>, y: yul::Expr<'ast>| match mul {
+                Some(mul) => b.call(mul, [x, y]),
+                None => cx.cleanup(repr, b.call("mul", [x, y])),
+            };
+            let body = vec![
+                b.assign1("power", b.num(1)),
+                b.for_(
+                    vec![],
+                    b.num(1),
+                    vec![],
+                    vec![
+                        b.if_(
+                            b.call("and", [b.path("exponent"), b.num(1)]),
+                            vec![b.assign1("power", mul(b.path("power"), b.path("base")))],
+                        ),
+                        b.assign1("exponent", b.call("shr", [b.num(1), b.path("exponent")])),
+                        b.if_(b.call("iszero", [b.path("exponent")]), vec![b.break_()]),
+                        b.assign1("base", mul(b.path("base"), b.path("base"))),
+                    ],
+                ),
+            ];
+            b.function(name, [sym("base"), sym("exponent")], [sym("power")], body)
+        })
+    }
+
+    fn unary(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        ty: Ty<'gcx>,
+        op: UnOpKind,
+        operand: &hir::Expr<'_>,
+    ) -> Result<Values> {
+        let b = self.b;
+        if let Some(Res::Item(ItemId::Function(id))) = self.gcx.res_of_expr(expr.id) {
+            return self.call_internal(expr.span, id, &[operand]);
+        }
+        let value = match op {
+            UnOpKind::Not => {
+                let value = self.expr1_to(operand, self.gcx.types.bool)?;
+                b.call("iszero", [self.cx.val(value)])
+            }
+            UnOpKind::BitNot => {
+                let value = self.expr1_to(operand, ty)?;
+                let Some(repr) = Repr::of(ty) else { unreachable!() };
+                self.cx.cleanup(repr, b.call("not", [self.cx.val(value)]))
+            }
+            UnOpKind::Neg => {
+                let value = self.expr1_to(operand, ty)?;
+                let Some(repr) = Repr::of(ty) else { unreachable!() };
+                if self.unchecked {
+                    self.cx.cleanup(repr, b.call("sub", [b.num(0), self.cx.val(value)]))
+                } else {
+                    let f = self.checked_arithmetic(BinOpKind::Sub, repr, ty);
+                    b.call(f, [b.num(0), self.cx.val(value)])
+                }
+            }
+            UnOpKind::PreInc | UnOpKind::PreDec | UnOpKind::PostInc | UnOpKind::PostDec => {
+                let lvalue = self.lvalue(operand)?;
+                let old = self.load(operand.span, &lvalue, ty)?[0];
+                let bin_op = if matches!(op, UnOpKind::PreInc | UnOpKind::PostInc) {
+                    BinOpKind::Add
+                } else {
+                    BinOpKind::Sub
+                };
+                let one = Value::Lit(U256::from(1));
+                let new = self.binary_op(expr.span, bin_op, ty, old, one, ty)?;
+                self.store(expr.span, &lvalue, new)?;
+                let result =
+                    if matches!(op, UnOpKind::PreInc | UnOpKind::PreDec) { new } else { old };
+                return Ok(smallvec![result]);
+            }
+        };
+        Ok(smallvec![self.define("expr", value)])
+    }
+
+    fn call(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        callee: &hir::Expr<'_>,
+        args: &CallArgs<'_>,
+    ) -> Result<Values> {
+        let callee_ty = self.ty(callee);
+        let (inner, options) = match callee.kind {
+            ExprKind::CallOptions(inner, options) => (inner, options),
+            _ => (callee, &[][..]),
+        };
+        if let TyKind::Type(to) = callee_ty.kind {
+            if matches!(to.kind, TyKind::Struct(_)) {
+                return Err(self.cx.unsupported(expr.span, "struct constructors"));
+            }
+            let CallArgs::Unnamed([arg]) = *args else { unreachable!("invalid conversion") };
+            let to = self.ty(expr);
+            return self.expr_conv(arg, to, true);
+        }
+        if let ExprKind::New(_) = inner.kind {
+            let what = match self.ty(expr).kind {
+                TyKind::Contract(_) => "contract creation",
+                _ => "allocation of memory arrays",
+            };
+            return Err(self.cx.unsupported(expr.span, what));
+        }
+        match self.gcx.res_of_expr(inner.id) {
+            Some(Res::Builtin(builtin)) => self.call_builtin(expr, inner, builtin, args, options),
+            Some(Res::Item(ItemId::Function(id))) => {
+                let TyKind::FnPtr(f) = callee_ty.kind else { unreachable!() };
+                if let ExprKind::Member(base, _) = inner.kind {
+                    if matches!(base.kind, ExprKind::Ident([Res::Builtin(Builtin::Super)])) {
+                        let id = self.resolve_super(id);
+                        let args =
+                            ordered_args(self.gcx, args, self.gcx.hir.function(id).parameters);
+                        return self.call_internal(expr.span, id, &args);
+                    }
+                    let base_ty = self.ty(base);
+                    if matches!(base_ty.kind, TyKind::Contract(_))
+                        && f.visibility == Visibility::External
+                    {
+                        return self.call_external(
+                            expr,
+                            base,
+                            id,
+                            f.state_mutability,
+                            args,
+                            options,
+                        );
+                    }
+                    let params = self.gcx.hir.function(id).parameters;
+                    if f.parameters.len() + 1 == params.len() {
+                        // A function attached with `using for`.
+                        let mut all_args = vec![base];
+                        all_args.extend(ordered_args(self.gcx, args, &params[1..]));
+                        return self.call_internal(expr.span, id, &all_args);
+                    }
+                    let args = ordered_args(self.gcx, args, params);
+                    return self.call_internal(expr.span, id, &args);
+                }
+                let id = self.resolve_virtual(id);
+                let args = ordered_args(self.gcx, args, self.gcx.hir.function(id).parameters);
+                self.call_internal(expr.span, id, &args)
+            }
+            None => {
+                if let ExprKind::Member(base, name) = inner.kind {
+                    let base_ty = self.ty(base);
+                    if is_storage(base_ty) {
+                        match base_ty.peel_refs().kind {
+                            TyKind::DynArray(elem) => match name.as_str() {
+                                "push" => return self.array_push(expr, base, base_ty, elem, args),
+                                "pop" => {
+                                    let array = self.expr1(base)?;
+                                    let pop = self.cx.storage_array_pop(expr.span, base_ty)?;
+                                    self.push(self.b.call_stmt(pop, [self.cx.val(array)]));
+                                    return Ok(Values::new());
+                                }
+                                _ => {}
+                            },
+                            TyKind::Elementary(ElementaryType::Bytes)
+                                if matches!(name.as_str(), "push" | "pop") =>
+                            {
+                                let what = "`push` and `pop` on storage `bytes`";
+                                return Err(self.cx.unsupported(expr.span, what));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Err(self.cx.unsupported(expr.span, "calls to function pointers"))
+            }
+            Some(_) => Err(self.cx.unsupported(expr.span, "calls to function pointers")),
+        }
+    }
+
+    /// Returns the implementation of a virtual function in the most derived contract.
+    fn resolve_virtual(&self, id: hir::FunctionId) -> hir::FunctionId {
+        let f = self.gcx.hir.function(id);
+        if !f.virtual_ || f.contract.is_none() {
+            return id;
+        }
+        self.find_override(id, 0)
+    }
+
+    /// Returns the implementation of `super.f` in the context of the current function.
+    fn resolve_super(&self, id: hir::FunctionId) -> hir::FunctionId {
+        let bases = self.gcx.hir.contract(self.cx.contract).linearized_bases;
+        let current = self.function.and_then(|f| self.gcx.hir.function(f).contract);
+        let start = bases.iter().position(|&c| Some(c) == current).map_or(0, |i| i + 1);
+        self.find_override(id, start)
+    }
+
+    /// Finds the first function with the same name and parameters as `id` in the linearized
+    /// bases of the contract, starting at `start`.
+    fn find_override(&self, id: hir::FunctionId, start: usize) -> hir::FunctionId {
+        let gcx = self.gcx;
+        let f = gcx.hir.function(id);
+        let param_tys = gcx.item_parameter_types(id.into());
+        let bases = gcx.hir.contract(self.cx.contract).linearized_bases;
+        bases[start.min(bases.len())..]
+            .iter()
+            .flat_map(|&base| gcx.hir.contract(base).functions())
+            .find(|&candidate| {
+                let c = gcx.hir.function(candidate);
+                c.kind == f.kind
+                    && c.body.is_some()
+                    && c.name.map(|n| n.name) == f.name.map(|n| n.name)
+                    && gcx.item_parameter_types(candidate.into()) == param_tys
+            })
+            .unwrap_or(id)
+    }
+
+    /// Calls an internal function with the given arguments.
+    fn call_internal(
+        &mut self,
+        span: Span,
+        id: hir::FunctionId,
+        args: &[&hir::Expr<'_>],
+    ) -> Result<Values> {
+        let f = self.gcx.hir.function(id);
+        if let Some(contract) = f.contract {
+            if self.gcx.hir.contract(contract).kind == ContractKind::Library
+                && f.visibility >= Visibility::Public
+            {
+                return Err(self.cx.unsupported(span, "calls to public library functions"));
+            }
+        }
+        let param_tys = self.gcx.item_parameter_types(id.into());
+        let mut values = Vec::with_capacity(args.len());
+        for (arg, &ty) in args.iter().zip(param_tys) {
+            values.extend(self.expr_to(arg, ty)?);
+        }
+        let name = self.cx.function(id);
+        let call = self.b.call(name, values.into_iter().map(|v| self.cx.val(v)));
+        Ok(self.call_values(call, f.returns.len()))
+    }
+
+    /// Stores the results of a call in new variables.
+    fn call_values(&mut self, call: yul::Expr<'ast>, n: usize) -> Values {
+        if n == 0 {
+            self.push(self.b.expr_stmt(call));
+            return Values::new();
+        }
+        let vars = (0..n).map(|_| self.cx.fresh("ret")).collect::<Vec<_>>();
+        self.push(self.b.let_(vars.iter().copied(), Some(call)));
+        vars.into_iter().map(Value::Var).collect()
+    }
+
+    fn array_push(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        base: &hir::Expr<'_>,
+        array_ty: Ty<'gcx>,
+        elem: Ty<'gcx>,
+        args: &CallArgs<'_>,
+    ) -> Result<Values> {
+        let array = self.expr1(base)?;
+        let value = match *args {
+            CallArgs::Unnamed([arg]) => Some(self.expr1_to(arg, self.memory_ty(elem))?),
+            _ => None,
+        };
+        let new_elem = self.array_push_zero(array_ty, array, elem);
+        let Some(value) = value else {
+            // `push()` returns a reference to the new element.
+            return self.load(expr.span, &new_elem, self.ty(expr));
+        };
+        if Repr::of(elem).is_none() && !is_byte_array(elem) {
+            let what = format!("pushing `{}` to storage", elem.display(self.gcx));
+            return Err(self.cx.unsupported(expr.span, &what));
+        }
+        self.store(expr.span, &new_elem, value)?;
+        Ok(Values::new())
+    }
+
+    /// Appends a zero-initialized element to a storage array, and returns it.
+    fn array_push_zero(
+        &mut self,
+        array_ty: Ty<'gcx>,
+        array: Value,
+        elem: Ty<'gcx>,
+    ) -> LValue<'gcx> {
+        let b = self.b;
+        let push = self.cx.storage_array_push_zero(array_ty);
+        let (slot, offset) = (self.cx.fresh("slot"), self.cx.fresh("offset"));
+        self.push(b.let_([slot, offset], Some(b.call(push, [self.cx.val(array)]))));
+        LValue::Storage {
+            slot: Value::Var(slot),
+            offset: Offset::Dynamic(offset),
+            ty: elem,
+            transient: false,
+        }
+    }
+
+    /// Calls an external function of another contract.
+    fn call_external(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        base: &hir::Expr<'_>,
+        id: hir::FunctionId,
+        state_mutability: StateMutability,
+        args: &CallArgs<'_>,
+        options: &[hir::NamedArg<'_>],
+    ) -> Result<Values> {
+        let b = self.b;
+        let gcx = self.gcx;
+        let address = self.expr1(base)?;
+        let (value, gas) = self.call_options(options)?;
+        let f = gcx.hir.function(id);
+        let param_tys = gcx
+            .item_parameter_types(id.into())
+            .iter()
+            .map(|&ty| self.memory_ty(ty))
+            .collect::<Vec<_>>();
+        let mut values = Vec::new();
+        for (arg, &ty) in ordered_args(gcx, args, f.parameters).into_iter().zip(&param_tys) {
+            values.push(self.expr1_to(arg, ty)?);
+        }
+        let ret_tys = f
+            .returns
+            .iter()
+            .map(|&ret| {
+                self.memory_ty(
+                    gcx.type_of_item(ret.into()).with_loc_if_ref(gcx, DataLocation::Memory),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if ret_tys.is_empty() {
+            // Calls to accounts without code succeed, so they are rejected explicitly when no
+            // return data is expected.
+            let has_code = b.call("extcodesize", [self.cx.val(address)]);
+            let revert = self.cx.revert_empty();
+            self.push(b.if_(b.call("iszero", [has_code]), vec![revert]));
+        }
+        let selector = b.call(
+            "shl",
+            [b.num(224), b.hex(U256::from(u32::from_be_bytes(gcx.function_selector(id).0)))],
+        );
+        let exprs = values.iter().map(|&v| self.cx.val(v)).collect();
+        let (stmts, data) =
+            self.cx.encode_to_memory(expr.span, Some(selector), &param_tys, exprs, false)?;
+        self.stmts.extend(stmts);
+        let input = b.call("add", [b.path(data), b.num(32)]);
+        let input_size = b.call("mload", [b.path(data)]);
+        let gas = gas.map_or_else(|| b.call("gas", []), |gas| self.cx.val(gas));
+        let call = if matches!(state_mutability, StateMutability::View | StateMutability::Pure) {
+            b.call("staticcall", [gas, self.cx.val(address), input, input_size, b.num(0), b.num(0)])
+        } else {
+            let value = self.cx.val(value.unwrap_or(Value::ZERO));
+            b.call(
+                "call",
+                [gas, self.cx.val(address), value, input, input_size, b.num(0), b.num(0)],
+            )
+        };
+        let success = self.define("success", call);
+        let forward = self.cx.revert_forward();
+        self.push(b.if_(b.call("iszero", [self.cx.val(success)]), vec![b.call_stmt(forward, [])]));
+        if ret_tys.is_empty() {
+            return Ok(Values::new());
+        }
+        let decode = self.cx.abi_decode(expr.span, &ret_tys, true)?;
+        let allocate = self.cx.allocate_unbounded();
+        let finalize = self.cx.finalize_allocation();
+        let pos = self.define("pos", b.call(allocate, []));
+        self.push(b.call_stmt(
+            "returndatacopy",
+            [self.cx.val(pos), b.num(0), b.call("returndatasize", [])],
+        ));
+        self.push(b.call_stmt(finalize, [self.cx.val(pos), b.call("returndatasize", [])]));
+        let end = b.call("add", [self.cx.val(pos), b.call("returndatasize", [])]);
+        let call = b.call(decode, [self.cx.val(pos), end]);
+        Ok(self.call_values(call, ret_tys.len()))
+    }
+
+    /// Lowers the `value` and `gas` call options.
+    fn call_options(
+        &mut self,
+        options: &[hir::NamedArg<'_>],
+    ) -> Result<(Option<Value>, Option<Value>)> {
+        let (mut value, mut gas) = (None, None);
+        for option in options {
+            let v = self.expr1_to(&option.value, self.gcx.types.uint(256))?;
+            match option.name.as_str() {
+                "value" => value = Some(v),
+                "gas" => gas = Some(v),
+                _ => return Err(self.cx.unsupported(option.name.span, "this call option")),
+            }
+        }
+        Ok((value, gas))
+    }
+
+    fn call_builtin(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        callee: &hir::Expr<'_>,
+        builtin: Builtin,
+        args: &CallArgs<'_>,
+        options: &[hir::NamedArg<'_>],
+    ) -> Result<Values> {
+        let b = self.b;
+        let gcx = self.gcx;
+        let CallArgs::Unnamed(args) = *args else {
+            return Err(self.cx.unsupported(expr.span, "named arguments to builtin functions"));
+        };
+        let uint256 = gcx.types.uint(256);
+        let bytes32 = gcx.types.fixed_bytes(32);
+        let bytes_memory = gcx.types.bytes_ref.memory;
+        let string_memory = gcx.types.string_ref.memory;
+        let value = match builtin {
+            Builtin::Assert => {
+                let cond = self.expr1_to(&args[0], gcx.types.bool)?;
+                let panic = self.cx.panic_stmt(0x01);
+                self.push(b.if_(b.call("iszero", [self.cx.val(cond)]), vec![panic]));
+                return Ok(Values::new());
+            }
+            Builtin::Require | Builtin::RequireMsg => {
+                let cond = self.expr1_to(&args[0], gcx.types.bool)?;
+                let failure = match args.get(1) {
+                    Some(msg) => {
+                        if !is_byte_array(self.ty(msg)) && !is_literal(self.ty(msg)) {
+                            return Err(self
+                                .cx
+                                .unsupported(msg.span, "custom errors in `require`"));
+                        }
+                        let msg = self.expr1_to(msg, string_memory)?;
+                        self.nested(|this| {
+                            this.revert_with(expr.span, ERROR_SELECTOR, &[string_memory], &[msg])
+                        })?
+                        .0
+                    }
+                    None => vec![self.cx.revert_empty()],
+                };
+                self.push(b.if_(b.call("iszero", [self.cx.val(cond)]), failure));
+                return Ok(Values::new());
+            }
+            Builtin::Revert | Builtin::RevertMsg => {
+                match args.first() {
+                    Some(msg) => {
+                        let msg = self.expr1_to(msg, string_memory)?;
+                        self.revert_with(expr.span, ERROR_SELECTOR, &[string_memory], &[msg])?;
+                    }
+                    None => self.push(self.cx.revert_empty()),
+                }
+                return Ok(Values::new());
+            }
+            Builtin::Keccak256 => {
+                let data = self.expr1_to(&args[0], bytes_memory)?;
+                let start = b.call("add", [self.cx.val(data), b.num(32)]);
+                b.call("keccak256", [start, b.call("mload", [self.cx.val(data)])])
+            }
+            Builtin::Sha256 | Builtin::Ripemd160 => {
+                let data = self.expr1_to(&args[0], bytes_memory)?;
+                let precompile = if builtin == Builtin::Sha256 { 2 } else { 3 };
+                let input = b.call("add", [self.cx.val(data), b.num(32)]);
+                let size = b.call("mload", [self.cx.val(data)]);
+                self.precompile(precompile, input, size);
+                if builtin == Builtin::Sha256 {
+                    b.call("mload", [b.num(0)])
+                } else {
+                    b.call("shl", [b.num(96), b.call("mload", [b.num(0)])])
+                }
+            }
+            Builtin::EcRecover => {
+                let tys = [bytes32, gcx.types.uint(8), bytes32, bytes32];
+                let mut values = Vec::new();
+                for (arg, &ty) in args.iter().zip(&tys) {
+                    values.push(self.expr1_to(arg, ty)?);
+                }
+                let allocate = self.cx.allocate_unbounded();
+                let pos = self.define("pos", b.call(allocate, []));
+                for (i, value) in values.into_iter().enumerate() {
+                    let addr = b.call("add", [self.cx.val(pos), b.num(32 * i)]);
+                    self.push(b.call_stmt("mstore", [addr, self.cx.val(value)]));
+                }
+                self.push(b.call_stmt("mstore", [b.num(0), b.num(0)]));
+                self.precompile(1, self.cx.val(pos), b.num(128));
+                b.call("mload", [b.num(0)])
+            }
+            Builtin::AddMod | Builtin::MulMod => {
+                let mut values = Vec::new();
+                for arg in args {
+                    values.push(self.expr1_to(arg, uint256)?);
+                }
+                let panic = self.cx.panic_stmt(0x12);
+                self.push(b.if_(b.call("iszero", [self.cx.val(values[2])]), vec![panic]));
+                let op = if builtin == Builtin::AddMod { "addmod" } else { "mulmod" };
+                b.call(op, values.into_iter().map(|v| self.cx.val(v)))
+            }
+            Builtin::Blockhash | Builtin::Blobhash => {
+                let n = self.expr1_to(&args[0], uint256)?;
+                let op = if builtin == Builtin::Blockhash { "blockhash" } else { "blobhash" };
+                b.call(op, [self.cx.val(n)])
+            }
+            Builtin::Gasleft => b.call("gas", []),
+            Builtin::AbiEncode | Builtin::AbiEncodePacked => {
+                let (tys, values) = self.encoded_args(args)?;
+                let packed = builtin == Builtin::AbiEncodePacked;
+                let exprs = values.iter().map(|&v| self.cx.val(v)).collect();
+                return self.encode(expr.span, None, &tys, exprs, packed);
+            }
+            Builtin::AbiEncodeWithSelector | Builtin::AbiEncodeWithSignature => {
+                let selector = if builtin == Builtin::AbiEncodeWithSelector {
+                    self.expr1_to(&args[0], gcx.types.fixed_bytes(4))?
+                } else {
+                    let signature = self.expr1_to(&args[0], string_memory)?;
+                    let sig = self.cx.val(signature);
+                    let hash = b.call(
+                        "keccak256",
+                        [
+                            b.call("add", [sig, b.num(32)]),
+                            b.call("mload", [self.cx.val(signature)]),
+                        ],
+                    );
+                    let mask = Repr::FixedBytes(4).mask();
+                    self.define("selector", b.call("and", [hash, b.hex(mask)]))
+                };
+                let (tys, values) = self.encoded_args(&args[1..])?;
+                let exprs = values.iter().map(|&v| self.cx.val(v)).collect();
+                let selector = self.cx.val(selector);
+                return self.encode(expr.span, Some(selector), &tys, exprs, false);
+            }
+            Builtin::AbiEncodeCall => {
+                let Some(Res::Item(ItemId::Function(id))) =
+                    gcx.res_of_expr(args[0].peel_parens().id)
+                else {
+                    return Err(self
+                        .cx
+                        .unsupported(args[0].span, "`abi.encodeCall` with this function"));
+                };
+                let param_tys = gcx
+                    .item_parameter_types(id.into())
+                    .iter()
+                    .map(|&ty| self.memory_ty(ty))
+                    .collect::<Vec<_>>();
+                let call_args = match args.get(1).map(|a| &a.peel_parens().kind) {
+                    None => vec![],
+                    Some(ExprKind::Tuple(components)) if param_tys.len() != 1 => {
+                        components.iter().map(|c| c.unwrap()).collect()
+                    }
+                    Some(_) => vec![&args[1]],
+                };
+                let mut values = Vec::new();
+                for (arg, &ty) in call_args.into_iter().zip(&param_tys) {
+                    values.push(self.expr1_to(arg, ty)?);
+                }
+                let selector = selector_word(gcx.function_selector(id).0);
+                let exprs = values.iter().map(|&v| self.cx.val(v)).collect();
+                return self.encode(expr.span, Some(b.hex(selector)), &param_tys, exprs, false);
+            }
+            Builtin::AbiDecode => {
+                let data = self.expr1_to(&args[0], bytes_memory)?;
+                let ty = self.ty(expr);
+                let tys = match ty.kind {
+                    TyKind::Tuple(tys) => tys.to_vec(),
+                    _ => vec![ty],
+                };
+                let decode = self.cx.abi_decode(expr.span, &tys, true)?;
+                let start = self.define("start", b.call("add", [self.cx.val(data), b.num(32)]));
+                let end = b.call("add", [self.cx.val(start), b.call("mload", [self.cx.val(data)])]);
+                let call = b.call(decode, [self.cx.val(start), end]);
+                return Ok(self.call_values(call, tys.len()));
+            }
+            Builtin::StringConcat | Builtin::BytesConcat => {
+                let mut tys = Vec::new();
+                let mut values = Vec::new();
+                for arg in args {
+                    let ty = mobile_type(gcx, self.ty(arg));
+                    let ty = if is_byte_array(ty) {
+                        if builtin == Builtin::StringConcat {
+                            string_memory
+                        } else {
+                            bytes_memory
+                        }
+                    } else {
+                        ty
+                    };
+                    values.push(self.expr1_to(arg, ty)?);
+                    tys.push(ty);
+                }
+                let exprs = values.iter().map(|&v| self.cx.val(v)).collect();
+                return self.encode(expr.span, None, &tys, exprs, true);
+            }
+            Builtin::AddressPayableTransfer | Builtin::AddressPayableSend => {
+                let ExprKind::Member(base, _) = callee.kind else { unreachable!() };
+                let address = self.expr1(base)?;
+                let amount = self.expr1_to(&args[0], uint256)?;
+                let stipend = b.call("mul", [b.call("iszero", [self.cx.val(amount)]), b.num(2300)]);
+                let call = b.call(
+                    "call",
+                    [
+                        stipend,
+                        self.cx.val(address),
+                        self.cx.val(amount),
+                        b.num(0),
+                        b.num(0),
+                        b.num(0),
+                        b.num(0),
+                    ],
+                );
+                let success = self.define("success", call);
+                if builtin == Builtin::AddressPayableSend {
+                    return Ok(smallvec![success]);
+                }
+                let forward = self.cx.revert_forward();
+                self.push(
+                    b.if_(b.call("iszero", [self.cx.val(success)]), vec![b.call_stmt(forward, [])]),
+                );
+                return Ok(Values::new());
+            }
+            Builtin::AddressCall | Builtin::AddressDelegatecall | Builtin::AddressStaticcall => {
+                let ExprKind::Member(base, _) = callee.kind else { unreachable!() };
+                let address = self.expr1(base)?;
+                let (value, gas) = self.call_options(options)?;
+                let data = self.expr1_to(&args[0], bytes_memory)?;
+                let gas = gas.map_or_else(|| b.call("gas", []), |gas| self.cx.val(gas));
+                let input = b.call("add", [self.cx.val(data), b.num(32)]);
+                let size = b.call("mload", [self.cx.val(data)]);
+                let address = self.cx.val(address);
+                let call = match builtin {
+                    Builtin::AddressCall => {
+                        let value = self.cx.val(value.unwrap_or(Value::ZERO));
+                        b.call("call", [gas, address, value, input, size, b.num(0), b.num(0)])
+                    }
+                    Builtin::AddressDelegatecall => {
+                        b.call("delegatecall", [gas, address, input, size, b.num(0), b.num(0)])
+                    }
+                    _ => b.call("staticcall", [gas, address, input, size, b.num(0), b.num(0)]),
+                };
+                let success = self.define("success", call);
+                let extract = self.cx.extract_returndata();
+                let data = self.define("data", b.call(extract, []));
+                return Ok(smallvec![success, data]);
+            }
+            Builtin::UdvtWrap | Builtin::UdvtUnwrap => {
+                let to = self.ty(expr);
+                return self.expr_conv(&args[0], to, true);
+            }
+            _ => {
+                let what = format!("`{}`", builtin.name());
+                return Err(self.cx.unsupported(expr.span, &what));
+            }
+        };
+        Ok(smallvec![self.define("expr", value)])
+    }
+
+    /// Calls a precompiled contract, reverting if the call fails. The output is written at
+    /// memory address 0.
+    fn precompile(&mut self, address: u8, input: yul::Expr<'ast>, size: yul::Expr<'ast>) {
+        let b = self.b;
+        let call = b.call(
+            "staticcall",
+            [b.call("gas", []), b.num(address), input, size, b.num(0), b.num(32)],
+        );
+        let success = self.define("success", call);
+        let forward = self.cx.revert_forward();
+        self.push(b.if_(b.call("iszero", [self.cx.val(success)]), vec![b.call_stmt(forward, [])]));
+    }
+
+    /// Lowers the arguments of the `abi.encode` family of functions, returning their types.
+    fn encoded_args(&mut self, args: &[hir::Expr<'_>]) -> Result<(Vec<Ty<'gcx>>, Vec<Value>)> {
+        let mut tys = Vec::with_capacity(args.len());
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args {
+            let ty = self.memory_ty(mobile_type(self.gcx, self.ty(arg)));
+            values.push(self.expr1_to(arg, ty)?);
+            tys.push(ty);
+        }
+        Ok((tys, values))
+    }
+
+    /// ABI encodes values into a new byte array.
+    fn encode(
+        &mut self,
+        span: Span,
+        selector: Option<yul::Expr<'ast>>,
+        tys: &[Ty<'gcx>],
+        values: Vec<yul::Expr<'ast>>,
+        packed: bool,
+    ) -> Result<Values> {
+        let (stmts, mem) = self.cx.encode_to_memory(span, selector, tys, values, packed)?;
+        self.stmts.extend(stmts);
+        Ok(smallvec![Value::Var(mem)])
+    }
+
+    /// Converts values between types. Explicit conversions have already been validated by the
+    /// type checker, so they are lowered the same way as implicit ones.
+    pub(crate) fn convert(
+        &mut self,
+        span: Span,
+        values: Values,
+        from: Ty<'gcx>,
+        to: Ty<'gcx>,
+    ) -> Result<Values> {
+        let b = self.b;
+        if from == to {
+            return Ok(values);
+        }
+        if let (&TyKind::Tuple(froms), &TyKind::Tuple(tos)) = (&from.kind, &to.kind) {
+            let mut values = values.into_iter();
+            let mut result = Values::new();
+            for (&from, &to) in froms.iter().zip(tos) {
+                let component = values.by_ref().take(stack_size(from)).collect();
+                result.extend(self.convert(span, component, from, to)?);
+            }
+            return Ok(result);
+        }
+        let unsupported = |this: &mut Self| {
+            let what = format!(
+                "conversions from `{}` to `{}`",
+                from.display(this.gcx),
+                to.display(this.gcx)
+            );
+            Err(this.cx.unsupported(span, &what))
+        };
+        if from.is_ref() || to.is_ref() {
+            let (&TyKind::Ref(from_inner, from_loc), &TyKind::Ref(_, to_loc)) =
+                (&from.kind, &to.kind)
+            else {
+                return unsupported(self);
+            };
+            let is_storage = |loc| matches!(loc, DataLocation::Storage | DataLocation::Transient);
+            return match (is_storage(from_loc), is_storage(to_loc)) {
+                (false, false) | (true, true) => Ok(values),
+                (true, false) if is_byte_array(from_inner) => {
+                    let read = self.cx.read_storage(
+                        span,
+                        from_inner,
+                        values[0],
+                        Offset::Static(0),
+                        false,
+                    )?;
+                    Ok(smallvec![self.define("expr", read)])
+                }
+                _ => unsupported(self),
+            };
+        }
+        let (Some(from_repr), Some(to_repr)) = (Repr::of(from), Repr::of(to)) else {
+            return unsupported(self);
+        };
+        let value = values[0];
+        if let TyKind::Enum(id) = to.kind {
+            if !matches!(from.kind, TyKind::Enum(_)) {
+                let n = self.gcx.hir.enumm(id).variants.len();
+                let panic = self.cx.panic_stmt(0x21);
+                let valid = b.call("lt", [self.cx.val(value), b.num(n)]);
+                self.push(b.if_(b.call("iszero", [valid]), vec![panic]));
+                return Ok(smallvec![value]);
+            }
+        }
+        let converted = match (from_repr, to_repr) {
+            (from, to) if from == to => return Ok(smallvec![value]),
+            (Repr::Uint(f) | Repr::Int(f), Repr::Uint(t) | Repr::Int(t)) => {
+                let widening = t > f && (!from_repr.is_signed() || to_repr.is_signed());
+                if widening {
+                    return Ok(smallvec![value]);
+                }
+                self.cx.cleanup(to_repr, self.cx.val(value))
+            }
+            (Repr::Uint(_) | Repr::Int(_), Repr::FixedBytes(n)) => {
+                let value = self.cx.cleanup(Repr::Uint(8 * n as u16), self.cx.val(value));
+                shift(b, "shl", 256 - 8 * n as u32, value)
+            }
+            (Repr::FixedBytes(n), Repr::Uint(_) | Repr::Int(_)) => {
+                let value = shift(b, "shr", 256 - 8 * n as u32, self.cx.val(value));
+                self.cx.cleanup(to_repr, value)
+            }
+            (Repr::FixedBytes(f), Repr::FixedBytes(t)) => {
+                if t >= f {
+                    return Ok(smallvec![value]);
+                }
+                self.cx.cleanup(to_repr, self.cx.val(value))
+            }
+            (Repr::Bool, Repr::Bool) => return Ok(smallvec![value]),
+            _ => return unsupported(self),
+        };
+        Ok(smallvec![self.define("expr", converted)])
+    }
+}
+
+/// The selector of `Error(string)`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Returns a 4-byte selector as a `bytes4` word.
+fn selector_word(selector: [u8; 4]) -> U256 {
+    U256::from(u32::from_be_bytes(selector)) << 224
+}
+
+/// Returns `true` if the type is a reference to storage.
+fn is_storage(ty: Ty<'_>) -> bool {
+    ty.is_ref_at(DataLocation::Storage)
+        || ty.is_ref_at(DataLocation::Transient)
+        || matches!(ty.kind, TyKind::Mapping(..))
+}
+
+/// Returns `op(bits, value)`, or `value` if `bits` is 0.
+fn shift<'ast>(
+    b: crate::builder::YulBuilder<'ast>,
+    op: &str,
+    bits: u32,
+    value: yul::Expr<'ast>,
+) -> yul::Expr<'ast> {
+    if bits == 0 {
+        value
+    } else {
+        b.call(op, [b.num(bits), value])
+    }
+}
@@ -0,0 +1,530 @@
+//! Type representations and the helper functions shared by the other modules.
+
+use super::ObjectCx;
+use alloy_primitives::{hex, keccak256, U256};
+use num_bigint::BigInt;
+use num_traits::Signed;
+use solar_ast::ast::{yul, DataLocation, ElementaryType};
+use solar_data_structures::smallvec::SmallVec;
+use solar_interface::Symbol;
+use solar_sema::{
+    eval::ConstValue,
+    ty::{Gcx, Ty, TyKind},
+};
+use std::fmt::Write;
+
+/// A value on the Yul stack.
+///
+/// Values are either literals or variables that are never reassigned, so they can be used
+/// multiple times without evaluating anything twice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Value {
+    Lit(U256),
+    Var(Symbol),
+}
+
+impl Value {
+    pub(crate) const ZERO: Self = Self::Lit(U256::ZERO);
+}
+
+/// The stack slots of an expression. Tuples span multiple slots.
+pub(crate) type Values = SmallVec<[Value; 2]>;
+
+/// The representation of a value type in a stack slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Repr {
+    /// An unsigned integer with the given number of bits, zero-extended.
+    Uint(u16),
+    /// A signed integer with the given number of bits, sign-extended.
+    Int(u16),
+    /// `0` or `1`.
+    Bool,
+    /// A fixed bytes value with the given number of bytes, aligned to the left.
+    FixedBytes(u8),
+}
+
+impl Repr {
+    /// Returns the representation of the given type, if it is a supported value type.
+    pub(crate) fn of(ty: Ty<'_>) -> Option<Self> {
+        Some(match ty.kind {
+            TyKind::Elementary(ty) => match ty {
+                ElementaryType::Address(_) => Self::Uint(160),
+                ElementaryType::Bool => Self::Bool,
+                ElementaryType::Int(size) => Self::Int(size.bits()),
+                ElementaryType::UInt(size) => Self::Uint(size.bits()),
+                ElementaryType::FixedBytes(size) => Self::FixedBytes(size.bytes()),
+                _ => return None,
+            },
+            TyKind::Contract(_) => Self::Uint(160),
+            TyKind::Enum(_) => Self::Uint(8),
+            TyKind::Udvt(ty, _) => return Self::of(ty),
+            _ => return None,
+        })
+    }
+
+    /// Returns the number of bytes of the value.
+    pub(crate) fn bytes(self) -> u8 {
+        match self {
+            Self::Uint(bits) | Self::Int(bits) => (bits / 8) as u8,
+            Self::Bool => 1,
+            Self::FixedBytes(bytes) => bytes,
+        }
+    }
+
+    pub(crate) fn is_signed(self) -> bool {
+        matches!(self, Self::Int(_))
+    }
+
+    /// Returns the mask of the bits that hold the value.
+    pub(crate) fn mask(self) -> U256 {
+        match self {
+            Self::FixedBytes(bytes) => U256::MAX << (256 - 8 * bytes as usize),
+            _ => low_mask(self.bytes()),
+        }
+    }
+
+    /// Returns the minimum value of an integer type, as a word.
+    pub(crate) fn min(self) -> U256 {
+        match self {
+            Self::Int(bits) => U256::MAX << (bits as usize - 1),
+            _ => U256::ZERO,
+        }
+    }
+
+    /// Returns the maximum value of an integer type, as a word.
+    pub(crate) fn max(self) -> U256 {
+        match self {
+            Self::Int(bits) => low_mask((bits / 8) as u8) >> 1,
+            _ => self.mask(),
+        }
+    }
+}
+
+/// Returns a mask of the `bytes` lower-order bytes.
+pub(crate) fn low_mask(bytes: u8) -> U256 {
+    if bytes >= 32 {
+        U256::MAX
+    } else {
+        (U256::from(1) << (8 * bytes as usize)) - U256::from(1)
+    }
+}
+
+/// Returns `true` if the type is a number or string literal type, whose values are only known at
+/// compile time.
+pub(crate) fn is_literal(ty: Ty<'_>) -> bool {
+    match ty.kind {
+        TyKind::IntLiteral(..) | TyKind::RationalLiteral(_) | TyKind::StringLiteral(..) => true,
+        TyKind::Tuple(tys) => tys.iter().any(|&ty| is_literal(ty)),
+        _ => false,
+    }
+}
+
+/// Returns the type that a literal is converted to when it's used without an expected type.
+pub(crate) fn mobile_type<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> Ty<'gcx> {
+    match ty.kind {
+        TyKind::IntLiteral(false, bits) if bits <= 256 => {
+            gcx.types.uint(bits.max(8).next_multiple_of(8))
+        }
+        TyKind::IntLiteral(true, bits) if bits < 256 => {
+            gcx.types.int((bits + 1).max(8).next_multiple_of(8))
+        }
+        TyKind::StringLiteral(..) => gcx.types.string_ref.memory,
+        TyKind::Tuple(tys) => {
+            gcx.mk_ty(TyKind::Tuple(gcx.mk_ty_iter(tys.iter().map(|&ty| mobile_type(gcx, ty)))))
+        }
+        _ => ty,
+    }
+}
+
+/// Returns `true` if the type is `bytes` or `string`.
+pub(crate) fn is_byte_array(ty: Ty<'_>) -> bool {
+    matches!(
+        ty.peel_refs().kind,
+        TyKind::Elementary(ElementaryType::Bytes | ElementaryType::String)
+    )
+}
+
+/// Returns the number of stack slots that a value of the given type occupies.
+pub(crate) fn stack_size(ty: Ty<'_>) -> usize {
+    match ty.kind {
+        TyKind::Tuple(tys) => tys.iter().map(|&ty| stack_size(ty)).sum(),
+        TyKind::Type(_) | TyKind::Meta(_) | TyKind::Module(_) | TyKind::BuiltinModule(_) => 0,
+        _ => 1,
+    }
+}
+
+/// Returns the identifier of the type that is used in the names of helper functions, following
+/// the naming scheme of solc.
+pub(crate) fn type_id(gcx: Gcx<'_>, ty: Ty<'_>) -> String {
+    let mut s = String::new();
+    write_type_id(gcx, ty, &mut s);
+    s
+}
+
+fn write_type_id(gcx: Gcx<'_>, ty: Ty<'_>, s: &mut String) {
+    let _ = match ty.kind {
+        TyKind::Elementary(ty) => match ty {
+            ElementaryType::Address(false) => write!(s, "t_address"),
+            ElementaryType::Address(true) => write!(s, "t_address_payable"),
+            ElementaryType::Bool => write!(s, "t_bool"),
+            ElementaryType::String => write!(s, "t_string"),
+            ElementaryType::Bytes => write!(s, "t_bytes"),
+            ElementaryType::Int(size) => write!(s, "t_int{}", size.bits()),
+            ElementaryType::UInt(size) => write!(s, "t_uint{}", size.bits()),
+            ElementaryType::FixedBytes(size) => write!(s, "t_bytes{}", size.bytes()),
+            ElementaryType::Fixed(m, n) => write!(s, "t_fixed{}x{}", m.bits(), n.get()),
+            ElementaryType::UFixed(m, n) => write!(s, "t_ufixed{}x{}", m.bits(), n.get()),
+        },
+        TyKind::Ref(inner, loc) => {
+            write_type_id(gcx, inner, s);
+            match loc {
+                DataLocation::Storage | DataLocation::Transient => write!(s, "_storage"),
+                DataLocation::Memory => write!(s, "_memory_ptr"),
+                DataLocation::Calldata => write!(s, "_calldata_ptr"),
+            }
+        }
+        TyKind::DynArray(elem) => {
+            s.push_str("t_array$_");
+            write_type_id(gcx, elem, s);
+            write!(s, "_$dyn")
+        }
+        TyKind::Array(elem, len) => {
+            s.push_str("t_array$_");
+            write_type_id(gcx, elem, s);
+            write!(s, "_${len}")
+        }
+        TyKind::Mapping(key, value) => {
+            s.push_str("t_mapping$_");
+            write_type_id(gcx, key, s);
+            s.push_str("_$_");
+            write_type_id(gcx, value, s);
+            write!(s, "_$")
+        }
+        TyKind::Tuple(tys) => {
+            s.push_str("t_tuple$_");
+            for (i, &ty) in tys.iter().enumerate() {
+                if i > 0 {
+                    s.push_str("_$_");
+                }
+                write_type_id(gcx, ty, s);
+            }
+            write!(s, "_$")
+        }
+        TyKind::Contract(id) => write!(s, "t_contract$_{}_${}", gcx.item_name(id), id.get()),
+        TyKind::Struct(id) => write!(s, "t_struct$_{}_${}", gcx.item_name(id), id.get()),
+        TyKind::Enum(id) => write!(s, "t_enum$_{}_${}", gcx.item_name(id), id.get()),
+        TyKind::Udvt(_, id) => {
+            write!(s, "t_userDefinedValueType$_{}_${}", gcx.item_name(id), id.get())
+        }
+        TyKind::StringLiteral(..) => write!(s, "t_stringliteral"),
+        TyKind::IntLiteral(..) | TyKind::RationalLiteral(_) => write!(s, "t_rational"),
+        _ => write!(s, "t_unknown"),
+    };
+}
+
+/// Returns the identifiers of the given types, separated by `sep`.
+pub(crate) fn type_ids(gcx: Gcx<'_>, tys: &[Ty<'_>], sep: &str) -> String {
+    tys.iter().map(|&ty| type_id(gcx, ty)).collect::<Vec<_>>().join(sep)
+}
+
+/// Returns the value of a compile-time constant as a word, if it fits in one.
+pub(crate) fn const_word(value: &ConstValue) -> Option<U256> {
+    match value {
+        ConstValue::Int(_) | ConstValue::Rational(_) => Some(int_word(&value.to_integer()?)),
+        ConstValue::Bool(b) => Some(U256::from(*b as u8)),
+        ConstValue::Address(address) => Some(address.into_word().into()),
+        ConstValue::FixedBytes(data, _) => Some((*data).into()),
+        ConstValue::String(_) | ConstValue::Tuple(_) => None,
+    }
+}
+
+/// Returns the two's complement representation of the integer as a word.
+fn int_word(value: &BigInt) -> U256 {
+    let (_, bytes) = value.abs().to_bytes_be();
+    let abs = U256::try_from_be_slice(&bytes).unwrap_or(U256::MAX);
+    if value.is_negative() {
+        abs.wrapping_neg()
+    } else {
+        abs
+    }
+}
+
+/// Returns the name suffix that identifies the given bytes, for helper functions specific to a
+/// literal.
+pub(crate) fn literal_id(bytes: &[u8]) -> String {
+    hex::encode(keccak256(bytes))
+}
+
+/// Returns the words of the given bytes, padded with zeros on the right.
+pub(crate) fn literal_words(bytes: &[u8]) -> impl Iterator<Item = U256> + '_ {
+    bytes.chunks(32).map(|chunk| {
+        let mut word = [0u8; 32];
+        word[..chunk.len()].copy_from_slice(chunk);
+        U256::from_be_bytes(word)
+    })
+}
+
+impl<'ast> ObjectCx<'ast, '_> {
+    /// Returns the expression of a value.
+    pub(crate) fn val(&self, value: Value) -> yul::Expr<'ast> {
+        match value {
+            Value::Lit(value) => self.b.num(value),
+            Value::Var(name) => self.b.path(name),
+        }
+    }
+
+    /// Returns an expression that cleans up the dirty higher-order bits of a value of the given
+    /// representation.
+    pub(crate) fn cleanup(&self, repr: Repr, value: yul::Expr<'ast>) -> yul::Expr<'ast> {
+        let b = self.b;
+        match repr {
+            Repr::Uint(bits) if bits < 256 => b.call("and", [value, b.hex(repr.mask())]),
+            Repr::Int(bits) if bits < 256 => b.call("signextend", [b.num(bits / 8 - 1), value]),
+            Repr::Bool => b.call("iszero", [b.call("iszero", [value])]),
+            Repr::FixedBytes(bytes) if bytes < 32 => b.call("and", [value, b.hex(repr.mask())]),
+            _ => value,
+        }
+    }
+
+    /// `panic_error_0x<code>()`: reverts with `Panic(code)`.
+    pub(crate) fn panic(&mut self, code: u8) -> Symbol {
+        self.helper(format!("panic_error_{code:#04x}"), |cx, name| {
+            let b = cx.b;
+            b.function(
+                name,
+                [],
+                [],
+                vec![
+                    b.call_stmt(
+                        "mstore",
+                        [b.num(0), b.call("shl", [b.num(224), b.hex(0x4e487b71u32)])],
+                    ),
+                    b.call_stmt("mstore", [b.num(4), b.hex(code)]),
+                    b.call_stmt("revert", [b.num(0), b.hex(0x24)]),
+                ],
+            )
+        })
+    }
+
+    /// Returns a statement that calls `panic_error_0x<code>()`.
+    pub(crate) fn panic_stmt(&mut self, code: u8) -> yul::Stmt<'ast> {
+        let panic = self.panic(code);
+        self.b.call_stmt(panic, [])
+    }
+
+    /// Returns `revert(0, 0)`.
+    pub(crate) fn revert_empty(&self) -> yul::Stmt<'ast> {
+        self.b.call_stmt("revert", [self.b.num(0), self.b.num(0)])
+    }
+
+    /// `revert_forward()`: reverts with the return data of the last call.
+    pub(crate) fn revert_forward(&mut self) -> Symbol {
+        self.helper("revert_forward", |cx, name| {
+            let b = cx.b;
+            let allocate = cx.allocate_unbounded();
+            b.function(
+                name,
+                [],
+                [],
+                vec![
+                    b.let1("pos", b.call(allocate, [])),
+                    b.call_stmt(
+                        "returndatacopy",
+                        [b.path("pos"), b.num(0), b.call("returndatasize", [])],
+                    ),
+                    b.call_stmt("revert", [b.path("pos"), b.call("returndatasize", [])]),
+                ],
+            )
+        })
+    }
+
+    /// `allocate_unbounded() -> memPtr`: returns the free memory pointer without updating it.
+    pub(crate) fn allocate_unbounded(&mut self) -> Symbol {
+        self.helper("allocate_unbounded", |cx, name| {
+            let b = cx.b;
+            b.function(
+                name,
+                [],
+                [sym("memPtr")],
+                vec![b.assign1("memPtr", b.call("mload", [b.num(64)]))],
+            )
+        })
+    }
+
+    /// `round_up_to_mul_of_32(value) -> result`
+    pub(crate) fn round_up_to_mul_of_32(&mut self) -> Symbol {
+        self.helper("round_up_to_mul_of_32", |cx, name| {
+            let b = cx.b;
+            b.function(
+                name,
+                [sym("value")],
+                [sym("result")],
+                vec![b.assign1(
+                    "result",
+                    b.call(
+                        "and",
+                        [b.call("add", [b.path("value"), b.num(31)]), b.call("not", [b.num(31)])],
+                    ),
+                )],
+            )
+        })
+    }
+
+    /// `finalize_allocation(memPtr, size)`: moves the free memory pointer past `size` bytes
+    /// from `memPtr`, rounded up to a multiple of 32.
+    pub(crate) fn finalize_allocation(&mut self) -> Symbol {
+        self.helper("finalize_allocation", |cx, name| {
+            let b = cx.b;
+            let round_up = cx.round_up_to_mul_of_32();
+            let panic = cx.panic_stmt(0x41);
+            b.function(
+                name,
+                [sym("memPtr"), sym("size")],
+                [],
+                vec![
+                    b.let1(
+                        "newFreePtr",
+                        b.call("add", [b.path("memPtr"), b.call(round_up, [b.path("size")])]),
+                    ),
+                    b.if_(
+                        b.call(
+                            "or",
+                            [
+                                b.call("gt", [b.path("newFreePtr"), b.hex(u64::MAX)]),
+                                b.call("lt", [b.path("newFreePtr"), b.path("memPtr")]),
+                            ],
+                        ),
+                        vec![panic],
+                    ),
+                    b.call_stmt("mstore", [b.num(64), b.path("newFreePtr")]),
+                ],
+            )
+        })
+    }
+
+    /// `allocate_memory(size) -> memPtr`
+    pub(crate) fn allocate_memory(&mut self) -> Symbol {
+        self.helper("allocate_memory", |cx, name| {
+            let b = cx.b;
+            let allocate = cx.allocate_unbounded();
+            let finalize = cx.finalize_allocation();
+            b.function(
+                name,
+                [sym("size")],
+                [sym("memPtr")],
+                vec![
+                    b.assign1("memPtr", b.call(allocate, [])),
+                    b.call_stmt(finalize, [b.path("memPtr"), b.path("size")]),
+                ],
+            )
+        })
+    }
+
+    /// `allocate_memory_array_t_bytes(length) -> memPtr`: allocates a byte array of the given
+    /// length, and stores the length.
+    pub(crate) fn allocate_byte_array(&mut self) -> Symbol {
+        self.helper("allocate_memory_array_t_bytes", |cx, name| {
+            let b = cx.b;
+            let allocate = cx.allocate_memory();
+            let round_up = cx.round_up_to_mul_of_32();
+            let panic = cx.panic_stmt(0x41);
+            b.function(
+                name,
+                [sym("length")],
+                [sym("memPtr")],
+                vec![
+                    b.if_(b.call("gt", [b.path("length"), b.hex(u64::MAX)]), vec![panic]),
+                    b.assign1(
+                        "memPtr",
+                        b.call(
+                            allocate,
+                            [b.call("add", [b.call(round_up, [b.path("length")]), b.num(32)])],
+                        ),
+                    ),
+                    b.call_stmt("mstore", [b.path("memPtr"), b.path("length")]),
+                ],
+            )
+        })
+    }
+
+    /// `copy_memory_to_memory_with_cleanup(src, dst, length)`: copies `length` bytes and
+    /// zeroes the word after them.
+    pub(crate) fn copy_memory_to_memory(&mut self) -> Symbol {
+        self.helper("copy_memory_to_memory_with_cleanup", |cx, name| {
+            let b = cx.b;
+            let mut body = Vec::new();
+            if cx.gcx.sess.evm_version.has_mcopy() {
+                body.push(b.call_stmt("mcopy", [b.path("dst"), b.path("src"), b.path("length")]));
+            } else {
+                body.push(b.let1("i", b.num(0)));
+                body.push(b.for_(
+                    vec![],
+                    b.call("lt", [b.path("i"), b.path("length")]),
+                    vec![b.assign1("i", b.call("add", [b.path("i"), b.num(32)]))],
+                    vec![b.call_stmt(
+                        "mstore",
+                        [
+                            b.call("add", [b.path("dst"), b.path("i")]),
+                            b.call("mload", [b.call("add", [b.path("src"), b.path("i")])]),
+                        ],
+                    )],
+                ));
+            }
+            body.push(
+                b.call_stmt("mstore", [b.call("add", [b.path("dst"), b.path("length")]), b.num(0)]),
+            );
+            b.function(name, [sym("src"), sym("dst"), sym("length")], [], body)
+        })
+    }
+
+    /// `copy_literal_to_memory_<hash>() -> memPtr`: allocates a byte array with the contents of
+    /// a literal.
+    pub(crate) fn copy_literal_to_memory(&mut self, bytes: &[u8]) -> Symbol {
+        self.helper(format!("copy_literal_to_memory_{}", literal_id(bytes)), |cx, name| {
+            let b = cx.b;
+            let allocate = cx.allocate_byte_array();
+            let mut body = vec![b.assign1("memPtr", b.call(allocate, [b.num(bytes.len())]))];
+            for (i, word) in literal_words(bytes).enumerate() {
+                body.push(b.call_stmt(
+                    "mstore",
+                    [b.call("add", [b.path("memPtr"), b.num(32 * (i + 1))]), b.hex(word)],
+                ));
+            }
+            b.function(name, [], [sym("memPtr")], body)
+        })
+    }
+
+    /// `extract_returndata() -> data`: copies the return data of the last call to a new byte
+    /// array.
+    pub(crate) fn extract_returndata(&mut self) -> Symbol {
+        self.helper("extract_returndata", |cx, name| {
+            let b = cx.b;
+            let allocate = cx.allocate_byte_array();
+            b.function(
+                name,
+                [],
+                [sym("data")],
+                vec![b.switch(
+                    b.call("returndatasize", []),
+                    [(b.num(0), vec![b.assign1("data", b.num(96))])],
+                    Some(vec![
+                        b.assign1("data", b.call(allocate, [b.call("returndatasize", [])])),
+                        b.call_stmt(
+                            "returndatacopy",
+                            [
+                                b.call("add", [b.path("data"), b.num(32)]),
+                                b.num(0),
+                                b.call("returndatasize", []),
+                            ],
+                        ),
+                    ]),
+                )],
+            )
+        })
+    }
+}
+
+/// Interns a symbol.
+pub(crate) fn sym(s: &str) -> Symbol {
+    Symbol::intern(s)
+}
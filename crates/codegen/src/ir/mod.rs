@@ -0,0 +1,273 @@
+//! Lowering of type-checked contracts into Yul objects.
+//!
+//! Each deployable contract is lowered into a creation object, which runs the constructors and
+//! returns the runtime code, and a runtime object, which dispatches the calls to the external
+//! functions. The generated code follows the structure of the code generated by `solc --ir`, so
+//! that the two can be compared.
+
+use crate::builder::YulBuilder;
+use alloy_primitives::U256;
+use solar_ast::ast::{yul, VarMut};
+use solar_data_structures::map::FxHashMap;
+use solar_interface::{diagnostics::ErrorGuaranteed, Result, Span, Symbol};
+use solar_sema::{hir, ty::Gcx};
+use std::collections::{btree_map, BTreeMap};
+
+mod abi;
+mod contract;
+mod expr;
+mod helpers;
+mod stmt;
+mod storage;
+
+pub(crate) use contract::lower_contract;
+pub(crate) use helpers::Value;
+
+/// Which of the two objects of a contract is being generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ObjectKind {
+    /// The creation code, which runs the constructors.
+    Creation,
+    /// The runtime code, which is returned by the creation code.
+    Runtime,
+}
+
+/// The location of a state variable in storage.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct StorageLocation {
+    pub(crate) slot: U256,
+    pub(crate) offset: u8,
+    pub(crate) transient: bool,
+}
+
+/// The state shared by all the functions of a Yul object.
+pub(crate) struct ObjectCx<'ast, 'gcx> {
+    pub(crate) gcx: Gcx<'gcx>,
+    pub(crate) b: YulBuilder<'ast>,
+    /// The contract being compiled.
+    pub(crate) contract: hir::ContractId,
+    pub(crate) kind: ObjectKind,
+    /// The functions of the object, sorted by name.
+    ///
+    /// Functions that are being generated are `None`.
+    functions: BTreeMap<String, Option<yul::Stmt<'ast>>>,
+    /// The internal functions and modifiers that were referenced but not generated yet.
+    queue: Vec<QueuedFunction>,
+    /// The locations of the state variables in storage.
+    storage: FxHashMap<hir::VariableId, StorageLocation>,
+    /// The immutable variables, in the order of their memory slots in the creation code.
+    immutables: Vec<hir::VariableId>,
+    /// The counter for the names of temporary variables.
+    tmp: u32,
+    /// Set if an error was emitted while generating the object.
+    guar: Option<ErrorGuaranteed>,
+}
+
+/// A function whose generation was deferred.
+#[derive(Clone, Copy, Debug)]
+enum QueuedFunction {
+    /// The function itself, including the modifiers.
+    Function(hir::FunctionId),
+    /// The body of a function that has modifiers.
+    Body(hir::FunctionId),
+    /// The `index`-th modifier of a function.
+    Modifier(hir::FunctionId, usize),
+}
+
+impl<'ast, 'gcx> ObjectCx<'ast, 'gcx> {
+    pub(crate) fn new(
+        gcx: Gcx<'gcx>,
+        b: YulBuilder<'ast>,
+        contract: hir::ContractId,
+        kind: ObjectKind,
+    ) -> Self {
+        let layout = gcx.storage_layout(contract);
+        let storage = layout
+            .storage
+            .iter()
+            .map(|item| (item, false))
+            .chain(layout.transient.iter().map(|item| (item, true)))
+            .map(|(item, transient)| {
+                let loc = StorageLocation { slot: item.slot, offset: item.offset, transient };
+                (item.variable, loc)
+            })
+            .collect();
+        let immutables = gcx
+            .hir
+            .contract(contract)
+            .linearized_bases
+            .iter()
+            .rev()
+            .flat_map(|&base| gcx.hir.contract(base).variables())
+            .filter(|&var| gcx.hir.variable(var).mutability == Some(VarMut::Immutable))
+            .collect();
+        Self {
+            gcx,
+            b,
+            contract,
+            kind,
+            functions: BTreeMap::new(),
+            queue: Vec::new(),
+            storage,
+            immutables,
+            tmp: 0,
+            guar: None,
+        }
+    }
+
+    /// Emits an error for a construct that is not supported by the code generator.
+    pub(crate) fn unsupported(&self, span: Span, what: &str) -> ErrorGuaranteed {
+        let msg = format!("the code generator does not support {what} yet");
+        self.gcx.dcx().err(msg).span(span).emit()
+    }
+
+    /// Returns a new unique name for a temporary variable.
+    pub(crate) fn fresh(&mut self, prefix: &str) -> Symbol {
+        self.tmp += 1;
+        Symbol::intern(&format!("{prefix}_{}", self.tmp))
+    }
+
+    /// Returns the name of the helper function with the given name, generating it with `build`
+    /// if it does not exist yet.
+    pub(crate) fn helper(
+        &mut self,
+        name: impl Into<String>,
+        build: impl FnOnce(&mut Self, Symbol) -> yul::Stmt<'ast>,
+    ) -> Symbol {
+        let name = name.into();
+        let sym = Symbol::intern(&name);
+        if !self.functions.contains_key(&name) {
+            self.functions.insert(name.clone(), None);
+            let function = build(self, sym);
+            self.functions.insert(name, Some(function));
+        }
+        sym
+    }
+
+    /// Same as [`helper`](Self::helper), but the function may fail to be generated.
+    pub(crate) fn try_helper(
+        &mut self,
+        name: impl Into<String>,
+        build: impl FnOnce(&mut Self, Symbol) -> Result<yul::Stmt<'ast>>,
+    ) -> Result<Symbol> {
+        let name = name.into();
+        let sym = Symbol::intern(&name);
+        if !self.functions.contains_key(&name) {
+            self.functions.insert(name.clone(), None);
+            let function = build(self, sym)?;
+            self.functions.insert(name, Some(function));
+        }
+        Ok(sym)
+    }
+
+    /// Returns the name of the Yul function that implements the given function, queueing its
+    /// generation.
+    pub(crate) fn function(&mut self, id: hir::FunctionId) -> Symbol {
+        self.queued(function_name(self.gcx, id), QueuedFunction::Function(id))
+    }
+
+    /// Returns the name of the Yul function that implements the body of a function with
+    /// modifiers.
+    pub(crate) fn function_body(&mut self, id: hir::FunctionId) -> Symbol {
+        let name = format!("{}_inner", function_name(self.gcx, id));
+        self.queued(name, QueuedFunction::Body(id))
+    }
+
+    /// Returns the name of the Yul function that implements the `index`-th modifier of the given
+    /// function.
+    pub(crate) fn modifier(&mut self, id: hir::FunctionId, index: usize) -> Symbol {
+        let f = self.gcx.hir.function(id);
+        let hir::ItemId::Function(modifier) = f.modifiers[index].id else { unreachable!() };
+        let name = self.gcx.item_name(modifier);
+        let name = format!("modifier_{name}_{index}_{}", id.get());
+        self.queued(name, QueuedFunction::Modifier(id, index))
+    }
+
+    fn queued(&mut self, name: String, f: QueuedFunction) -> Symbol {
+        let sym = Symbol::intern(&name);
+        if let btree_map::Entry::Vacant(entry) = self.functions.entry(name) {
+            entry.insert(None);
+            self.queue.push(f);
+        }
+        sym
+    }
+
+    /// Generates the queued functions, and the functions they reference.
+    pub(crate) fn generate_queued(&mut self) {
+        while let Some(f) = self.queue.pop() {
+            let (name, function) = match f {
+                QueuedFunction::Function(id) => {
+                    (function_name(self.gcx, id), stmt::lower_function(self, id))
+                }
+                QueuedFunction::Body(id) => (
+                    format!("{}_inner", function_name(self.gcx, id)),
+                    stmt::lower_function_body(self, id),
+                ),
+                QueuedFunction::Modifier(id, index) => {
+                    let sym = self.modifier(id, index);
+                    (sym.as_str().to_string(), stmt::lower_modifier(self, id, index))
+                }
+            };
+            match function {
+                Ok(function) => {
+                    self.functions.insert(name, Some(function));
+                }
+                Err(guar) => self.guar = Some(guar),
+            }
+        }
+    }
+
+    /// Records an error, so that the object is not emitted.
+    pub(crate) fn record_err<T>(&mut self, result: Result<T>) -> Option<T> {
+        result.map_err(|guar| self.guar = Some(guar)).ok()
+    }
+
+    /// Returns the generated functions, sorted by name, or the error that prevented the object from
+    /// being generated.
+    pub(crate) fn finish(mut self) -> Result<Vec<yul::Stmt<'ast>>> {
+        self.generate_queued();
+        if let Some(guar) = self.guar {
+            return Err(guar);
+        }
+        Ok(self.functions.into_values().flatten().collect())
+    }
+
+    /// Returns the location of the given state variable in storage.
+    pub(crate) fn storage_location(&self, var: hir::VariableId) -> Option<StorageLocation> {
+        self.storage.get(&var).copied()
+    }
+
+    /// Returns the memory address of the given immutable variable in the creation code.
+    pub(crate) fn immutable_address(&self, var: hir::VariableId) -> U256 {
+        let index = self.immutables.iter().position(|&v| v == var).expect("not an immutable");
+        U256::from(128 + 32 * index)
+    }
+
+    /// Returns the first memory address that is not used to store immutable variables.
+    pub(crate) fn memory_start(&self) -> U256 {
+        U256::from(128 + 32 * self.immutables.len())
+    }
+}
+
+/// Returns the name of the Yul function that implements the given function.
+pub(crate) fn function_name(gcx: Gcx<'_>, id: hir::FunctionId) -> String {
+    let f = gcx.hir.function(id);
+    match f.name {
+        Some(name) => format!("fun_{name}_{}", id.get()),
+        None => format!("fun_{}_{}", f.kind, id.get()),
+    }
+}
+
+/// Returns the name of the Yul variable that holds the given local variable or parameter.
+pub(crate) fn variable_name(gcx: Gcx<'_>, id: hir::VariableId) -> Symbol {
+    let name = match gcx.hir.variable(id).name {
+        Some(name) => format!("var_{name}_{}", id.get()),
+        None => format!("var_{}", id.get()),
+    };
+    Symbol::intern(&name)
+}
+
+/// Returns the name of the object of the given contract.
+pub(crate) fn object_name(gcx: Gcx<'_>, id: hir::ContractId) -> String {
+    format!("{}_{}", gcx.hir.contract(id).name, id.get())
+}
@@ -0,0 +1,621 @@
+//! Lowering of functions, modifiers, and statements.
+
+use super::{
+    function_name,
+    helpers::{is_byte_array, stack_size, Repr, Values},
+    storage::Offset,
+    variable_name, ObjectCx, ObjectKind, Value,
+};
+use crate::builder::YulBuilder;
+use solar_ast::ast::{yul, DataLocation, VarMut};
+use solar_interface::{Result, Span, Symbol};
+use solar_sema::{
+    hir::{self, CallArgs},
+    ty::{Gcx, Ty, TyKind},
+};
+
+/// The state of the function that is being generated.
+pub(crate) struct FnCx<'a, 'ast, 'gcx> {
+    pub(crate) cx: &'a mut ObjectCx<'ast, 'gcx>,
+    pub(crate) gcx: Gcx<'gcx>,
+    pub(crate) b: YulBuilder<'ast>,
+    /// The function or modifier whose body is being lowered. Used to resolve `super`.
+    pub(crate) function: Option<hir::FunctionId>,
+    /// The statements of the current block.
+    pub(crate) stmts: Vec<yul::Stmt<'ast>>,
+    /// Whether arithmetic wraps on overflow, inside of `unchecked` blocks.
+    pub(crate) unchecked: bool,
+    /// The return variables of the function, with their types.
+    rets: Vec<(Symbol, Ty<'gcx>)>,
+    /// In modifiers, the function that `_` calls, and its arguments.
+    placeholder: Option<(Symbol, Vec<Symbol>)>,
+}
+
+impl<'a, 'ast, 'gcx> FnCx<'a, 'ast, 'gcx> {
+    pub(crate) fn new(cx: &'a mut ObjectCx<'ast, 'gcx>, function: Option<hir::FunctionId>) -> Self {
+        let (gcx, b) = (cx.gcx, cx.b);
+        Self {
+            cx,
+            gcx,
+            b,
+            function,
+            stmts: Vec::new(),
+            unchecked: false,
+            rets: Vec::new(),
+            placeholder: None,
+        }
+    }
+
+    /// Appends a statement to the current block.
+    pub(crate) fn push(&mut self, stmt: yul::Stmt<'ast>) {
+        self.stmts.push(stmt);
+    }
+
+    /// Runs `f` with an empty block, returning the statements it generated.
+    pub(crate) fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<(Vec<yul::Stmt<'ast>>, T)> {
+        let outer = std::mem::take(&mut self.stmts);
+        let result = f(self);
+        let stmts = std::mem::replace(&mut self.stmts, outer);
+        result.map(|value| (stmts, value))
+    }
+
+    /// Declares the return variables of a function, initializing memory pointers to the empty
+    /// array at `0x60`.
+    fn init_rets(&mut self, returns: &[hir::VariableId]) {
+        for &ret in returns {
+            let name = variable_name(self.gcx, ret);
+            let ty = self.gcx.type_of_item(ret.into());
+            if ty.is_ref_at(DataLocation::Memory) || ty.is_ref_at(DataLocation::Calldata) {
+                self.push(self.b.assign1(name, self.b.num(0x60)));
+            }
+            self.rets.push((name, ty));
+        }
+    }
+
+    fn ret_names(&self) -> Vec<Symbol> {
+        self.rets.iter().map(|&(name, _)| name).collect()
+    }
+
+    /// Lowers a function body.
+    fn body(&mut self, span: Span, body: Option<hir::Block<'_>>) -> Result<()> {
+        match body {
+            Some(body) => self.block(body),
+            None => Err(self.cx.unsupported(span, "functions without implementation")),
+        }
+    }
+
+    /// Lowers the statements of a block into the current block.
+    fn block(&mut self, stmts: &[hir::Stmt<'_>]) -> Result<()> {
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+        Ok(())
+    }
+
+    /// Lowers a statement into a new block, inlining the statements of blocks.
+    fn branch(&mut self, stmt: &hir::Stmt<'_>) -> Result<Vec<yul::Stmt<'ast>>> {
+        self.nested(|this| match stmt.kind {
+            hir::StmtKind::Block(stmts) => this.block(stmts),
+            _ => this.stmt(stmt),
+        })
+        .map(|(stmts, ())| stmts)
+    }
+
+    fn stmt(&mut self, stmt: &hir::Stmt<'_>) -> Result<()> {
+        let b = self.b;
+        match stmt.kind {
+            hir::StmtKind::DeclSingle(id) => {
+                let var = self.gcx.hir.variable(id);
+                let ty = self.gcx.type_of_item(id.into());
+                let value = match var.initializer {
+                    Some(init) => self.expr_to(init, ty)?[0],
+                    None => self.zero_value(var.span, ty)?,
+                };
+                let value = self.cx.val(value);
+                self.push(b.let1(variable_name(self.gcx, id), value));
+            }
+            hir::StmtKind::DeclMulti(vars, init) => {
+                let ty = self.ty(init);
+                let values = self.expr(init)?;
+                let tys = match ty.kind {
+                    TyKind::Tuple(tys) => tys,
+                    _ => std::slice::from_ref(&ty),
+                };
+                let mut values = values.into_iter();
+                for (&var, &from) in vars.iter().zip(tys) {
+                    let component: Values = values.by_ref().take(stack_size(from)).collect();
+                    let Some(var) = var else { continue };
+                    let to = self.gcx.type_of_item(var.into());
+                    let value = self.convert(init.span, component, from, to)?[0];
+                    self.push(b.let1(variable_name(self.gcx, var), self.cx.val(value)));
+                }
+            }
+            hir::StmtKind::Block(stmts) => {
+                let (stmts, ()) = self.nested(|this| this.block(stmts))?;
+                self.push(b.block_stmt(stmts));
+            }
+            hir::StmtKind::UncheckedBlock(stmts) => {
+                let unchecked = std::mem::replace(&mut self.unchecked, true);
+                let result = self.nested(|this| this.block(stmts));
+                self.unchecked = unchecked;
+                let (stmts, ()) = result?;
+                self.push(b.block_stmt(stmts));
+            }
+            hir::StmtKind::Emit(callee, ref args) => self.emit(callee, args)?,
+            hir::StmtKind::Revert(callee, ref args) => {
+                let Some(hir::Res::Item(hir::ItemId::Error(id))) = self.gcx.res_of_expr(callee.id)
+                else {
+                    unreachable!("revert of a non-error")
+                };
+                let params = self.gcx.item_parameters(id.into());
+                let tys = self.gcx.item_parameter_types(id.into());
+                let args = ordered_args(self.gcx, args, params);
+                let mut values = Vec::with_capacity(args.len());
+                let mut encoded = Vec::with_capacity(args.len());
+                for (arg, &ty) in args.into_iter().zip(tys) {
+                    let ty = self.memory_ty(ty);
+                    values.push(self.expr_to(arg, ty)?[0]);
+                    encoded.push(ty);
+                }
+                let selector = self.gcx.function_selector(id);
+                self.revert_with(stmt.span, selector.0, &encoded, &values)?;
+            }
+            hir::StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    let tys = self.rets.iter().map(|&(_, ty)| ty).collect::<Vec<_>>();
+                    let to = match tys[..] {
+                        [ty] => ty,
+                        _ => self.gcx.mk_ty(TyKind::Tuple(self.gcx.mk_ty_iter(tys.into_iter()))),
+                    };
+                    let values = self.expr_to(expr, to)?;
+                    for (name, value) in self.ret_names().into_iter().zip(values) {
+                        self.push(b.assign1(name, self.cx.val(value)));
+                    }
+                }
+                self.push(b.leave());
+            }
+            hir::StmtKind::Break => self.push(b.break_()),
+            hir::StmtKind::Continue => self.push(b.continue_()),
+            hir::StmtKind::Loop(block, source) => self.lower_loop(block, source)?,
+            hir::StmtKind::If(cond, then, else_) => {
+                let cond = self.expr_to(cond, self.gcx.types.bool)?[0];
+                let cond = self.cx.val(cond);
+                let then = self.branch(then)?;
+                let stmt = match else_ {
+                    Some(else_) => {
+                        let else_ = self.branch(else_)?;
+                        b.switch(cond, [(b.num(0), else_)], Some(then))
+                    }
+                    None => b.if_(cond, then),
+                };
+                self.push(stmt);
+            }
+            hir::StmtKind::Try(_) => {
+                return Err(self.cx.unsupported(stmt.span, "`try` statements"));
+            }
+            hir::StmtKind::Assembly(_) => {
+                return Err(self.cx.unsupported(stmt.span, "inline assembly"));
+            }
+            hir::StmtKind::Expr(expr) => {
+                self.expr(expr)?;
+            }
+            hir::StmtKind::Placeholder => {
+                let (next, args) = self.placeholder.clone().expect("placeholder outside modifier");
+                let call = b.call(next, args.into_iter().map(|arg| b.path(arg)));
+                let stmt = if self.rets.is_empty() {
+                    b.expr_stmt(call)
+                } else {
+                    b.assign(self.ret_names(), call)
+                };
+                self.push(stmt);
+            }
+            hir::StmtKind::Err(guar) => return Err(guar),
+        }
+        Ok(())
+    }
+
+    /// Lowers a loop into a Yul `for` loop, recovering the parts of the source loop from its
+    /// desugaring.
+    fn lower_loop(&mut self, block: hir::Block<'_>, source: hir::LoopSource) -> Result<()> {
+        let b = self.b;
+        let (init, step, body) = match (source, block) {
+            (
+                hir::LoopSource::While,
+                [hir::Stmt { kind: hir::StmtKind::If(cond, body, _), .. }],
+            ) => {
+                let (body, ()) = self.nested(|this| {
+                    this.break_unless(cond)?;
+                    this.inline(body)
+                })?;
+                (vec![], vec![], body)
+            }
+            (hir::LoopSource::For, [stmt]) => {
+                let (cond, inner) = match stmt.kind {
+                    hir::StmtKind::If(cond, inner, Some(_)) if inner.span == stmt.span => {
+                        (Some(cond), inner)
+                    }
+                    _ => (None, stmt),
+                };
+                let (body, next) = match inner.kind {
+                    hir::StmtKind::Block(
+                        [body, hir::Stmt { kind: hir::StmtKind::Expr(next), .. }],
+                    ) if body.span == inner.span => (body, Some(next)),
+                    _ => (inner, None),
+                };
+                let (step, ()) = self.nested(|this| {
+                    if let Some(next) = next {
+                        this.expr(next)?;
+                    }
+                    Ok(())
+                })?;
+                let (body, ()) = self.nested(|this| {
+                    if let Some(cond) = cond {
+                        this.break_unless(cond)?;
+                    }
+                    this.inline(body)
+                })?;
+                (vec![], step, body)
+            }
+            (
+                hir::LoopSource::DoWhile,
+                [body, hir::Stmt { kind: hir::StmtKind::If(cond, _, _), .. }],
+            ) => {
+                let first_run = self.cx.fresh("firstRun");
+                let (check, ()) = self.nested(|this| this.break_unless(cond))?;
+                let (body, ()) = self.nested(|this| {
+                    this.push(b.if_(b.call("iszero", [b.path(first_run)]), check));
+                    this.inline(body)
+                })?;
+                (vec![b.let1(first_run, b.num(1))], vec![b.assign1(first_run, b.num(0))], body)
+            }
+            _ => {
+                let (body, ()) = self.nested(|this| this.block(block))?;
+                (vec![], vec![], body)
+            }
+        };
+        self.push(b.for_(init, b.num(1), step, body));
+        Ok(())
+    }
+
+    /// Lowers a statement into the current block, inlining the statements of blocks.
+    fn inline(&mut self, stmt: &hir::Stmt<'_>) -> Result<()> {
+        match stmt.kind {
+            hir::StmtKind::Block(stmts) => self.block(stmts),
+            _ => self.stmt(stmt),
+        }
+    }
+
+    /// Breaks out of the current loop if the condition is false.
+    fn break_unless(&mut self, cond: &hir::Expr<'_>) -> Result<()> {
+        let cond = self.expr_to(cond, self.gcx.types.bool)?[0];
+        let cond = self.b.call("iszero", [self.cx.val(cond)]);
+        self.push(self.b.if_(cond, vec![self.b.break_()]));
+        Ok(())
+    }
+
+    fn emit(&mut self, callee: &hir::Expr<'_>, args: &CallArgs<'_>) -> Result<()> {
+        let b = self.b;
+        let Some(hir::Res::Item(hir::ItemId::Event(id))) = self.gcx.res_of_expr(callee.id) else {
+            unreachable!("emit of a non-event")
+        };
+        let event = self.gcx.hir.event(id);
+        let tys = self.gcx.item_parameter_types(id.into());
+        let args = ordered_args(self.gcx, args, event.parameters);
+        let mut topics = Vec::new();
+        if !event.anonymous {
+            topics
+                .push(b.hex(alloy_primitives::U256::from_be_bytes(self.gcx.event_selector(id).0)));
+        }
+        let mut data_tys = Vec::new();
+        let mut data = Vec::new();
+        for ((arg, &param), &ty) in args.into_iter().zip(event.parameters).zip(tys) {
+            let ty = self.memory_ty(ty);
+            let value = self.expr_to(arg, ty)?[0];
+            if self.gcx.hir.variable(param).indexed {
+                if is_byte_array(ty) {
+                    let start = b.call("add", [self.cx.val(value), b.num(32)]);
+                    let hash = b.call("keccak256", [start, b.call("mload", [self.cx.val(value)])]);
+                    let topic = self.define("topic", hash);
+                    topics.push(self.cx.val(topic));
+                } else if let Some(repr) = Repr::of(ty) {
+                    topics.push(self.cx.cleanup(repr, self.cx.val(value)));
+                } else {
+                    let msg = "indexed event parameters of this type";
+                    return Err(self.cx.unsupported(arg.span, msg));
+                }
+            } else {
+                data_tys.push(ty);
+                data.push(value);
+            }
+        }
+        let encode = self.cx.abi_encode(callee.span, &data_tys)?;
+        let allocate = self.cx.allocate_unbounded();
+        let pos = self.define("pos", b.call(allocate, []));
+        let args = std::iter::once(pos).chain(data).map(|value| self.cx.val(value));
+        let end = self.define("end", b.call(encode, args));
+        let size = b.call("sub", [self.cx.val(end), self.cx.val(pos)]);
+        let log = format!("log{}", topics.len());
+        self.push(b.call_stmt(log, [self.cx.val(pos), size].into_iter().chain(topics)));
+        Ok(())
+    }
+
+    /// Returns the value that a variable of the given type has when it's declared without an
+    /// initializer.
+    pub(crate) fn zero_value(&mut self, span: Span, ty: Ty<'gcx>) -> Result<Value> {
+        if Repr::of(ty).is_some() {
+            return Ok(Value::ZERO);
+        }
+        match ty.kind {
+            TyKind::Ref(inner, DataLocation::Memory)
+                if matches!(inner.kind, TyKind::DynArray(_)) || is_byte_array(inner) =>
+            {
+                Ok(Value::Lit(alloy_primitives::U256::from(0x60)))
+            }
+            _ => Err(self
+                .cx
+                .unsupported(span, &format!("default values of type `{}`", ty.display(self.gcx)))),
+        }
+    }
+
+    /// Returns the type to which a value of the given type is converted to be encoded or passed
+    /// around: byte arrays are copied to memory.
+    pub(crate) fn memory_ty(&self, ty: Ty<'gcx>) -> Ty<'gcx> {
+        if is_byte_array(ty) {
+            ty.peel_refs().with_loc(self.gcx, DataLocation::Memory)
+        } else {
+            ty
+        }
+    }
+}
+
+/// Returns the arguments of a call in the order of the parameters.
+pub(crate) fn ordered_args<'e, 'hir>(
+    gcx: Gcx<'_>,
+    args: &'e CallArgs<'hir>,
+    params: &[hir::VariableId],
+) -> Vec<&'e hir::Expr<'hir>> {
+    match args {
+        CallArgs::Unnamed(args) => args.iter().collect(),
+        CallArgs::Named(args) => params
+            .iter()
+            .filter_map(|&param| {
+                let name = gcx.hir.variable(param).name?;
+                args.iter().find(|arg| arg.name.name == name.name).map(|arg| &arg.value)
+            })
+            .collect(),
+    }
+}
+
+/// Generates the Yul function of a function, which runs its modifiers and its body.
+pub(crate) fn lower_function<'ast>(
+    cx: &mut ObjectCx<'ast, '_>,
+    id: hir::FunctionId,
+) -> Result<yul::Stmt<'ast>> {
+    let gcx = cx.gcx;
+    let f = gcx.hir.function(id);
+    let name = function_name(gcx, id);
+    let params = f.parameters.iter().map(|&p| variable_name(gcx, p)).collect::<Vec<_>>();
+    let mut fcx = FnCx::new(cx, Some(id));
+    fcx.init_rets(f.returns);
+    if f.is_getter() {
+        fcx.getter(id)?;
+    } else if let Some(first) = modifier_indices(f).next() {
+        let modifier = fcx.cx.modifier(id, first);
+        let call = fcx.b.call(modifier, params.iter().map(|&p| fcx.b.path(p)));
+        let stmt = if f.returns.is_empty() {
+            fcx.b.expr_stmt(call)
+        } else {
+            fcx.b.assign(fcx.ret_names(), call)
+        };
+        fcx.push(stmt);
+    } else {
+        fcx.body(f.span, f.body)?;
+    }
+    let rets = fcx.ret_names();
+    let body = fcx.stmts;
+    Ok(cx.b.function(name, params, rets, body))
+}
+
+/// Generates the Yul function of the body of a function with modifiers, which is called by the
+/// last modifier.
+pub(crate) fn lower_function_body<'ast>(
+    cx: &mut ObjectCx<'ast, '_>,
+    id: hir::FunctionId,
+) -> Result<yul::Stmt<'ast>> {
+    let gcx = cx.gcx;
+    let f = gcx.hir.function(id);
+    let name = format!("{}_inner", function_name(gcx, id));
+    let params = f.parameters.iter().map(|&p| variable_name(gcx, p)).collect::<Vec<_>>();
+    let mut fcx = FnCx::new(cx, Some(id));
+    fcx.init_rets(f.returns);
+    fcx.body(f.span, f.body)?;
+    let rets = fcx.ret_names();
+    let body = fcx.stmts;
+    Ok(cx.b.function(name, params, rets, body))
+}
+
+/// Generates the Yul function of the `index`-th modifier of a function. The placeholder calls the
+/// next modifier, or the body of the function.
+pub(crate) fn lower_modifier<'ast>(
+    cx: &mut ObjectCx<'ast, '_>,
+    id: hir::FunctionId,
+    index: usize,
+) -> Result<yul::Stmt<'ast>> {
+    let gcx = cx.gcx;
+    let f = gcx.hir.function(id);
+    let invocation = &f.modifiers[index];
+    let hir::ItemId::Function(modifier_id) = invocation.id else { unreachable!() };
+    let modifier_id = gcx.hir.resolve_modifier(modifier_id, cx.contract);
+    let modifier = gcx.hir.function(modifier_id);
+    let name = cx.modifier(id, index);
+    let next = match modifier_indices(f).find(|&i| i > index) {
+        Some(next) => cx.modifier(id, next),
+        None => cx.function_body(id),
+    };
+    let params = f.parameters.iter().map(|&p| variable_name(gcx, p)).collect::<Vec<_>>();
+    let mut fcx = FnCx::new(cx, Some(modifier_id));
+    fcx.init_rets(f.returns);
+    fcx.placeholder = Some((next, params.clone()));
+    let args = ordered_args(gcx, &invocation.args, modifier.parameters);
+    for (arg, &param) in args.into_iter().zip(modifier.parameters) {
+        let ty = gcx.type_of_item(param.into());
+        let value = fcx.expr_to(arg, ty)?[0];
+        let value = fcx.cx.val(value);
+        fcx.push(fcx.b.let1(variable_name(gcx, param), value));
+    }
+    fcx.body(modifier.span, modifier.body)?;
+    let rets = fcx.ret_names();
+    let body = fcx.stmts;
+    Ok(cx.b.function(name, params, rets, body))
+}
+
+/// Returns the indices of the modifier invocations of a function, skipping the base constructor
+/// calls.
+fn modifier_indices<'a>(f: &'a hir::Function<'_>) -> impl Iterator<Item = usize> + 'a {
+    f.modifiers
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| matches!(m.id, hir::ItemId::Function(_)))
+        .map(|(i, _)| i)
+}
+
+impl<'ast, 'gcx> FnCx<'_, 'ast, 'gcx> {
+    /// Lowers the body of a getter, reading the variable directly from its location.
+    fn getter(&mut self, id: hir::FunctionId) -> Result<()> {
+        let gcx = self.gcx;
+        let b = self.b;
+        let f = gcx.hir.function(id);
+        let var_id = f.gettee.expect("getter without variable");
+        let var = gcx.hir.variable(var_id);
+        let rets = self.ret_names();
+        match var.mutability {
+            Some(VarMut::Constant) => {
+                let ty = gcx.type_of_item(var_id.into());
+                let init = var.initializer.expect("constant without initializer");
+                let value = self.expr_to(init, self.memory_ty(ty))?[0];
+                self.push(b.assign1(rets[0], self.cx.val(value)));
+            }
+            Some(VarMut::Immutable) => {
+                let value = self.load_immutable(var_id);
+                self.push(b.assign1(rets[0], value));
+            }
+            None => {
+                let loc = self.cx.storage_location(var_id).expect("state variable without slot");
+                let mut ty = gcx.type_of_item(var_id.into());
+                let mut slot = Value::Lit(loc.slot);
+                let mut offset = Offset::Static(loc.offset);
+                if loc.transient && !ty.is_value_type() {
+                    return Err(self.cx.unsupported(var.span, "transient reference types"));
+                }
+                for &param in f.parameters {
+                    let key = Value::Var(variable_name(gcx, param));
+                    match ty.peel_refs().kind {
+                        TyKind::Mapping(_, value) => {
+                            let slot_expr = self.cx.mapping_slot(ty, slot, key);
+                            slot = self.define("slot", slot_expr);
+                            offset = Offset::Static(0);
+                            ty = value;
+                        }
+                        TyKind::Array(elem, _) | TyKind::DynArray(elem) => {
+                            let access = self.cx.storage_array_index_access(ty);
+                            let (s, o) = (self.cx.fresh("slot"), self.cx.fresh("offset"));
+                            let call = b.call(access, [self.cx.val(slot), self.cx.val(key)]);
+                            self.push(b.let_([s, o], Some(call)));
+                            slot = Value::Var(s);
+                            offset = Offset::Dynamic(o);
+                            ty = elem;
+                        }
+                        _ => unreachable!("invalid getter parameter"),
+                    }
+                }
+                if let TyKind::Struct(sid) = ty.peel_refs().kind {
+                    let layout = gcx.struct_storage_layout(sid);
+                    let fields = gcx.hir.strukt(sid).fields;
+                    let field_tys = gcx.struct_field_types(sid);
+                    let mut rets = rets.into_iter();
+                    for (&field, &field_ty) in fields.iter().zip(field_tys) {
+                        let field_ty = field_ty.peel_refs();
+                        if matches!(
+                            field_ty.kind,
+                            TyKind::Mapping(..) | TyKind::Array(..) | TyKind::DynArray(_)
+                        ) {
+                            continue;
+                        }
+                        let Some(ret) = rets.next() else { break };
+                        let item = layout.members.iter().find(|m| m.variable == field).unwrap();
+                        let field_slot = self.slot_add(slot, item.slot);
+                        let value = self.cx.read_storage(
+                            var.span,
+                            field_ty,
+                            field_slot,
+                            Offset::Static(item.offset),
+                            false,
+                        )?;
+                        self.push(b.assign1(ret, value));
+                    }
+                } else {
+                    let value = self.cx.read_storage(var.span, ty, slot, offset, loc.transient)?;
+                    self.push(b.assign1(rets[0], value));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an expression that reads an immutable variable.
+    pub(crate) fn load_immutable(&mut self, var: hir::VariableId) -> yul::Expr<'ast> {
+        let b = self.b;
+        match self.cx.kind {
+            ObjectKind::Creation => b.call("mload", [b.num(self.cx.immutable_address(var))]),
+            ObjectKind::Runtime => b.call("loadimmutable", [b.str(&var.get().to_string())]),
+        }
+    }
+
+    /// Reverts with the ABI encoding of the given values, prefixed with a 4-byte selector.
+    pub(crate) fn revert_with(
+        &mut self,
+        span: Span,
+        selector: [u8; 4],
+        tys: &[Ty<'gcx>],
+        values: &[Value],
+    ) -> Result<()> {
+        let b = self.b;
+        let encode = self.cx.abi_encode(span, tys)?;
+        let allocate = self.cx.allocate_unbounded();
+        let pos = self.define("pos", b.call(allocate, []));
+        let selector = alloy_primitives::U256::from(u32::from_be_bytes(selector));
+        self.push(
+            b.call_stmt("mstore", [self.cx.val(pos), b.call("shl", [b.num(224), b.hex(selector)])]),
+        );
+        let start = b.call("add", [self.cx.val(pos), b.num(4)]);
+        let args = std::iter::once(start).chain(values.iter().map(|&v| self.cx.val(v)));
+        let end = self.define("end", b.call(encode, args));
+        let size = b.call("sub", [self.cx.val(end), self.cx.val(pos)]);
+        self.push(b.call_stmt("revert", [self.cx.val(pos), size]));
+        Ok(())
+    }
+
+    /// Declares a new variable with the given value.
+    pub(crate) fn define(&mut self, prefix: &str, value: yul::Expr<'ast>) -> Value {
+        let name = self.cx.fresh(prefix);
+        self.push(self.b.let1(name, value));
+        Value::Var(name)
+    }
+
+    /// Returns `slot + n`, folding literals.
+    pub(crate) fn slot_add(&mut self, slot: Value, n: alloy_primitives::U256) -> Value {
+        if n.is_zero() {
+            return slot;
+        }
+        match slot {
+            Value::Lit(slot) => Value::Lit(slot.wrapping_add(n)),
+            Value::Var(_) => {
+                let value = self.b.call("add", [self.cx.val(slot), self.b.num(n)]);
+                self.define("slot", value)
+            }
+        }
+    }
+}
@@ -0,0 +1,681 @@
+//! Storage access.
+//!
+//! Value types are packed in slots as described by [`Gcx::storage_layout`], `bytes` and `string`
+//! use the short and long encodings of solc, and the elements of dynamic arrays and the values of
+//! mappings are stored at the slots derived with `keccak256`.
+//!
+//! [`Gcx::storage_layout`]: solar_sema::ty::Gcx::storage_layout
+
+use super::{
+    helpers::{is_byte_array, low_mask, sym, type_id, Repr},
+    ObjectCx, Value,
+};
+use alloy_primitives::U256;
+use solar_ast::ast::yul;
+use solar_interface::{Result, Span, Symbol};
+use solar_sema::ty::{Ty, TyKind};
+
+/// The offset in bytes of a value in its storage slot.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Offset {
+    /// An offset known at compile time.
+    Static(u8),
+    /// An offset computed at runtime, such as the offset of an element of a packed array.
+    Dynamic(Symbol),
+}
+
+/// The instructions that access persistent or transient storage.
+fn ops(transient: bool) -> (&'static str, &'static str, &'static str) {
+    if transient {
+        ("tload", "tstore", "transient_storage")
+    } else {
+        ("sload", "sstore", "storage")
+    }
+}
+
+impl<'ast, 'gcx> ObjectCx<'ast, 'gcx> {
+    /// Returns an expression that reads a value from storage.
+    ///
+    /// Value types are read from their position in the slot, and `bytes` and `string` are copied
+    /// to memory.
+    pub(crate) fn read_storage(
+        &mut self,
+        span: Span,
+        ty: Ty<'gcx>,
+        slot: Value,
+        offset: Offset,
+        transient: bool,
+    ) -> Result<yul::Expr<'ast>> {
+        let b = self.b;
+        if is_byte_array(ty) {
+            if transient {
+                return Err(self.unsupported(span, "transient byte arrays"));
+            }
+            let copy = self.copy_byte_array_from_storage(ty)?;
+            return Ok(b.call(copy, [self.val(slot)]));
+        }
+        let Some(repr) = Repr::of(ty) else {
+            return Err(self.unsupported(span, "reading this type from storage"));
+        };
+        let (load, _, what) = ops(transient);
+        let tid = type_id(self.gcx, ty);
+        Ok(match offset {
+            Offset::Static(offset) => {
+                let name = format!("read_from_{what}_split_offset_{offset}_{tid}");
+                let f = self.helper(name, |cx, name| {
+                    let value = b.call(load, [b.path("slot")]);
+                    let value = if offset == 0 {
+                        value
+                    } else {
+                        b.call("shr", [b.num(8 * offset as u32), value])
+                    };
+                    let body = vec![b.assign1("value", cx.cleanup_from_storage(repr, value))];
+                    b.function(name, [sym("slot")], [sym("value")], body)
+                });
+                b.call(f, [self.val(slot)])
+            }
+            Offset::Dynamic(offset) => {
+                let name = format!("read_from_{what}_split_dynamic_{tid}");
+                let f = self.helper(name, |cx, name| {
+                    let shift = b.call("mul", [b.path("offset"), b.num(8)]);
+                    let value = b.call("shr", [shift, b.call(load, [b.path("slot")])]);
+                    let body = vec![b.assign1("value", cx.cleanup_from_storage(repr, value))];
+                    b.function(name, [sym("slot"), sym("offset")], [sym("value")], body)
+                });
+                b.call(f, [self.val(slot), b.path(offset)])
+            }
+        })
+    }
+
+    /// Returns a statement that writes a value to storage.
+    ///
+    /// Byte arrays are copied from memory.
+    pub(crate) fn write_storage(
+        &mut self,
+        span: Span,
+        ty: Ty<'gcx>,
+        slot: Value,
+        offset: Offset,
+        transient: bool,
+        value: Value,
+    ) -> Result<yul::Stmt<'ast>> {
+        let b = self.b;
+        if is_byte_array(ty) {
+            if transient {
+                return Err(self.unsupported(span, "transient byte arrays"));
+            }
+            let copy = self.copy_byte_array_to_storage(ty)?;
+            return Ok(b.call_stmt(copy, [self.val(slot), self.val(value)]));
+        }
+        let Some(repr) = Repr::of(ty) else {
+            return Err(self.unsupported(span, "writing this type to storage"));
+        };
+        let (load, store, what) = ops(transient);
+        let tid = type_id(self.gcx, ty);
+        let bytes = repr.bytes();
+        Ok(match offset {
+            Offset::Static(offset) => {
+                let name = format!("update_{what}_value_offset_{offset}_{tid}");
+                let f = self.helper(name, |cx, name| {
+                    let value = cx.prepare_store(repr, b.path("value"));
+                    let stored = if bytes == 32 {
+                        value
+                    } else {
+                        let shift = 8 * offset as usize;
+                        let mask = low_mask(bytes) << shift;
+                        let value =
+                            if offset == 0 { value } else { b.call("shl", [b.num(shift), value]) };
+                        b.call(
+                            "or",
+                            [
+                                b.call("and", [b.call(load, [b.path("slot")]), b.hex(!mask)]),
+                                b.call("and", [value, b.hex(mask)]),
+                            ],
+                        )
+                    };
+                    let body = vec![b.call_stmt(store, [b.path("slot"), stored])];
+                    b.function(name, [sym("slot"), sym("value")], [], body)
+                });
+                b.call_stmt(f, [self.val(slot), self.val(value)])
+            }
+            Offset::Dynamic(offset) => {
+                let name = format!("update_{what}_value_{tid}");
+                let f = self.helper(name, |cx, name| {
+                    let value = cx.prepare_store(repr, b.path("value"));
+                    let body = vec![
+                        b.let1("shift", b.call("mul", [b.path("offset"), b.num(8)])),
+                        b.let1("mask", b.call("shl", [b.path("shift"), b.hex(low_mask(bytes))])),
+                        b.call_stmt(
+                            store,
+                            [
+                                b.path("slot"),
+                                b.call(
+                                    "or",
+                                    [
+                                        b.call(
+                                            "and",
+                                            [
+                                                b.call(load, [b.path("slot")]),
+                                                b.call("not", [b.path("mask")]),
+                                            ],
+                                        ),
+                                        b.call(
+                                            "and",
+                                            [
+                                                b.call("shl", [b.path("shift"), value]),
+                                                b.path("mask"),
+                                            ],
+                                        ),
+                                    ],
+                                ),
+                            ],
+                        ),
+                    ];
+                    b.function(name, [sym("slot"), sym("offset"), sym("value")], [], body)
+                });
+                b.call_stmt(f, [self.val(slot), b.path(offset), self.val(value)])
+            }
+        })
+    }
+
+    /// Cleans up a value read from storage, which is aligned to the right.
+    fn cleanup_from_storage(&self, repr: Repr, value: yul::Expr<'ast>) -> yul::Expr<'ast> {
+        let b = self.b;
+        match repr {
+            Repr::FixedBytes(32) => value,
+            Repr::FixedBytes(bytes) => b.call(
+                "shl",
+                [b.num(256 - 8 * bytes as u32), b.call("and", [value, b.hex(low_mask(bytes))])],
+            ),
+            Repr::Int(bits) if bits < 256 => b.call("signextend", [b.num(bits / 8 - 1), value]),
+            Repr::Uint(256) | Repr::Int(256) => value,
+            _ => b.call("and", [value, b.hex(low_mask(repr.bytes()))]),
+        }
+    }
+
+    /// Aligns a value to the right to store it.
+    fn prepare_store(&self, repr: Repr, value: yul::Expr<'ast>) -> yul::Expr<'ast> {
+        let b = self.b;
+        match repr {
+            Repr::FixedBytes(bytes) if bytes < 32 => {
+                b.call("shr", [b.num(256 - 8 * bytes as u32), value])
+            }
+            _ => value,
+        }
+    }
+
+    /// `array_dataslot_t_bytes_storage(ptr) -> data`: returns the slot of the data of a dynamic
+    /// array or of a long byte array.
+    pub(crate) fn array_dataslot(&mut self) -> Symbol {
+        self.helper("array_dataslot", |cx, name| {
+            let b = cx.b;
+            b.function(
+                name,
+                [sym("ptr")],
+                [sym("data")],
+                vec![
+                    b.call_stmt("mstore", [b.num(0), b.path("ptr")]),
+                    b.assign1("data", b.call("keccak256", [b.num(0), b.hex(0x20)])),
+                ],
+            )
+        })
+    }
+
+    /// `extract_byte_array_length(data) -> length`: decodes the length of a byte array from its
+    /// first slot.
+    pub(crate) fn extract_byte_array_length(&mut self) -> Symbol {
+        self.helper("extract_byte_array_length", |cx, name| {
+            let b = cx.b;
+            let panic = cx.panic_stmt(0x22);
+            b.function(
+                name,
+                [sym("data")],
+                [sym("length")],
+                vec![
+                    b.assign1("length", b.call("div", [b.path("data"), b.num(2)])),
+                    b.let1("outOfPlaceEncoding", b.call("and", [b.path("data"), b.num(1)])),
+                    b.if_(
+                        b.call("iszero", [b.path("outOfPlaceEncoding")]),
+                        vec![b.assign1("length", b.call("and", [b.path("length"), b.hex(0x7f)]))],
+                    ),
+                    b.if_(
+                        b.call(
+                            "eq",
+                            [
+                                b.path("outOfPlaceEncoding"),
+                                b.call("lt", [b.path("length"), b.num(32)]),
+                            ],
+                        ),
+                        vec![panic],
+                    ),
+                ],
+            )
+        })
+    }
+
+    /// `copy_array_from_storage_to_memory_<ty>(slot) -> memPtr`
+    fn copy_byte_array_from_storage(&mut self, ty: Ty<'gcx>) -> Result<Symbol> {
+        let tid = type_id(self.gcx, ty.peel_refs());
+        let name = format!("copy_array_from_storage_to_memory_{tid}_storage");
+        Ok(self.helper(name, |cx, name| {
+            let b = cx.b;
+            let length = cx.extract_byte_array_length();
+            let allocate = cx.allocate_byte_array();
+            let dataslot = cx.array_dataslot();
+            let data = |offset| b.call("add", [b.path("memPtr"), offset]);
+            b.function(
+                name,
+                [sym("slot")],
+                [sym("memPtr")],
+                vec![
+                    b.let1("slotValue", b.call("sload", [b.path("slot")])),
+                    b.let1("length", b.call(length, [b.path("slotValue")])),
+                    b.assign1("memPtr", b.call(allocate, [b.path("length")])),
+                    b.switch(
+                        b.call("and", [b.path("slotValue"), b.num(1)]),
+                        [
+                            (
+                                b.num(0),
+                                vec![b.call_stmt(
+                                    "mstore",
+                                    [
+                                        data(b.num(32)),
+                                        b.call(
+                                            "and",
+                                            [b.path("slotValue"), b.call("not", [b.hex(0xff)])],
+                                        ),
+                                    ],
+                                )],
+                            ),
+                            (
+                                b.num(1),
+                                vec![
+                                    b.let1("dataPos", b.call(dataslot, [b.path("slot")])),
+                                    b.let1("i", b.num(0)),
+                                    b.for_(
+                                        vec![],
+                                        b.call("lt", [b.path("i"), b.path("length")]),
+                                        vec![
+                                            b.assign1("i", b.call("add", [b.path("i"), b.num(32)]))
+                                        ],
+                                        vec![
+                                            b.call_stmt(
+                                                "mstore",
+                                                [
+                                                    data(b.call("add", [b.path("i"), b.num(32)])),
+                                                    b.call("sload", [b.path("dataPos")]),
+                                                ],
+                                            ),
+                                            b.assign1(
+                                                "dataPos",
+                                                b.call("add", [b.path("dataPos"), b.num(1)]),
+                                            ),
+                                        ],
+                                    ),
+                                ],
+                            ),
+                        ],
+                        None,
+                    ),
+                ],
+            )
+        }))
+    }
+
+    /// `copy_byte_array_to_storage_from_<ty>_memory_ptr(slot, src)`: copies a byte array from
+    /// memory to storage, clearing the slots that are no longer used.
+    fn copy_byte_array_to_storage(&mut self, ty: Ty<'gcx>) -> Result<Symbol> {
+        let tid = type_id(self.gcx, ty.peel_refs());
+        let name = format!("copy_byte_array_to_storage_from_{tid}_memory_ptr");
+        Ok(self.helper(name, |cx, name| {
+            let b = cx.b;
+            let length = cx.extract_byte_array_length();
+            let clean_up = cx.clean_up_byte_array_end_slots();
+            let dataslot = cx.array_dataslot();
+            let src_at = || b.call("mload", [b.call("add", [b.path("src"), b.path("srcOffset")])]);
+            let long = vec![
+                b.let1("loopEnd", b.call("and", [b.path("newLen"), b.call("not", [b.num(31)])])),
+                b.let1("dstPtr", b.call(dataslot, [b.path("slot")])),
+                b.let1("i", b.num(0)),
+                b.for_(
+                    vec![],
+                    b.call("lt", [b.path("i"), b.path("loopEnd")]),
+                    vec![b.assign1("i", b.call("add", [b.path("i"), b.num(32)]))],
+                    vec![
+                        b.call_stmt("sstore", [b.path("dstPtr"), src_at()]),
+                        b.assign1("dstPtr", b.call("add", [b.path("dstPtr"), b.num(1)])),
+                        b.assign1("srcOffset", b.call("add", [b.path("srcOffset"), b.num(32)])),
+                    ],
+                ),
+                b.if_(
+                    b.call("lt", [b.path("loopEnd"), b.path("newLen")]),
+                    vec![b.call_stmt(
+                        "sstore",
+                        [
+                            b.path("dstPtr"),
+                            b.call(
+                                "and",
+                                [
+                                    src_at(),
+                                    b.call(
+                                        "not",
+                                        [b.call(
+                                            "shr",
+                                            [
+                                                b.call(
+                                                    "mul",
+                                                    [
+                                                        b.num(8),
+                                                        b.call(
+                                                            "and",
+                                                            [b.path("newLen"), b.num(31)],
+                                                        ),
+                                                    ],
+                                                ),
+                                                b.call("not", [b.num(0)]),
+                                            ],
+                                        )],
+                                    ),
+                                ],
+                            ),
+                        ],
+                    )],
+                ),
+                b.call_stmt(
+                    "sstore",
+                    [
+                        b.path("slot"),
+                        b.call("add", [b.call("mul", [b.path("newLen"), b.num(2)]), b.num(1)]),
+                    ],
+                ),
+            ];
+            let short = vec![
+                b.let1("value", b.num(0)),
+                b.if_(b.path("newLen"), vec![b.assign1("value", src_at())]),
+                b.call_stmt(
+                    "sstore",
+                    [
+                        b.path("slot"),
+                        b.call(
+                            "or",
+                            [
+                                b.call(
+                                    "and",
+                                    [
+                                        b.path("value"),
+                                        b.call(
+                                            "not",
+                                            [b.call(
+                                                "shr",
+                                                [
+                                                    b.call("mul", [b.num(8), b.path("newLen")]),
+                                                    b.call("not", [b.num(0)]),
+                                                ],
+                                            )],
+                                        ),
+                                    ],
+                                ),
+                                b.call("mul", [b.path("newLen"), b.num(2)]),
+                            ],
+                        ),
+                    ],
+                ),
+            ];
+            b.function(
+                name,
+                [sym("slot"), sym("src")],
+                [],
+                vec![
+                    b.let1("newLen", b.call("mload", [b.path("src")])),
+                    b.let1("oldLen", b.call(length, [b.call("sload", [b.path("slot")])])),
+                    b.call_stmt(clean_up, [b.path("slot"), b.path("oldLen"), b.path("newLen")]),
+                    b.let1("srcOffset", b.hex(0x20)),
+                    b.switch(
+                        b.call("gt", [b.path("newLen"), b.num(31)]),
+                        [(b.num(1), long)],
+                        Some(short),
+                    ),
+                ],
+            )
+        }))
+    }
+
+    /// `clean_up_bytearray_end_slots(array, len, startIndex)`: clears the data slots of a long
+    /// byte array from the given index.
+    fn clean_up_byte_array_end_slots(&mut self) -> Symbol {
+        self.helper("clean_up_bytearray_end_slots", |cx, name| {
+            let b = cx.b;
+            let dataslot = cx.array_dataslot();
+            let slots = |len| b.call("div", [b.call("add", [len, b.num(31)]), b.num(32)]);
+            b.function(
+                name,
+                [sym("array"), sym("len"), sym("startIndex")],
+                [],
+                vec![b.if_(
+                    b.call("gt", [b.path("len"), b.num(31)]),
+                    vec![
+                        b.let1("dataArea", b.call(dataslot, [b.path("array")])),
+                        b.let1(
+                            "deleteStart",
+                            b.call("add", [b.path("dataArea"), slots(b.path("startIndex"))]),
+                        ),
+                        b.if_(
+                            b.call("lt", [b.path("startIndex"), b.num(32)]),
+                            vec![b.assign1("deleteStart", b.path("dataArea"))],
+                        ),
+                        b.let1("end", b.call("add", [b.path("dataArea"), slots(b.path("len"))])),
+                        b.for_(
+                            vec![],
+                            b.call("lt", [b.path("deleteStart"), b.path("end")]),
+                            vec![b.assign1(
+                                "deleteStart",
+                                b.call("add", [b.path("deleteStart"), b.num(1)]),
+                            )],
+                            vec![b.call_stmt("sstore", [b.path("deleteStart"), b.num(0)])],
+                        ),
+                    ],
+                )],
+            )
+        })
+    }
+
+    /// Returns an expression that computes the slot of the value of a mapping for the given key.
+    ///
+    /// `key` must be a cleaned up value type, or a pointer to a byte array in memory.
+    pub(crate) fn mapping_slot(
+        &mut self,
+        mapping: Ty<'gcx>,
+        slot: Value,
+        key: Value,
+    ) -> yul::Expr<'ast> {
+        let TyKind::Mapping(key_ty, _) = mapping.peel_refs().kind else { unreachable!() };
+        let b = self.b;
+        let name = format!(
+            "mapping_index_access_{}_of_{}",
+            type_id(self.gcx, mapping.peel_refs()),
+            type_id(self.gcx, key_ty)
+        );
+        let f = self.helper(name, |cx, name| {
+            let body = if is_byte_array(key_ty) {
+                let allocate = cx.allocate_unbounded();
+                let copy = cx.copy_memory_to_memory();
+                vec![
+                    b.let1("length", b.call("mload", [b.path("key")])),
+                    b.let1("pos", b.call(allocate, [])),
+                    b.call_stmt(
+                        copy,
+                        [
+                            b.call("add", [b.path("key"), b.num(32)]),
+                            b.path("pos"),
+                            b.path("length"),
+                        ],
+                    ),
+                    b.call_stmt(
+                        "mstore",
+                        [b.call("add", [b.path("pos"), b.path("length")]), b.path("slot")],
+                    ),
+                    b.assign1(
+                        "dataSlot",
+                        b.call(
+                            "keccak256",
+                            [b.path("pos"), b.call("add", [b.path("length"), b.num(32)])],
+                        ),
+                    ),
+                ]
+            } else {
+                vec![
+                    b.call_stmt("mstore", [b.num(0), b.path("key")]),
+                    b.call_stmt("mstore", [b.hex(0x20), b.path("slot")]),
+                    b.assign1("dataSlot", b.call("keccak256", [b.num(0), b.hex(0x40)])),
+                ]
+            };
+            b.function(name, [sym("slot"), sym("key")], [sym("dataSlot")], body)
+        });
+        b.call(f, [self.val(slot), self.val(key)])
+    }
+
+    /// Returns an expression that computes the length of a storage array.
+    pub(crate) fn storage_array_length(&mut self, array: Ty<'gcx>, slot: Value) -> yul::Expr<'ast> {
+        let b = self.b;
+        match array.peel_refs().kind {
+            TyKind::Array(_, len) => b.num(len),
+            TyKind::DynArray(_) => b.call("sload", [self.val(slot)]),
+            _ => {
+                let length = self.extract_byte_array_length();
+                b.call(length, [b.call("sload", [self.val(slot)])])
+            }
+        }
+    }
+
+    /// `storage_array_index_access_<ty>(array, index) -> slot, offset`: returns the position of
+    /// an element of a storage array, checking that the index is in bounds.
+    pub(crate) fn storage_array_index_access(&mut self, array: Ty<'gcx>) -> Symbol {
+        let array = array.peel_refs();
+        let elem = match array.kind {
+            TyKind::Array(elem, _) | TyKind::DynArray(elem) => elem,
+            _ => unreachable!("not a storage array: {array:?}"),
+        };
+        let name = format!("storage_array_index_access_{}", type_id(self.gcx, array));
+        self.helper(name, |cx, name| {
+            let b = cx.b;
+            let length = cx.storage_array_length(array, Value::Var(sym("array")));
+            let panic = cx.panic_stmt(0x32);
+            let size = cx.gcx.storage_size(elem);
+            let mut body = vec![
+                b.let1("arrayLength", length),
+                b.if_(
+                    b.call("iszero", [b.call("lt", [b.path("index"), b.path("arrayLength")])]),
+                    vec![panic],
+                ),
+            ];
+            let data_area = if matches!(array.kind, TyKind::DynArray(_)) {
+                let dataslot = cx.array_dataslot();
+                b.call(dataslot, [b.path("array")])
+            } else {
+                b.path("array")
+            };
+            body.push(b.let1("dataArea", data_area));
+            let per_slot = 32 / size.bytes;
+            if per_slot > 1 {
+                body.push(b.assign1(
+                    "slot",
+                    b.call(
+                        "add",
+                        [b.path("dataArea"), b.call("div", [b.path("index"), b.num(per_slot)])],
+                    ),
+                ));
+                body.push(b.assign1(
+                    "offset",
+                    b.call(
+                        "mul",
+                        [b.call("mod", [b.path("index"), b.num(per_slot)]), b.num(size.bytes)],
+                    ),
+                ));
+            } else {
+                let index = if size.slots == U256::from(1) {
+                    b.path("index")
+                } else {
+                    b.call("mul", [b.path("index"), b.num(size.slots)])
+                };
+                body.push(b.assign1("slot", b.call("add", [b.path("dataArea"), index])));
+                body.push(b.assign1("offset", b.num(0)));
+            }
+            b.function(name, [sym("array"), sym("index")], [sym("slot"), sym("offset")], body)
+        })
+    }
+
+    /// `array_push_zero_<ty>(array) -> slot, offset`: appends a zero element to a dynamic storage
+    /// array, returning its position.
+    pub(crate) fn storage_array_push_zero(&mut self, array: Ty<'gcx>) -> Symbol {
+        let array = array.peel_refs();
+        let name = format!("array_push_zero_{}", type_id(self.gcx, array));
+        self.helper(name, |cx, name| {
+            let b = cx.b;
+            let panic = cx.panic_stmt(0x41);
+            let access = cx.storage_array_index_access(array);
+            b.function(
+                name,
+                [sym("array")],
+                [sym("slot"), sym("offset")],
+                vec![
+                    b.let1("oldLen", b.call("sload", [b.path("array")])),
+                    b.if_(
+                        b.call(
+                            "iszero",
+                            [b.call("lt", [b.path("oldLen"), b.hex(U256::from(1) << 64)])],
+                        ),
+                        vec![panic],
+                    ),
+                    b.call_stmt(
+                        "sstore",
+                        [b.path("array"), b.call("add", [b.path("oldLen"), b.num(1)])],
+                    ),
+                    b.assign(
+                        [sym("slot"), sym("offset")],
+                        b.call(access, [b.path("array"), b.path("oldLen")]),
+                    ),
+                ],
+            )
+        })
+    }
+
+    /// `array_pop_<ty>(array)`: removes the last element of a dynamic storage array of value
+    /// types.
+    pub(crate) fn storage_array_pop(&mut self, span: Span, array: Ty<'gcx>) -> Result<Symbol> {
+        let array = array.peel_refs();
+        let TyKind::DynArray(elem) = array.kind else { unreachable!() };
+        if Repr::of(elem).is_none() {
+            return Err(self.unsupported(span, "popping elements that are not value types"));
+        }
+        let name = format!("array_pop_{}", type_id(self.gcx, array));
+        self.try_helper(name, |cx, name| {
+            let b = cx.b;
+            let panic = cx.panic_stmt(0x31);
+            let access = cx.storage_array_index_access(array);
+            let zero = cx.write_storage(
+                span,
+                elem,
+                Value::Var(sym("slot")),
+                Offset::Dynamic(sym("offset")),
+                false,
+                Value::ZERO,
+            )?;
+            Ok(b.function(
+                name,
+                [sym("array")],
+                [],
+                vec![
+                    b.let1("oldLen", b.call("sload", [b.path("array")])),
+                    b.if_(b.call("iszero", [b.path("oldLen")]), vec![panic]),
+                    b.let1("newLen", b.call("sub", [b.path("oldLen"), b.num(1)])),
+                    b.let_(
+                        [sym("slot"), sym("offset")],
+                        Some(b.call(access, [b.path("array"), b.path("newLen")])),
+                    ),
+                    zero,
+                    b.call_stmt("sstore", [b.path("array"), b.path("newLen")]),
+                ],
+            ))
+        })
+    }
+}
@@ -0,0 +1,105 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/solar/main/assets/logo.png",
+    html_favicon_url = "https://raw.githubusercontent.com/paradigmxyz/solar/main/assets/favicon.ico"
+)]
+#![cfg_attr(feature = "nightly", feature(rustc_attrs), allow(internal_features))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+#[macro_use]
+extern crate tracing;
+
+use solar_ast::ast;
+use solar_interface::Result;
+use solar_sema::ty::Gcx;
+use std::{fmt::Write as _, io::Write as _};
+
+pub mod builder;
+
+pub mod evm;
+
+mod ir;
+
+/// Generates the Yul IR of all the deployable contracts, and writes it to the standard output, or
+/// to `<out_dir>/<Contract>.yul` files if an output directory was specified.
+#[instrument(level = "debug", skip_all)]
+pub fn emit_ir(gcx: Gcx<'_>) -> Result<()> {
+    check_evm_version(gcx)?;
+
+    let arena = ast::Arena::new();
+    let mut guar = None;
+    let mut stdout = String::new();
+    for id in gcx.hir.contract_ids() {
+        let c = gcx.hir.contract(id);
+        if !c.can_be_deployed() || c.is_abstract() {
+            continue;
+        }
+        let object = match ir::lower_contract(gcx, &arena, id) {
+            Ok(object) => object,
+            Err(e) => {
+                guar = Some(e);
+                continue;
+            }
+        };
+        match gcx.sess.out_dir.as_deref() {
+            Some(dir) => {
+                let path = dir.join(format!("{}.yul", c.name));
+                if let Err(e) = std::fs::write(&path, format!("{object}\n")) {
+                    let msg = format!("failed to write to {}: {e}", path.display());
+                    guar = Some(gcx.dcx().err(msg).emit());
+                }
+            }
+            None => {
+                let name = gcx.contract_fully_qualified_name(id);
+                let _ = write!(stdout, "======= {name} =======\nIR:\n{object}\n\n");
+            }
+        }
+    }
+    if !stdout.is_empty() {
+        let _ = std::io::stdout().lock().write_all(stdout.as_bytes());
+    }
+    match guar {
+        Some(guar) => Err(guar),
+        None => Ok(()),
+    }
+}
+
+/// Compiles all the deployable contracts to EVM bytecode, and records it in the global context
+/// with [`Gcx::set_contract_bytecode`] so that it is emitted with the other outputs.
+#[instrument(level = "debug", skip_all)]
+pub fn emit_bytecode(gcx: Gcx<'_>) -> Result<()> {
+    check_evm_version(gcx)?;
+
+    let arena = ast::Arena::new();
+    let mut guar = None;
+    for id in gcx.hir.contract_ids() {
+        let c = gcx.hir.contract(id);
+        if !c.can_be_deployed() || c.is_abstract() {
+            continue;
+        }
+        let result = ir::lower_contract(gcx, &arena, id).and_then(|object| {
+            let asm = evm::lower_object(gcx.dcx(), gcx.sess.evm_version, &object)?;
+            asm.assemble().map_err(|e| gcx.dcx().err(e.to_string()).emit())
+        });
+        match result {
+            Ok(bytecode) => {
+                // The runtime code is the only sub-object of the creation code.
+                let runtime = bytecode.subs.iter().next().map_or(&[][..], |sub| &sub.code[..]);
+                gcx.set_contract_bytecode(id, &bytecode.code, runtime);
+            }
+            Err(e) => guar = Some(e),
+        }
+    }
+    match guar {
+        Some(guar) => Err(guar),
+        None => Ok(()),
+    }
+}
+
+fn check_evm_version(gcx: Gcx<'_>) -> Result<()> {
+    if !gcx.sess.evm_version.has_bitwise_shifting() {
+        let msg = "the code generator requires EVM version `constantinople` or later";
+        return Err(gcx.dcx().err(msg).emit());
+    }
+    Ok(())
+}
@@ -95,6 +95,9 @@ impl EvmVersion {
     pub fn has_push0(self) -> bool {
         self >= Self::Shanghai
     }
+    pub fn has_mcopy(self) -> bool {
+        self >= Self::Cancun
+    }
 }
 
 str_enum! {
@@ -103,12 +106,23 @@ str_enum! {
     pub enum CompilerOutput {
         /// JSON ABI.
         Abi,
-        // /// Creation bytecode.
-        // Bin,
-        // /// Runtime bytecode.
-        // BinRuntime,
+        /// Creation bytecode.
+        Bin,
+        /// Runtime bytecode.
+        BinRuntime,
         /// Function signature hashes.
         Hashes,
+        /// Yul intermediate representation.
+        Ir,
+        /// Storage layout of the state variables, in both persistent and transient storage.
+        StorageLayout,
+    }
+}
+
+impl CompilerOutput {
+    /// Returns `true` if the output is part of the `combined.json` output.
+    pub fn is_json(self) -> bool {
+        !matches!(self, Self::Ir)
     }
 }
 
@@ -40,6 +40,9 @@ pub struct Session {
     /// Pretty-print any JSON output.
     #[builder(default)]
     pub pretty_json: bool,
+    /// Path to previously emitted storage layouts to check the current ones against.
+    #[builder(default)]
+    pub storage_layout_base: Option<PathBuf>,
     /// Number of threads to use. Already resolved to a non-zero value.
     #[builder(default = "NonZeroUsize::MIN")]
     pub jobs: NonZeroUsize,
@@ -739,7 +739,6 @@ symbols! {
         // Some builtins have already been previously declared, so they can't be redeclared here.
         // See `is_yul_builtin`.
         // https://docs.soliditylang.org/en/latest/yul.html#evm-dialect
-        // TODO: The remaining internal dialect builtins.
         Add:            "add",
         Addmod:         "addmod",
         And:            "and",
@@ -756,6 +755,8 @@ symbols! {
         Caller:         "caller",
         Callvalue:      "callvalue",
         Chainid:        "chainid",
+        Codecopy:       "codecopy",
+        Codesize:       "codesize",
         Coinbase:       "coinbase",
         Create:         "create",
         Create2:        "create2",
@@ -816,6 +817,18 @@ symbols! {
         Tstore:         "tstore",
         Xor:            "xor",
 
+        // Yul object builtins.
+        // These are only available in Yul objects, so they are not reserved and can be used as
+        // identifiers in inline assembly.
+        // https://docs.soliditylang.org/en/latest/yul.html#specification-of-yul-object
+        Datacopy:       "datacopy",
+        Dataoffset:     "dataoffset",
+        Datasize:       "datasize",
+        Linkersymbol:   "linkersymbol",
+        Loadimmutable:  "loadimmutable",
+        Memoryguard:    "memoryguard",
+        Setimmutable:   "setimmutable",
+
         // Experimental Solidity specific keywords.
         Class:         "class",
         Instantiation: "instantiation",
@@ -845,6 +858,8 @@ symbols! {
     // There is currently no checking that all symbols are used; that would be
     // nice to have.
     Symbols {
+        Error,
+        Panic,
         X,
         __tmp_struct,
         abi,
@@ -864,6 +879,7 @@ symbols! {
         encodeWithSelector,
         encodeWithSignature,
         error,
+        evmasm,
         experimental,
         from,
         gasleft,
@@ -871,19 +887,23 @@ symbols! {
         interfaceId,
         length,
         max,
+        memory_dash_safe: "memory-safe",
         min,
         msg,
         name,
         object,
+        offset,
         push,
         require,
         ripemd160,
         runtimeCode,
+        salt,
         selector,
         send,
         sender,
         sha256,
         sig,
+        slot,
         solidity,
         super_: "super",
         this,
@@ -46,11 +46,11 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         let mut precedence = token_precedence(&self.token);
         while precedence >= min_precedence {
             while token_precedence(&self.token) == precedence {
-                // Parse a**b**c as a**(b**c)
-                let next_precedence = if self.token.kind == TokenKind::BinOp(BinOpToken::Star) {
-                    precedence + 1
-                } else {
+                // Parse a**b**c as a**(b**c), and a-b-c as (a-b)-c
+                let next_precedence = if self.token.kind == TokenKind::StarStar {
                     precedence
+                } else {
+                    precedence + 1
                 };
 
                 let token = self.token.clone();
@@ -297,7 +297,7 @@ fn token_precedence(t: &Token) -> usize {
         BinOp(Star) => 13,
         BinOp(Slash) => 13,
         BinOp(Percent) => 13,
-        StarStar => 4,
+        StarStar => 14,
         EqEq => 6,
         Ne => 6,
         Lt => 7,
@@ -325,3 +325,96 @@ unsafe fn option_boxes_unwrap_unchecked<'a, 'b, T>(
     // SAFETY: Caller must ensure that all elements are `Some`.
     unsafe { std::mem::transmute(list) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::{source_map::FileName, Result, Session};
+
+    /// Formats the operations of an expression with explicit parentheses.
+    fn parenthesize(expr: &Expr<'_>) -> String {
+        match &expr.kind {
+            ExprKind::Assign(lhs, op, rhs) => {
+                let op = op.map(|op| op.to_string()).unwrap_or_default();
+                format!("({} {op}= {})", parenthesize(lhs), parenthesize(rhs))
+            }
+            ExprKind::Binary(lhs, op, rhs) => {
+                format!("({} {op} {})", parenthesize(lhs), parenthesize(rhs))
+            }
+            ExprKind::Ternary(cond, then, else_) => format!(
+                "({} ? {} : {})",
+                parenthesize(cond),
+                parenthesize(then),
+                parenthesize(else_)
+            ),
+            ExprKind::Unary(op, expr) if op.kind.is_prefix() => {
+                format!("({op}{})", parenthesize(expr))
+            }
+            ExprKind::Unary(op, expr) => format!("({}{op})", parenthesize(expr)),
+            ExprKind::Ident(ident) => ident.to_string(),
+            ExprKind::Lit(lit, _) => lit.symbol.to_string(),
+            _ => panic!("unexpected expression: {expr:?}"),
+        }
+    }
+
+    fn check(tests: &[(&str, &str)]) {
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| -> Result {
+            for (i, &(src, expected)) in tests.iter().enumerate() {
+                let name = i.to_string();
+                let arena = Arena::new();
+                let mut parser =
+                    Parser::from_source_code(&sess, &arena, FileName::Custom(name), src.into())?;
+
+                let expr = parser.parse_expr().map_err(|e| e.emit()).unwrap();
+                sess.dcx.has_errors().unwrap();
+                assert_eq!(parenthesize(expr), expected, "{src:?}");
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn binary_precedence() {
+        check(&[
+            ("a ** b * c", "((a ** b) * c)"),
+            ("a * b ** c", "(a * (b ** c))"),
+            ("a * b + c", "((a * b) + c)"),
+            ("a + b * c", "(a + (b * c))"),
+            ("a + b << c", "((a + b) << c)"),
+            ("a << b & c", "((a << b) & c)"),
+            ("a & b ^ c", "((a & b) ^ c)"),
+            ("a ^ b | c", "((a ^ b) | c)"),
+            ("a | b < c", "((a | b) < c)"),
+            ("a < b == c", "((a < b) == c)"),
+            ("a == b && c", "((a == b) && c)"),
+            ("a && b || c", "((a && b) || c)"),
+            ("a || b ? c : d", "((a || b) ? c : d)"),
+            ("a = b ? c : d", "(a = (b ? c : d))"),
+            ("-a ** b", "((-a) ** b)"),
+            ("a++ ** b", "((a++) ** b)"),
+        ]);
+    }
+
+    #[test]
+    fn binary_associativity() {
+        check(&[
+            ("a ** b ** c", "(a ** (b ** c))"),
+            ("a - b - c", "((a - b) - c)"),
+            ("a - b + c", "((a - b) + c)"),
+            ("a / b / c", "((a / b) / c)"),
+            ("a / b * c", "((a / b) * c)"),
+            ("a % b * c", "((a % b) * c)"),
+            ("a << b >> c", "((a << b) >> c)"),
+            ("a < b < c", "((a < b) < c)"),
+            ("a == b != c", "((a == b) != c)"),
+            ("a && b && c", "((a && b) && c)"),
+            ("a || b || c", "((a || b) || c)"),
+            ("a ? b : c ? d : e", "(a ? b : (c ? d : e))"),
+            ("a = b = c", "(a = (b = c))"),
+            ("a += b -= c", "(a += (b -= c))"),
+            ("a ^= b", "(a ^= b)"),
+        ]);
+    }
+}
@@ -510,7 +510,8 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
 
     #[must_use]
     fn check_elementary_type(&mut self) -> bool {
-        self.check_or_expected(self.token.is_elementary_type(), ExpectedToken::ElementaryType)
+        let ok = self.token.is_elementary_type() || self.token.is_ident_where(ty::is_fixed_type);
+        self.check_or_expected(ok, ExpectedToken::ElementaryType)
     }
 
     #[must_use]
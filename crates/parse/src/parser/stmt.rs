@@ -142,6 +142,8 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         let mut catch = SmallVec::<[_; 4]>::new();
         self.expect_keyword(kw::Catch)?;
         loop {
+            // The `catch` keyword has just been eaten.
+            let lo = self.prev_token.span;
             let name = self.parse_ident_opt()?;
             let args = if self.check(&TokenKind::OpenDelim(Delimiter::Parenthesis)) {
                 self.parse_parameter_list(false, VarFlags::FUNCTION)?
@@ -149,7 +151,8 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 Default::default()
             };
             let block = self.parse_block()?;
-            catch.push(CatchClause { name, args, block });
+            let span = lo.to(self.prev_token.span);
+            catch.push(CatchClause { span, name, args, block });
             if !self.eat_keyword(kw::Catch) {
                 break;
             }
@@ -335,7 +338,11 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     fn parse_iap(&mut self) -> PResult<'sess, IndexAccessedPath<'ast>> {
         // https://github.com/ethereum/solidity/blob/194b114664c7daebc2ff68af3c573272f5d28913/libsolidity/parsing/Parser.cpp#L2559
         let mut path = SmallVec::<[_; 4]>::new();
-        if self.check_nr_ident() {
+        // Elementary types are checked first since `[u]fixedMxN` are also identifiers.
+        if self.check_elementary_type() {
+            let (span, kind) = self.parse_spanned(Self::parse_elementary_type)?;
+            path.push(IapKind::MemberTy(span, kind));
+        } else if self.check_nr_ident() {
             path.push(IapKind::Member(self.parse_ident()?));
             while self.eat(&TokenKind::Dot) {
                 let id = self.ident_or_err(true)?;
@@ -345,9 +352,6 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 self.bump(); // `id`
                 path.push(IapKind::Member(id));
             }
-        } else if self.check_elementary_type() {
-            let (span, kind) = self.parse_spanned(Self::parse_elementary_type)?;
-            path.push(IapKind::MemberTy(span, kind));
         } else {
             return self.unexpected();
         }
@@ -1,7 +1,7 @@
 use super::item::FunctionFlags;
 use crate::{PResult, Parser};
 use solar_ast::{ast::*, token::*};
-use solar_interface::kw;
+use solar_interface::{kw, Ident};
 use std::{fmt, ops::RangeInclusive};
 
 impl<'sess, 'ast> Parser<'sess, 'ast> {
@@ -66,7 +66,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     /// Must be used after checking that the next token is an elementary type.
     pub(super) fn parse_elementary_type(&mut self) -> PResult<'sess, ElementaryType> {
         let id = self.parse_ident_any()?;
-        debug_assert!(id.is_elementary_type());
+        debug_assert!(id.is_elementary_type() || is_fixed_type(id));
         let mut ty = match id.name {
             kw::Address => ElementaryType::Address(false),
             kw::Bool => ElementaryType::Bool,
@@ -88,7 +88,10 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 let bytes = s.as_u32() - kw::Bytes1.as_u32() + 1;
                 ElementaryType::FixedBytes(TypeSize::new(bytes as u8).unwrap())
             }
-            s => unreachable!("unexpected elementary type: {s}"),
+            s => match parse_fixed_type(s.as_str()) {
+                Ok(Some(ty)) => ty,
+                _ => unreachable!("unexpected elementary type: {s}"),
+            },
         };
 
         let sm = self.parse_state_mutability();
@@ -160,8 +163,14 @@ impl fmt::Display for ParseTySizeError {
     }
 }
 
+/// Returns `true` if the identifier is a `fixedMxN` or `ufixedMxN` type name.
+///
+/// These are not pre-interned as keywords, unlike the other elementary type names.
+pub(super) fn is_fixed_type(id: Ident) -> bool {
+    matches!(parse_fixed_type(id.as_str()), Ok(Some(_)))
+}
+
 /// Parses `fixedMxN` or `ufixedMxN`.
-#[allow(dead_code)]
 fn parse_fixed_type(original: &str) -> Result<Option<ElementaryType>, ParseTySizeError> {
     let s = original;
     let tmp = s.strip_prefix('u');
@@ -186,12 +195,10 @@ fn parse_fb_size(s: &str) -> Result<TypeSize, ParseTySizeError> {
     parse_ty_size_u8(s, 1..=32, false).map(|x| TypeSize::new(x).unwrap())
 }
 
-#[allow(dead_code)]
 fn parse_int_size(s: &str) -> Result<TypeSize, ParseTySizeError> {
     parse_ty_size_u8(s, 1..=32, true).map(|x| TypeSize::new(x).unwrap())
 }
 
-#[allow(dead_code)]
 fn parse_fixed_size(s: &str) -> Result<(TypeSize, TypeFixedSize), ParseTySizeError> {
     let (m, n) = s.split_once('x').ok_or(ParseTySizeError::FixedX)?;
     let m = parse_int_size(m)?;
@@ -16,15 +16,15 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
                 file: source.file.clone(),
                 imports: self.arena.alloc_slice_copy(&source.imports),
                 items: &[],
+                using_directives: &[],
             };
             if let Some(ast) = &source.ast {
                 let mut items = SmallVec::<[_; 16]>::new();
                 self.current_source_id = id;
                 for item in ast.items.iter() {
                     match &item.kind {
-                        ast::ItemKind::Pragma(_)
-                        | ast::ItemKind::Import(_)
-                        | ast::ItemKind::Using(_) => {}
+                        ast::ItemKind::Pragma(_) | ast::ItemKind::Import(_) => {}
+                        ast::ItemKind::Using(_) => self.using_directives.push((id, None, item)),
                         ast::ItemKind::Contract(_)
                         | ast::ItemKind::Function(_)
                         | ast::ItemKind::Variable(_)
@@ -55,12 +55,14 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
 
             // Set later.
             bases: &[],
+            bases_args: &[],
             linearized_bases: &[],
 
             ctor: None,
             fallback: None,
             receive: None,
             items: &[],
+            using_directives: &[],
         });
         let prev_contract_id = std::mem::replace(&mut self.current_contract_id, Some(id));
         debug_assert_eq!(prev_contract_id, None);
@@ -71,7 +73,10 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
                 ast::ItemKind::Pragma(_)
                 | ast::ItemKind::Import(_)
                 | ast::ItemKind::Contract(_) => unreachable!("illegal item in contract body"),
-                ast::ItemKind::Using(_) => continue,
+                ast::ItemKind::Using(_) => {
+                    self.using_directives.push((self.current_source_id, Some(id), item));
+                    continue;
+                }
                 ast::ItemKind::Variable(_) => {
                     let hir::ItemId::Variable(id) = self.lower_item(item) else { unreachable!() };
                     items.push(hir::ItemId::Variable(id));
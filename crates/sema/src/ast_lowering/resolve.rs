@@ -1,8 +1,8 @@
 use crate::{builtins::Builtin, hir, ParsedSources};
-use solar_ast::ast;
+use solar_ast::ast::{self, yul, DataLocation};
 use solar_data_structures::{
     index::{Idx, IndexVec},
-    map::{FxIndexMap, IndexEntry},
+    map::{FxHashMap, FxIndexMap, IndexEntry},
     smallvec::SmallVec,
     BumpExt,
 };
@@ -191,6 +191,9 @@ impl super::LoweringContext<'_, '_, '_> {
                     continue;
                 }
                 bases.push(base_id);
+                if !base.arguments.is_empty() {
+                    self.bases_args.push((contract_id, base_id, base));
+                }
             }
             self.hir.contracts[contract_id].bases = self.arena.alloc_slice_copy(&bases);
         }
@@ -236,7 +239,7 @@ impl super::LoweringContext<'_, '_, '_> {
     }
 }
 
-impl super::LoweringContext<'_, '_, '_> {
+impl<'hir> super::LoweringContext<'_, '_, 'hir> {
     #[instrument(level = "debug", skip_all)]
     pub(super) fn resolve_symbols(&mut self) {
         let next_id = &AtomicUsize::new(0);
@@ -311,7 +314,7 @@ impl super::LoweringContext<'_, '_, '_> {
 
             let scopes = SymbolResolverScopes::new_in(func.source, func.contract);
 
-            self.hir.functions[id].modifiers = {
+            let modifiers = {
                 let mut modifiers = SmallVec::<[_; 8]>::new();
                 for modifier in ast_func.header.modifiers.iter() {
                     let expected = if func.kind.is_constructor() {
@@ -339,34 +342,13 @@ impl super::LoweringContext<'_, '_, '_> {
                             continue;
                         }
                     }
-                    modifiers.push(id);
+                    modifiers.push((id, modifier));
                 }
-                self.arena.alloc_smallvec(modifiers)
+                modifiers
             };
 
-            let func = self.hir.function(id);
-            self.hir.functions[id].overrides = {
-                let mut overrides = SmallVec::<[_; 8]>::new();
-                if let Some(ov) = &ast_func.header.override_ {
-                    for path in ov.paths.iter() {
-                        let Ok(id) = self.resolver.resolve_path_as(path, &scopes, "contract")
-                        else {
-                            continue;
-                        };
-                        // TODO: Move to override checker.
-                        let Some(c) = func.contract else {
-                            self.dcx().err("free functions cannot override").span(ov.span).emit();
-                            continue;
-                        };
-                        if !self.hir.contract(c).linearized_bases[1..].contains(&id) {
-                            self.dcx().err("override is not a base contract").span(ov.span).emit();
-                            continue;
-                        }
-                        overrides.push(id);
-                    }
-                }
-                self.arena.alloc_smallvec(overrides)
-            };
+            self.hir.functions[id].overrides =
+                self.resolve_overrides(ast_func.header.override_.as_ref(), &scopes);
 
             let mut cx = ResolveContext::new(self, scopes, next_id);
             cx.hir.functions[id].parameters = cx.arena.alloc_slice_fill_iter(
@@ -375,17 +357,199 @@ impl super::LoweringContext<'_, '_, '_> {
             cx.hir.functions[id].returns = cx.arena.alloc_slice_fill_iter(
                 ast_func.header.returns.iter().map(|ret| cx.lower_variable(ret).0),
             );
+            // Modifier arguments can reference the function parameters.
+            cx.hir.functions[id].modifiers =
+                cx.arena.alloc_slice_fill_iter(modifiers.into_iter().map(|(id, modifier)| {
+                    hir::Modifier {
+                        span: modifier_span(modifier),
+                        id,
+                        args: cx.lower_call_args(&modifier.arguments),
+                    }
+                }));
             if let Some(body) = &ast_func.body {
                 cx.hir.functions[id].body = Some(cx.lower_stmts(body));
             }
         }
 
+        self.resolve_bases_args(next_id);
+
+        self.resolve_using_directives(next_id);
+
         // Resolve function parameters and local variables, created while resolving functions.
         for id in self.hir.variable_ids().skip(normal_vars) {
             self.resolve_var(id, next_id);
         }
     }
 
+    fn resolve_bases_args(&mut self, next_id: &AtomicUsize) {
+        let mut bases_args = FxIndexMap::<_, SmallVec<[_; 4]>>::default();
+        for (contract_id, base_id, base) in std::mem::take(&mut self.bases_args) {
+            let contract = self.hir.contract(contract_id);
+            let scopes = SymbolResolverScopes::new_in(contract.source, Some(contract_id));
+            let mut cx = ResolveContext::new(self, scopes, next_id);
+            let args = cx.lower_call_args(&base.arguments);
+            let modifier = hir::Modifier {
+                span: modifier_span(base),
+                id: hir::ItemId::Contract(base_id),
+                args,
+            };
+            bases_args.entry(contract_id).or_default().push(modifier);
+        }
+        for (contract_id, bases_args) in bases_args {
+            self.hir.contracts[contract_id].bases_args = self.arena.alloc_smallvec(bases_args);
+        }
+    }
+
+    fn resolve_using_directives(&mut self, next_id: &AtomicUsize) {
+        let mut directives = FxIndexMap::<_, SmallVec<[_; 4]>>::default();
+        for (source, contract, item) in std::mem::take(&mut self.using_directives) {
+            let ast::ItemKind::Using(using) = &item.kind else { unreachable!() };
+            if let Some(directive) =
+                self.lower_using_directive(source, contract, item.span, using, next_id)
+            {
+                directives.entry((source, contract)).or_default().push(directive);
+            }
+        }
+        for ((source, contract), directives) in directives {
+            let directives = self.arena.alloc_smallvec(directives);
+            match contract {
+                Some(id) => self.hir.contracts[id].using_directives = directives,
+                None => self.hir.sources[source].using_directives = directives,
+            }
+        }
+    }
+
+    fn lower_using_directive(
+        &mut self,
+        source: hir::SourceId,
+        contract: Option<hir::ContractId>,
+        span: Span,
+        using: &ast::UsingDirective<'_>,
+        next_id: &AtomicUsize,
+    ) -> Option<hir::UsingDirective<'hir>> {
+        let ast::UsingDirective { ref list, ref ty, global } = *using;
+        if contract.is_some_and(|id| self.hir.contract(id).kind.is_interface()) {
+            let msg = "the `using for` directive is not allowed inside interfaces";
+            self.dcx().err(msg).span(span).emit();
+            return None;
+        }
+        if ty.is_none() {
+            let msg = if contract.is_none() {
+                Some("the type has to be specified explicitly at file level (cannot use `*`)")
+            } else if matches!(list, ast::UsingList::Multiple(_)) {
+                Some("the type has to be specified explicitly when attaching specific functions")
+            } else {
+                None
+            };
+            if let Some(msg) = msg {
+                self.dcx().err(msg).span(span).emit();
+                return None;
+            }
+        }
+
+        let scopes = SymbolResolverScopes::new_in(source, contract);
+        let list = match list {
+            ast::UsingList::Single(path) => {
+                let id = self.resolver.resolve_path_as(path, &scopes, "library").ok()?;
+                if self.hir.contract(id).kind != ast::ContractKind::Library {
+                    let found = self.hir.contract(id).kind.to_str();
+                    self.dcx()
+                        .err(format!("expected library, found {found}"))
+                        .span(path.span())
+                        .help("use `{ ... }` to attach functions")
+                        .emit();
+                    return None;
+                }
+                hir::UsingList::Library(id)
+            }
+            ast::UsingList::Multiple(paths) => {
+                let mut functions = SmallVec::<[_; 8]>::new();
+                for &(ref path, operator) in paths.iter() {
+                    let Ok(id) = self.resolver.resolve_path_as(path, &scopes, "function") else {
+                        continue;
+                    };
+                    let hir::ItemId::Function(function) = id else {
+                        self.resolver.report_expected(
+                            "function",
+                            self.hir.item(id).description(),
+                            path.span(),
+                        );
+                        continue;
+                    };
+                    let func = self.hir.function(function);
+                    let is_library = func
+                        .contract
+                        .is_some_and(|c| self.hir.contract(c).kind == ast::ContractKind::Library);
+                    if func.contract.is_some() && !is_library {
+                        let msg = "only file-level functions and library functions can be attached to a type";
+                        self.dcx().err(msg).span(path.span()).emit();
+                        continue;
+                    }
+                    if operator.is_some() {
+                        if !global {
+                            let msg =
+                                "operators can only be defined in a global `using for` directive";
+                            self.dcx().err(msg).span(path.span()).emit();
+                            continue;
+                        }
+                        if is_library || func.state_mutability != ast::StateMutability::Pure {
+                            let msg = "only pure free functions can be used to define operators";
+                            self.dcx().err(msg).span(path.span()).emit();
+                            continue;
+                        }
+                    }
+                    functions.push(hir::UsingFunction { span: path.span(), function, operator });
+                }
+                hir::UsingList::Functions(self.arena.alloc_smallvec(functions))
+            }
+        };
+
+        let ty = ty.as_ref().map(|ty| {
+            let mut cx = ResolveContext::new(self, scopes, next_id);
+            cx.lower_type(ty)
+        });
+
+        let mut global = global;
+        if global {
+            let msg = if contract.is_some() {
+                Some("`global` can only be used at file level")
+            } else {
+                match ty.as_ref().map(|ty| &ty.kind) {
+                    Some(&hir::TypeKind::Custom(id)) if self.hir.item(id).source() != source => {
+                        Some("`global` can only be used with types defined in the same source unit")
+                    }
+                    Some(hir::TypeKind::Custom(
+                        hir::ItemId::Contract(_)
+                        | hir::ItemId::Struct(_)
+                        | hir::ItemId::Enum(_)
+                        | hir::ItemId::Udvt(_),
+                    ))
+                    | Some(hir::TypeKind::Err(_)) => None,
+                    _ => Some("`global` can only be used with user-defined types"),
+                }
+            };
+            if let Some(msg) = msg {
+                self.dcx().err(msg).span(span).emit();
+                global = false;
+            }
+        }
+
+        if let hir::UsingList::Functions(functions) = list {
+            let is_udvt = matches!(
+                ty.as_ref().map(|ty| &ty.kind),
+                Some(hir::TypeKind::Custom(hir::ItemId::Udvt(_)) | hir::TypeKind::Err(_))
+            );
+            if !is_udvt {
+                for f in functions.iter().filter(|f| f.operator.is_some()) {
+                    let msg = "operators can only be implemented for user-defined value types";
+                    self.dcx().err(msg).span(f.span).emit();
+                }
+            }
+        }
+
+        Some(hir::UsingDirective { span, list, ty, global })
+    }
+
     fn resolve_var(&mut self, id: hir::VariableId, next_id: &AtomicUsize) {
         let var = self.hir.variable(id);
 
@@ -395,12 +559,38 @@ impl super::LoweringContext<'_, '_, '_> {
         };
         let ast::ItemKind::Variable(ast_var) = &ast_item.kind else { unreachable!() };
 
-        let scopes = SymbolResolverScopes::new_in(var.source, var.contract);
+        let (source, contract) = (var.source, var.contract);
+        let scopes = SymbolResolverScopes::new_in(source, contract);
         let mut cx = ResolveContext::new(self, scopes, next_id);
         let init = ast_var.initializer.as_deref().map(|init| cx.lower_expr(init));
         let ty = cx.lower_type(&ast_var.ty);
         self.hir.variables[id].initializer = init;
         self.hir.variables[id].ty = ty;
+
+        if let Some(ov) = &ast_var.override_ {
+            let scopes = SymbolResolverScopes::new_in(source, contract);
+            let overrides = self.resolve_overrides(Some(ov), &scopes);
+            self.hir.variables[id].overrides = overrides;
+            if let Some(getter) = self.hir.variables[id].getter {
+                self.hir.functions[getter].overrides = overrides;
+            }
+        }
+    }
+
+    /// Resolves the contracts of an `override(...)` specifier.
+    fn resolve_overrides(
+        &self,
+        ov: Option<&ast::Override<'_>>,
+        scopes: &SymbolResolverScopes,
+    ) -> &'hir [hir::ContractId] {
+        let Some(ov) = ov else { return &[] };
+        let mut overrides = SmallVec::<[_; 8]>::new();
+        for path in ov.paths.iter() {
+            if let Ok(id) = self.resolver.resolve_path_as(path, scopes, "contract") {
+                overrides.push(id);
+            }
+        }
+        self.arena.alloc_smallvec(overrides)
     }
 
     /// Resolves a getter function.
@@ -638,6 +828,16 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
             .map(|decls| &*self.arena.alloc_slice_fill_iter(decls.iter().map(|decl| decl.res)))
     }
 
+    /// Resolves the given path and lowers it to an identifier expression.
+    fn lower_path_expr(
+        &mut self,
+        path: &ast::PathSlice,
+    ) -> Result<&'hir hir::Expr<'hir>, ErrorGuaranteed> {
+        let res = self.resolve_path(path)?;
+        let kind = hir::ExprKind::Ident(res);
+        Ok(self.arena.alloc(hir::Expr { id: self.next_id(), kind, span: path.span() }))
+    }
+
     fn resolve_path_as<T: TryFrom<Res>>(
         &self,
         path: &ast::PathSlice,
@@ -659,6 +859,35 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
         self.arena.alloc(self.lower_stmt_full(stmt))
     }
 
+    /// Checks the dialect and the flags of an inline assembly block.
+    fn check_assembly_flags(&self, assembly: &ast::StmtAssembly<'_>) {
+        let dcx = &self.sess.dcx;
+        if let Some(dialect) = &assembly.dialect {
+            if dialect.value != sym::evmasm {
+                dcx.err(format!("unknown inline assembly dialect `{}`", dialect.value))
+                    .span(dialect.span)
+                    .help("the only supported dialect is `\"evmasm\"`")
+                    .emit();
+            }
+        }
+        let mut memory_safe = None;
+        for flag in assembly.flags.iter() {
+            if flag.value != sym::memory_dash_safe {
+                dcx.err(format!("unknown inline assembly flag `{}`", flag.value))
+                    .span(flag.span)
+                    .help("the only supported flag is `\"memory-safe\"`")
+                    .emit();
+            } else if let Some(prev) = memory_safe {
+                dcx.err("inline assembly block is marked as `\"memory-safe\"` more than once")
+                    .span(flag.span)
+                    .span_note(prev, "first marked here")
+                    .emit();
+            } else {
+                memory_safe = Some(flag.span);
+            }
+        }
+    }
+
     #[instrument(name = "lower_stmt", level = "debug", skip_all)]
     fn lower_stmt_full(&mut self, stmt: &ast::Stmt<'_>) -> hir::Stmt<'hir> {
         let kind = match &stmt.kind {
@@ -672,10 +901,17 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
                 ),
                 self.lower_expr(expr),
             ),
-            ast::StmtKind::Assembly(_) => hir::StmtKind::Err(
-                // self.dcx().err("assembly is not yet implemented").span(stmt.span).emit(),
-                ErrorGuaranteed::new_unchecked(),
-            ),
+            ast::StmtKind::Assembly(assembly) => {
+                self.check_assembly_flags(assembly);
+                let mut resolver = AssemblyResolver { cx: self, externals: FxHashMap::default() };
+                crate::yul::analyze_inline_assembly(self.sess, assembly.block, &mut resolver);
+                let lowering = YulLowering { arena: self.arena, externals: &resolver.externals };
+                hir::StmtKind::Assembly(self.arena.alloc(hir::StmtAssembly {
+                    dialect: assembly.dialect.clone(),
+                    flags: self.arena.alloc_slice_clone(assembly.flags),
+                    block: lowering.block(assembly.block),
+                }))
+            }
             ast::StmtKind::Block(stmts) => hir::StmtKind::Block(self.lower_block(stmts)),
             ast::StmtKind::UncheckedBlock(stmts) => {
                 hir::StmtKind::UncheckedBlock(self.lower_block(stmts))
@@ -688,12 +924,12 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
             ast::StmtKind::While(_, _)
             | ast::StmtKind::DoWhile(_, _)
             | ast::StmtKind::For { .. } => self.lower_loop_stmt(stmt),
-            ast::StmtKind::Emit(path, args) => match self.resolve_path(path) {
-                Ok(res) => hir::StmtKind::Emit(res, self.lower_call_args(args)),
+            ast::StmtKind::Emit(path, args) => match self.lower_path_expr(path) {
+                Ok(path) => hir::StmtKind::Emit(path, self.lower_call_args(args)),
                 Err(guar) => hir::StmtKind::Err(guar),
             },
-            ast::StmtKind::Revert(path, args) => match self.resolve_path(path) {
-                Ok(res) => hir::StmtKind::Revert(res, self.lower_call_args(args)),
+            ast::StmtKind::Revert(path, args) => match self.lower_path_expr(path) {
+                Ok(path) => hir::StmtKind::Revert(path, self.lower_call_args(args)),
                 Err(guar) => hir::StmtKind::Err(guar),
             },
             ast::StmtKind::Expr(expr) => hir::StmtKind::Expr(self.lower_expr(expr)),
@@ -703,18 +939,19 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
                 else_.as_deref().map(|stmt| self.lower_stmt(stmt)),
             ),
             ast::StmtKind::Try(ast::StmtTry { expr, returns, block, catch }) => {
-                hir::StmtKind::Try(self.arena.alloc(hir::StmtTry {
-                    expr: self.lower_expr_full(expr),
-                    returns: self.lower_variables(returns),
-                    block: self.lower_block(block),
-                    catch: self.arena.alloc_slice_fill_iter(catch.iter().map(|catch| {
-                        hir::CatchClause {
-                            name: catch.name,
-                            args: self.lower_variables(catch.args),
-                            block: self.lower_block(catch.block),
-                        }
-                    })),
-                }))
+                let expr = self.lower_expr_full(expr);
+                // The return and catch variables are only visible in their own block.
+                let (returns, block) =
+                    self.in_scope(|this| (this.lower_variables(returns), this.lower_block(block)));
+                let catch = self.arena.alloc_slice_fill_iter(catch.iter().map(|catch| {
+                    self.in_scope(|this| hir::CatchClause {
+                        span: catch.span,
+                        name: catch.name,
+                        args: this.lower_variables(catch.args),
+                        block: this.lower_block(catch.block),
+                    })
+                }));
+                hir::StmtKind::Try(self.arena.alloc(hir::StmtTry { expr, returns, block, catch }))
             }
             ast::StmtKind::Placeholder => hir::StmtKind::Placeholder,
         };
@@ -935,6 +1172,16 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
         }
     }
 
+    fn lower_type_function_params(
+        &mut self,
+        params: &[ast::VariableDefinition<'_>],
+    ) -> &'hir [hir::TypeFunctionParam<'hir>] {
+        self.arena.alloc_slice_fill_iter(params.iter().map(|p| hir::TypeFunctionParam {
+            ty: self.lower_type(&p.ty),
+            data_location: p.data_location,
+        }))
+    }
+
     #[instrument(name = "lower_stmt", level = "debug", skip_all)]
     fn lower_type(&mut self, ty: &ast::Type<'_>) -> hir::Type<'hir> {
         let kind = match &ty.kind {
@@ -943,18 +1190,14 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
                 element: self.lower_type(&array.element),
                 size: self.lower_expr_opt(array.size.as_deref()),
             })),
-            ast::TypeKind::Function(f) => hir::TypeKind::Function(
-                self.arena.alloc(hir::TypeFunction {
-                    parameters: self
-                        .arena
-                        .alloc_slice_fill_iter(f.parameters.iter().map(|p| self.lower_type(&p.ty))),
-                    visibility: f.visibility.unwrap_or(ast::Visibility::Public),
+            ast::TypeKind::Function(f) => {
+                hir::TypeKind::Function(self.arena.alloc(hir::TypeFunction {
+                    parameters: self.lower_type_function_params(f.parameters),
+                    visibility: f.visibility.unwrap_or(ast::Visibility::Internal),
                     state_mutability: f.state_mutability,
-                    returns: self
-                        .arena
-                        .alloc_slice_fill_iter(f.returns.iter().map(|p| self.lower_type(&p.ty))),
-                }),
-            ),
+                    returns: self.lower_type_function_params(f.returns),
+                }))
+            }
             ast::TypeKind::Mapping(mapping) => {
                 hir::TypeKind::Mapping(self.arena.alloc(hir::TypeMapping {
                     key: self.lower_type(&mapping.key),
@@ -1025,6 +1268,257 @@ pub(crate) struct SymbolResolver<'sess> {
     builtin_members_scopes: Box<[Option<Declarations>; Builtin::COUNT]>,
 }
 
+/// Resolves the Solidity declarations that are referenced from inline assembly.
+struct AssemblyResolver<'a, 'sess, 'hir, 'b> {
+    cx: &'a ResolveContext<'sess, 'hir, 'b>,
+    /// The resolved references, by the span of their path.
+    externals: FxHashMap<Span, Result<hir::yul::External, ErrorGuaranteed>>,
+}
+
+impl crate::yul::ResolveExternal for AssemblyResolver<'_, '_, '_, '_> {
+    fn lookup(&mut self, name: Ident) -> Option<Span> {
+        let decls = self.cx.resolver.resolve_name_raw(name, &self.cx.scopes)?;
+        decls.iter().map(|decl| decl.span).find(|span| !span.is_dummy())
+    }
+
+    fn resolve(&mut self, path: &ast::PathSlice, in_function: bool, assign: bool) -> bool {
+        let Some(decls) = self.cx.resolver.resolve_name_raw(*path.first(), &self.cx.scopes) else {
+            return false;
+        };
+        let result = match decls {
+            [] => return false,
+            [Declaration { res: Res::Item(hir::ItemId::Variable(id)), .. }] => {
+                self.resolve_variable(*id, path, in_function, assign)
+            }
+            [Declaration { res: Res::Err(guar), .. }, ..] => Err(*guar),
+            [decl, ..] => {
+                let msg =
+                    format!("expected variable, found {} `{}`", decl.description(), path.first());
+                Err(self.cx.sess.dcx.err(msg).span(path.span()).emit())
+            }
+        };
+        self.externals.insert(path.span(), result);
+        true
+    }
+}
+
+impl AssemblyResolver<'_, '_, '_, '_> {
+    /// Checks a reference to a Solidity variable, with its optional suffix.
+    ///
+    /// Reference: <https://docs.soliditylang.org/en/latest/assembly.html#access-to-external-variables-functions-and-libraries>
+    fn resolve_variable(
+        &self,
+        id: hir::VariableId,
+        path: &ast::PathSlice,
+        in_function: bool,
+        assign: bool,
+    ) -> Result<hir::yul::External, ErrorGuaranteed> {
+        use hir::yul::Suffix;
+
+        let dcx = &self.cx.sess.dcx;
+        let var = self.cx.hir.variable(id);
+        let name = path.first();
+        let span = path.span();
+
+        let rest = &path.segments()[1..];
+        let suffix = match rest {
+            [suffix] => Suffix::from_symbol(suffix.name),
+            _ => None,
+        };
+        if suffix.is_none() && !rest.is_empty() {
+            let rest = rest.iter().map(|ident| ident.as_str()).collect::<Vec<_>>().join(".");
+            let help =
+                "the valid suffixes are `.slot`, `.offset`, `.length`, `.selector` and `.address`";
+            let msg = format!("unknown suffix `.{rest}`");
+            return Err(dcx.err(msg).span(span).help(help).emit());
+        }
+
+        let is_constant = var.mutability == Some(ast::VarMut::Constant);
+        if var.mutability == Some(ast::VarMut::Immutable) {
+            let msg = "immutable variables cannot be accessed from inline assembly";
+            return Err(dcx.err(msg).span(span).emit());
+        }
+        if in_function && !var.is_state_variable() && !is_constant {
+            let msg = "cannot access local variables from inside of an inline assembly function";
+            return Err(dcx
+                .err(msg)
+                .span(span)
+                .span_note(var.span, "variable declared here")
+                .emit());
+        }
+
+        let is_storage = (var.is_state_variable() && !is_constant)
+            || var.data_location == Some(DataLocation::Storage);
+        let is_dynamic_calldata = var.data_location == Some(DataLocation::Calldata)
+            && match var.ty.kind {
+                hir::TypeKind::Array(array) => array.size.is_none(),
+                hir::TypeKind::Elementary(ty) => {
+                    matches!(ty, hir::ElementaryType::String | hir::ElementaryType::Bytes)
+                }
+                _ => false,
+            };
+        let is_external_function = !var.is_state_variable()
+            && matches!(
+                var.ty.kind,
+                hir::TypeKind::Function(f) if f.visibility == hir::Visibility::External
+            );
+
+        let Some(suffix) = suffix else {
+            let help = if var.is_state_variable() && !is_constant {
+                "only local variables can be accessed directly; use the `.slot` and `.offset` \
+                 suffixes to access the storage location of state variables"
+            } else if is_storage {
+                "storage pointers cannot be accessed directly; use the `.slot` suffix to access \
+                 the storage slot that they point to"
+            } else if is_dynamic_calldata {
+                "dynamic calldata arrays cannot be accessed directly; use the `.offset` and \
+                 `.length` suffixes instead"
+            } else if is_constant && assign {
+                let msg = format!("cannot assign to constant `{name}`");
+                return Err(dcx.err(msg).span(span).emit());
+            } else {
+                return Ok(hir::yul::External { variable: id, suffix: None });
+            };
+            let msg = format!("cannot access `{name}` without a suffix");
+            return Err(dcx.err(msg).span(span).help(help).emit());
+        };
+
+        let (valid, expected) = match suffix {
+            Suffix::Slot => (is_storage, "storage variables"),
+            Suffix::Offset => {
+                (is_storage || is_dynamic_calldata, "storage variables and dynamic calldata arrays")
+            }
+            Suffix::Length => (is_dynamic_calldata, "dynamic calldata arrays"),
+            Suffix::Selector | Suffix::Address => {
+                (is_external_function, "local variables of external function type")
+            }
+        };
+        if !valid {
+            let msg = format!("the `.{suffix}` suffix can only be used with {expected}");
+            return Err(dcx
+                .err(msg)
+                .span(span)
+                .span_note(var.span, "variable declared here")
+                .emit());
+        }
+
+        if assign && is_storage {
+            if var.is_state_variable() {
+                let msg = format!("cannot assign to the `.{suffix}` of state variable `{name}`");
+                return Err(dcx
+                    .err(msg)
+                    .span(span)
+                    .help("use `sstore` to write to storage")
+                    .emit());
+            }
+            if suffix == Suffix::Offset {
+                let msg = format!("cannot assign to the `.offset` of storage pointer `{name}`");
+                return Err(dcx
+                    .err(msg)
+                    .span(span)
+                    .note("only the `.slot` of storage pointers can be assigned to")
+                    .emit());
+            }
+        }
+
+        Ok(hir::yul::External { variable: id, suffix: Some(suffix) })
+    }
+}
+
+/// Lowers an inline assembly block to HIR, once it has been analyzed.
+struct YulLowering<'a, 'hir> {
+    arena: &'hir hir::Arena,
+    /// The resolved references to Solidity variables, by the span of their path.
+    externals: &'a FxHashMap<Span, Result<hir::yul::External, ErrorGuaranteed>>,
+}
+
+impl<'hir> YulLowering<'_, 'hir> {
+    fn block(&self, block: &[yul::Stmt<'_>]) -> hir::yul::Block<'hir> {
+        self.arena.alloc_slice_fill_iter(block.iter().map(|stmt| self.stmt(stmt)))
+    }
+
+    fn stmt(&self, stmt: &yul::Stmt<'_>) -> hir::yul::Stmt<'hir> {
+        let kind = match &stmt.kind {
+            yul::StmtKind::Block(block) => hir::yul::StmtKind::Block(self.block(block)),
+            yul::StmtKind::AssignSingle(path, expr) => hir::yul::StmtKind::Assign(
+                self.arena.alloc_as_slice(self.path(path)),
+                self.arena.alloc(self.expr(expr)),
+            ),
+            yul::StmtKind::AssignMulti(paths, call) => hir::yul::StmtKind::Assign(
+                self.arena.alloc_slice_fill_iter(paths.iter().map(|path| self.path(path))),
+                self.arena.alloc(self.call(call, call.name.span.to(stmt.span))),
+            ),
+            yul::StmtKind::Expr(call) => {
+                hir::yul::StmtKind::Expr(self.arena.alloc(self.call(call, stmt.span)))
+            }
+            yul::StmtKind::If(cond, block) => {
+                hir::yul::StmtKind::If(self.arena.alloc(self.expr(cond)), self.block(block))
+            }
+            yul::StmtKind::For { init, cond, step, body } => hir::yul::StmtKind::For {
+                init: self.block(init),
+                cond: self.arena.alloc(self.expr(cond)),
+                step: self.block(step),
+                body: self.block(body),
+            },
+            yul::StmtKind::Switch(switch) => {
+                hir::yul::StmtKind::Switch(self.arena.alloc(hir::yul::StmtSwitch {
+                    selector: self.expr(&switch.selector),
+                    cases: self.arena.alloc_slice_fill_iter(switch.branches.iter().map(|case| {
+                        hir::yul::StmtSwitchCase {
+                            constant: self.arena.literals.alloc(ast::Lit::clone(case.constant)),
+                            body: self.block(case.body),
+                        }
+                    })),
+                    default_case: switch.default_case.as_ref().map(|block| self.block(block)),
+                }))
+            }
+            yul::StmtKind::Leave => hir::yul::StmtKind::Leave,
+            yul::StmtKind::Break => hir::yul::StmtKind::Break,
+            yul::StmtKind::Continue => hir::yul::StmtKind::Continue,
+            yul::StmtKind::FunctionDef(f) => {
+                hir::yul::StmtKind::FunctionDef(self.arena.alloc(hir::yul::Function {
+                    name: f.name,
+                    parameters: self.arena.alloc_slice_copy(f.parameters),
+                    returns: self.arena.alloc_slice_copy(f.returns),
+                    body: self.block(f.body),
+                }))
+            }
+            yul::StmtKind::VarDecl(names, expr) => hir::yul::StmtKind::VarDecl(
+                self.arena.alloc_slice_copy(names),
+                expr.as_ref().map(|expr| &*self.arena.alloc(self.expr(expr))),
+            ),
+        };
+        hir::yul::Stmt { span: stmt.span, kind }
+    }
+
+    fn expr(&self, expr: &yul::Expr<'_>) -> hir::yul::Expr<'hir> {
+        match &expr.kind {
+            yul::ExprKind::Path(path) => self.path(path),
+            yul::ExprKind::Call(call) => self.call(call, expr.span),
+            yul::ExprKind::Lit(lit) => hir::yul::Expr {
+                span: expr.span,
+                kind: hir::yul::ExprKind::Lit(self.arena.literals.alloc(ast::Lit::clone(lit))),
+            },
+        }
+    }
+
+    fn call(&self, call: &yul::ExprCall<'_>, span: Span) -> hir::yul::Expr<'hir> {
+        let args =
+            self.arena.alloc_slice_fill_iter(call.arguments.iter().map(|arg| self.expr(arg)));
+        hir::yul::Expr { span, kind: hir::yul::ExprKind::Call(call.name, args) }
+    }
+
+    fn path(&self, path: &ast::PathSlice) -> hir::yul::Expr<'hir> {
+        let span = path.span();
+        let kind = match self.externals.get(&span) {
+            Some(&Ok(external)) => hir::yul::ExprKind::External(external),
+            Some(&Err(guar)) => hir::yul::ExprKind::Err(guar),
+            None => hir::yul::ExprKind::Var(Ident::new(crate::yul::path_name(path), span)),
+        };
+        hir::yul::Expr { span, kind }
+    }
+}
+
 impl<'sess> SymbolResolver<'sess> {
     pub(crate) fn new(dcx: &'sess DiagCtxt) -> Self {
         let (global_builtin_scope, builtin_members_scopes) = crate::builtins::scopes();
@@ -1345,3 +1839,13 @@ pub(super) fn report_conflict(
 
     err.emit()
 }
+
+/// Returns the span of a modifier invocation or inheritance specifier, up to its last argument.
+fn modifier_span(modifier: &ast::Modifier<'_>) -> Span {
+    let name_span = modifier.name.span();
+    let last = match &modifier.arguments {
+        ast::CallArgs::Unnamed(args) => args.last().map(|arg| arg.span),
+        ast::CallArgs::Named(args) => args.last().map(|arg| arg.value.span),
+    };
+    last.map_or(name_span, |last| name_span.to(last))
+}
@@ -3,7 +3,7 @@ use crate::{
     hir,
     ty::{Gcx, Ty, TyFnPtr, TyKind},
 };
-use solar_ast::ast::{DataLocation, ElementaryType, StateMutability as SM};
+use solar_ast::ast::{DataLocation, ElementaryType, StateMutability as SM, Visibility};
 use solar_data_structures::BumpExt;
 use solar_interface::{kw, sym, Symbol};
 
@@ -25,8 +25,8 @@ pub(crate) fn members_of<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> MemberList<'gcx>
             ElementaryType::FixedBytes(_size) => fixed_bytes(gcx),
         },
         TyKind::StringLiteral(_utf8, _size) => Default::default(),
-        TyKind::IntLiteral(_size) => Default::default(),
-        TyKind::Ref(inner, loc) => reference(gcx, ty, inner, loc),
+        TyKind::IntLiteral(..) | TyKind::RationalLiteral(_) => Default::default(),
+        TyKind::Ref(inner, loc) => reference(gcx, inner, loc),
         TyKind::DynArray(_ty) => expected_ref(),
         TyKind::Array(_ty, _len) => expected_ref(),
         TyKind::Tuple(_tys) => Default::default(),
@@ -42,7 +42,9 @@ pub(crate) fn members_of<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> MemberList<'gcx>
             .declarations
             .iter()
             .flat_map(|(&name, decls)| {
-                decls.iter().map(move |decl| Member::new(name, gcx.type_of_res(decl.res)))
+                decls
+                    .iter()
+                    .map(move |decl| Member::with_res(name, gcx.type_of_res(decl.res), decl.res))
             })
             .collect(),
         TyKind::BuiltinModule(builtin) => builtin
@@ -51,8 +53,8 @@ pub(crate) fn members_of<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> MemberList<'gcx>
             .iter()
             .map(|&b| Member::of_builtin(gcx, b))
             .collect(),
-        TyKind::Type(_ty) => type_type(gcx, ty),
-        TyKind::Meta(_ty) => meta(gcx, ty),
+        TyKind::Type(ty) => type_type(gcx, ty),
+        TyKind::Meta(ty) => meta(gcx, ty),
         TyKind::Err(_guar) => Default::default(),
     })
 }
@@ -78,7 +80,7 @@ impl<'gcx> Member<'gcx> {
     }
 
     pub fn of_builtin(gcx: Gcx<'gcx>, builtin: Builtin) -> Self {
-        Self { name: builtin.name(), ty: builtin.ty(gcx), res: None }
+        Self::with_builtin(builtin, builtin.ty(gcx))
     }
 
     pub fn of_builtins(
@@ -136,16 +138,15 @@ pub(crate) fn contract(gcx: Gcx<'_>, id: hir::ContractId) -> MemberListOwned<'_>
 }
 
 fn function<'gcx>(gcx: Gcx<'gcx>, f: &'gcx TyFnPtr<'gcx>) -> MemberListOwned<'gcx> {
-    let _ = (gcx, f);
-    todo!()
+    match f.visibility {
+        Visibility::External => {
+            Member::of_builtins(gcx, [Builtin::FunctionSelector, Builtin::FunctionAddress])
+        }
+        _ => Default::default(),
+    }
 }
 
-fn reference<'gcx>(
-    gcx: Gcx<'gcx>,
-    this: Ty<'gcx>,
-    inner: Ty<'gcx>,
-    loc: DataLocation,
-) -> MemberListOwned<'gcx> {
+fn reference<'gcx>(gcx: Gcx<'gcx>, inner: Ty<'gcx>, loc: DataLocation) -> MemberListOwned<'gcx> {
     match (&inner.kind, loc) {
         (&TyKind::Struct(id), _) => {
             let fields = gcx.hir.strukt(id).fields;
@@ -154,7 +155,7 @@ fn reference<'gcx>(
             fields
                 .iter()
                 .zip(tys)
-                .map(|(&f, &ty)| Member::new(gcx.item_name(f).name, ty.with_loc(gcx, loc)))
+                .map(|(&f, &ty)| Member::new(gcx.item_name(f).name, ty.with_loc_if_ref(gcx, loc)))
                 .collect()
         }
         (
@@ -166,11 +167,12 @@ fn reference<'gcx>(
             } else {
                 gcx.types.fixed_bytes(1)
             };
+            let inner_ref = inner.with_loc_if_ref(gcx, loc);
             vec![
                 Member::new(sym::length, gcx.types.uint(256)),
-                Member::new(sym::push, gcx.mk_builtin_fn(&[this, inner], SM::NonPayable, &[])),
-                Member::new(sym::push, gcx.mk_builtin_fn(&[this], SM::NonPayable, &[inner])),
-                Member::new(kw::Pop, gcx.mk_builtin_fn(&[this], SM::NonPayable, &[])),
+                Member::new(sym::push, gcx.mk_builtin_fn(&[inner], SM::NonPayable, &[])),
+                Member::new(sym::push, gcx.mk_builtin_fn(&[], SM::NonPayable, &[inner_ref])),
+                Member::new(kw::Pop, gcx.mk_builtin_fn(&[], SM::NonPayable, &[])),
             ]
         }
         (
@@ -184,8 +186,7 @@ fn reference<'gcx>(
 // `Enum.Variant`, `Udvt.wrap`
 fn type_type<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> MemberListOwned<'gcx> {
     match ty.kind {
-        // TODO: https://github.com/ethereum/solidity/blob/9d7cc42bc1c12bb43e9dccf8c6c36833fdfcbbca/libsolidity/ast/Types.cpp#L3913
-        TyKind::Contract(_) => Default::default(),
+        TyKind::Contract(id) => contract_type(gcx, id),
         TyKind::Enum(id) => {
             gcx.hir.enumm(id).variants.iter().map(|v| Member::new(v.name, ty)).collect()
         }
@@ -207,6 +208,51 @@ fn type_type<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> MemberListOwned<'gcx> {
     }
 }
 
+// `Contract.member`
+// https://github.com/ethereum/solidity/blob/9d7cc42bc1c12bb43e9dccf8c6c36833fdfcbbca/libsolidity/ast/Types.cpp#L3913
+fn contract_type(gcx: Gcx<'_>, id: hir::ContractId) -> MemberListOwned<'_> {
+    gcx.symbol_resolver.contract_scopes[id]
+        .declarations
+        .iter()
+        .flat_map(|(&name, decls)| decls.iter().map(move |decl| (name, decl.res)))
+        .filter_map(|(name, res)| {
+            let hir::Res::Item(item_id) = res else { return None };
+            let ty = match gcx.hir.item(item_id) {
+                hir::Item::Function(f) => {
+                    if !f.is_ordinary() || f.is_getter() || f.visibility == Visibility::Private {
+                        return None;
+                    }
+                    // Functions accessed through the contract type are internal, unless they can
+                    // only be called externally.
+                    let ty = gcx.type_of_item(item_id);
+                    let TyKind::FnPtr(f) = ty.kind else {
+                        return Some(Member::with_res(name, ty, res));
+                    };
+                    if f.visibility == Visibility::External {
+                        ty
+                    } else {
+                        gcx.mk_ty_fn(
+                            f.parameters,
+                            f.state_mutability,
+                            Visibility::Internal,
+                            f.returns,
+                        )
+                    }
+                }
+                hir::Item::Variable(var) => {
+                    if var.mutability != Some(hir::VarMut::Constant) {
+                        return None;
+                    }
+                    gcx.type_of_item(item_id)
+                }
+                hir::Item::Contract(_) => return None,
+                _ => gcx.type_of_res(res),
+            };
+            Some(Member::with_res(name, ty, res))
+        })
+        .collect()
+}
+
 // `type(T)`
 fn meta<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> MemberListOwned<'gcx> {
     match ty.kind {
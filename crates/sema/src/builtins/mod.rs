@@ -82,7 +82,7 @@ declare_builtins! {
     Revert                 => kw::Revert
                            => gcx.mk_builtin_fn(&[], SM::Pure, &[]);
     RevertMsg              => kw::Revert
-                           => gcx.mk_builtin_fn(&[gcx.types.string], SM::Pure, &[]);
+                           => gcx.mk_builtin_fn(&[gcx.types.string_ref.memory], SM::Pure, &[]);
 
     AddMod                 => kw::Addmod
                            => gcx.mk_builtin_fn(&[gcx.types.uint(256), gcx.types.uint(256), gcx.types.uint(256)], SM::Pure, &[gcx.types.uint(256)]);
@@ -90,13 +90,13 @@ declare_builtins! {
                            => gcx.mk_builtin_fn(&[gcx.types.uint(256), gcx.types.uint(256), gcx.types.uint(256)], SM::Pure, &[gcx.types.uint(256)]);
 
     Keccak256              => kw::Keccak256
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.fixed_bytes(32)]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::Pure, &[gcx.types.fixed_bytes(32)]);
     Sha256                 => sym::sha256
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.fixed_bytes(32)]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::Pure, &[gcx.types.fixed_bytes(32)]);
     Ripemd160              => sym::ripemd160
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.fixed_bytes(20)]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::Pure, &[gcx.types.fixed_bytes(20)]);
     EcRecover              => sym::ecrecover
-                           => gcx.mk_builtin_fn(&[gcx.types.fixed_bytes(32), gcx.types.uint(8), gcx.types.fixed_bytes(32), gcx.types.fixed_bytes(32)], SM::View, &[gcx.types.address]);
+                           => gcx.mk_builtin_fn(&[gcx.types.fixed_bytes(32), gcx.types.uint(8), gcx.types.fixed_bytes(32), gcx.types.fixed_bytes(32)], SM::Pure, &[gcx.types.address]);
 
     Block                  => sym::block
                            => gcx.mk_builtin_mod(Self::Block);
@@ -150,22 +150,23 @@ declare_builtins! {
                            => gcx.types.uint(256);
 
     // `abi`
-    // TODO                => `(T...) pure returns(bytes memory)`
+    // The variadic functions are type checked specially, see `TypeChecker::check_variadic_call`.
+    // Variadic            => `(T...) pure returns(bytes memory)`
     AbiEncode              => sym::encode
                            => gcx.mk_builtin_fn(&[], SM::Pure, &[gcx.types.bytes_ref.memory]);
-    // TODO                => `(T...) pure returns(bytes memory)`
+    // Variadic            => `(T...) pure returns(bytes memory)`
     AbiEncodePacked        => sym::encodePacked
                            => gcx.mk_builtin_fn(&[], SM::Pure, &[gcx.types.bytes_ref.memory]);
-    // TODO                => `(bytes4, T...) pure returns(bytes memory)`
+    // Variadic            => `(bytes4, T...) pure returns(bytes memory)`
     AbiEncodeWithSelector  => sym::encodeWithSelector
                            => gcx.mk_builtin_fn(&[], SM::Pure, &[gcx.types.bytes_ref.memory]);
-    // TODO                => `(F, T...) pure returns(bytes memory)`
+    // Variadic            => `(F, (T...)) pure returns(bytes memory)`
     AbiEncodeCall          => sym::encodeCall
                            => gcx.mk_builtin_fn(&[], SM::Pure, &[gcx.types.bytes_ref.memory]);
-    // TODO                => `(string memory, T...) pure returns(bytes memory)`
+    // Variadic            => `(string memory, T...) pure returns(bytes memory)`
     AbiEncodeWithSignature => sym::encodeWithSignature
                            => gcx.mk_builtin_fn(&[], SM::Pure, &[gcx.types.bytes_ref.memory]);
-    // TODO                => `(bytes memory, (T...)) pure returns(T...)`
+    // Variadic            => `(bytes memory, (T...)) pure returns(T...)`
     AbiDecode              => sym::decode
                            => gcx.mk_builtin_fn(&[], SM::Pure, &[]);
 
@@ -178,11 +179,11 @@ declare_builtins! {
     AddressCodehash        => sym::codehash
                            => gcx.types.fixed_bytes(32);
     AddressCall            => kw::Call
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.bytes_ref.memory]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::Payable, &[gcx.types.bool, gcx.types.bytes_ref.memory]);
     AddressDelegatecall    => kw::Delegatecall
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.bytes_ref.memory]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::NonPayable, &[gcx.types.bool, gcx.types.bytes_ref.memory]);
     AddressStaticcall      => kw::Staticcall
-                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.bytes_ref.memory]);
+                           => gcx.mk_builtin_fn(&[gcx.types.bytes_ref.memory], SM::View, &[gcx.types.bool, gcx.types.bytes_ref.memory]);
 
     AddressPayableTransfer => sym::transfer
                            => gcx.mk_builtin_fn(&[gcx.types.uint(256)], SM::NonPayable, &[]);
@@ -201,6 +202,11 @@ declare_builtins! {
     EventSelector          => sym::selector
                            => gcx.types.fixed_bytes(32);
 
+    FunctionSelector       => sym::selector
+                           => gcx.types.fixed_bytes(4);
+    FunctionAddress        => kw::Address
+                           => gcx.types.address;
+
     // `type(T)`
     ContractCreationCode   => sym::creationCode
                            => gcx.types.bytes_ref.memory;
@@ -217,11 +223,11 @@ declare_builtins! {
     UdvtWrap               => sym::wrap   => unreachable!();
     UdvtUnwrap             => sym::unwrap => unreachable!();
 
-    // TODO                => `(string memory...) pure returns(string memory)`
+    // Variadic            => `(string memory...) pure returns(string memory)`
     StringConcat           => sym::concat
                            => gcx.mk_builtin_fn(&[], SM::Pure, &[gcx.types.string_ref.memory]);
 
-    // TODO                => `(bytes memory...) pure returns(bytes memory)`
+    // Variadic            => `(bytes memory...) pure returns(bytes memory)`
     BytesConcat            => sym::concat
                            => gcx.mk_builtin_fn(&[], SM::Pure, &[gcx.types.bytes_ref.memory]);
 }
@@ -0,0 +1,336 @@
+//! Control-flow graphs of function bodies.
+//!
+//! A [`Cfg`] splits the statements of a [`hir::Block`] into [`BasicBlock`]s, which are sequences
+//! of statements that are always executed together, connected by [`Terminator`]s.
+//!
+//! Control-flow statements, such as `if`, `return` or `break`, never appear in the statements of a
+//! basic block: they are lowered to terminators instead.
+
+use crate::{
+    builtins::Builtin,
+    hir::{self, yul},
+};
+use solar_data_structures::{
+    index::{IndexSlice, IndexVec},
+    newtype_index,
+    smallvec::{smallvec, SmallVec},
+};
+
+newtype_index! {
+    /// A [`BasicBlock`] ID.
+    pub struct BasicBlockId;
+}
+
+/// The control-flow graph of a function body.
+#[derive(Debug)]
+pub struct Cfg<'hir> {
+    blocks: IndexVec<BasicBlockId, BasicBlock<'hir>>,
+}
+
+/// A sequence of statements that are executed one after the other, followed by a terminator.
+#[derive(Debug)]
+pub struct BasicBlock<'hir> {
+    /// The statements of the block.
+    ///
+    /// These are only declarations, expressions, `emit` statements, inline assembly blocks,
+    /// modifier placeholders, `revert` statements whose target is not an error, or statements
+    /// that could not be lowered.
+    pub stmts: Vec<&'hir hir::Stmt<'hir>>,
+    /// The terminator of the block.
+    pub terminator: Terminator<'hir>,
+}
+
+/// The way a [`BasicBlock`] transfers control once all of its statements have been executed.
+#[derive(Debug)]
+pub enum Terminator<'hir> {
+    /// Continues to the given block.
+    Goto(BasicBlockId),
+    /// Continues to `then` if the condition is true, and to `else_` otherwise.
+    ///
+    /// This is used for `if` statements and for the conditions of loops.
+    Branch { cond: &'hir hir::Expr<'hir>, then: BasicBlockId, else_: BasicBlockId },
+    /// A `try` statement: continues to `success` if the external call succeeds, and to one of the
+    /// `catch` blocks otherwise, in the same order as the catch clauses.
+    Try { stmt: &'hir hir::Stmt<'hir>, success: BasicBlockId, catch: SmallVec<[BasicBlockId; 2]> },
+    /// Returns from the function, either with a `return` statement or by reaching the end of the
+    /// body (`None`).
+    Return(Option<&'hir hir::Stmt<'hir>>),
+    /// Reverts the execution, either with a `revert` statement or with a call to the `revert`
+    /// builtin function.
+    Revert(&'hir hir::Stmt<'hir>),
+    /// Ends the execution of the whole call from inline assembly, for example with the `return`
+    /// or `revert` builtins. The assembly block is the last statement of the basic block.
+    Exit,
+}
+
+impl<'hir> Cfg<'hir> {
+    /// The ID of the entry block.
+    pub const ENTRY: BasicBlockId = BasicBlockId::new(0);
+
+    /// Builds the control-flow graph of the given function body.
+    pub fn new(body: hir::Block<'hir>) -> Self {
+        let mut builder = CfgBuilder::new();
+        // The last block returns, since it is not terminated explicitly.
+        builder.lower_block(body);
+        Self { blocks: builder.blocks }
+    }
+
+    /// Returns the basic blocks of the graph.
+    #[inline]
+    pub fn blocks(&self) -> &IndexSlice<BasicBlockId, [BasicBlock<'hir>]> {
+        &self.blocks
+    }
+
+    /// Returns the basic block with the given ID.
+    #[inline]
+    pub fn block(&self, id: BasicBlockId) -> &BasicBlock<'hir> {
+        &self.blocks[id]
+    }
+
+    /// Returns the predecessors of each block.
+    pub fn predecessors(&self) -> IndexVec<BasicBlockId, SmallVec<[BasicBlockId; 4]>> {
+        let mut preds = IndexVec::from_vec(vec![SmallVec::new(); self.blocks.len()]);
+        for (id, block) in self.blocks.iter_enumerated() {
+            for succ in block.terminator.successors() {
+                preds[succ].push(id);
+            }
+        }
+        preds
+    }
+
+    /// Returns whether each block can be reached from the entry block.
+    pub fn reachable_blocks(&self) -> IndexVec<BasicBlockId, bool> {
+        let mut reachable = IndexVec::from_vec(vec![false; self.blocks.len()]);
+        let mut stack = vec![Self::ENTRY];
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut reachable[id], true) {
+                continue;
+            }
+            stack.extend(self.blocks[id].terminator.successors());
+        }
+        reachable
+    }
+}
+
+impl<'hir> Terminator<'hir> {
+    /// Returns the blocks that control can be transferred to.
+    pub fn successors(&self) -> SmallVec<[BasicBlockId; 2]> {
+        match self {
+            Self::Goto(target) => smallvec![*target],
+            Self::Branch { then, else_, .. } => smallvec![*then, *else_],
+            Self::Try { success, catch, .. } => {
+                std::iter::once(*success).chain(catch.iter().copied()).collect()
+            }
+            Self::Return(_) | Self::Revert(_) | Self::Exit => SmallVec::new(),
+        }
+    }
+}
+
+/// The targets of `break` and `continue` statements in a loop.
+struct LoopTargets<'hir> {
+    header: BasicBlockId,
+    break_: BasicBlockId,
+    continue_: BasicBlockId,
+    /// The statement at the end of the loop body that `continue` jumps to, if it has not been
+    /// lowered yet. See [`continue_stmt`].
+    continue_stmt: Option<&'hir hir::Stmt<'hir>>,
+}
+
+struct CfgBuilder<'hir> {
+    blocks: IndexVec<BasicBlockId, BasicBlock<'hir>>,
+    /// The block that statements are currently being added to.
+    current: BasicBlockId,
+    loops: Vec<LoopTargets<'hir>>,
+}
+
+impl<'hir> CfgBuilder<'hir> {
+    fn new() -> Self {
+        let mut this = Self { blocks: IndexVec::new(), current: Cfg::ENTRY, loops: Vec::new() };
+        this.current = this.new_block();
+        this
+    }
+
+    fn new_block(&mut self) -> BasicBlockId {
+        // Blocks that are not terminated explicitly return at the end of the body.
+        self.blocks.push(BasicBlock { stmts: Vec::new(), terminator: Terminator::Return(None) })
+    }
+
+    /// Terminates the current block, and continues in a new block that is unreachable unless it is
+    /// jumped to.
+    fn terminate(&mut self, terminator: Terminator<'hir>) {
+        self.blocks[self.current].terminator = terminator;
+        self.current = self.new_block();
+    }
+
+    /// Terminates the current block with a jump to `target`, and continues in `target`.
+    fn goto(&mut self, target: BasicBlockId) {
+        self.blocks[self.current].terminator = Terminator::Goto(target);
+        self.current = target;
+    }
+
+    /// Lowers `f` into `block`, and jumps to `next` at the end.
+    fn lower_into(&mut self, block: BasicBlockId, next: BasicBlockId, f: impl FnOnce(&mut Self)) {
+        self.current = block;
+        f(self);
+        self.goto(next);
+    }
+
+    fn lower_block(&mut self, block: hir::Block<'hir>) {
+        for stmt in block {
+            self.lower_stmt(stmt);
+        }
+    }
+
+    fn lower_stmt(&mut self, stmt: &'hir hir::Stmt<'hir>) {
+        if let Some(targets) = self.loops.last_mut() {
+            if targets.continue_stmt.is_some_and(|s| std::ptr::eq(s, stmt)) {
+                // The statement starts the block that `continue` jumps to. A `continue` inside of
+                // it, as in the condition of a `do while` loop, jumps back to the header.
+                targets.continue_stmt = None;
+                let continue_ = std::mem::replace(&mut targets.continue_, targets.header);
+                self.goto(continue_);
+            }
+        }
+        match stmt.kind {
+            hir::StmtKind::Block(block) | hir::StmtKind::UncheckedBlock(block) => {
+                self.lower_block(block)
+            }
+            hir::StmtKind::Expr(expr) if is_revert_call(expr) => {
+                self.terminate(Terminator::Revert(stmt))
+            }
+            hir::StmtKind::Revert(error, _) if may_be_error(error) => {
+                self.terminate(Terminator::Revert(stmt))
+            }
+            hir::StmtKind::Assembly(assembly) if yul_block_terminates(assembly.block) => {
+                self.blocks[self.current].stmts.push(stmt);
+                self.terminate(Terminator::Exit);
+            }
+            hir::StmtKind::DeclSingle(_)
+            | hir::StmtKind::DeclMulti(..)
+            | hir::StmtKind::Emit(..)
+            | hir::StmtKind::Revert(..)
+            | hir::StmtKind::Expr(_)
+            | hir::StmtKind::Assembly(_)
+            | hir::StmtKind::Placeholder
+            | hir::StmtKind::Err(_) => self.blocks[self.current].stmts.push(stmt),
+            hir::StmtKind::Return(_) => self.terminate(Terminator::Return(Some(stmt))),
+            hir::StmtKind::Break => {
+                // Outside of a loop this is already an error; ignore it.
+                if let Some(target) = self.loops.last().map(|l| l.break_) {
+                    self.terminate(Terminator::Goto(target));
+                }
+            }
+            hir::StmtKind::Continue => {
+                if let Some(target) = self.loops.last().map(|l| l.continue_) {
+                    self.terminate(Terminator::Goto(target));
+                }
+            }
+            hir::StmtKind::Loop(body, source) => {
+                let header = self.new_block();
+                let after = self.new_block();
+                let continue_stmt = continue_stmt(body, source);
+                let continue_ = if continue_stmt.is_some() { self.new_block() } else { header };
+                self.goto(header);
+                self.loops.push(LoopTargets { header, break_: after, continue_, continue_stmt });
+                self.lower_block(body);
+                self.goto(header);
+                self.loops.pop();
+                self.current = after;
+            }
+            hir::StmtKind::If(cond, then, else_) => {
+                let then_block = self.new_block();
+                let else_block = else_.map(|_| self.new_block());
+                let after = self.new_block();
+                self.blocks[self.current].terminator = Terminator::Branch {
+                    cond,
+                    then: then_block,
+                    else_: else_block.unwrap_or(after),
+                };
+                self.lower_into(then_block, after, |this| this.lower_stmt(then));
+                if let (Some(else_), Some(else_block)) = (else_, else_block) {
+                    self.lower_into(else_block, after, |this| this.lower_stmt(else_));
+                }
+                self.current = after;
+            }
+            hir::StmtKind::Try(try_) => {
+                let success = self.new_block();
+                let catch = try_.catch.iter().map(|_| self.new_block()).collect::<SmallVec<_>>();
+                let after = self.new_block();
+                self.blocks[self.current].terminator =
+                    Terminator::Try { stmt, success, catch: catch.clone() };
+                self.lower_into(success, after, |this| this.lower_block(try_.block));
+                for (clause, block) in try_.catch.iter().zip(catch) {
+                    self.lower_into(block, after, |this| this.lower_block(clause.block));
+                }
+                self.current = after;
+            }
+        }
+    }
+}
+
+/// Returns the statement at the end of a desugared loop body that `continue` jumps to: the `next`
+/// expression of a `for` loop, or the condition of a `do while` loop.
+fn continue_stmt<'hir>(
+    body: hir::Block<'hir>,
+    source: hir::LoopSource,
+) -> Option<&'hir hir::Stmt<'hir>> {
+    match (source, body) {
+        (hir::LoopSource::DoWhile, [_, cond]) => Some(cond),
+        (hir::LoopSource::For, [stmt]) => {
+            // See the desugaring of `for` loops in AST lowering.
+            let inner = match stmt.kind {
+                hir::StmtKind::If(_, inner, Some(_)) if inner.span == stmt.span => inner,
+                _ => stmt,
+            };
+            match inner.kind {
+                hir::StmtKind::Block(
+                    [body, next @ hir::Stmt { kind: hir::StmtKind::Expr(_), .. }],
+                ) if body.span == inner.span => Some(next),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns `true` if the Yul block always ends the execution of the call with a builtin such as
+/// `return` or `revert`.
+///
+/// Calls to functions that are defined in the block are assumed to return.
+fn yul_block_terminates(block: yul::Block<'_>) -> bool {
+    block.iter().any(|stmt| match stmt.kind {
+        yul::StmtKind::Expr(expr) => expr.builtin().is_some_and(|builtin| builtin.terminates()),
+        yul::StmtKind::Block(block) => yul_block_terminates(block),
+        yul::StmtKind::For { init, .. } => yul_block_terminates(init),
+        yul::StmtKind::Switch(switch) => {
+            switch.default_case.is_some_and(yul_block_terminates)
+                && switch.cases.iter().all(|case| yul_block_terminates(case.body))
+        }
+        _ => false,
+    })
+}
+
+/// Returns `false` if the target of a `revert` statement is known not to be an error, in which
+/// case the statement is invalid and is not treated as reverting.
+///
+/// Errors that are accessed through a member, such as `L.E`, are only resolved during type
+/// checking.
+fn may_be_error(expr: &hir::Expr<'_>) -> bool {
+    match expr.peel_parens().kind {
+        hir::ExprKind::Ident(res) => {
+            res.iter().any(|res| matches!(res, hir::Res::Item(hir::ItemId::Error(_))))
+        }
+        hir::ExprKind::Err(_) => false,
+        _ => true,
+    }
+}
+
+/// Returns `true` if the expression is a call to the `revert` builtin function.
+fn is_revert_call(expr: &hir::Expr<'_>) -> bool {
+    let hir::ExprKind::Call(callee, _) = expr.kind else { return false };
+    let hir::ExprKind::Ident(res) = callee.peel_parens().kind else { return false };
+    !res.is_empty()
+        && res
+            .iter()
+            .all(|res| matches!(res, hir::Res::Builtin(Builtin::Revert | Builtin::RevertMsg)))
+}
@@ -1,8 +1,14 @@
-use crate::ty::Gcx;
-use serde::Serialize;
+use crate::{
+    hir,
+    ty::{Gcx, StorageItem, Ty, TyKind},
+};
+use alloy_primitives::{hex, U256};
+use serde::{Deserialize, Serialize};
+use solar_ast::ast::{ElementaryType, Visibility};
 use solar_interface::config::CompilerOutput;
 use std::{
     collections::BTreeMap,
+    fmt::Write as _,
     io::{self, Write},
     path::Path,
 };
@@ -19,12 +25,60 @@ struct CombinedJsonContract {
     #[serde(skip_serializing_if = "Option::is_none")]
     abi: Option<Abi>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    bin: Option<String>,
+    #[serde(rename = "bin-runtime", skip_serializing_if = "Option::is_none")]
+    bin_runtime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     hashes: Option<Hashes>,
+    #[serde(rename = "storage-layout", skip_serializing_if = "Option::is_none")]
+    storage_layout: Option<StorageLayout>,
+    #[serde(rename = "transient-storage-layout", skip_serializing_if = "Option::is_none")]
+    transient_storage_layout: Option<StorageLayout>,
 }
 
 type Abi = Vec<alloy_json_abi::AbiItem<'static>>;
 type Hashes = BTreeMap<String, String>;
 
+/// The solc `storageLayout` JSON format.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html#json-output>
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct StorageLayout {
+    pub(crate) storage: Vec<StorageLayoutItem>,
+    /// `null` if there are no variables.
+    pub(crate) types: Option<BTreeMap<String, StorageLayoutType>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StorageLayoutItem {
+    /// The ID of the variable declaration.
+    pub(crate) ast_id: u32,
+    /// The fully qualified name of the contract whose layout this is.
+    pub(crate) contract: String,
+    pub(crate) label: String,
+    pub(crate) offset: u8,
+    pub(crate) slot: String,
+    #[serde(rename = "type")]
+    pub(crate) ty: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StorageLayoutType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) base: Option<String>,
+    pub(crate) encoding: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) key: Option<String>,
+    pub(crate) label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) members: Option<Vec<StorageLayoutItem>>,
+    pub(crate) number_of_bytes: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) value: Option<String>,
+}
+
 pub(crate) fn emit(gcx: Gcx<'_>) {
     let mut output =
         CombinedJson { contracts: Default::default(), version: solar_interface::VERSION };
@@ -34,16 +88,36 @@ pub(crate) fn emit(gcx: Gcx<'_>) {
         for &emit in &gcx.sess.emit {
             match emit {
                 CompilerOutput::Abi => contract_output.abi = Some(gcx.contract_abi(id)),
+                // Abstract contracts and interfaces have empty bytecode.
+                CompilerOutput::Bin => {
+                    let bytecode = gcx.contract_bytecode(id);
+                    contract_output.bin =
+                        Some(bytecode.map(|b| hex::encode(b.creation)).unwrap_or_default());
+                }
+                CompilerOutput::BinRuntime => {
+                    let bytecode = gcx.contract_bytecode(id);
+                    contract_output.bin_runtime =
+                        Some(bytecode.map(|b| hex::encode(b.runtime)).unwrap_or_default());
+                }
                 CompilerOutput::Hashes => {
                     let mut hashes = Hashes::default();
                     for f in gcx.interface_functions(id) {
                         hashes.insert(
                             gcx.item_signature(f.id.into()).to_string(),
-                            alloy_primitives::hex::encode(f.selector),
+                            hex::encode(f.selector),
                         );
                     }
                     contract_output.hashes = Some(hashes);
                 }
+                CompilerOutput::StorageLayout => {
+                    let layout = gcx.storage_layout(id);
+                    contract_output.storage_layout =
+                        Some(StorageLayoutBuilder::new(gcx, id).build(layout.storage));
+                    contract_output.transient_storage_layout =
+                        Some(StorageLayoutBuilder::new(gcx, id).build(layout.transient));
+                }
+                // Emitted by the code generator.
+                CompilerOutput::Ir => {}
             }
         }
     }
@@ -57,6 +131,142 @@ pub(crate) fn emit(gcx: Gcx<'_>) {
     .map_err(|e| gcx.dcx().err(format!("failed to write to output: {e}")).emit());
 }
 
+pub(crate) struct StorageLayoutBuilder<'gcx> {
+    gcx: Gcx<'gcx>,
+    contract: String,
+    types: BTreeMap<String, StorageLayoutType>,
+}
+
+impl<'gcx> StorageLayoutBuilder<'gcx> {
+    pub(crate) fn new(gcx: Gcx<'gcx>, contract: hir::ContractId) -> Self {
+        let contract = gcx.contract_fully_qualified_name(contract).to_string();
+        Self { gcx, contract, types: BTreeMap::new() }
+    }
+
+    pub(crate) fn build(mut self, items: &[StorageItem<'gcx>]) -> StorageLayout {
+        let storage = self.items(items);
+        let types = (!storage.is_empty()).then_some(self.types);
+        StorageLayout { storage, types }
+    }
+
+    fn items(&mut self, items: &[StorageItem<'gcx>]) -> Vec<StorageLayoutItem> {
+        items
+            .iter()
+            .map(|item| StorageLayoutItem {
+                ast_id: item.variable.get(),
+                contract: self.contract.clone(),
+                label: self.gcx.item_name(item.variable).to_string(),
+                offset: item.offset,
+                slot: item.slot.to_string(),
+                ty: self.add_type(item.ty, false),
+            })
+            .collect()
+    }
+
+    /// Adds the type and the types it contains to the `types` map, and returns its identifier.
+    fn add_type(&mut self, ty: Ty<'gcx>, mapping_key: bool) -> String {
+        let ty = ty.peel_refs();
+        let id = type_identifier(self.gcx, ty, mapping_key);
+        if self.types.contains_key(&id) {
+            return id;
+        }
+
+        let mut base = None;
+        let mut key = None;
+        let mut value = None;
+        let encoding = match ty.kind {
+            TyKind::Elementary(ElementaryType::String | ElementaryType::Bytes) => "bytes",
+            TyKind::Mapping(k, v) => {
+                key = Some(self.add_type(k, true));
+                value = Some(self.add_type(v, false));
+                "mapping"
+            }
+            TyKind::DynArray(elem) => {
+                base = Some(self.add_type(elem, false));
+                "dynamic_array"
+            }
+            TyKind::Array(elem, _) => {
+                base = Some(self.add_type(elem, false));
+                "inplace"
+            }
+            _ => "inplace",
+        };
+        let size = self.gcx.storage_size(ty);
+        let number_of_bytes = if ty.is_value_type() {
+            size.bytes.to_string()
+        } else {
+            size.slots.saturating_mul(U256::from(32)).to_string()
+        };
+        let entry = StorageLayoutType {
+            base,
+            encoding: encoding.to_string(),
+            key,
+            label: ty.display_short(self.gcx).to_string(),
+            members: None,
+            number_of_bytes,
+            value,
+        };
+        self.types.insert(id.clone(), entry);
+        // Structs can contain themselves, so they are added before their members.
+        if let TyKind::Struct(struct_id) = ty.kind {
+            let members = self.items(self.gcx.struct_storage_layout(struct_id).members);
+            self.types.get_mut(&id).unwrap().members = Some(members);
+        }
+        id
+    }
+}
+
+/// Returns the solc type identifier of a type in storage, such as `t_uint256` or
+/// `t_mapping(t_address,t_uint256)`.
+///
+/// Mapping keys are stored in memory when hashing, so their reference types are identified as
+/// memory pointers.
+fn type_identifier<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>, mapping_key: bool) -> String {
+    let mut s = String::from("t_");
+    write_type_identifier(gcx, ty, mapping_key, &mut s);
+    s
+}
+
+fn write_type_identifier<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>, mapping_key: bool, s: &mut String) {
+    let loc = if mapping_key { "_memory_ptr" } else { "_storage" };
+    let _ = match ty.kind {
+        TyKind::Elementary(ElementaryType::Address(true)) => write!(s, "address_payable"),
+        TyKind::Elementary(ty @ (ElementaryType::String | ElementaryType::Bytes)) => {
+            write!(s, "{}{loc}", ty.to_abi_str())
+        }
+        TyKind::Elementary(ty) => write!(s, "{}", ty.to_abi_str()),
+        TyKind::Ref(ty, _) => return write_type_identifier(gcx, ty, mapping_key, s),
+        TyKind::Contract(id) => write!(s, "contract({}){}", gcx.item_name(id), id.get()),
+        TyKind::Enum(id) => write!(s, "enum({}){}", gcx.item_name(id), id.get()),
+        TyKind::Udvt(_, id) => {
+            write!(s, "userDefinedValueType({}){}", gcx.item_name(id), id.get())
+        }
+        TyKind::Struct(id) => write!(s, "struct({}){}{loc}", gcx.item_name(id), id.get()),
+        TyKind::DynArray(elem) => {
+            write!(s, "array({})dyn{loc}", type_identifier(gcx, elem, false))
+        }
+        TyKind::Array(elem, len) => {
+            write!(s, "array({}){len}{loc}", type_identifier(gcx, elem, false))
+        }
+        TyKind::Mapping(key, value) => write!(
+            s,
+            "mapping({},{})",
+            type_identifier(gcx, key, true),
+            type_identifier(gcx, value, false)
+        ),
+        TyKind::FnPtr(f) => {
+            let kind = if f.visibility == Visibility::External { "external" } else { "internal" };
+            let _ = write!(s, "function_{kind}_{}", f.state_mutability);
+            let list = |tys: &[Ty<'gcx>]| {
+                let ids = tys.iter().map(|&ty| type_identifier(gcx, ty, false));
+                format!("$_{}_$", ids.collect::<Vec<_>>().join("_$_"))
+            };
+            write!(s, "{}returns{}", list(f.parameters), list(f.returns))
+        }
+        _ => write!(s, "{}", ty.display_short(gcx)),
+    };
+}
+
 fn out_writer(path: Option<&Path>) -> io::Result<impl io::Write> {
     let out: Box<dyn io::Write> = if let Some(path) = path {
         Box::new(std::fs::File::create(path)?)
@@ -1,25 +1,38 @@
-use crate::{hir, ty::Gcx};
-use alloy_primitives::U256;
-use solar_ast::ast::LitKind;
-use solar_interface::{diagnostics::ErrorGuaranteed, Span};
-use std::fmt;
+use crate::{
+    builtins::Builtin,
+    hir,
+    ty::{Gcx, Ty, TyKind},
+};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use solar_ast::ast::{ElementaryType, LitKind, TypeSize};
+use solar_interface::{diagnostics::ErrorGuaranteed, sym, Span};
+use std::{borrow::Cow, cmp::Ordering, fmt};
 
 const RECURSION_LIMIT: usize = 64;
 
-// TODO: `convertType` for truncating and extending correctly: https://github.com/ethereum/solidity/blob/de1a017ccb935d149ed6bcbdb730d89883f8ce02/libsolidity/analysis/ConstantEvaluator.cpp#L234
+/// The maximum number of bits of the numerator and denominator of number literals and of the
+/// intermediate results of operations on them.
+const LITERAL_BITS_LIMIT: u64 = 4096;
 
-/// Evaluates simple constants.
+/// Evaluates constant expressions at compile time.
 ///
-/// This only supports basic arithmetic and logical operations, and does not support more complex
-/// operations like function calls or memory allocation.
+/// Values are typed: number literals and operations on them are computed with arbitrary
+/// precision, while typed integers are checked for overflow, and truncated or extended by explicit
+/// conversions, according to their type.
 ///
-/// This is only supposed to be used for array sizes and other simple constants.
+/// Supported expressions are literals, constant variables, unary and binary operations,
+/// ternaries, tuples, explicit conversions to elementary types, `type(T).min` and `type(T).max`,
+/// and `keccak256` of string literals. Function calls and memory allocation are not supported.
 pub struct ConstantEvaluator<'gcx> {
     pub gcx: Gcx<'gcx>,
     depth: usize,
 }
 
-type EvalResult<'gcx> = Result<IntScalar, EvalError>;
+type EvalResult = Result<ConstValue, EvalError>;
 
 impl<'gcx> ConstantEvaluator<'gcx> {
     /// Creates a new constant evaluator.
@@ -28,12 +41,28 @@ impl<'gcx> ConstantEvaluator<'gcx> {
     }
 
     /// Evaluates the given expression, emitting an error diagnostic if it fails.
-    pub fn eval(&mut self, expr: &hir::Expr<'_>) -> Result<IntScalar, ErrorGuaranteed> {
+    pub fn eval(&mut self, expr: &hir::Expr<'_>) -> Result<ConstValue, ErrorGuaranteed> {
         self.try_eval(expr).map_err(|err| self.emit_eval_error(expr, err))
     }
 
+    /// Evaluates the given array length expression, emitting an error diagnostic if it fails or if
+    /// the value is not a valid length.
+    pub fn eval_array_len(&mut self, expr: &hir::Expr<'_>) -> Result<U256, ErrorGuaranteed> {
+        let value = self.eval(expr)?;
+        let kind = match value.to_integer() {
+            Some(int) if int.is_negative() => EE::NegativeArrayLength,
+            Some(int) => match int_to_u256(&int) {
+                Some(len) => return Ok(len),
+                None => EE::IntTooBig,
+            },
+            None if matches!(value, ConstValue::Rational(_)) => EE::FractionalArrayLength,
+            None => EE::InvalidArrayLength,
+        };
+        Err(self.emit_eval_error(expr, kind.spanned(expr.span)))
+    }
+
     /// Evaluates the given expression, returning an error if it fails.
-    pub fn try_eval(&mut self, expr: &hir::Expr<'_>) -> EvalResult<'gcx> {
+    pub fn try_eval(&mut self, expr: &hir::Expr<'_>) -> EvalResult {
         self.depth += 1;
         if self.depth > RECURSION_LIMIT {
             return Err(EE::RecursionLimitReached.spanned(expr.span));
@@ -59,39 +88,64 @@ impl<'gcx> ConstantEvaluator<'gcx> {
         }
     }
 
-    fn eval_expr(&mut self, expr: &hir::Expr<'_>) -> EvalResult<'gcx> {
+    fn eval_expr(&mut self, expr: &hir::Expr<'_>) -> EvalResult {
         let expr = expr.peel_parens();
         match expr.kind {
-            // hir::ExprKind::Array(_) => todo!(),
-            // hir::ExprKind::Assign(_, _, _) => todo!(),
             hir::ExprKind::Binary(l, bin_op, r) => {
                 let l = self.try_eval(l)?;
                 let r = self.try_eval(r)?;
                 l.binop(&r, bin_op.kind).map_err(Into::into)
             }
-            // hir::ExprKind::Call(_, _) => todo!(),
-            // hir::ExprKind::CallOptions(_, _) => todo!(),
-            // hir::ExprKind::Delete(_) => todo!(),
+            hir::ExprKind::Call(callee, ref args) => self.eval_call(callee, args),
             hir::ExprKind::Ident(&[hir::Res::Item(hir::ItemId::Variable(v))]) => {
-                let v = self.gcx.hir.variable(v);
-                if v.mutability != Some(hir::VarMut::Constant) {
+                let var = self.gcx.hir.variable(v);
+                if var.mutability != Some(hir::VarMut::Constant) {
                     return Err(EE::NonConstantVar.into());
                 }
-                self.try_eval(v.initializer.expect("constant variable has no initializer"))
+                let init = var.initializer.expect("constant variable has no initializer");
+                let value = self.try_eval(init)?;
+                let ty = self.gcx.type_of_item(v.into());
+                value.convert(ty, false).map_err(|kind| kind.spanned(init.span))
             }
-            // hir::ExprKind::Index(_, _) => todo!(),
-            // hir::ExprKind::Slice(_, _, _) => todo!(),
             hir::ExprKind::Lit(lit) => self.eval_lit(lit),
-            // hir::ExprKind::Member(_, ident) => todo!(),
-            // hir::ExprKind::New(_) => todo!(),
-            // hir::ExprKind::Payable(_) => todo!(),
+            hir::ExprKind::Member(base, member) => {
+                let hir::ExprKind::TypeCall(ref ty) = base.kind else {
+                    return Err(EE::UnsupportedExpr.into());
+                };
+                let ty = self.gcx.type_of_hir_ty(ty);
+                let Some(int_ty) = IntTy::from_ty(ty) else {
+                    return Err(EE::UnsupportedExpr.into());
+                };
+                let value = match member.name {
+                    sym::min => int_ty.min(),
+                    sym::max => int_ty.max(),
+                    _ => return Err(EE::UnsupportedExpr.into()),
+                };
+                Ok(ConstValue::Int(IntValue { value, ty: int_ty }))
+            }
+            hir::ExprKind::Payable(inner) => {
+                let ty = self.gcx.types.address_payable;
+                self.try_eval(inner)?.convert(ty, true).map_err(Into::into)
+            }
             hir::ExprKind::Ternary(cond, t, f) => {
-                let cond = self.try_eval(cond)?;
-                Ok(if cond.to_bool() { self.try_eval(t)? } else { self.try_eval(f)? })
+                let Some(cond) = self.try_eval(cond)?.to_bool() else {
+                    return Err(EE::InvalidCondition.spanned(cond.span));
+                };
+                if cond {
+                    self.try_eval(t)
+                } else {
+                    self.try_eval(f)
+                }
             }
-            // hir::ExprKind::Tuple(_) => todo!(),
-            // hir::ExprKind::TypeCall(_) => todo!(),
-            // hir::ExprKind::Type(_) => todo!(),
+            hir::ExprKind::Tuple([Some(expr)]) => self.try_eval(expr),
+            hir::ExprKind::Tuple(exprs) => exprs
+                .iter()
+                .map(|expr| match expr {
+                    Some(expr) => self.try_eval(expr),
+                    None => Err(EE::UnsupportedExpr.into()),
+                })
+                .collect::<Result<_, _>>()
+                .map(ConstValue::Tuple),
             hir::ExprKind::Unary(un_op, v) => {
                 let v = self.try_eval(v)?;
                 v.unop(un_op.kind).map_err(Into::into)
@@ -101,115 +155,622 @@ impl<'gcx> ConstantEvaluator<'gcx> {
         }
     }
 
-    fn eval_lit(&mut self, lit: &hir::Lit) -> EvalResult<'gcx> {
+    /// Evaluates explicit type conversions and `keccak256` calls.
+    fn eval_call(&mut self, callee: &hir::Expr<'_>, args: &hir::CallArgs<'_>) -> EvalResult {
+        let hir::CallArgs::Unnamed([arg]) = *args else {
+            return Err(EE::UnsupportedExpr.into());
+        };
+        match callee.peel_parens().kind {
+            hir::ExprKind::Type(ref ty) => {
+                let ty = self.gcx.type_of_hir_ty(ty);
+                self.try_eval(arg)?.convert(ty, true).map_err(Into::into)
+            }
+            hir::ExprKind::Ident(&[hir::Res::Builtin(Builtin::Keccak256)]) => {
+                match self.try_eval(arg)? {
+                    ConstValue::String(bytes) => {
+                        Ok(ConstValue::FixedBytes(keccak256(&bytes), TypeSize::new_fb_bytes(32)))
+                    }
+                    _ => Err(EE::UnsupportedExpr.into()),
+                }
+            }
+            _ => Err(EE::UnsupportedExpr.into()),
+        }
+    }
+
+    fn eval_lit(&mut self, lit: &hir::Lit) -> EvalResult {
         match lit.kind {
-            // LitKind::Str(str_kind, arc) => todo!(),
+            LitKind::Str(_, ref bytes) => Ok(ConstValue::String(bytes.to_vec().into())),
             LitKind::Number(ref big_int) => {
-                let (_, bytes) = big_int.to_bytes_be();
-                if bytes.len() > 32 {
-                    return Err(EE::IntTooBig.into());
-                }
-                Ok(IntScalar::from_be_bytes(&bytes))
+                ConstValue::rational(BigRational::from_integer(big_int.clone())).map_err(Into::into)
             }
-            // LitKind::Rational(ratio) => todo!(),
-            LitKind::Address(address) => Ok(IntScalar::from_be_bytes(address.as_slice())),
-            LitKind::Bool(bool) => Ok(IntScalar::from_be_bytes(&[bool as u8])),
+            LitKind::Rational(ref ratio) => ConstValue::rational(ratio.clone()).map_err(Into::into),
+            LitKind::Address(address) => Ok(ConstValue::Address(address)),
+            LitKind::Bool(bool) => Ok(ConstValue::Bool(bool)),
             LitKind::Err(guar) => Err(EE::AlreadyEmitted(guar).into()),
-            _ => Err(EE::UnsupportedLiteral.into()),
         }
     }
 }
 
-pub struct IntScalar {
-    pub data: U256,
+/// A value computed at compile time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstValue {
+    /// A number literal, or the result of an operation on number literals.
+    ///
+    /// These are computed with arbitrary precision, and can be fractional.
+    Rational(BigRational),
+    /// An integer of a specific type.
+    Int(IntValue),
+    /// A boolean.
+    Bool(bool),
+    /// An address.
+    Address(Address),
+    /// A fixed-size byte array, `bytesN`. The bytes are left-aligned, and the rest is zero.
+    FixedBytes(B256, TypeSize),
+    /// A string or a dynamic byte array.
+    String(Box<[u8]>),
+    /// A tuple of values.
+    Tuple(Box<[Self]>),
+}
+
+/// An integer of a specific type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntValue {
+    /// The value, which is always in the range of `ty`.
+    pub value: BigInt,
+    /// The integer type.
+    pub ty: IntTy,
+}
+
+/// An integer type: `uintN` or `intN`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IntTy {
+    /// Whether the type is signed.
+    pub signed: bool,
+    /// The number of bits.
+    pub bits: u16,
 }
 
-impl IntScalar {
-    pub fn new(data: U256) -> Self {
-        Self { data }
+impl IntTy {
+    /// Returns the integer type of the given type, if any.
+    pub fn from_ty(ty: Ty<'_>) -> Option<Self> {
+        match ty.kind {
+            TyKind::Elementary(ElementaryType::Int(size)) => Some(Self::new(true, size.bits())),
+            TyKind::Elementary(ElementaryType::UInt(size)) => Some(Self::new(false, size.bits())),
+            _ => None,
+        }
     }
 
-    /// Creates a new integer value from a boolean.
-    pub fn from_bool(value: bool) -> Self {
-        Self { data: U256::from(value as u8) }
+    /// Creates a new integer type.
+    pub fn new(signed: bool, bits: u16) -> Self {
+        Self { signed, bits }
     }
 
-    /// Creates a new integer value from big-endian bytes.
-    ///
-    /// # Panics
+    /// Returns the minimum value of the type.
+    pub fn min(self) -> BigInt {
+        if self.signed {
+            -(BigInt::one() << (self.bits - 1))
+        } else {
+            BigInt::zero()
+        }
+    }
+
+    /// Returns the maximum value of the type.
+    pub fn max(self) -> BigInt {
+        let bits = if self.signed { self.bits - 1 } else { self.bits };
+        (BigInt::one() << bits) - 1
+    }
+
+    /// Returns `true` if the value is in the range of the type.
+    pub fn fits(self, value: &BigInt) -> bool {
+        *value >= self.min() && *value <= self.max()
+    }
+
+    /// Truncates the value to the type, wrapping around in two's complement.
+    pub fn wrap(self, value: &BigInt) -> BigInt {
+        let modulus = BigInt::one() << self.bits;
+        let value = value.mod_floor(&modulus);
+        if self.signed && value > self.max() {
+            value - modulus
+        } else {
+            value
+        }
+    }
+
+    /// Returns `true` if every value of this type can be represented by `other`.
+    pub fn is_subset_of(self, other: Self) -> bool {
+        if self.signed == other.signed {
+            self.bits <= other.bits
+        } else {
+            !self.signed && self.bits < other.bits
+        }
+    }
+
+    /// Returns the smallest type that can represent the given value.
+    pub fn smallest_for(value: &BigInt) -> Option<Self> {
+        let signed = value.is_negative();
+        (8..=256).step_by(8).map(|bits| Self::new(signed, bits)).find(|ty| ty.fits(value))
+    }
+}
+
+impl fmt::Display for IntTy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}int{}", if self.signed { "" } else { "u" }, self.bits)
+    }
+}
+
+impl ConstValue {
+    /// Creates a new number literal value, checking its size.
+    fn rational(value: BigRational) -> Result<Self, EE> {
+        let too_big = |int: &BigInt| int.bits() > LITERAL_BITS_LIMIT;
+        if too_big(value.numer()) || too_big(value.denom()) {
+            return Err(EE::IntTooBig);
+        }
+        Ok(Self::Rational(value))
+    }
+
+    fn int(value: BigInt, ty: IntTy) -> Result<Self, EE> {
+        if !ty.fits(&value) {
+            return Err(EE::TypeOverflow(ty));
+        }
+        Ok(Self::Int(IntValue { value, ty }))
+    }
+
+    /// Returns the description of the kind of value.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Rational(_) => "number",
+            Self::Int(_) => "integer",
+            Self::Bool(_) => "boolean",
+            Self::Address(_) => "address",
+            Self::FixedBytes(..) => "fixed bytes",
+            Self::String(_) => "string",
+            Self::Tuple(_) => "tuple",
+        }
+    }
+
+    /// Returns the value as an integer, if it is an integer or an integral number literal.
+    pub fn to_integer(&self) -> Option<BigInt> {
+        match self {
+            Self::Rational(r) if r.is_integer() => Some(r.to_integer()),
+            Self::Int(int) => Some(int.value.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `U256`, if it is a non-negative integer that fits in 256 bits.
+    pub fn to_u256(&self) -> Option<U256> {
+        int_to_u256(&self.to_integer()?)
+    }
+
+    /// Returns the value as a boolean, if it is one.
+    pub fn to_bool(&self) -> Option<bool> {
+        match *self {
+            Self::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Converts the value to the given type.
     ///
-    /// Panics if `bytes` is empty or has a length greater than 32.
-    pub fn from_be_bytes(bytes: &[u8]) -> Self {
-        Self { data: U256::from_be_slice(bytes) }
+    /// Explicit conversions truncate or extend typed integers and fixed bytes to the size of the
+    /// target type, while implicit conversions and conversions of number literals require the
+    /// value to fit.
+    pub fn convert(self, to: Ty<'_>, explicit: bool) -> Result<Self, EE> {
+        let to = to.peel_refs();
+        let ty = match to.kind {
+            TyKind::Udvt(inner, _) => return self.convert(inner, explicit),
+            TyKind::Elementary(elementary) => match IntTy::from_ty(to) {
+                Some(ty) => ty,
+                None => return self.convert_non_int(elementary),
+            },
+            _ => return Err(EE::UnsupportedConversion),
+        };
+        let value = match self {
+            Self::Rational(r) => {
+                if !r.is_integer() {
+                    return Err(EE::UnsupportedConversion);
+                }
+                let value = r.to_integer();
+                if !ty.fits(&value) {
+                    return Err(EE::IntOutOfRange(ty));
+                }
+                value
+            }
+            Self::Int(int) => {
+                if !explicit && !int.ty.is_subset_of(ty) {
+                    return Err(EE::UnsupportedConversion);
+                }
+                int.value
+            }
+            Self::FixedBytes(data, size) if explicit && size.bits() == ty.bits => {
+                BigInt::from_bytes_be(Sign::Plus, &data[..size.bytes() as usize])
+            }
+            Self::Address(address) if explicit => {
+                BigInt::from_bytes_be(Sign::Plus, address.as_slice())
+            }
+            _ => return Err(EE::UnsupportedConversion),
+        };
+        if !explicit && !ty.fits(&value) {
+            return Err(EE::IntOutOfRange(ty));
+        }
+        Ok(Self::Int(IntValue { value: ty.wrap(&value), ty }))
     }
 
-    /// Converts the integer value to a boolean.
-    pub fn to_bool(&self) -> bool {
-        !self.data.is_zero()
+    fn convert_non_int(self, to: ElementaryType) -> Result<Self, EE> {
+        Ok(match (to, self) {
+            (ElementaryType::Bool, this @ Self::Bool(_)) => this,
+            (ElementaryType::Address(_), this @ Self::Address(_)) => this,
+            (ElementaryType::Address(_), Self::FixedBytes(data, size)) if size.bytes() == 20 => {
+                Self::Address(Address::from_slice(&data[..20]))
+            }
+            (ElementaryType::Address(_), Self::Int(int)) if int.ty == IntTy::new(false, 160) => {
+                Self::Address(Address::from_word(int_to_u256(&int.value).unwrap().into()))
+            }
+            (ElementaryType::Address(_), Self::Rational(r)) if r.is_integer() => {
+                let value = int_to_u256(&r.to_integer()).ok_or(EE::UnsupportedConversion)?;
+                if value.bit_len() > 160 {
+                    return Err(EE::UnsupportedConversion);
+                }
+                Self::Address(Address::from_word(value.into()))
+            }
+            (ElementaryType::FixedBytes(to_size), Self::FixedBytes(data, _)) => {
+                Self::FixedBytes(mask_bytes(data, to_size), to_size)
+            }
+            (ElementaryType::FixedBytes(to_size), Self::Int(int))
+                if !int.ty.signed && int.ty.bits == to_size.bits() =>
+            {
+                Self::FixedBytes(left_align(&int.value, to_size), to_size)
+            }
+            (ElementaryType::FixedBytes(to_size), Self::Rational(r)) if r.is_integer() => {
+                let value = r.to_integer();
+                if value.is_negative() || value.bits() > u64::from(to_size.bits()) {
+                    return Err(EE::UnsupportedConversion);
+                }
+                Self::FixedBytes(left_align(&value, to_size), to_size)
+            }
+            (ElementaryType::FixedBytes(to_size), Self::String(bytes)) => {
+                let mut data = B256::ZERO;
+                let len = bytes.len().min(to_size.bytes() as usize);
+                data[..len].copy_from_slice(&bytes[..len]);
+                Self::FixedBytes(data, to_size)
+            }
+            (ElementaryType::String | ElementaryType::Bytes, this @ Self::String(_)) => this,
+            _ => return Err(EE::UnsupportedConversion),
+        })
     }
 
     /// Applies the given unary operation to this value.
     pub fn unop(&self, op: hir::UnOpKind) -> Result<Self, EE> {
-        Ok(match op {
-            hir::UnOpKind::PreInc
-            | hir::UnOpKind::PreDec
-            | hir::UnOpKind::PostInc
-            | hir::UnOpKind::PostDec => return Err(EE::UnsupportedUnaryOp),
-            hir::UnOpKind::Not | hir::UnOpKind::BitNot => Self::new(!self.data),
-            hir::UnOpKind::Neg => Self::new(self.data.wrapping_neg()),
-        })
+        match (op, self) {
+            (hir::UnOpKind::Not, Self::Bool(b)) => Ok(Self::Bool(!b)),
+            (hir::UnOpKind::Neg, Self::Rational(r)) => Self::rational(-r),
+            (hir::UnOpKind::Neg, Self::Int(int)) if int.ty.signed => Self::int(-&int.value, int.ty),
+            (hir::UnOpKind::BitNot, Self::Rational(r)) if r.is_integer() => {
+                Self::rational(BigRational::from_integer(!r.to_integer()))
+            }
+            (hir::UnOpKind::BitNot, Self::Int(int)) => {
+                Ok(Self::Int(IntValue { value: int.ty.wrap(&!&int.value), ty: int.ty }))
+            }
+            (hir::UnOpKind::BitNot, Self::FixedBytes(data, size)) => {
+                Ok(Self::FixedBytes(mask_bytes(!*data, *size), *size))
+            }
+            _ => Err(EE::UnsupportedUnaryOp),
+        }
     }
 
     /// Applies the given binary operation to this value.
     pub fn binop(&self, r: &Self, op: hir::BinOpKind) -> Result<Self, EE> {
+        use hir::BinOpKind::*;
+
         let l = self;
-        Ok(match op {
-            hir::BinOpKind::Lt => Self::from_bool(l.data < r.data),
-            hir::BinOpKind::Le => Self::from_bool(l.data <= r.data),
-            hir::BinOpKind::Gt => Self::from_bool(l.data > r.data),
-            hir::BinOpKind::Ge => Self::from_bool(l.data >= r.data),
-            hir::BinOpKind::Eq => Self::from_bool(l.data == r.data),
-            hir::BinOpKind::Ne => Self::from_bool(l.data != r.data),
-            hir::BinOpKind::Or | hir::BinOpKind::BitOr => Self::new(l.data | r.data),
-            hir::BinOpKind::And | hir::BinOpKind::BitAnd => Self::new(l.data & r.data),
-            hir::BinOpKind::BitXor => Self::new(l.data ^ r.data),
-            hir::BinOpKind::Shr => {
-                Self::new(l.data.wrapping_shr(r.data.try_into().unwrap_or(usize::MAX)))
-            }
-            hir::BinOpKind::Shl => {
-                Self::new(l.data.wrapping_shl(r.data.try_into().unwrap_or(usize::MAX)))
-            }
-            hir::BinOpKind::Sar => {
-                Self::new(l.data.arithmetic_shr(r.data.try_into().unwrap_or(usize::MAX)))
-            }
-            hir::BinOpKind::Add => {
-                Self::new(l.data.checked_add(r.data).ok_or(EE::ArithmeticOverflow)?)
-            }
-            hir::BinOpKind::Sub => {
-                Self::new(l.data.checked_sub(r.data).ok_or(EE::ArithmeticOverflow)?)
-            }
-            hir::BinOpKind::Pow => {
-                Self::new(l.data.checked_pow(r.data).ok_or(EE::ArithmeticOverflow)?)
-            }
-            hir::BinOpKind::Mul => {
-                Self::new(l.data.checked_mul(r.data).ok_or(EE::ArithmeticOverflow)?)
-            }
-            hir::BinOpKind::Div => Self::new(l.data.checked_div(r.data).ok_or(EE::DivisionByZero)?),
-            hir::BinOpKind::Rem => Self::new(l.data.checked_rem(r.data).ok_or(EE::DivisionByZero)?),
+        match op {
+            And | Or => {
+                let (Some(l), Some(r)) = (l.to_bool(), r.to_bool()) else {
+                    return Err(EE::UnsupportedBinaryOp);
+                };
+                Ok(Self::Bool(if op == And { l && r } else { l || r }))
+            }
+            Lt | Le | Gt | Ge | Eq | Ne => {
+                let ordering = l.compare(r).ok_or(EE::UnsupportedBinaryOp)?;
+                let is_eq_op = matches!(op, Eq | Ne);
+                if ordering.is_none() && !is_eq_op {
+                    return Err(EE::UnsupportedBinaryOp);
+                }
+                Ok(Self::Bool(match op {
+                    Eq => ordering == Some(Ordering::Equal),
+                    Ne => ordering != Some(Ordering::Equal),
+                    Lt => ordering == Some(Ordering::Less),
+                    Le => ordering != Some(Ordering::Greater),
+                    Gt => ordering == Some(Ordering::Greater),
+                    Ge => ordering != Some(Ordering::Less),
+                    _ => unreachable!(),
+                }))
+            }
+            Shl | Shr | Sar => l.shift(r, op),
+            Pow => l.pow(r),
+            _ => match (l, r) {
+                (Self::Rational(l), Self::Rational(r)) => rational_binop(l, r, op),
+                (Self::Int(_), _) | (_, Self::Int(_)) => {
+                    let (ty, l, r) = common_int(l, r)?;
+                    int_binop(&l, &r, op, ty)
+                }
+                (Self::FixedBytes(l, l_size), Self::FixedBytes(r, r_size)) if l_size == r_size => {
+                    let data = match op {
+                        BitAnd => *l & *r,
+                        BitOr => *l | *r,
+                        BitXor => *l ^ *r,
+                        _ => return Err(EE::UnsupportedBinaryOp),
+                    };
+                    Ok(Self::FixedBytes(data, *l_size))
+                }
+                _ => Err(EE::UnsupportedBinaryOp),
+            },
+        }
+    }
+
+    /// Compares two values. Returns `Some(None)` if the values can only be compared for equality
+    /// and are different.
+    fn compare(&self, r: &Self) -> Option<Option<Ordering>> {
+        Some(match (self, r) {
+            (Self::Rational(l), Self::Rational(r)) => Some(l.cmp(r)),
+            (Self::Int(_), Self::Int(_) | Self::Rational(_))
+            | (Self::Rational(_), Self::Int(_)) => {
+                let to_rational = |v: &Self| match v {
+                    Self::Int(int) => BigRational::from_integer(int.value.clone()),
+                    Self::Rational(r) => r.clone(),
+                    _ => unreachable!(),
+                };
+                Some(to_rational(self).cmp(&to_rational(r)))
+            }
+            (Self::Address(l), Self::Address(r)) => Some(l.cmp(r)),
+            (Self::FixedBytes(l, l_size), Self::FixedBytes(r, r_size)) if l_size == r_size => {
+                Some(l.cmp(r))
+            }
+            (Self::Bool(l), Self::Bool(r)) => (l == r).then_some(Ordering::Equal),
+            _ => return None,
         })
     }
+
+    fn shift(&self, r: &Self, op: hir::BinOpKind) -> Result<Self, EE> {
+        let amount = match r.to_integer() {
+            Some(amount) if !amount.is_negative() => amount,
+            _ => return Err(EE::UnsupportedBinaryOp),
+        };
+        let is_shl = op == hir::BinOpKind::Shl;
+        match self {
+            Self::Rational(l) if l.is_integer() => {
+                let l = l.to_integer();
+                if is_shl {
+                    let amount = amount
+                        .to_u64()
+                        .filter(|&amount| l.bits() + amount <= LITERAL_BITS_LIMIT)
+                        .ok_or(EE::IntTooBig)?;
+                    Self::rational(BigRational::from_integer(l << amount))
+                } else {
+                    let amount = amount.to_u64().unwrap_or(u64::MAX);
+                    Self::rational(BigRational::from_integer(l >> amount.min(LITERAL_BITS_LIMIT)))
+                }
+            }
+            Self::Int(int) => {
+                let amount = amount.to_u64().unwrap_or(u64::MAX).min(u64::from(int.ty.bits));
+                let value = if is_shl {
+                    int.ty.wrap(&(&int.value << amount))
+                } else {
+                    &int.value >> amount
+                };
+                Ok(Self::Int(IntValue { value, ty: int.ty }))
+            }
+            Self::FixedBytes(data, size) => {
+                let amount = amount.to_usize().unwrap_or(usize::MAX);
+                let data = U256::from_be_bytes(data.0);
+                let data =
+                    if is_shl { data.wrapping_shl(amount) } else { data.wrapping_shr(amount) };
+                Ok(Self::FixedBytes(mask_bytes(data.into(), *size), *size))
+            }
+            _ => Err(EE::UnsupportedBinaryOp),
+        }
+    }
+
+    fn pow(&self, r: &Self) -> Result<Self, EE> {
+        match (self, r) {
+            (Self::Rational(l), Self::Rational(r)) => {
+                if !r.is_integer() {
+                    return Err(EE::UnsupportedBinaryOp);
+                }
+                let exp = r.to_integer();
+                if l.is_zero() && exp.is_negative() {
+                    return Err(EE::DivisionByZero);
+                }
+                if l.is_zero() || l.is_one() || exp.is_zero() {
+                    return Self::rational(if exp.is_zero() {
+                        BigRational::one()
+                    } else {
+                        l.clone()
+                    });
+                }
+                let l_bits = l.numer().bits().max(l.denom().bits());
+                let exp = exp
+                    .to_i32()
+                    .filter(|exp| {
+                        l_bits.saturating_mul(exp.unsigned_abs().into()) <= 2 * LITERAL_BITS_LIMIT
+                    })
+                    .ok_or(EE::IntTooBig)?;
+                Self::rational(l.pow(exp))
+            }
+            (Self::Rational(l), Self::Int(_)) => {
+                // The base of a literal power with a typed exponent is given its smallest type.
+                let value =
+                    l.is_integer().then(|| l.to_integer()).ok_or(EE::UnsupportedBinaryOp)?;
+                let ty = IntTy::smallest_for(&value).ok_or(EE::IntTooBig)?;
+                Self::Int(IntValue { value, ty }).pow(r)
+            }
+            (Self::Int(int), _) => {
+                let exp = match r.to_integer() {
+                    Some(exp) if !exp.is_negative() => exp,
+                    _ => return Err(EE::UnsupportedBinaryOp),
+                };
+                let value = if exp.is_zero() {
+                    BigInt::one()
+                } else if int.value.is_zero() || int.value.abs().is_one() {
+                    if int.value.is_negative() && exp.is_odd() {
+                        -BigInt::one()
+                    } else {
+                        int.value.abs()
+                    }
+                } else {
+                    // Any base with an absolute value of at least 2 overflows 256 bits.
+                    let exp =
+                        exp.to_u32().filter(|&exp| exp <= 256).ok_or(EE::TypeOverflow(int.ty))?;
+                    num_traits::Pow::pow(&int.value, exp)
+                };
+                Self::int(value, int.ty)
+            }
+            _ => Err(EE::UnsupportedBinaryOp),
+        }
+    }
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rational(r) => r.fmt(f),
+            Self::Int(int) => int.value.fmt(f),
+            Self::Bool(b) => b.fmt(f),
+            Self::Address(address) => address.fmt(f),
+            Self::FixedBytes(data, size) => {
+                write!(f, "0x{}", alloy_primitives::hex::encode(&data[..size.bytes() as usize]))
+            }
+            Self::String(bytes) => match std::str::from_utf8(bytes) {
+                Ok(s) => write!(f, "{s:?}"),
+                Err(_) => write!(f, "hex\"{}\"", alloy_primitives::hex::encode(bytes)),
+            },
+            Self::Tuple(values) => {
+                f.write_str("(")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    value.fmt(f)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// Converts the operands of an integer operation to their common type.
+fn common_int(l: &ConstValue, r: &ConstValue) -> Result<(IntTy, BigInt, BigInt), EE> {
+    let ty = match (l, r) {
+        (ConstValue::Int(l), ConstValue::Int(r)) => {
+            if l.ty.is_subset_of(r.ty) {
+                r.ty
+            } else if r.ty.is_subset_of(l.ty) {
+                l.ty
+            } else {
+                return Err(EE::UnsupportedBinaryOp);
+            }
+        }
+        (ConstValue::Int(int), _) | (_, ConstValue::Int(int)) => int.ty,
+        _ => unreachable!(),
+    };
+    let convert = |v: &ConstValue| match v {
+        ConstValue::Int(int) => Ok(int.value.clone()),
+        ConstValue::Rational(r) if r.is_integer() => {
+            let value = r.to_integer();
+            if ty.fits(&value) {
+                Ok(value)
+            } else {
+                Err(EE::IntOutOfRange(ty))
+            }
+        }
+        _ => Err(EE::UnsupportedBinaryOp),
+    };
+    Ok((ty, convert(l)?, convert(r)?))
+}
+
+/// Applies an arithmetic or bitwise operation to two integers of the given type.
+fn int_binop(l: &BigInt, r: &BigInt, op: hir::BinOpKind, ty: IntTy) -> Result<ConstValue, EE> {
+    use hir::BinOpKind::*;
+
+    let value = match op {
+        Add => l + r,
+        Sub => l - r,
+        Mul => l * r,
+        // Division and remainder truncate towards zero, like in the EVM.
+        Div | Rem if r.is_zero() => return Err(EE::DivisionByZero),
+        Div => l / r,
+        Rem => l % r,
+        BitAnd => l & r,
+        BitOr => l | r,
+        BitXor => l ^ r,
+        _ => return Err(EE::UnsupportedBinaryOp),
+    };
+    ConstValue::int(value, ty)
+}
+
+/// Applies an arithmetic or bitwise operation to two number literals.
+fn rational_binop(l: &BigRational, r: &BigRational, op: hir::BinOpKind) -> Result<ConstValue, EE> {
+    use hir::BinOpKind::*;
+
+    let value = match op {
+        Add => l + r,
+        Sub => l - r,
+        Mul => l * r,
+        Div | Rem if r.is_zero() => return Err(EE::DivisionByZero),
+        Div => l / r,
+        Rem => l % r,
+        BitAnd | BitOr | BitXor => {
+            if !l.is_integer() || !r.is_integer() {
+                return Err(EE::UnsupportedBinaryOp);
+            }
+            let (l, r) = (l.to_integer(), r.to_integer());
+            BigRational::from_integer(match op {
+                BitAnd => l & r,
+                BitOr => l | r,
+                _ => l ^ r,
+            })
+        }
+        _ => return Err(EE::UnsupportedBinaryOp),
+    };
+    ConstValue::rational(value)
+}
+
+fn int_to_u256(int: &BigInt) -> Option<U256> {
+    let (sign, bytes) = int.to_bytes_be();
+    if sign == Sign::Minus || bytes.len() > 32 {
+        return None;
+    }
+    Some(U256::from_be_slice(&bytes))
+}
+
+/// Left-aligns the unsigned integer value in a `bytesN` value of the given size.
+fn left_align(value: &BigInt, size: TypeSize) -> B256 {
+    let value = int_to_u256(value).unwrap_or_default();
+    (value << (256 - usize::from(size.bits()))).into()
+}
+
+/// Clears the bytes after the first `size` bytes.
+fn mask_bytes(mut data: B256, size: TypeSize) -> B256 {
+    data[size.bytes() as usize..].fill(0);
+    data
 }
 
 #[derive(Debug)]
 pub enum EvalErrorKind {
     RecursionLimitReached,
-    ArithmeticOverflow,
+    TypeOverflow(IntTy),
+    IntOutOfRange(IntTy),
     IntTooBig,
     DivisionByZero,
-    UnsupportedLiteral,
     UnsupportedUnaryOp,
+    UnsupportedBinaryOp,
+    UnsupportedConversion,
     UnsupportedExpr,
+    InvalidCondition,
     NonConstantVar,
+    NegativeArrayLength,
+    FractionalArrayLength,
+    InvalidArrayLength,
     AlreadyEmitted(ErrorGuaranteed),
 }
 use EvalErrorKind as EE;
@@ -219,18 +780,24 @@ impl EvalErrorKind {
         EvalError { kind: self, span }
     }
 
-    fn msg(&self) -> &'static str {
-        match self {
+    fn msg(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
             Self::RecursionLimitReached => "recursion limit reached",
-            Self::ArithmeticOverflow => "arithmetic overflow",
+            Self::TypeOverflow(ty) => return format!("arithmetic overflow of type `{ty}`").into(),
+            Self::IntOutOfRange(ty) => return format!("value does not fit in type `{ty}`").into(),
             Self::IntTooBig => "integer value is too big",
             Self::DivisionByZero => "division by zero",
-            Self::UnsupportedLiteral => "unsupported literal",
             Self::UnsupportedUnaryOp => "unsupported unary operation",
+            Self::UnsupportedBinaryOp => "unsupported binary operation",
+            Self::UnsupportedConversion => "unsupported type conversion",
             Self::UnsupportedExpr => "unsupported expression",
+            Self::InvalidCondition => "condition is not a boolean",
             Self::NonConstantVar => "only constant variables are allowed",
+            Self::NegativeArrayLength => "array length cannot be negative",
+            Self::FractionalArrayLength => "array length must be an integer",
+            Self::InvalidArrayLength => "array length must be an integer or a constant expression",
             Self::AlreadyEmitted(_) => "error already emitted",
-        }
+        })
     }
 }
 
@@ -8,13 +8,15 @@ use solar_data_structures::{
     index::{Idx, IndexVec},
     newtype_index, BumpExt,
 };
-use solar_interface::{diagnostics::ErrorGuaranteed, source_map::SourceFile, Ident, Span};
+use solar_interface::{diagnostics::ErrorGuaranteed, source_map::SourceFile, sym, Ident, Span};
 use std::{fmt, ops::ControlFlow, sync::Arc};
 use strum::EnumIs;
 
+pub mod yul;
+
 pub use ast::{
     BinOp, BinOpKind, ContractKind, DataLocation, ElementaryType, FunctionKind, Lit,
-    StateMutability, UnOp, UnOpKind, VarMut, Visibility,
+    StateMutability, UnOp, UnOpKind, UserDefinableOperator, VarMut, Visibility,
 };
 
 /// HIR arena allocator.
@@ -223,6 +225,26 @@ impl<'hir> Hir<'hir> {
     pub fn contract_items(&self, id: ContractId) -> impl Iterator<Item = Item<'_, 'hir>> + Clone {
         self.contract_item_ids(id).map(move |id| self.item(id))
     }
+
+    /// Returns the modifier that is executed when `modifier` is invoked in the context of the
+    /// given most derived contract.
+    ///
+    /// Virtual modifiers are looked up by name in the linearized bases of the contract.
+    pub fn resolve_modifier(&self, modifier: FunctionId, contract: ContractId) -> FunctionId {
+        let m = self.function(modifier);
+        if !m.virtual_ {
+            return modifier;
+        }
+        self.contract(contract)
+            .linearized_bases
+            .iter()
+            .flat_map(|&base| self.contract(base).functions())
+            .find(|&f| {
+                let f = self.function(f);
+                f.kind.is_modifier() && f.name.map(|n| n.name) == m.name.map(|n| n.name)
+            })
+            .unwrap_or(modifier)
+    }
 }
 
 newtype_index! {
@@ -265,6 +287,8 @@ pub struct Source<'hir> {
     pub imports: &'hir [(ast::ItemId, SourceId)],
     /// The source items.
     pub items: &'hir [ItemId],
+    /// The file-level `using` directives.
+    pub using_directives: &'hir [UsingDirective<'hir>],
 }
 
 impl fmt::Debug for Source<'_> {
@@ -273,6 +297,7 @@ impl fmt::Debug for Source<'_> {
             .field("file", &self.file.name)
             .field("imports", &self.imports)
             .field("items", &self.items)
+            .field("using_directives", &self.using_directives)
             .finish()
     }
 }
@@ -335,6 +360,21 @@ impl<'hir> Item<'_, 'hir> {
         }
     }
 
+    /// Returns the ID of the source this item is defined in.
+    #[inline]
+    pub fn source(self) -> SourceId {
+        match self {
+            Item::Contract(c) => c.source,
+            Item::Function(f) => f.source,
+            Item::Struct(s) => s.source,
+            Item::Enum(e) => e.source,
+            Item::Udvt(u) => u.source,
+            Item::Error(e) => e.source,
+            Item::Event(e) => e.source,
+            Item::Variable(v) => v.source,
+        }
+    }
+
     /// Returns the contract ID if this item is part of a contract.
     #[inline]
     pub fn contract(self) -> Option<ContractId> {
@@ -490,6 +530,10 @@ pub struct Contract<'hir> {
     pub kind: ContractKind,
     /// The contract bases.
     pub bases: &'hir [ContractId],
+    /// The base constructor calls in the inheritance specifiers: `is Base(args)`.
+    ///
+    /// Only contains the specifiers with arguments.
+    pub bases_args: &'hir [Modifier<'hir>],
     /// The linearized contract bases.
     pub linearized_bases: &'hir [ContractId],
     /// The constructor function.
@@ -503,6 +547,8 @@ pub struct Contract<'hir> {
     /// Note that this only includes items defined in the contract itself, not inherited items.
     /// For getting all items, use [`Hir::contract_items`].
     pub items: &'hir [ItemId],
+    /// The `using` directives declared in the contract.
+    pub using_directives: &'hir [UsingDirective<'hir>],
 }
 
 impl Contract<'_> {
@@ -535,6 +581,39 @@ impl Contract<'_> {
     }
 }
 
+/// A `using` directive: `using { A, B.add as + } for uint256 global;`.
+#[derive(Debug)]
+pub struct UsingDirective<'hir> {
+    /// The directive span.
+    pub span: Span,
+    /// The attached library or functions.
+    pub list: UsingList<'hir>,
+    /// The type for which this directive applies. This is `*` if the value is `None`.
+    pub ty: Option<Type<'hir>>,
+    /// Whether the directive applies to the type in all source files.
+    pub global: bool,
+}
+
+/// The attached library or functions of a `using` directive.
+#[derive(Clone, Copy, Debug)]
+pub enum UsingList<'hir> {
+    /// `using L for T;`: all the non-private functions of the library.
+    Library(ContractId),
+    /// `using { f, L.g, h as + } for T;`
+    Functions(&'hir [UsingFunction]),
+}
+
+/// A function attached with a `using` directive.
+#[derive(Clone, Copy, Debug)]
+pub struct UsingFunction {
+    /// The span of the function path.
+    pub span: Span,
+    /// The attached function.
+    pub function: FunctionId,
+    /// The operator that the function implements, if any.
+    pub operator: Option<UserDefinableOperator>,
+}
+
 /// A function.
 #[derive(Debug)]
 pub struct Function<'hir> {
@@ -554,7 +633,7 @@ pub struct Function<'hir> {
     /// The state mutability of the function.
     pub state_mutability: StateMutability,
     /// Modifiers, or base classes if this is a constructor.
-    pub modifiers: &'hir [ItemId],
+    pub modifiers: &'hir [Modifier<'hir>],
     /// Whether this function is marked with the `virtual` keyword.
     pub marked_virtual: bool,
     /// Whether this function is marked with the `virtual` keyword or is defined in an interface.
@@ -597,6 +676,17 @@ impl Function<'_> {
     }
 }
 
+/// A modifier invocation or a base constructor call: `m(args)`, `Base(args)`.
+#[derive(Debug)]
+pub struct Modifier<'hir> {
+    /// The invocation span.
+    pub span: Span,
+    /// The modifier function or the base contract.
+    pub id: ItemId,
+    /// The arguments.
+    pub args: CallArgs<'hir>,
+}
+
 /// A struct.
 #[derive(Debug)]
 pub struct Struct<'hir> {
@@ -751,9 +841,9 @@ pub struct Stmt<'hir> {
 /// A kind of statement.
 #[derive(Debug)]
 pub enum StmtKind<'hir> {
-    // TODO: Yul to HIR.
-    // /// An assembly block, with optional flags: `assembly "evmasm" (...) { ... }`.
-    // Assembly(StmtAssembly<'hir>),
+    /// An assembly block, with optional flags: `assembly "evmasm" (...) { ... }`.
+    Assembly(&'hir StmtAssembly<'hir>),
+
     /// A single-variable declaration statement: `uint256 foo = 42;`.
     DeclSingle(VariableId),
 
@@ -769,10 +859,16 @@ pub enum StmtKind<'hir> {
     UncheckedBlock(Block<'hir>),
 
     /// An emit statement: `emit Foo.bar(42);`.
-    Emit(&'hir [Res], CallArgs<'hir>),
+    ///
+    /// The path is lowered to an [`ExprKind::Ident`] expression, whose resolution to a single
+    /// event is recorded during type checking.
+    Emit(&'hir Expr<'hir>, CallArgs<'hir>),
 
     /// A revert statement: `revert Foo.bar(42);`.
-    Revert(&'hir [Res], CallArgs<'hir>),
+    ///
+    /// The path is lowered to an [`ExprKind::Ident`] expression, whose resolution to a single
+    /// error is recorded during type checking.
+    Revert(&'hir Expr<'hir>, CallArgs<'hir>),
 
     /// A return statement: `return 42;`.
     Return(Option<&'hir Expr<'hir>>),
@@ -801,6 +897,24 @@ pub enum StmtKind<'hir> {
     Err(ErrorGuaranteed),
 }
 
+/// An assembly block, with optional flags: `assembly "evmasm" (...) { ... }`.
+#[derive(Debug)]
+pub struct StmtAssembly<'hir> {
+    /// The assembly block dialect. Can only be `"evmasm"`.
+    pub dialect: Option<ast::StrLit>,
+    /// Additional flags. Can only be `"memory-safe"`.
+    pub flags: &'hir [ast::StrLit],
+    /// The assembly block.
+    pub block: yul::Block<'hir>,
+}
+
+impl StmtAssembly<'_> {
+    /// Returns `true` if the block is marked as `"memory-safe"`.
+    pub fn is_memory_safe(&self) -> bool {
+        self.flags.iter().any(|flag| flag.value == sym::memory_dash_safe)
+    }
+}
+
 /// A try statement: `try fooBar(42) returns (...) { ... } catch (...) { ... }`.
 ///
 /// Reference: <https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.tryStatement>
@@ -819,6 +933,8 @@ pub struct StmtTry<'hir> {
 /// Reference: <https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.catchClause>
 #[derive(Debug)]
 pub struct CatchClause<'hir> {
+    /// The clause span, from the `catch` keyword to the end of the block.
+    pub span: Span,
     pub name: Option<Ident>,
     pub args: &'hir [VariableId],
     pub block: Block<'hir>,
@@ -1028,6 +1144,19 @@ impl CallArgs<'_> {
     pub fn empty() -> Self {
         Self::Unnamed(Default::default())
     }
+
+    /// Returns the number of arguments.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Unnamed(args) => args.len(),
+            Self::Named(args) => args.len(),
+        }
+    }
+
+    /// Returns `true` if there are no arguments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 /// A type name.
@@ -1053,11 +1182,8 @@ impl Type<'_> {
             TypeKind::Elementary(_) => ControlFlow::Continue(()),
             TypeKind::Array(ty) => ty.element.visit(f),
             TypeKind::Function(ty) => {
-                for ty in ty.parameters {
-                    ty.visit(f)?;
-                }
-                for ty in ty.returns {
-                    ty.visit(f)?;
+                for param in ty.parameters.iter().chain(ty.returns) {
+                    param.ty.visit(f)?;
                 }
                 ControlFlow::Continue(())
             }
@@ -1107,10 +1233,17 @@ pub struct TypeArray<'hir> {
 /// A function type name.
 #[derive(Debug)]
 pub struct TypeFunction<'hir> {
-    pub parameters: &'hir [Type<'hir>],
+    pub parameters: &'hir [TypeFunctionParam<'hir>],
     pub visibility: Visibility,
     pub state_mutability: StateMutability,
-    pub returns: &'hir [Type<'hir>],
+    pub returns: &'hir [TypeFunctionParam<'hir>],
+}
+
+/// A parameter or return type of a function type name.
+#[derive(Debug)]
+pub struct TypeFunctionParam<'hir> {
+    pub ty: Type<'hir>,
+    pub data_location: Option<DataLocation>,
 }
 
 /// A mapping type.
@@ -0,0 +1,183 @@
+//! Inline assembly in the HIR.
+
+use super::{Lit, VariableId};
+use crate::yul::BuiltinFunction;
+use solar_interface::{diagnostics::ErrorGuaranteed, kw, sym, Ident, Span, Symbol};
+use std::fmt;
+
+/// A block of Yul statements: `{ ... }`.
+pub type Block<'hir> = &'hir [Stmt<'hir>];
+
+/// A Yul statement.
+#[derive(Debug)]
+pub struct Stmt<'hir> {
+    /// The span of the statement.
+    pub span: Span,
+    /// The kind of statement.
+    pub kind: StmtKind<'hir>,
+}
+
+/// A kind of Yul statement.
+#[derive(Debug)]
+pub enum StmtKind<'hir> {
+    /// A blocked scope: `{ ... }`.
+    Block(Block<'hir>),
+
+    /// An assignment statement: `x := 1` or `x, y, z := foo(1, 2)`.
+    ///
+    /// The targets are [`ExprKind::Var`] or [`ExprKind::External`] expressions.
+    Assign(&'hir [Expr<'hir>], &'hir Expr<'hir>),
+
+    /// An expression statement. This can only be a function call.
+    Expr(&'hir Expr<'hir>),
+
+    /// An if statement: `if lt(a, b) { ... }`.
+    If(&'hir Expr<'hir>, Block<'hir>),
+
+    /// A for statement: `for {let i := 0} lt(i,10) {i := add(i,1)} { ... }`.
+    For { init: Block<'hir>, cond: &'hir Expr<'hir>, step: Block<'hir>, body: Block<'hir> },
+
+    /// A switch statement: `switch expr case 0 { ... } default { ... }`.
+    Switch(&'hir StmtSwitch<'hir>),
+
+    /// A leave statement: `leave`.
+    Leave,
+
+    /// A break statement: `break`.
+    Break,
+
+    /// A continue statement: `continue`.
+    Continue,
+
+    /// A function definition statement: `function f() { ... }`.
+    FunctionDef(&'hir Function<'hir>),
+
+    /// A variable declaration statement: `let x := 0`.
+    VarDecl(&'hir [Ident], Option<&'hir Expr<'hir>>),
+}
+
+/// A switch statement: `switch expr case 0 { ... } default { ... }`.
+#[derive(Debug)]
+pub struct StmtSwitch<'hir> {
+    pub selector: Expr<'hir>,
+    pub cases: &'hir [StmtSwitchCase<'hir>],
+    pub default_case: Option<Block<'hir>>,
+}
+
+/// A non-default case of a switch statement. See [`StmtSwitch`].
+#[derive(Debug)]
+pub struct StmtSwitchCase<'hir> {
+    pub constant: &'hir Lit,
+    pub body: Block<'hir>,
+}
+
+/// A function definition: `function f() -> a, b { ... }`.
+#[derive(Debug)]
+pub struct Function<'hir> {
+    pub name: Ident,
+    pub parameters: &'hir [Ident],
+    pub returns: &'hir [Ident],
+    pub body: Block<'hir>,
+}
+
+/// A Yul expression.
+#[derive(Debug)]
+pub struct Expr<'hir> {
+    /// The span of the expression.
+    pub span: Span,
+    /// The kind of expression.
+    pub kind: ExprKind<'hir>,
+}
+
+/// A kind of Yul expression.
+#[derive(Debug)]
+pub enum ExprKind<'hir> {
+    /// A reference to a variable declared in Yul.
+    ///
+    /// The name may contain dots, as in `x.y`.
+    Var(Ident),
+
+    /// A reference to a Solidity variable, with an optional suffix: `x`, `x.slot`.
+    External(External),
+
+    /// A call to a function or to a builtin: `foo(a, b)`.
+    Call(Ident, &'hir [Expr<'hir>]),
+
+    /// A literal.
+    Lit(&'hir Lit),
+
+    Err(ErrorGuaranteed),
+}
+
+impl Expr<'_> {
+    /// Returns the builtin that this expression calls, if any.
+    ///
+    /// Functions declared in inline assembly can never shadow the builtins that are available in
+    /// it, so the callee is resolved by name.
+    pub fn builtin(&self) -> Option<BuiltinFunction> {
+        match self.kind {
+            ExprKind::Call(name, _) => {
+                BuiltinFunction::from_name(name.name).filter(|builtin| !builtin.object_only)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A reference to a Solidity variable from inline assembly.
+#[derive(Clone, Copy, Debug)]
+pub struct External {
+    /// The referenced variable.
+    pub variable: VariableId,
+    /// The suffix, if any.
+    pub suffix: Option<Suffix>,
+}
+
+/// A suffix of a reference to a Solidity variable: `x.slot`.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/assembly.html#access-to-external-variables-functions-and-libraries>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Suffix {
+    /// `.slot`: the storage slot of a storage variable or pointer.
+    Slot,
+    /// `.offset`: the byte offset in the storage slot of a storage variable, or the offset of a
+    /// dynamic calldata array.
+    Offset,
+    /// `.length`: the length of a dynamic calldata array.
+    Length,
+    /// `.selector`: the function selector of an external function pointer.
+    Selector,
+    /// `.address`: the address of an external function pointer.
+    Address,
+}
+
+impl fmt::Display for Suffix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl Suffix {
+    /// Returns the suffix with the given name, if any.
+    pub fn from_symbol(name: Symbol) -> Option<Self> {
+        Some(match name {
+            sym::slot => Self::Slot,
+            sym::offset => Self::Offset,
+            sym::length => Self::Length,
+            sym::selector => Self::Selector,
+            kw::Address => Self::Address,
+            _ => return None,
+        })
+    }
+
+    /// Returns the name of the suffix.
+    pub const fn to_str(self) -> &'static str {
+        match self {
+            Self::Slot => "slot",
+            Self::Offset => "offset",
+            Self::Length => "length",
+            Self::Selector => "selector",
+            Self::Address => "address",
+        }
+    }
+}
@@ -28,16 +28,32 @@ mod parse;
 pub use parse::{ParsedSource, ParsedSources, ParsingContext};
 
 pub mod builtins;
+pub mod cfg;
 pub mod eval;
 pub mod hir;
 pub mod ty;
+pub mod yul;
 
 mod typeck;
 
 mod emit;
 
+mod storage_diff;
+
 /// Parses and semantically analyzes all the loaded sources, recursing into imports.
 pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
+    parse_and_resolve_with(pcx, |_| Ok(()))
+}
+
+/// Parses and semantically analyzes all the loaded sources, recursing into imports, then calls
+/// `f` with the global context if the analysis succeeded.
+///
+/// `f` runs before the outputs are emitted, so it can record the contracts' bytecode with
+/// [`Gcx::set_contract_bytecode`].
+pub fn parse_and_resolve_with(
+    pcx: ParsingContext<'_>,
+    f: impl FnOnce(Gcx<'_>) -> Result<()>,
+) -> Result<()> {
     let sess = pcx.sess;
 
     if pcx.sources.is_empty() {
@@ -88,6 +104,8 @@ pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
         });
     let gcx = ty::Gcx::new(unsafe { trustme::decouple_lt(&global_context) });
     analysis(gcx)?;
+    f(gcx)?;
+    emit_outputs(gcx)?;
 
     Ok(())
 }
@@ -134,11 +152,22 @@ fn analysis(gcx: Gcx<'_>) -> Result<()> {
     typeck::check(gcx);
     gcx.sess.dcx.has_errors()?;
 
-    if !gcx.sess.emit.is_empty() {
+    Ok(())
+}
+
+/// Emits the requested outputs, after code generation.
+#[instrument(level = "debug", skip_all)]
+fn emit_outputs(gcx: Gcx<'_>) -> Result<()> {
+    if gcx.sess.emit.iter().any(|emit| emit.is_json()) {
         emit::emit(gcx);
         gcx.sess.dcx.has_errors()?;
     }
 
+    if let Some(path) = &gcx.sess.storage_layout_base {
+        storage_diff::check(gcx, path);
+        gcx.sess.dcx.has_errors()?;
+    }
+
     Ok(())
 }
 
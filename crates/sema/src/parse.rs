@@ -1,4 +1,4 @@
-use crate::hir::SourceId;
+use crate::{hir::SourceId, ty::Gcx};
 use rayon::prelude::*;
 use solar_ast::ast;
 use solar_data_structures::{
@@ -6,6 +6,7 @@ use solar_data_structures::{
     map::FxHashSet,
 };
 use solar_interface::{
+    config::CompilerStage,
     diagnostics::DiagCtxt,
     source_map::{FileName, FileResolver, SourceFile},
     Result, Session,
@@ -90,6 +91,13 @@ impl<'sess> ParsingContext<'sess> {
         crate::parse_and_resolve(self)
     }
 
+    /// Parses and analyzes all the loaded sources, then calls `f` with the global context.
+    ///
+    /// See [`parse_and_resolve_with`](crate::parse_and_resolve_with).
+    pub fn parse_and_resolve_with(self, f: impl FnOnce(Gcx<'_>) -> Result<()>) -> Result<()> {
+        crate::parse_and_resolve_with(self, f)
+    }
+
     /// Parses all the loaded sources, recursing into imports.
     ///
     /// Sources are not guaranteed to be in any particular order, as they may be parsed in parallel.
@@ -181,7 +189,11 @@ impl<'sess> ParsingContext<'sess> {
         let lexer = Lexer::from_source_file(self.sess, file);
         let mut parser = Parser::from_lexer(arena, lexer);
         let r = if self.sess.language.is_yul() {
-            let _file = parser.parse_yul_file_object().map_err(|e| e.emit());
+            if let Ok(object) = parser.parse_yul_file_object().map_err(|e| e.emit()) {
+                if !self.sess.stop_after(CompilerStage::Parsed) {
+                    crate::yul::analyze_object(self.sess, &object);
+                }
+            }
             None
         } else {
             parser.parse_file().map_err(|e| e.emit()).ok()
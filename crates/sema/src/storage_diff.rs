@@ -0,0 +1,285 @@
+//! Storage layout compatibility checks for contract upgrades.
+//!
+//! A contract that is upgraded behind a proxy keeps the storage of the previous implementation, so
+//! the new implementation must not remove, move, or change the type of the existing state
+//! variables. New variables can only be appended, or take the place of a storage gap.
+
+use crate::{
+    emit::{StorageLayout, StorageLayoutBuilder, StorageLayoutItem, StorageLayoutType},
+    hir,
+    ty::Gcx,
+};
+use alloy_primitives::U256;
+use serde::Deserialize;
+use solar_data_structures::map::FxHashSet;
+use solar_interface::Span;
+use std::{cell::RefCell, collections::BTreeMap, path::Path};
+
+/// The `combined.json` output of `--emit storage-layout`.
+#[derive(Deserialize)]
+struct BaseJson {
+    contracts: BTreeMap<String, BaseContract>,
+}
+
+#[derive(Deserialize)]
+struct BaseContract {
+    #[serde(rename = "storage-layout")]
+    storage_layout: Option<StorageLayout>,
+    #[serde(rename = "transient-storage-layout")]
+    transient_storage_layout: Option<StorageLayout>,
+}
+
+/// Checks the storage layouts of all the contracts against the ones in the given file.
+pub(crate) fn check(gcx: Gcx<'_>, path: &Path) {
+    let base = match std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|s| serde_json::from_str::<BaseJson>(&s).map_err(|e| e.to_string()))
+    {
+        Ok(base) => base,
+        Err(e) => {
+            let msg = format!("failed to read storage layouts from {}: {e}", path.display());
+            gcx.dcx().err(msg).emit();
+            return;
+        }
+    };
+
+    for id in gcx.hir.contract_ids() {
+        let Some(old) = find_contract(gcx, &base, id) else { continue };
+        let layout = gcx.storage_layout(id);
+        let layouts = [
+            (&old.storage_layout, layout.storage),
+            (&old.transient_storage_layout, layout.transient),
+        ];
+        for (old, new) in layouts {
+            let Some(old) = old else { continue };
+            let new = StorageLayoutBuilder::new(gcx, id).build(new);
+            let empty = BTreeMap::new();
+            let checker = LayoutChecker {
+                gcx,
+                contract: id,
+                old_types: old.types.as_ref().unwrap_or(&empty),
+                new_types: new.types.as_ref().unwrap_or(&empty),
+                checked_structs: Default::default(),
+            };
+            checker.check_items(&old.storage, &new.storage, None);
+        }
+    }
+}
+
+/// Finds the contract by its fully qualified name, or by its name if there is only one contract
+/// with that name.
+fn find_contract<'a>(
+    gcx: Gcx<'_>,
+    base: &'a BaseJson,
+    id: hir::ContractId,
+) -> Option<&'a BaseContract> {
+    let fqn = gcx.contract_fully_qualified_name(id).to_string();
+    if let Some(contract) = base.contracts.get(&fqn) {
+        return Some(contract);
+    }
+    let name = gcx.hir.contract(id).name.as_str();
+    let mut matches = base.contracts.iter().filter(|(fqn, _)| fqn.rsplit(':').next() == Some(name));
+    let (_, contract) = matches.next()?;
+    matches.next().is_none().then_some(contract)
+}
+
+struct LayoutChecker<'a, 'gcx> {
+    gcx: Gcx<'gcx>,
+    contract: hir::ContractId,
+    old_types: &'a BTreeMap<String, StorageLayoutType>,
+    new_types: &'a BTreeMap<String, StorageLayoutType>,
+    /// The pairs of old and new struct types whose members have already been checked.
+    checked_structs: RefCell<FxHashSet<(&'a str, &'a str)>>,
+}
+
+impl<'a> LayoutChecker<'a, '_> {
+    /// Checks the state variables of a contract, or the members of the struct `parent`.
+    fn check_items(
+        &self,
+        old: &'a [StorageLayoutItem],
+        new: &'a [StorageLayoutItem],
+        parent: Option<&str>,
+    ) {
+        let what = |label: &str| match parent {
+            Some(parent) => format!("member `{label}` of `{parent}`"),
+            None => format!("state variable `{label}`"),
+        };
+        let is_new = |item: &StorageLayoutItem| !old.iter().any(|o| o.label == item.label);
+
+        for old_item in old {
+            let is_gap = parent.is_none() && old_item.label.starts_with("__gap");
+            let Some(new_item) = new.iter().find(|i| i.label == old_item.label) else {
+                if is_gap {
+                    continue;
+                }
+                if let Some(renamed) = new.iter().find(|i| is_new(i) && same_position(old_item, i))
+                {
+                    let msg =
+                        format!("{} was renamed to `{}`", what(&old_item.label), renamed.label);
+                    self.gcx
+                        .dcx()
+                        .warn(msg)
+                        .span(self.span(renamed))
+                        .note("the value of the old variable is kept")
+                        .emit();
+                    self.check_type(&old_item.ty, &renamed.ty, &what(&renamed.label), renamed);
+                    continue;
+                }
+                let msg = format!("{} was removed", what(&old_item.label));
+                let note = format!(
+                    "it was stored in slot {} at offset {}",
+                    old_item.slot, old_item.offset
+                );
+                self.gcx
+                    .dcx()
+                    .err(msg)
+                    .span(self.gcx.hir.contract(self.contract).name.span)
+                    .note(note)
+                    .help("keep the variable, even if it is unused, to preserve the storage layout")
+                    .emit();
+                continue;
+            };
+
+            if is_gap {
+                let old_end = end_slot(old_item, self.old_types);
+                let new_end = end_slot(new_item, self.new_types);
+                if old_end != new_end {
+                    let msg = format!(
+                        "storage gap `{}` ends at slot {new_end} instead of slot {old_end}",
+                        new_item.label
+                    );
+                    self.gcx
+                        .dcx()
+                        .err(msg)
+                        .span(self.span(new_item))
+                        .note("the size of a storage gap must be reduced by the number of slots used by the variables added before it")
+                        .emit();
+                }
+                continue;
+            }
+
+            if !same_position(old_item, new_item) {
+                let msg = format!(
+                    "{} was moved from slot {} at offset {} to slot {} at offset {}",
+                    what(&new_item.label),
+                    old_item.slot,
+                    old_item.offset,
+                    new_item.slot,
+                    new_item.offset
+                );
+                let mut diag = self.gcx.dcx().err(msg).span(self.span(new_item));
+                let inserted =
+                    new.iter().take_while(|i| !std::ptr::eq(*i, new_item)).find(|i| is_new(i));
+                if let Some(inserted) = inserted {
+                    diag =
+                        diag.span_note(self.span(inserted), "this variable was inserted before it");
+                } else {
+                    diag = diag.note("existing variables must not be reordered");
+                }
+                diag.emit();
+                continue;
+            }
+
+            self.check_type(&old_item.ty, &new_item.ty, &what(&new_item.label), new_item);
+        }
+    }
+
+    /// Checks that the value of the old type can be read as the new type.
+    fn check_type(&self, old: &'a str, new: &'a str, what: &str, item: &StorageLayoutItem) {
+        self.check_type_(old, new, what, item, false);
+    }
+
+    fn check_type_(
+        &self,
+        old: &'a str,
+        new: &'a str,
+        what: &str,
+        item: &StorageLayoutItem,
+        is_mapping_value: bool,
+    ) {
+        let (Some(old_ty), Some(new_ty)) = (self.old_types.get(old), self.new_types.get(new))
+        else {
+            return;
+        };
+        let span = self.span(item);
+
+        let changed =
+            || format!("the type of {what} changed from `{}` to `{}`", old_ty.label, new_ty.label);
+        if old_ty.encoding != new_ty.encoding {
+            let note = format!(
+                "`{}` is stored as `{}`, but `{}` is stored as `{}`",
+                old_ty.label, old_ty.encoding, new_ty.label, new_ty.encoding
+            );
+            self.gcx.dcx().err(changed()).span(span).note(note).emit();
+            return;
+        }
+        // Structs that are mapping values can grow, since their slots are not followed by any
+        // other value.
+        let can_grow = is_mapping_value && old_ty.members.is_some();
+        if old_ty.number_of_bytes != new_ty.number_of_bytes && !can_grow {
+            let note = format!(
+                "the size in storage changes from {} to {} bytes",
+                old_ty.number_of_bytes, new_ty.number_of_bytes
+            );
+            self.gcx.dcx().err(changed()).span(span).note(note).emit();
+            return;
+        }
+
+        if let (Some(old_key), Some(new_key)) = (&old_ty.key, &new_ty.key) {
+            let old_key = self.old_types.get(old_key).map_or(old_key, |ty| &ty.label);
+            let new_key = self.new_types.get(new_key).map_or(new_key, |ty| &ty.label);
+            if old_key != new_key {
+                let msg = format!(
+                    "the key type of the mapping in {what} changed from `{old_key}` to `{new_key}`"
+                );
+                self.gcx
+                    .dcx()
+                    .err(msg)
+                    .span(span)
+                    .note("existing values are not accessible with the new keys")
+                    .emit();
+            }
+        }
+        if let (Some(old_value), Some(new_value)) = (&old_ty.value, &new_ty.value) {
+            self.check_type_(old_value, new_value, what, item, true);
+        }
+        if let (Some(old_base), Some(new_base)) = (&old_ty.base, &new_ty.base) {
+            self.check_type_(old_base, new_base, what, item, false);
+        }
+        if let (Some(old_members), Some(new_members)) = (&old_ty.members, &new_ty.members) {
+            if self.checked_structs.borrow_mut().insert((old, new)) {
+                self.check_items(old_members, new_members, Some(&new_ty.label));
+            }
+            return;
+        }
+
+        let is_leaf = old_ty.key.is_none() && old_ty.base.is_none();
+        if is_leaf && old_ty.label != new_ty.label {
+            self.gcx
+                .dcx()
+                .warn(changed())
+                .span(span)
+                .note("the existing value will be interpreted as the new type")
+                .emit();
+        }
+    }
+
+    /// Returns the span of the declaration of the new variable.
+    fn span(&self, item: &StorageLayoutItem) -> Span {
+        self.gcx.hir.variable(hir::VariableId::new(item.ast_id)).span
+    }
+}
+
+fn same_position(a: &StorageLayoutItem, b: &StorageLayoutItem) -> bool {
+    a.slot == b.slot && a.offset == b.offset
+}
+
+/// Returns the first slot after the item.
+fn end_slot(item: &StorageLayoutItem, types: &BTreeMap<String, StorageLayoutType>) -> U256 {
+    let slot = item.slot.parse::<U256>().unwrap_or_default();
+    let bytes = types
+        .get(&item.ty)
+        .and_then(|ty| ty.number_of_bytes.parse::<U256>().ok())
+        .unwrap_or_default();
+    slot.saturating_add(bytes.div_ceil(U256::from(32)))
+}
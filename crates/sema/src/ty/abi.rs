@@ -1,9 +1,25 @@
 use super::{Gcx, Ty, TyKind};
 use crate::hir;
 use alloy_json_abi as json;
-use solar_ast::ast::ElementaryType;
+use solar_ast::ast::{ElementaryType, StateMutability, Visibility};
+use solar_data_structures::fmt_from_fn;
 use std::{fmt, ops::ControlFlow};
 
+impl<'gcx> Ty<'gcx> {
+    /// Returns a value that displays the type as it would appear in solc diagnostics, including
+    /// data locations.
+    pub fn display(self, gcx: Gcx<'gcx>) -> impl fmt::Display + use<'gcx> {
+        fmt_from_fn(move |f| TyPrinter::new(gcx, f).solc(true).data_locations(true).print(self))
+    }
+
+    /// Returns a value that displays the type as it would appear in solc, without data locations.
+    ///
+    /// This is the format used for type labels in the storage layout output.
+    pub fn display_short(self, gcx: Gcx<'gcx>) -> impl fmt::Display + use<'gcx> {
+        fmt_from_fn(move |f| TyPrinter::new(gcx, f).solc(true).print(self))
+    }
+}
+
 impl<'gcx> Gcx<'gcx> {
     /// Formats the ABI signature of a function in the form `{name}({tys},*)`.
     pub(super) fn mk_abi_signature(
@@ -145,7 +161,7 @@ struct TyPrinter<'gcx, W: fmt::Write> {
     ///
     /// Has effect only when printing as ABI.
     recurse: bool,
-    /// If `true`, prints the data location of references.
+    /// If `true`, prints the data location of references and full function pointer signatures.
     ///
     /// Only has effect when printing as solc.
     data_locations: bool,
@@ -166,7 +182,6 @@ impl<'gcx, W: fmt::Write> TyPrinter<'gcx, W> {
         self
     }
 
-    #[allow(dead_code)]
     fn data_locations(mut self, yes: bool) -> Self {
         self.data_locations = yes;
         self
@@ -219,7 +234,25 @@ impl<'gcx, W: fmt::Write> TyPrinter<'gcx, W> {
             TyKind::Contract(id) => {
                 write!(self.buf, "contract {}", self.gcx.item_canonical_name(id))
             }
-            TyKind::FnPtr(_) => self.buf.write_str("function"),
+            TyKind::FnPtr(f) => {
+                self.buf.write_str("function")?;
+                if !self.data_locations {
+                    return Ok(());
+                }
+                self.buf.write_str(" ")?;
+                self.print_tuple(f.parameters.iter().copied())?;
+                if f.visibility == Visibility::External {
+                    self.buf.write_str(" external")?;
+                }
+                if f.state_mutability != StateMutability::NonPayable {
+                    write!(self.buf, " {}", f.state_mutability)?;
+                }
+                if !f.returns.is_empty() {
+                    self.buf.write_str(" returns ")?;
+                    self.print_tuple(f.returns.iter().copied())?;
+                }
+                Ok(())
+            }
             TyKind::Struct(id) => {
                 write!(self.buf, "struct {}", self.gcx.item_canonical_name(id))
             }
@@ -240,7 +273,32 @@ impl<'gcx, W: fmt::Write> TyPrinter<'gcx, W> {
                 self.print_solc(ty)?;
                 write!(self.buf, "[{len}]")
             }
-            _ => panic!("printing invalid solc type: {ty:?}"),
+            TyKind::StringLiteral(..) => self.buf.write_str("literal_string"),
+            TyKind::IntLiteral(..) => self.buf.write_str("int_const"),
+            TyKind::RationalLiteral(_) => self.buf.write_str("rational_const"),
+            TyKind::Tuple(tys) => {
+                self.buf.write_str("tuple")?;
+                self.print_tuple(tys.iter().copied())
+            }
+            TyKind::Mapping(key, value) => {
+                self.buf.write_str("mapping(")?;
+                self.print_solc(key)?;
+                self.buf.write_str(" => ")?;
+                self.print_solc(value)?;
+                self.buf.write_str(")")
+            }
+            TyKind::Error(_, id) => write!(self.buf, "error {}", self.gcx.item_canonical_name(id)),
+            TyKind::Event(_, id) => write!(self.buf, "event {}", self.gcx.item_canonical_name(id)),
+            TyKind::Module(id) => {
+                write!(self.buf, "module \"{}\"", self.gcx.hir.source(id).file.name.display())
+            }
+            TyKind::BuiltinModule(b) => write!(self.buf, "{}", b.name()),
+            TyKind::Type(ty) | TyKind::Meta(ty) => {
+                self.buf.write_str("type(")?;
+                self.print_solc(ty)?;
+                self.buf.write_str(")")
+            }
+            TyKind::Err(_) => self.buf.write_str("<error>"),
         }
     }
 
@@ -0,0 +1,290 @@
+use super::{Gcx, Ty, TyKind};
+use crate::hir;
+use solar_ast::ast::{DataLocation, ElementaryType, StateMutability};
+
+/// The reason why a type conversion is not allowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConversionError {
+    /// The types are unrelated.
+    Incompatible,
+    /// The integer literal does not fit in the target type.
+    LiteralOutOfRange,
+    /// A negative integer literal cannot be converted to an unsigned type.
+    NegativeLiteral,
+    /// A rational literal with a fractional part cannot be converted to an integer type.
+    NonIntegralLiteral,
+    /// The literal has more fractional digits than the target fixed point type.
+    FractionalDigits,
+    /// Only zero or a hexadecimal literal of the same size can be converted to a fixed bytes type.
+    NonZeroLiteralToFixedBytes,
+    /// The string literal is not valid UTF-8.
+    InvalidUtf8,
+    /// The string literal is longer than the target fixed bytes type.
+    StringLiteralTooLong,
+    /// The conversion would truncate the value.
+    Truncation,
+    /// The conversion changes both the size and the signedness of an integer.
+    SizeAndSignChange,
+    /// The conversion between an integer and a fixed bytes type changes the size.
+    SizeMismatch,
+    /// Only `uint160` and `bytes20` can be converted to and from `address`.
+    AddressSize,
+    /// `address` cannot be converted to `address payable` implicitly.
+    NonPayableAddress,
+    /// The contract has no `receive` or payable `fallback` function.
+    NonPayableContract,
+    /// The target contract is not a base of the source contract.
+    NotABaseContract,
+    /// The data locations are incompatible.
+    DataLocation,
+    /// Storage pointers can only reference storage.
+    StoragePointer,
+    /// The tuples have a different number of components.
+    TupleLength,
+    /// The function types have different parameter or return types.
+    FunctionSignature,
+    /// The function types have different visibilities.
+    FunctionVisibility,
+    /// The state mutability of the function is less restrictive than the target's.
+    FunctionMutability,
+    /// User-defined value types can only be converted with `wrap` and `unwrap`.
+    UserDefinedValueType,
+}
+
+impl ConversionError {
+    /// Returns a note explaining the error, if any.
+    pub fn note(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Incompatible => return None,
+            Self::LiteralOutOfRange => "the literal does not fit in the target type",
+            Self::NegativeLiteral => "negative literals cannot be converted to unsigned types",
+            Self::NonIntegralLiteral => {
+                "the literal has a fractional part, so it cannot be converted to an integer"
+            }
+            Self::FractionalDigits => {
+                "the literal has more fractional digits than the target type can represent"
+            }
+            Self::NonZeroLiteralToFixedBytes => {
+                "only zero or a hexadecimal literal of the same size can be converted to fixed bytes"
+            }
+            Self::InvalidUtf8 => "the string literal is not valid UTF-8",
+            Self::StringLiteralTooLong => "the string literal is longer than the target type",
+            Self::Truncation => "the conversion would truncate the value",
+            Self::SizeAndSignChange => {
+                "integer conversions cannot change both the size and the signedness"
+            }
+            Self::SizeMismatch => "the conversion changes the size of the value",
+            Self::AddressSize => {
+                "only `uint160` and `bytes20` can be converted to and from `address`"
+            }
+            Self::NonPayableAddress => "use `payable(<address>)` to convert to `address payable`",
+            Self::NonPayableContract => {
+                "the contract does not have a `receive` or a payable `fallback` function"
+            }
+            Self::NotABaseContract => "the target contract is not a base of the source contract",
+            Self::DataLocation => "the data locations are incompatible",
+            Self::StoragePointer => "storage pointers can only reference storage",
+            Self::TupleLength => "the tuples have a different number of components",
+            Self::FunctionSignature => "the function types have different parameters or returns",
+            Self::FunctionVisibility => "the function types have different visibilities",
+            Self::FunctionMutability => "the function's state mutability is less restrictive",
+            Self::UserDefinedValueType => {
+                "user-defined value types can only be converted with `wrap` and `unwrap`"
+            }
+        })
+    }
+}
+
+type ConversionResult = Result<(), ConversionError>;
+use ConversionError as CE;
+
+impl<'gcx> Ty<'gcx> {
+    /// Returns `Ok(())` if the type is implicitly convertible to `to`, otherwise returns the reason
+    /// why it is not.
+    ///
+    /// Error types are convertible to and from any type.
+    pub fn is_implicitly_convertible_to(self, gcx: Gcx<'gcx>, to: Self) -> ConversionResult {
+        if self == to || self.has_error() || to.has_error() {
+            return Ok(());
+        }
+        use ElementaryType::*;
+        match (&self.kind, &to.kind) {
+            (&TyKind::IntLiteral(negative, bits), &TyKind::Elementary(to)) => match to {
+                UInt(_) if negative => Err(CE::NegativeLiteral),
+                UInt(size) => ensure(bits <= size.bits(), CE::LiteralOutOfRange),
+                Int(size) => ensure(bits < size.bits(), CE::LiteralOutOfRange),
+                FixedBytes(_) => ensure(!negative && bits == 0, CE::NonZeroLiteralToFixedBytes),
+                UFixed(..) if negative => Err(CE::NegativeLiteral),
+                Fixed(..) | UFixed(..) => Ok(()),
+                _ => Err(CE::Incompatible),
+            },
+            (&TyKind::RationalLiteral(negative), &TyKind::Elementary(to)) => match to {
+                UInt(_) | Int(_) => Err(CE::NonIntegralLiteral),
+                UFixed(..) if negative => Err(CE::NegativeLiteral),
+                Fixed(..) | UFixed(..) => Ok(()),
+                _ => Err(CE::Incompatible),
+            },
+            (&TyKind::StringLiteral(utf8, size), _) => {
+                if let TyKind::Ref(_, loc) = to.kind {
+                    if loc == DataLocation::Calldata {
+                        return Err(CE::DataLocation);
+                    }
+                }
+                match to.peel_refs().kind {
+                    TyKind::Elementary(String) => ensure(utf8, CE::InvalidUtf8),
+                    TyKind::Elementary(Bytes) => Ok(()),
+                    TyKind::Elementary(FixedBytes(to_size)) => {
+                        ensure(size.bytes_raw() <= to_size.bytes(), CE::StringLiteralTooLong)
+                    }
+                    _ => Err(CE::Incompatible),
+                }
+            }
+            (&TyKind::Elementary(from), &TyKind::Elementary(to)) => match (from, to) {
+                (UInt(a), UInt(b)) | (Int(a), Int(b)) | (FixedBytes(a), FixedBytes(b)) => {
+                    ensure(a.bits() <= b.bits(), CE::Truncation)
+                }
+                (UInt(a), Int(b)) => ensure(a.bits() < b.bits(), CE::Truncation),
+                (UFixed(a, fa), UFixed(b, fb)) | (Fixed(a, fa), Fixed(b, fb)) => {
+                    ensure(a.bits() <= b.bits() && fa.get() <= fb.get(), CE::Truncation)
+                }
+                (UFixed(a, fa), Fixed(b, fb)) => {
+                    ensure(a.bits() < b.bits() && fa.get() <= fb.get(), CE::Truncation)
+                }
+                (Address(true), Address(false)) => Ok(()),
+                (Address(false), Address(true)) => Err(CE::NonPayableAddress),
+                _ => Err(CE::Incompatible),
+            },
+            (&TyKind::Contract(from), &TyKind::Contract(to)) => {
+                ensure(gcx.hir.contract(from).linearized_bases.contains(&to), CE::NotABaseContract)
+            }
+            (&TyKind::Tuple(from), &TyKind::Tuple(to)) => {
+                if from.len() != to.len() {
+                    return Err(CE::TupleLength);
+                }
+                from.iter().zip(to).try_for_each(|(&a, &b)| a.is_implicitly_convertible_to(gcx, b))
+            }
+            (&TyKind::FnPtr(from), &TyKind::FnPtr(to)) => {
+                if from.parameters != to.parameters || from.returns != to.returns {
+                    return Err(CE::FunctionSignature);
+                }
+                if from.visibility != to.visibility {
+                    return Err(CE::FunctionVisibility);
+                }
+                ensure(
+                    is_mutability_convertible(from.state_mutability, to.state_mutability),
+                    CE::FunctionMutability,
+                )
+            }
+            (&TyKind::Ref(from, from_loc), &TyKind::Ref(to, to_loc)) => {
+                if from != to {
+                    return Err(CE::Incompatible);
+                }
+                match to_loc {
+                    DataLocation::Calldata => ensure(from_loc == to_loc, CE::DataLocation),
+                    // Copying to storage is only possible through an assignment, otherwise the
+                    // target is a storage pointer.
+                    DataLocation::Storage => {
+                        ensure(from_loc == DataLocation::Storage, CE::StoragePointer)
+                    }
+                    _ => Ok(()),
+                }
+            }
+            // Reference types without a location, such as mapping keys.
+            (&TyKind::Ref(from, _), _) => ensure(from == to, CE::Incompatible),
+            (_, &TyKind::Ref(to, _)) => ensure(self == to, CE::Incompatible),
+            _ => Err(CE::Incompatible),
+        }
+    }
+
+    /// Returns `Ok(())` if the type is explicitly convertible to `to`, otherwise returns the reason
+    /// why it is not.
+    ///
+    /// All implicit conversions are also allowed explicitly. User-defined value types are not
+    /// convertible to or from any other type: `wrap` and `unwrap` must be used instead.
+    ///
+    /// Reference: <https://docs.soliditylang.org/en/latest/types.html#explicit-conversions>
+    pub fn is_explicitly_convertible_to(self, gcx: Gcx<'gcx>, to: Self) -> ConversionResult {
+        let implicit = self.is_implicitly_convertible_to(gcx, to);
+        if implicit.is_ok() {
+            return implicit;
+        }
+        use ElementaryType::*;
+        match (&self.kind, &to.kind) {
+            (&TyKind::IntLiteral(negative, bits), _) => match to.kind {
+                TyKind::Elementary(Address(false)) => {
+                    ensure(!negative && bits <= 160, CE::LiteralOutOfRange)
+                }
+                TyKind::Enum(_) => ensure(!negative && bits <= 8, CE::LiteralOutOfRange),
+                _ => implicit,
+            },
+            (&TyKind::Elementary(from), &TyKind::Elementary(to)) => match (from, to) {
+                (UInt(a) | Int(a), UInt(b) | Int(b)) => ensure(
+                    a.bits() == b.bits() || matches!(from, UInt(_)) == matches!(to, UInt(_)),
+                    CE::SizeAndSignChange,
+                ),
+                (FixedBytes(_), FixedBytes(_)) | (Address(_), Address(_)) => Ok(()),
+                (UInt(_) | Int(_) | UFixed(..) | Fixed(..), UFixed(..) | Fixed(..))
+                | (UFixed(..) | Fixed(..), UInt(_) | Int(_)) => Ok(()),
+                (UInt(a), FixedBytes(b)) | (FixedBytes(b), UInt(a)) => {
+                    ensure(a.bits() == b.bits(), CE::SizeMismatch)
+                }
+                (UInt(a), Address(false)) | (Address(_), UInt(a)) => {
+                    ensure(a.bits() == 160, CE::AddressSize)
+                }
+                (FixedBytes(a), Address(false)) | (Address(_), FixedBytes(a)) => {
+                    ensure(a.bytes() == 20, CE::AddressSize)
+                }
+                (UInt(_) | FixedBytes(_), Address(true)) => Err(CE::NonPayableAddress),
+                _ => implicit,
+            },
+            (&TyKind::Elementary(from), &TyKind::Enum(_)) => {
+                ensure(matches!(from, UInt(_) | Int(_)), CE::Incompatible)
+            }
+            (&TyKind::Enum(_), &TyKind::Elementary(to)) => {
+                ensure(matches!(to, UInt(_) | Int(_)), CE::Incompatible)
+            }
+            (&TyKind::Contract(from), &TyKind::Elementary(Address(payable))) => {
+                ensure(!payable || contract_is_payable(gcx, from), CE::NonPayableContract)
+            }
+            (&TyKind::Elementary(Address(payable)), &TyKind::Contract(to)) => {
+                ensure(payable || !contract_is_payable(gcx, to), CE::NonPayableAddress)
+            }
+            (&TyKind::Udvt(..), _) | (_, &TyKind::Udvt(..)) => Err(CE::UserDefinedValueType),
+            (&TyKind::Ref(from, from_loc), _) => match (&from.kind, &to.kind) {
+                // `bytes` to `bytesN`.
+                (TyKind::Elementary(Bytes), TyKind::Elementary(FixedBytes(_))) => Ok(()),
+                // `bytes` to `string` and vice versa.
+                (TyKind::Elementary(String | Bytes), &TyKind::Ref(to_inner, to_loc))
+                    if matches!(to_inner.kind, TyKind::Elementary(String | Bytes)) =>
+                {
+                    ensure(from_loc == to_loc, CE::DataLocation)
+                }
+                _ => implicit,
+            },
+            _ => implicit,
+        }
+    }
+}
+
+fn ensure(cond: bool, err: ConversionError) -> ConversionResult {
+    if cond {
+        Ok(())
+    } else {
+        Err(err)
+    }
+}
+
+/// Returns `true` if the contract can receive Ether through a `receive` or a payable `fallback`
+/// function.
+fn contract_is_payable(gcx: Gcx<'_>, id: hir::ContractId) -> bool {
+    let c = gcx.hir.contract(id);
+    c.receive.is_some()
+        || c.fallback
+            .is_some_and(|f| gcx.hir.function(f).state_mutability == StateMutability::Payable)
+}
+
+/// Returns `true` if a function with mutability `from` can be used as one with mutability `to`.
+fn is_mutability_convertible(from: StateMutability, to: StateMutability) -> bool {
+    use StateMutability::*;
+    from == to || matches!((from, to), (Pure, View | NonPayable) | (View | Payable, NonPayable))
+}
@@ -4,7 +4,9 @@ use crate::{
     hir::{self, Hir},
 };
 use alloy_primitives::{keccak256, Selector, B256};
-use solar_ast::ast::{DataLocation, StateMutability, TypeSize, Visibility};
+use solar_ast::ast::{
+    DataLocation, ElementaryType, StateMutability, TypeFixedSize, TypeSize, Visibility,
+};
 use solar_data_structures::{
     fmt_from_fn,
     map::{FxBuildHasher, FxHashMap, FxHashSet},
@@ -25,9 +27,15 @@ mod abi;
 mod common;
 pub use common::{CommonTypes, EachDataLoc};
 
+mod convert;
+pub use convert::ConversionError;
+
 mod interner;
 use interner::Interner;
 
+mod storage;
+pub use storage::{StorageItem, StorageLayout, StorageSize, StructStorageLayout};
+
 #[allow(clippy::module_inception)]
 mod ty;
 pub use ty::{Ty, TyData, TyFlags, TyFnPtr, TyKind};
@@ -45,6 +53,17 @@ pub struct InterfaceFunction<'gcx> {
     pub ty: Ty<'gcx>,
 }
 
+/// The bytecode of a compiled contract.
+///
+/// Return type of [`Gcx::contract_bytecode`].
+#[derive(Clone, Copy, Debug)]
+pub struct ContractBytecode<'gcx> {
+    /// The creation bytecode, which runs the constructor and returns the runtime bytecode.
+    pub creation: &'gcx [u8],
+    /// The runtime bytecode.
+    pub runtime: &'gcx [u8],
+}
+
 /// List of all the functions exported by a contract.
 ///
 /// Return type of [`Gcx::interface_functions`].
@@ -73,6 +92,19 @@ impl<'gcx> InterfaceFunctions<'gcx> {
     }
 }
 
+/// A read of an immutable variable in the runtime code of a contract.
+///
+/// Return type of [`Gcx::immutable_references`].
+#[derive(Clone, Copy, Debug)]
+pub struct ImmutableReference {
+    /// The immutable variable.
+    pub variable: hir::VariableId,
+    /// The expression that reads the variable.
+    pub expr: hir::ExprId,
+    /// The span of the expression.
+    pub span: Span,
+}
+
 impl<'gcx> std::ops::Deref for InterfaceFunctions<'gcx> {
     type Target = &'gcx [InterfaceFunction<'gcx>];
 
@@ -121,6 +153,18 @@ pub struct GlobalCtxt<'gcx> {
 
     interner: Interner<'gcx>,
     cache: Cache<'gcx>,
+    typeck_results: TypeckResults<'gcx>,
+    /// The bytecode of the contracts, populated by the code generator.
+    bytecodes: FxOnceMap<hir::ContractId, ContractBytecode<'gcx>>,
+}
+
+/// Side tables populated by the type checker.
+#[derive(Default)]
+struct TypeckResults<'gcx> {
+    /// The types of the checked expressions.
+    expr_types: FxOnceMap<hir::ExprId, Ty<'gcx>>,
+    /// The declarations that identifiers and member accesses resolved to.
+    expr_res: FxOnceMap<hir::ExprId, hir::Res>,
 }
 
 impl<'gcx> GlobalCtxt<'gcx> {
@@ -138,6 +182,8 @@ impl<'gcx> GlobalCtxt<'gcx> {
             symbol_resolver,
             interner,
             cache: Cache::default(),
+            typeck_results: TypeckResults::default(),
+            bytecodes: FxOnceMap::default(),
         }
     }
 }
@@ -187,8 +233,12 @@ impl<'gcx> Gcx<'gcx> {
         ))
     }
 
-    pub fn mk_ty_int_literal(self, size: TypeSize) -> Ty<'gcx> {
-        self.mk_ty(TyKind::IntLiteral(size))
+    pub fn mk_ty_int_literal(self, negative: bool, bits: u16) -> Ty<'gcx> {
+        self.mk_ty(TyKind::IntLiteral(negative, bits))
+    }
+
+    pub fn mk_ty_rational_literal(self, negative: bool) -> Ty<'gcx> {
+        self.mk_ty(TyKind::RationalLiteral(negative))
     }
 
     pub fn mk_ty_fn_ptr(self, ptr: TyFnPtr<'gcx>) -> Ty<'gcx> {
@@ -357,27 +407,35 @@ impl<'gcx> Gcx<'gcx> {
     /// Computes the [`Ty`] of the given [`hir::Type`]. Not cached.
     pub fn type_of_hir_ty(self, ty: &hir::Type<'_>) -> Ty<'gcx> {
         let kind = match ty.kind {
-            hir::TypeKind::Elementary(ty) => TyKind::Elementary(ty),
+            hir::TypeKind::Elementary(ty) => TyKind::Elementary(normalize_elementary(ty)),
             hir::TypeKind::Array(array) => {
                 let ty = self.type_of_hir_ty(&array.element);
                 match array.size {
-                    Some(size) => match crate::eval::ConstantEvaluator::new(self).eval(size) {
-                        Ok(int) => {
-                            if int.data.is_zero() {
-                                let msg = "array length must be greater than zero";
-                                self.dcx().err(msg).span(size.span).emit();
+                    Some(size) => {
+                        match crate::eval::ConstantEvaluator::new(self).eval_array_len(size) {
+                            Ok(len) => {
+                                if len.is_zero() {
+                                    let msg = "array length must be greater than zero";
+                                    self.dcx().err(msg).span(size.span).emit();
+                                }
+                                TyKind::Array(ty, len)
                             }
-                            TyKind::Array(ty, int.data)
+                            Err(guar) => TyKind::Err(guar),
                         }
-                        Err(guar) => TyKind::Err(guar),
-                    },
+                    }
                     None => TyKind::DynArray(ty),
                 }
             }
             hir::TypeKind::Function(f) => {
-                let parameters =
-                    self.mk_ty_iter(f.parameters.iter().map(|ty| self.type_of_hir_ty(ty)));
-                let returns = self.mk_ty_iter(f.returns.iter().map(|ty| self.type_of_hir_ty(ty)));
+                let param_ty = |param: &hir::TypeFunctionParam<'_>| {
+                    let ty = self.type_of_hir_ty(&param.ty);
+                    match param.data_location {
+                        Some(loc) => ty.with_loc_if_ref(self, loc),
+                        None => ty,
+                    }
+                };
+                let parameters = self.mk_ty_iter(f.parameters.iter().map(param_ty));
+                let returns = self.mk_ty_iter(f.returns.iter().map(param_ty));
                 TyKind::FnPtr(self.interner.intern_ty_fn_ptr(TyFnPtr {
                     parameters,
                     returns,
@@ -410,14 +468,63 @@ impl<'gcx> Gcx<'gcx> {
     }
 
     /// Returns the type of the given [`hir::Res`].
+    ///
+    /// User-defined types are wrapped in [`TyKind::Type`].
     pub fn type_of_res(self, res: hir::Res) -> Ty<'gcx> {
         match res {
+            hir::Res::Item(
+                id @ (hir::ItemId::Contract(_)
+                | hir::ItemId::Struct(_)
+                | hir::ItemId::Enum(_)
+                | hir::ItemId::Udvt(_)),
+            ) => self.type_of_item(id).make_type_type(self),
             hir::Res::Item(id) => self.type_of_item(id),
             hir::Res::Namespace(id) => self.mk_ty(TyKind::Module(id)),
             hir::Res::Builtin(builtin) => builtin.ty(self),
             hir::Res::Err(guar) => self.mk_ty_err(guar),
         }
     }
+
+    /// Returns the type of the given expression, as computed by the type checker.
+    ///
+    /// Returns `None` if the expression has not been type checked.
+    pub fn type_of_expr(self, id: hir::ExprId) -> Option<Ty<'gcx>> {
+        self.typeck_results.expr_types.map_get(&id, |_k, v| *v)
+    }
+
+    /// Records the type of the given expression.
+    pub(crate) fn set_type_of_expr(self, id: hir::ExprId, ty: Ty<'gcx>) -> Ty<'gcx> {
+        cache_insert(&self.typeck_results.expr_types, id, |_| ty)
+    }
+
+    /// Returns the declaration that the given identifier or member access expression refers to,
+    /// after overload resolution.
+    ///
+    /// Returns `None` if the expression does not refer to a declaration, or if it could not be
+    /// resolved to a single one.
+    pub fn res_of_expr(self, id: hir::ExprId) -> Option<hir::Res> {
+        self.typeck_results.expr_res.map_get(&id, |_k, v| *v)
+    }
+
+    /// Records the declaration that the given expression refers to.
+    pub(crate) fn set_res_of_expr(self, id: hir::ExprId, res: hir::Res) -> hir::Res {
+        cache_insert(&self.typeck_results.expr_res, id, |_| res)
+    }
+
+    /// Returns the bytecode of the given contract.
+    ///
+    /// Returns `None` if the contract has not been compiled.
+    pub fn contract_bytecode(self, id: hir::ContractId) -> Option<ContractBytecode<'gcx>> {
+        self.bytecodes.map_get(&id, |_k, v| *v)
+    }
+
+    /// Records the bytecode of the given contract. This is called by the code generator, so that
+    /// the bytecode is emitted together with the other outputs.
+    pub fn set_contract_bytecode(self, id: hir::ContractId, creation: &[u8], runtime: &[u8]) {
+        let creation = self.bump().alloc_slice_copy(creation);
+        let runtime = self.bump().alloc_slice_copy(runtime);
+        cache_insert(&self.bytecodes, id, |_| ContractBytecode { creation, runtime });
+    }
 }
 
 macro_rules! cached {
@@ -534,6 +641,32 @@ pub fn interface_functions(gcx: _, id: hir::ContractId) -> InterfaceFunctions<'g
     InterfaceFunctions { functions, inheritance_start }
 }
 
+/// Checks the initialization of the immutable variables declared in the given contract, and
+/// returns the references to immutable variables in the runtime code of the contract, including
+/// the ones in inherited functions.
+///
+/// This must only be called after type checking, as it uses the resolutions of the expressions.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/contracts.html#immutable>
+pub fn immutable_references(gcx: _, id: hir::ContractId) -> &'gcx [ImmutableReference] {
+    crate::typeck::immutables::check_contract(gcx, id)
+}
+
+/// Returns the storage layout of the given contract, including the state variables of its base
+/// contracts.
+///
+/// Constants and immutable variables are not stored in storage, and are not included.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html>
+pub fn storage_layout(gcx: _, id: hir::ContractId) -> StorageLayout<'gcx> {
+    storage::contract_storage_layout(gcx, id)
+}
+
+/// Returns the storage layout of the given struct.
+pub fn struct_storage_layout(gcx: _, id: hir::StructId) -> StructStorageLayout<'gcx> {
+    storage::struct_storage_layout(gcx, id)
+}
+
 /// Returns the ABI signature of the given item. Only accepts functions, errors, and events.
 pub fn item_signature(gcx: _, id: hir::ItemId) -> &'gcx str {
     let name = gcx.item_name(id);
@@ -561,11 +694,18 @@ pub fn type_of_item(gcx: _, id: hir::ItemId) -> Ty<'gcx> {
         hir::ItemId::Variable(id) => {
             let var = gcx.hir.variable(id);
             let ty = gcx.type_of_hir_ty(&var.ty);
-            match (var.contract, var.data_location) {
-                (_, Some(loc)) => TyKind::Ref(ty, loc),
-                (Some(_), None) => TyKind::Ref(ty, DataLocation::Storage),
-                (None, None) => return ty,
-            }
+            // Constants are evaluated in memory, other state variables live in storage.
+            let loc = var.data_location.or(if var.mutability.is_some_and(|m| m.is_constant()) {
+                Some(DataLocation::Memory)
+            } else if var.is_state_variable() {
+                Some(DataLocation::Storage)
+            } else {
+                None
+            });
+            return match loc {
+                Some(loc) => ty.with_loc_if_ref(gcx, loc),
+                None => ty,
+            };
         }
         hir::ItemId::Struct(id) => TyKind::Struct(id),
         hir::ItemId::Enum(id) => TyKind::Enum(id),
@@ -604,6 +744,32 @@ pub fn members_of(gcx: _, ty: Ty<'gcx>) -> members::MemberList<'gcx> {
 }
 }
 
+/// Replaces the implicit sizes of `int`, `uint`, `fixed` and `ufixed` with their explicit ones, so
+/// that e.g. `uint` and `uint256` map to the same type.
+fn normalize_elementary(ty: ElementaryType) -> ElementaryType {
+    let int = |size: TypeSize| TypeSize::new(size.bytes()).unwrap();
+    let fixed = |size: TypeSize, fsize: TypeFixedSize| {
+        if size == TypeSize::ZERO {
+            (TypeSize::new(16).unwrap(), TypeFixedSize::new(18).unwrap())
+        } else {
+            (size, fsize)
+        }
+    };
+    match ty {
+        ElementaryType::Int(size) => ElementaryType::Int(int(size)),
+        ElementaryType::UInt(size) => ElementaryType::UInt(int(size)),
+        ElementaryType::Fixed(size, fsize) => {
+            let (size, fsize) = fixed(size, fsize);
+            ElementaryType::Fixed(size, fsize)
+        }
+        ElementaryType::UFixed(size, fsize) => {
+            let (size, fsize) = fixed(size, fsize);
+            ElementaryType::UFixed(size, fsize)
+        }
+        ty => ty,
+    }
+}
+
 /// `OnceMap::insert` but with `Copy` keys and values.
 fn cache_insert<K, V, S>(
     map: &once_map::OnceMap<K, V, S>,
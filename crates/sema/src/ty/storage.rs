@@ -0,0 +1,248 @@
+use super::{Gcx, Ty, TyKind};
+use crate::hir;
+use alloy_primitives::U256;
+use solar_ast::ast::{DataLocation, ElementaryType, Visibility};
+use solar_data_structures::BumpExt;
+use solar_interface::{diagnostics::ErrorGuaranteed, Span};
+
+/// The storage layout of a contract.
+///
+/// Return type of [`Gcx::storage_layout`].
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html>
+#[derive(Clone, Copy, Debug)]
+pub struct StorageLayout<'gcx> {
+    /// The state variables in persistent storage, including the inherited ones.
+    pub storage: &'gcx [StorageItem<'gcx>],
+    /// The state variables in transient storage, including the inherited ones.
+    ///
+    /// Transient storage is laid out separately from persistent storage, starting at slot 0.
+    pub transient: &'gcx [StorageItem<'gcx>],
+}
+
+/// The storage layout of a struct.
+///
+/// Return type of [`Gcx::struct_storage_layout`].
+#[derive(Clone, Copy, Debug)]
+pub struct StructStorageLayout<'gcx> {
+    /// The members of the struct. Their slots are relative to the first slot of the struct.
+    pub members: &'gcx [StorageItem<'gcx>],
+    /// The number of slots that the struct occupies.
+    pub slots: U256,
+}
+
+/// A variable at a fixed position in storage.
+#[derive(Clone, Copy, Debug)]
+pub struct StorageItem<'gcx> {
+    /// The state variable or struct member.
+    pub variable: hir::VariableId,
+    /// The type of the variable.
+    pub ty: Ty<'gcx>,
+    /// The slot where the variable starts.
+    pub slot: U256,
+    /// The offset in bytes of the variable in its slot, starting from the lower-order bytes.
+    ///
+    /// This is always 0 for variables that are not value types.
+    pub offset: u8,
+}
+
+/// The size of a type in storage.
+///
+/// Return type of [`Gcx::storage_size`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageSize {
+    /// The number of bytes that the type occupies in a slot. Values of types smaller than 32
+    /// bytes are packed together in the same slot.
+    ///
+    /// This is always 32 for types that are not value types.
+    pub bytes: u8,
+    /// The number of slots that the type occupies.
+    pub slots: U256,
+}
+
+impl StorageSize {
+    const SLOT: Self = Self { bytes: 32, slots: U256::from_limbs([1, 0, 0, 0]) };
+
+    fn value(bytes: u8) -> Self {
+        Self { bytes, slots: U256::from(1) }
+    }
+
+    fn slots(slots: U256) -> Self {
+        Self { bytes: 32, slots }
+    }
+}
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the size of the given type in storage.
+    ///
+    /// The number of slots saturates at `U256::MAX` for types that are too large for storage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type cannot be stored. Must not be called with structs that contain
+    /// themselves without a mapping or a dynamic array in between.
+    pub fn storage_size(self, ty: Ty<'gcx>) -> StorageSize {
+        match ty.kind {
+            TyKind::Elementary(ty) => match ty {
+                ElementaryType::Address(_) => StorageSize::value(20),
+                ElementaryType::Bool => StorageSize::value(1),
+                ElementaryType::String | ElementaryType::Bytes => StorageSize::SLOT,
+                ElementaryType::Fixed(size, _)
+                | ElementaryType::UFixed(size, _)
+                | ElementaryType::Int(size)
+                | ElementaryType::UInt(size)
+                | ElementaryType::FixedBytes(size) => StorageSize::value(size.bytes()),
+            },
+            TyKind::Contract(_) => StorageSize::value(20),
+            // Enums cannot have more than 256 variants.
+            TyKind::Enum(_) => StorageSize::value(1),
+            TyKind::Udvt(ty, _) => self.storage_size(ty),
+            TyKind::FnPtr(f) => match f.visibility {
+                // Address and selector.
+                Visibility::External => StorageSize::value(24),
+                _ => StorageSize::value(8),
+            },
+            TyKind::Ref(ty, _) => self.storage_size(ty),
+            TyKind::Mapping(..) | TyKind::DynArray(_) => StorageSize::SLOT,
+            TyKind::Array(elem, len) => {
+                let elem = self.storage_size(elem);
+                let per_slot = 32 / elem.bytes;
+                let slots = if per_slot > 1 {
+                    let per_slot = U256::from(per_slot);
+                    len.div_ceil(per_slot)
+                } else {
+                    len.saturating_mul(elem.slots)
+                };
+                StorageSize::slots(slots)
+            }
+            TyKind::Struct(id) => StorageSize::slots(self.struct_storage_layout(id).slots),
+            _ => panic!("type cannot be stored: {ty:?}"),
+        }
+    }
+}
+
+/// Computes the storage layout of the given contract.
+pub(super) fn contract_storage_layout(gcx: Gcx<'_>, id: hir::ContractId) -> StorageLayout<'_> {
+    let c = gcx.hir.contract(id);
+    let mut storage = Layouter::default();
+    let mut transient = Layouter::default();
+    // Variables of base contracts come first.
+    for &base in c.linearized_bases.iter().rev() {
+        for var_id in gcx.hir.contract(base).variables() {
+            let var = gcx.hir.variable(var_id);
+            if !var.is_state_variable() || var.mutability.is_some() {
+                continue;
+            }
+            let ty = gcx.type_of_item(var_id.into());
+            if ty.has_error() || check_finite_size(gcx, ty, var.span).is_err() {
+                continue;
+            }
+            let layouter = match var.data_location {
+                Some(DataLocation::Transient) => &mut transient,
+                _ => &mut storage,
+            };
+            if layouter.push(gcx, var_id, ty).is_none() {
+                let msg = "contract requires too much storage";
+                gcx.dcx().err(msg).span(c.name.span).span_note(var.span, "declared here").emit();
+                break;
+            }
+        }
+    }
+    StorageLayout {
+        storage: gcx.bump().alloc_vec(storage.items),
+        transient: gcx.bump().alloc_vec(transient.items),
+    }
+}
+
+/// Computes the storage layout of the given struct.
+pub(super) fn struct_storage_layout(gcx: Gcx<'_>, id: hir::StructId) -> StructStorageLayout<'_> {
+    let mut layouter = Layouter::default();
+    let mut slots = None;
+    for (ty, field) in gcx.item_fields(id) {
+        if layouter.push(gcx, field, ty).is_none() {
+            slots = Some(U256::MAX);
+            break;
+        }
+    }
+    // Empty structs are not allowed, but still occupy a slot.
+    let slots = slots.unwrap_or_else(|| layouter.finish()).max(U256::from(1));
+    StructStorageLayout { members: gcx.bump().alloc_vec(layouter.items), slots }
+}
+
+/// Assigns consecutive storage positions to variables.
+#[derive(Default)]
+struct Layouter<'gcx> {
+    items: Vec<StorageItem<'gcx>>,
+    slot: U256,
+    offset: u8,
+}
+
+impl<'gcx> Layouter<'gcx> {
+    /// Pushes a new variable. Returns `None` if the storage space overflows.
+    fn push(&mut self, gcx: Gcx<'gcx>, variable: hir::VariableId, ty: Ty<'gcx>) -> Option<()> {
+        let size = gcx.storage_size(ty);
+        // Values that do not fit in the rest of the current slot start a new one.
+        if self.offset as usize + size.bytes as usize > 32 {
+            self.slot = self.slot.checked_add(U256::from(1))?;
+            self.offset = 0;
+        }
+        self.items.push(StorageItem { variable, ty, slot: self.slot, offset: self.offset });
+        if size.slots == U256::from(1) && self.offset as usize + size.bytes as usize <= 32 {
+            self.offset += size.bytes;
+        } else {
+            self.slot = self.slot.checked_add(size.slots)?;
+            self.offset = 0;
+        }
+        Some(())
+    }
+
+    /// Returns the total number of slots used.
+    fn finish(&self) -> U256 {
+        if self.offset > 0 {
+            self.slot.saturating_add(U256::from(1))
+        } else {
+            self.slot
+        }
+    }
+}
+
+/// Checks that the type does not contain a struct that contains itself directly, which would have
+/// an infinite size.
+fn check_finite_size<'gcx>(
+    gcx: Gcx<'gcx>,
+    ty: Ty<'gcx>,
+    span: Span,
+) -> Result<(), ErrorGuaranteed> {
+    fn has_infinite_size<'gcx>(
+        gcx: Gcx<'gcx>,
+        ty: Ty<'gcx>,
+        stack: &mut Vec<hir::StructId>,
+    ) -> bool {
+        match ty.kind {
+            TyKind::Ref(ty, _) | TyKind::Array(ty, _) => has_infinite_size(gcx, ty, stack),
+            TyKind::Struct(id) => {
+                if stack.contains(&id) {
+                    return true;
+                }
+                stack.push(id);
+                let r =
+                    gcx.struct_field_types(id).iter().any(|&ty| has_infinite_size(gcx, ty, stack));
+                stack.pop();
+                r
+            }
+            _ => false,
+        }
+    }
+
+    // Only recursive types can have an infinite size.
+    if !ty.is_recursive() || !has_infinite_size(gcx, ty, &mut Vec::new()) {
+        return Ok(());
+    }
+    let msg = format!("type `{}` has an infinite size in storage", ty.peel_refs().display(gcx));
+    Err(gcx
+        .dcx()
+        .err(msg)
+        .span(span)
+        .note("structs can only contain themselves through a mapping or a dynamic array")
+        .emit())
+}
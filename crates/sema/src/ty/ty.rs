@@ -29,7 +29,6 @@ impl<'gcx> Ty<'gcx> {
         gcx.mk_ty(kind)
     }
 
-    // TODO: with_loc_if_ref ?
     pub fn with_loc(self, gcx: Gcx<'gcx>, loc: DataLocation) -> Self {
         let mut ty = self;
         if let TyKind::Ref(inner, l2) = self.kind {
@@ -41,6 +40,16 @@ impl<'gcx> Ty<'gcx> {
         Self::new(gcx, TyKind::Ref(ty, loc))
     }
 
+    /// Returns the type with the given data location if it is a reference type, otherwise returns
+    /// the type itself.
+    pub fn with_loc_if_ref(self, gcx: Gcx<'gcx>, loc: DataLocation) -> Self {
+        if self.is_reference_type() {
+            self.with_loc(gcx, loc)
+        } else {
+            self
+        }
+    }
+
     /// Peels `Ref` layers from the type, returning the inner type.
     pub fn peel_refs(self) -> Self {
         let mut ty = self;
@@ -50,13 +59,16 @@ impl<'gcx> Ty<'gcx> {
         ty
     }
 
+    /// Returns the type of the function when called externally, with external visibility and
+    /// `calldata` parameters converted to `memory`.
     pub fn as_externally_callable_function(self, gcx: Gcx<'gcx>) -> Self {
         let is_calldata = |param: &Ty<'_>| param.is_ref_at(DataLocation::Calldata);
         let parameters = self.parameters().unwrap_or_default();
         let returns = self.returns().unwrap_or_default();
         let any_parameter = parameters.iter().any(is_calldata);
         let any_return = returns.iter().any(is_calldata);
-        if !any_parameter && !any_return {
+        let is_external = !matches!(self.visibility(), Some(v) if v != Visibility::External);
+        if !any_parameter && !any_return && is_external {
             return self;
         }
         gcx.mk_ty_fn_ptr(TyFnPtr {
@@ -83,7 +95,7 @@ impl<'gcx> Ty<'gcx> {
                 returns
             },
             state_mutability: self.state_mutability().unwrap_or_default(),
-            visibility: self.visibility().unwrap_or(Visibility::Public),
+            visibility: Visibility::External,
         })
     }
 
@@ -132,6 +144,23 @@ impl<'gcx> Ty<'gcx> {
         }
     }
 
+    /// Returns `true` if the type is a reference type, meaning that it must be stored in a data
+    /// location.
+    ///
+    /// Reference: <https://docs.soliditylang.org/en/latest/types.html#reference-types>
+    #[inline]
+    pub fn is_reference_type(self) -> bool {
+        match self.kind {
+            TyKind::Elementary(t) => matches!(t, ElementaryType::String | ElementaryType::Bytes),
+            TyKind::Ref(..)
+            | TyKind::DynArray(_)
+            | TyKind::Array(..)
+            | TyKind::Struct(_)
+            | TyKind::Mapping(..) => true,
+            _ => false,
+        }
+    }
+
     /// Returns `true` if the type is recursive.
     pub fn is_recursive(self) -> bool {
         self.flags.contains(TyFlags::IS_RECURSIVE)
@@ -196,7 +225,8 @@ impl<'gcx> Ty<'gcx> {
         match self.kind {
             TyKind::Elementary(_)
             | TyKind::StringLiteral(..)
-            | TyKind::IntLiteral(_)
+            | TyKind::IntLiteral(..)
+            | TyKind::RationalLiteral(_)
             | TyKind::Contract(_)
             | TyKind::FnPtr(_)
             | TyKind::Enum(_)
@@ -256,8 +286,17 @@ pub enum TyKind<'gcx> {
     /// - only string literals with `len <= N` can coerce to `bytesN`
     StringLiteral(bool, TypeSize),
 
-    /// Any integer or fixed-point number literal. Contains `min(s.len(), 32)`.
-    IntLiteral(TypeSize),
+    /// Any integer literal. Contains `(is_negative, bits)`, where `bits` is the minimum number of
+    /// bits required to represent the absolute value of the literal, minus one if it is negative.
+    /// - non-negative literals can coerce to `uintN` if `bits <= N`
+    /// - all integer literals can coerce to `intN` if `bits < N`
+    IntLiteral(bool, u16),
+
+    /// Any rational number literal that is not an integer. Contains `is_negative`.
+    /// - rational literals cannot coerce to integer types
+    /// - all rational literals can coerce to `fixedMxN`
+    /// - non-negative rational literals can coerce to `ufixedMxN`
+    RationalLiteral(bool),
 
     /// A reference to another type which lives in the data location.
     Ref(Ty<'gcx>, DataLocation),
@@ -353,7 +392,8 @@ impl TyFlags {
         match *ty {
             TyKind::Elementary(_)
             | TyKind::StringLiteral(..)
-            | TyKind::IntLiteral(_)
+            | TyKind::IntLiteral(..)
+            | TyKind::RationalLiteral(_)
             | TyKind::Contract(_)
             | TyKind::FnPtr(_)
             | TyKind::Enum(_)
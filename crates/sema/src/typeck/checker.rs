@@ -0,0 +1,2296 @@
+//! Type checking of expressions and statements.
+
+use super::data_locations;
+use crate::{
+    builtins::Builtin,
+    eval::{ConstValue, ConstantEvaluator, EvalErrorKind, IntTy},
+    hir::{self, BinOpKind, ContractKind, Res, UnOpKind},
+    ty::{ConversionError, Gcx, Ty, TyKind},
+};
+use alloy_primitives::U256;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Pow, Signed};
+use solar_ast::ast::{
+    DataLocation, ElementaryType, LitKind, StateMutability, TypeSize, Visibility,
+};
+use solar_data_structures::{
+    map::FxHashMap,
+    smallvec::{smallvec, SmallVec},
+};
+use solar_interface::{
+    diagnostics::{DiagnosticBuilder, ErrorGuaranteed},
+    error_code, kw, pluralize, sym, Ident, Span, Symbol,
+};
+
+/// Type checks the body of the given function.
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if func.is_getter() {
+        return;
+    }
+    let mut checker = TypeChecker::new(gcx, func.source, func.contract, Some(id));
+    checker.check_modifiers(func);
+    let Some(body) = func.body else { return };
+    checker.check_block(body);
+}
+
+/// Type checks the base constructor arguments given in the inheritance specifiers of the given
+/// contract.
+pub(super) fn check_contract(gcx: Gcx<'_>, id: hir::ContractId) {
+    let contract = gcx.hir.contract(id);
+    if contract.bases_args.is_empty() {
+        return;
+    }
+    let mut checker = TypeChecker::new(gcx, contract.source, Some(id), None);
+    for base in contract.bases_args {
+        checker.check_modifier(base);
+    }
+}
+
+/// Type checks the initializer of the given state or file-level variable.
+pub(super) fn check_var(gcx: Gcx<'_>, id: hir::VariableId) {
+    let var = gcx.hir.variable(id);
+    let Some(init) = var.initializer else { return };
+    let ty = gcx.type_of_item(id.into());
+    let mut checker = TypeChecker::new(gcx, var.source, var.contract, None);
+    checker.check_expr(init);
+    checker.check_checked_expr_copyable(init, ty);
+}
+
+struct TypeChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The source being checked.
+    source: hir::SourceId,
+    /// The contract being checked, if any.
+    contract: Option<hir::ContractId>,
+    /// The function being checked, if any.
+    function: Option<hir::FunctionId>,
+}
+
+/// A possible callee, along with the resolution it came from.
+type Candidate<'gcx> = (Ty<'gcx>, Option<Res>);
+
+impl<'gcx> TypeChecker<'gcx> {
+    fn new(
+        gcx: Gcx<'gcx>,
+        source: hir::SourceId,
+        contract: Option<hir::ContractId>,
+        function: Option<hir::FunctionId>,
+    ) -> Self {
+        Self { gcx, source, contract, function }
+    }
+
+    fn check_block(&mut self, block: hir::Block<'_>) {
+        for stmt in block {
+            self.check_stmt(stmt);
+        }
+    }
+
+    fn check_stmt(&mut self, stmt: &hir::Stmt<'_>) {
+        match stmt.kind {
+            hir::StmtKind::DeclSingle(id) => {
+                data_locations::check_local_var(self.gcx, id);
+                let var = self.gcx.hir.variable(id);
+                if let Some(init) = var.initializer {
+                    let ty = self.gcx.type_of_item(id.into());
+                    self.check_expr_coercible(init, ty);
+                }
+            }
+            hir::StmtKind::DeclMulti(vars, expr) => {
+                for &var in vars.iter().flatten() {
+                    data_locations::check_local_var(self.gcx, var);
+                }
+                let ty = self.check_expr(expr);
+                let tys = tuple_components(&ty);
+                if tys.len() == vars.len() {
+                    for (&var, &from) in vars.iter().zip(tys) {
+                        if let Some(var) = var {
+                            let to = self.gcx.type_of_item(var.into());
+                            self.check_coercible(expr.span, from, to);
+                        }
+                    }
+                } else if !ty.has_error() {
+                    self.component_count_mismatch(stmt.span, vars.len(), tys.len());
+                }
+            }
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => self.check_block(block),
+            hir::StmtKind::Emit(event, ref args) => {
+                self.check_event_or_error_call(stmt.span, event, args, true)
+            }
+            hir::StmtKind::Revert(error, ref args) => {
+                self.check_event_or_error_call(stmt.span, error, args, false)
+            }
+            hir::StmtKind::Return(expr) => self.check_return(stmt.span, expr),
+            hir::StmtKind::If(cond, then, else_) => {
+                self.check_expr_coercible(cond, self.gcx.types.bool);
+                self.check_stmt(then);
+                if let Some(else_) = else_ {
+                    self.check_stmt(else_);
+                }
+            }
+            hir::StmtKind::Try(try_) => self.check_try(try_),
+            hir::StmtKind::Expr(expr) => {
+                self.check_expr(expr);
+            }
+            hir::StmtKind::Assembly(_)
+            | hir::StmtKind::Break
+            | hir::StmtKind::Continue
+            | hir::StmtKind::Placeholder
+            | hir::StmtKind::Err(_) => {}
+        }
+    }
+
+    /// Type checks the modifier invocations and base constructor calls of a function.
+    fn check_modifiers(&mut self, func: &hir::Function<'_>) {
+        let gcx = self.gcx;
+        let mut applied = FxHashMap::<hir::FunctionId, Span>::default();
+        for modifier in func.modifiers {
+            self.check_modifier(modifier);
+
+            let Some(id) = modifier.id.as_function() else { continue };
+            let id = func.contract.map_or(id, |c| gcx.hir.resolve_modifier(id, c));
+            if let Some(&prev) = applied.get(&id) {
+                let msg = format!("modifier `{}` is applied more than once", gcx.item_name(id));
+                gcx.dcx().err(msg).span(modifier.span).span_note(prev, "first applied here").emit();
+            } else {
+                applied.insert(id, modifier.span);
+            }
+        }
+    }
+
+    /// Type checks the arguments of a modifier invocation or base constructor call.
+    fn check_modifier(&mut self, modifier: &hir::Modifier<'_>) {
+        let gcx = self.gcx;
+        self.check_args_unknown(&modifier.args);
+        let (kind, callee) = match modifier.id {
+            hir::ItemId::Function(id) => ("modifier invocation", Some(id)),
+            hir::ItemId::Contract(id) => ("constructor call", gcx.hir.contract(id).ctor),
+            _ => unreachable!("invalid modifier: {:?}", modifier.id),
+        };
+        let params = match callee {
+            Some(id) => gcx.type_of_item(id.into()).parameters().unwrap_or_default(),
+            None => &[],
+        };
+        let res = callee.map(|id| Res::Item(id.into()));
+        self.check_args(kind, modifier.span, &modifier.args, params, res);
+    }
+
+    /// Type checks the target and the arguments of an `emit` or a `revert` statement.
+    fn check_event_or_error_call(
+        &mut self,
+        span: Span,
+        callee: &hir::Expr<'_>,
+        args: &hir::CallArgs<'_>,
+        is_emit: bool,
+    ) {
+        let gcx = self.gcx;
+        let hir::ExprKind::Ident(res) = callee.kind else { unreachable!() };
+        self.check_args_unknown(args);
+        let candidates = self
+            .candidates_of_res(res)
+            .into_iter()
+            .filter(|(ty, _)| match ty.kind {
+                TyKind::Event(..) => is_emit,
+                TyKind::Error(..) => !is_emit,
+                _ => false,
+            })
+            .collect::<SmallVec<[_; 4]>>();
+        let (ty, res) = match candidates[..] {
+            [candidate] => candidate,
+            [] => {
+                let msg = if is_emit {
+                    "expression has to be an event invocation"
+                } else {
+                    "expression has to be an error"
+                };
+                let mut diag = gcx.dcx().err(msg).span(callee.span);
+                if let [Res::Item(id)] = *res {
+                    let item = gcx.hir.item(id);
+                    if let Some(name) = item.name() {
+                        let note = format!("{} `{name}` is declared here", item.description());
+                        diag = diag.span_note(name.span, note);
+                    }
+                }
+                let guar = diag.emit();
+                self.record_callee(callee, gcx.mk_ty_err(guar), None);
+                return;
+            }
+            _ => match self.resolve_overload(callee.span, &candidates, args) {
+                Ok(candidate) => candidate,
+                Err(guar) => {
+                    self.record_callee(callee, gcx.mk_ty_err(guar), None);
+                    return;
+                }
+            },
+        };
+        self.record_callee(callee, ty, res);
+        let kind = if is_emit { "event invocation" } else { "error call" };
+        self.check_args(kind, span, args, ty.parameters().unwrap_or_default(), res);
+    }
+
+    fn check_try(&mut self, try_: &hir::StmtTry<'_>) {
+        let gcx = self.gcx;
+        for &var in try_.returns.iter().chain(try_.catch.iter().flat_map(|c| c.args)) {
+            data_locations::check_try_catch_param(gcx, var);
+        }
+
+        let ty = self.check_expr(&try_.expr);
+        if !ty.has_error() {
+            match self.try_call_returns(&try_.expr) {
+                Some(returns) => self.check_try_returns(&try_.expr, returns, try_.returns),
+                None => {
+                    let msg =
+                        "try can only be used with external function calls and contract creation calls";
+                    gcx.dcx().err(msg).code(error_code!(5347)).span(try_.expr.span).emit();
+                }
+            }
+        }
+        self.check_block(try_.block);
+
+        let mut error_clause = None;
+        let mut panic_clause = None;
+        let mut low_level_clause = None;
+        for catch in try_.catch {
+            self.check_catch_clause(
+                catch,
+                &mut error_clause,
+                &mut panic_clause,
+                &mut low_level_clause,
+            );
+            self.check_block(catch.block);
+        }
+    }
+
+    /// Returns the return types of the external call or contract creation in a `try` statement.
+    fn try_call_returns(&self, expr: &hir::Expr<'_>) -> Option<&'gcx [Ty<'gcx>]> {
+        let gcx = self.gcx;
+        let hir::ExprKind::Call(callee, _) = expr.peel_parens().kind else { return None };
+        let mut callee = callee.peel_parens();
+        if let hir::ExprKind::CallOptions(inner, _) = callee.kind {
+            callee = inner.peel_parens();
+        }
+        let TyKind::FnPtr(f) = gcx.type_of_expr(callee.id)?.kind else { return None };
+        match callee.kind {
+            hir::ExprKind::New(_) => {
+                matches!(f.returns, [ret] if matches!(ret.kind, TyKind::Contract(_)))
+                    .then_some(f.returns)
+            }
+            hir::ExprKind::Member(base, _) => {
+                let TyKind::Contract(id) = gcx.type_of_expr(base.id)?.peel_refs().kind else {
+                    return None;
+                };
+                let is_external = f.visibility == Visibility::External
+                    && gcx.hir.contract(id).kind != ContractKind::Library;
+                is_external.then_some(f.returns)
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks that the variables of the `returns` clause of a `try` statement match the return
+    /// types of the call.
+    fn check_try_returns(
+        &self,
+        expr: &hir::Expr<'_>,
+        returns: &[Ty<'gcx>],
+        vars: &[hir::VariableId],
+    ) {
+        let gcx = self.gcx;
+        if vars.is_empty() {
+            return;
+        }
+        if returns.len() != vars.len() {
+            let (n_returns, n_vars) = (returns.len(), vars.len());
+            let msg = format!(
+                "function returns {n_returns} value{}, but the `returns` clause has {n_vars} variable{}",
+                pluralize!(n_returns),
+                pluralize!(n_vars),
+            );
+            let note = format!("the call returns {}", return_ty(gcx, returns).display(gcx));
+            let span = vars.iter().map(|&var| gcx.item_span(var)).reduce(Span::to).unwrap();
+            gcx.dcx().err(msg).span(span).span_note(expr.span, note).emit();
+            return;
+        }
+        for (&ret, &var) in returns.iter().zip(vars) {
+            let var_ty = gcx.type_of_item(var.into());
+            // Invalid data locations have already been reported.
+            let invalid =
+                |ty: Ty<'_>| ty.has_error() || (ty.is_ref() && !ty.is_ref_at(DataLocation::Memory));
+            if invalid(var_ty) || invalid(ret) || var_ty == ret {
+                continue;
+            }
+            let msg = format!(
+                "invalid type in the `returns` clause: expected `{}`, found `{}`",
+                ret.display(gcx),
+                var_ty.display(gcx)
+            );
+            gcx.dcx().err(msg).code(error_code!(6509)).span(gcx.item_span(var)).emit();
+        }
+    }
+
+    /// Checks that a catch clause has one of the allowed forms, and that each form is used at
+    /// most once in a `try` statement.
+    fn check_catch_clause(
+        &self,
+        catch: &hir::CatchClause<'_>,
+        error_clause: &mut Option<Span>,
+        panic_clause: &mut Option<Span>,
+        low_level_clause: &mut Option<Span>,
+    ) {
+        let gcx = self.gcx;
+        let param_is = |expected: ElementaryType| match catch.args {
+            [param] => {
+                let ty = gcx.type_of_item((*param).into());
+                ty.has_error()
+                    || matches!(ty.peel_refs().kind, TyKind::Elementary(t) if t == expected)
+            }
+            _ => false,
+        };
+        let (prev, kind, valid, expected) = match catch.name {
+            None => (
+                low_level_clause,
+                "a low-level",
+                catch.args.is_empty() || param_is(ElementaryType::Bytes),
+                "`catch (bytes memory ...) { ... }` or `catch { ... }`",
+            ),
+            Some(name) if name.name == sym::Error => (
+                error_clause,
+                "an `Error`",
+                param_is(ElementaryType::String),
+                "`catch Error(string memory ...) { ... }`",
+            ),
+            Some(name) if name.name == sym::Panic => (
+                panic_clause,
+                "a `Panic`",
+                param_is(ElementaryType::UInt(TypeSize::new_int_bits(256))),
+                "`catch Panic(uint256 ...) { ... }`",
+            ),
+            Some(name) => {
+                let msg = format!("invalid catch clause name `{name}`");
+                gcx.dcx()
+                    .err(msg)
+                    .code(error_code!(3542))
+                    .span(name.span)
+                    .help("expected `Error`, `Panic` or no name for a low-level catch clause")
+                    .emit();
+                return;
+            }
+        };
+        if !valid {
+            gcx.dcx().err(format!("expected {expected}")).span(catch.span).emit();
+        }
+        if let Some(prev) = *prev {
+            let msg = format!("this `try` statement already has {kind} catch clause");
+            gcx.dcx().err(msg).span(catch.span).span_note(prev, "previous clause here").emit();
+        } else {
+            *prev = Some(catch.span);
+        }
+    }
+
+    fn check_return(&mut self, span: Span, expr: Option<&hir::Expr<'_>>) {
+        let Some(expr) = expr else { return };
+        let returns = match self.function {
+            Some(id) => self.gcx.type_of_item(id.into()).returns().unwrap_or_default(),
+            None => &[],
+        };
+        match returns {
+            [] => {
+                self.check_expr(expr);
+                self.return_count_mismatch(span);
+            }
+            &[ret] => {
+                if let hir::ExprKind::Tuple(exprs) = expr.peel_parens().kind {
+                    if exprs.len() != 1 {
+                        self.check_expr(expr);
+                        self.return_count_mismatch(span);
+                        return;
+                    }
+                }
+                self.check_expr_coercible(expr, ret);
+            }
+            returns => {
+                let ty = self.check_expr(expr);
+                if ty.has_error() {
+                    return;
+                }
+                let tys = tuple_components(&ty);
+                if tys.len() != returns.len() {
+                    self.return_count_mismatch(span);
+                    return;
+                }
+                match expr.peel_parens().kind {
+                    hir::ExprKind::Tuple(exprs) if exprs.len() == returns.len() => {
+                        for (expr, (&from, &to)) in exprs.iter().zip(tys.iter().zip(returns)) {
+                            let span = expr.map(|e| e.span).unwrap_or(span);
+                            self.check_coercible(span, from, to);
+                        }
+                    }
+                    _ => {
+                        for (&from, &to) in tys.iter().zip(returns) {
+                            self.check_coercible(expr.span, from, to);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn return_count_mismatch(&self, span: Span) {
+        let msg = "different number of arguments in return statement than in returns declaration";
+        self.gcx.dcx().err(msg).span(span).emit();
+    }
+
+    /// Type checks the expression and checks that its type is implicitly convertible to `expected`.
+    fn check_expr_coercible(&mut self, expr: &hir::Expr<'_>, expected: Ty<'gcx>) -> Ty<'gcx> {
+        let ty = self.check_expr(expr);
+        self.check_checked_expr_coercible(expr, expected);
+        ty
+    }
+
+    /// Checks that the type of the already checked expression is implicitly convertible to
+    /// `expected`.
+    fn check_checked_expr_coercible(&self, expr: &hir::Expr<'_>, expected: Ty<'gcx>) {
+        if !self.is_hex_literal_of_size(expr, expected)
+            && self.check_coercible(expr.span, self.checked_type(expr), expected)
+        {
+            self.check_fixed_point_literal(expr, expected);
+        }
+    }
+
+    /// Checks that the already checked expression can be assigned to a non-pointer location of
+    /// type `expected`. See [`check_copyable`](Self::check_copyable).
+    fn check_checked_expr_copyable(&self, expr: &hir::Expr<'_>, expected: Ty<'gcx>) {
+        if !self.is_hex_literal_of_size(expr, expected)
+            && self.check_copyable(expr.span, self.checked_type(expr), expected)
+        {
+            self.check_fixed_point_literal(expr, expected);
+        }
+    }
+
+    /// Checks that the value of a number literal expression, whose type is implicitly convertible
+    /// to `to`, is in the range and has at most the number of fractional digits of `to` if it is a
+    /// fixed point type.
+    fn check_fixed_point_literal(&self, expr: &hir::Expr<'_>, to: Ty<'gcx>) {
+        let (signed, size, digits) = match to.kind {
+            TyKind::Elementary(ElementaryType::Fixed(size, digits)) => (true, size, digits),
+            TyKind::Elementary(ElementaryType::UFixed(size, digits)) => (false, size, digits),
+            _ => return,
+        };
+        let from = self.checked_type(expr);
+        if !is_number_literal(from) {
+            return;
+        }
+        let Ok(ConstValue::Rational(value)) = ConstantEvaluator::new(self.gcx).try_eval(expr)
+        else {
+            return;
+        };
+        let scaled =
+            value * BigRational::from_integer(BigInt::from(10u8).pow(u32::from(digits.get())));
+        let err = if !scaled.is_integer() {
+            ConversionError::FractionalDigits
+        } else if !IntTy::new(signed, size.bits()).fits(&scaled.to_integer()) {
+            ConversionError::LiteralOutOfRange
+        } else {
+            return;
+        };
+        self.report_not_coercible(expr.span, from, to, err);
+    }
+
+    /// Returns the type of the already checked expression.
+    fn checked_type(&self, expr: &hir::Expr<'_>) -> Ty<'gcx> {
+        self.gcx.type_of_expr(expr.id).expect("expression has not been type checked")
+    }
+
+    /// Checks that `from` is implicitly convertible to `to`, emitting an error if it is not.
+    fn check_coercible(&self, span: Span, from: Ty<'gcx>, to: Ty<'gcx>) -> bool {
+        let Err(err) = from.is_implicitly_convertible_to(self.gcx, to) else { return true };
+        self.report_not_coercible(span, from, to, err)
+    }
+
+    /// Checks that `from` can be assigned to `to`, where `to` is not a storage pointer.
+    ///
+    /// Unlike [`check_coercible`](Self::check_coercible), this allows copying memory and calldata
+    /// values to storage.
+    fn check_copyable(&self, span: Span, from: Ty<'gcx>, to: Ty<'gcx>) -> bool {
+        let copied = match from.kind {
+            TyKind::Ref(inner, DataLocation::Memory | DataLocation::Calldata)
+                if to.is_ref_at(DataLocation::Storage) =>
+            {
+                inner.with_loc(self.gcx, DataLocation::Storage)
+            }
+            _ => from,
+        };
+        let Err(err) = copied.is_implicitly_convertible_to(self.gcx, to) else { return true };
+        self.report_not_coercible(span, from, to, err)
+    }
+
+    fn report_not_coercible(
+        &self,
+        span: Span,
+        from: Ty<'gcx>,
+        to: Ty<'gcx>,
+        err: ConversionError,
+    ) -> bool {
+        let msg = format!(
+            "type `{}` is not implicitly convertible to expected type `{}`",
+            from.display(self.gcx),
+            to.display(self.gcx),
+        );
+        let mut diag = self.gcx.dcx().err(msg).span(span);
+        if let Some(note) = err.note() {
+            diag = diag.note(note);
+        }
+        diag.emit();
+        false
+    }
+
+    /// Type checks the expression, recording its type.
+    fn check_expr(&mut self, expr: &hir::Expr<'_>) -> Ty<'gcx> {
+        let ty = self.check_expr_kind(expr);
+        self.gcx.set_type_of_expr(expr.id, ty)
+    }
+
+    fn check_expr_kind(&mut self, expr: &hir::Expr<'_>) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        match expr.kind {
+            hir::ExprKind::Array(exprs) => {
+                let tys = exprs.iter().map(|e| self.check_expr(e)).collect::<SmallVec<[_; 8]>>();
+                let Some(&first) = tys.first() else {
+                    let msg = "unable to deduce common type for empty array";
+                    return gcx.mk_ty_err(gcx.dcx().err(msg).span(expr.span).emit());
+                };
+                let mut common = self.mobile_type(first);
+                for &ty in &tys[1..] {
+                    let Some(ty) = self.common_type(common, ty) else {
+                        let msg = "unable to deduce common type for array elements";
+                        return gcx.mk_ty_err(gcx.dcx().err(msg).span(expr.span).emit());
+                    };
+                    common = ty;
+                }
+                if common.has_error() {
+                    return common;
+                }
+                let array = gcx.mk_ty(TyKind::Array(common, U256::from(exprs.len())));
+                array.with_loc(gcx, DataLocation::Memory)
+            }
+            hir::ExprKind::Assign(lhs, op, rhs) => {
+                if let (hir::ExprKind::Tuple(lhs_exprs), None) = (&lhs.peel_parens().kind, op) {
+                    if lhs_exprs.len() > 1 {
+                        return self.check_tuple_assign(lhs, lhs_exprs, rhs);
+                    }
+                }
+                let lhs_ty = self.check_expr(lhs);
+                if !self.check_lvalue(lhs, false) {
+                    // The right-hand side cannot be checked against a type that is not assignable.
+                    self.check_expr(rhs);
+                    return lhs_ty;
+                }
+                match op {
+                    Some(op) => {
+                        let rhs_ty = self.check_expr(rhs);
+                        let result = self.check_binop(lhs, lhs_ty, op, rhs, rhs_ty, true);
+                        self.check_coercible(expr.span, result, lhs_ty);
+                    }
+                    None if self.is_storage_pointer(lhs) => {
+                        self.check_expr_coercible(rhs, lhs_ty);
+                    }
+                    None => {
+                        self.check_expr(rhs);
+                        self.check_checked_expr_copyable(rhs, lhs_ty);
+                    }
+                }
+                lhs_ty
+            }
+            hir::ExprKind::Binary(lhs, op, rhs) => {
+                let lhs_ty = self.check_expr(lhs);
+                let rhs_ty = self.check_expr(rhs);
+                if is_number_literal(lhs_ty) && is_number_literal(rhs_ty) {
+                    if let Some(ty) = self.check_literal_binop(expr, op) {
+                        return ty;
+                    }
+                }
+                if let Some(ty) = self.check_user_defined_binop(expr, lhs_ty, op, rhs) {
+                    return ty;
+                }
+                self.check_binop(lhs, lhs_ty, op, rhs, rhs_ty, false)
+            }
+            hir::ExprKind::Call(callee, ref args) => self.check_call(expr, callee, args),
+            hir::ExprKind::CallOptions(callee, options) => {
+                let ty = self.check_expr(callee);
+                self.check_call_options(options);
+                ty
+            }
+            hir::ExprKind::Delete(inner) => {
+                let ty = self.check_expr(inner);
+                self.check_lvalue(inner, false);
+                if matches!(ty.peel_refs().kind, TyKind::Mapping(..)) {
+                    let msg = format!(
+                        "built-in unary operator `delete` cannot be applied to type `{}`",
+                        ty.peel_refs().display(gcx)
+                    );
+                    let note = "mappings can only be deleted one value at a time";
+                    gcx.dcx().err(msg).code(error_code!(9767)).span(expr.span).note(note).emit();
+                }
+                gcx.types.unit
+            }
+            hir::ExprKind::Ident([Res::Builtin(Builtin::Super)]) => {
+                let msg = "`super` can only be used to access members of the base contracts";
+                gcx.mk_ty_err(gcx.dcx().err(msg).span(expr.span).emit())
+            }
+            hir::ExprKind::Ident(res) => match self.candidates_of_res(res)[..] {
+                [(ty, res)] => {
+                    if let Some(res) = res {
+                        gcx.set_res_of_expr(expr.id, res);
+                    }
+                    ty
+                }
+                ref candidates => {
+                    let msg = "no matching declaration found after variable lookup";
+                    let mut diag = gcx.dcx().err(msg).span(expr.span);
+                    for &(_, res) in candidates {
+                        if let Some(Res::Item(id)) = res {
+                            diag = diag.span_note(gcx.item_span(id), "candidate");
+                        }
+                    }
+                    gcx.mk_ty_err(diag.emit())
+                }
+            },
+            hir::ExprKind::Index(base, index) => self.check_index(expr, base, index),
+            hir::ExprKind::Slice(base, start, end) => {
+                let ty = self.check_expr(base);
+                for index in [start, end].into_iter().flatten() {
+                    self.check_expr_coercible(index, gcx.types.uint(256));
+                }
+                if ty.has_error() {
+                    return ty;
+                }
+                match ty.kind {
+                    TyKind::Ref(inner, DataLocation::Calldata)
+                        if matches!(
+                            inner.kind,
+                            TyKind::DynArray(_) | TyKind::Elementary(ElementaryType::Bytes)
+                        ) =>
+                    {
+                        ty
+                    }
+                    _ => {
+                        let msg =
+                            "index range access is only supported for dynamic calldata arrays";
+                        gcx.mk_ty_err(gcx.dcx().err(msg).span(expr.span).emit())
+                    }
+                }
+            }
+            hir::ExprKind::Lit(lit) => self.check_lit(lit),
+            hir::ExprKind::Member(base, ident) => match self.member_candidates(base, ident)[..] {
+                [(ty, res)] => {
+                    if let Some(res) = res {
+                        gcx.set_res_of_expr(expr.id, res);
+                    }
+                    ty
+                }
+                [] => self.unknown(),
+                ref candidates => {
+                    let base = match base.kind {
+                        hir::ExprKind::Ident([Res::Builtin(Builtin::Super)]) => "super".into(),
+                        _ => self.checked_type(base).display(gcx).to_string(),
+                    };
+                    let msg = format!(
+                        "member `{ident}` not unique after argument-dependent lookup in `{base}`"
+                    );
+                    let mut diag = gcx.dcx().err(msg).code(error_code!(6675)).span(ident.span);
+                    for &(_, res) in candidates {
+                        if let Some(Res::Item(id)) = res {
+                            diag = diag.span_note(gcx.item_span(id), "candidate");
+                        }
+                    }
+                    gcx.mk_ty_err(diag.emit())
+                }
+            },
+            hir::ExprKind::New(ref ty) => self.check_new(ty),
+            hir::ExprKind::Payable(inner) => {
+                self.check_expr(inner);
+                gcx.types.address_payable
+            }
+            hir::ExprKind::Ternary(cond, true_, false_) => {
+                self.check_expr_coercible(cond, gcx.types.bool);
+                let true_ty = self.check_expr(true_);
+                let false_ty = self.check_expr(false_);
+                let true_ty = self.mobile_type(true_ty);
+                let false_ty = self.mobile_type(false_ty);
+                match self.common_type(true_ty, false_ty) {
+                    Some(ty) => ty,
+                    None => {
+                        let msg = format!(
+                            "true expression's type `{}` does not match false expression's type `{}`",
+                            true_ty.display(gcx),
+                            false_ty.display(gcx),
+                        );
+                        gcx.mk_ty_err(gcx.dcx().err(msg).span(expr.span).emit())
+                    }
+                }
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                if let [Some(expr)] = exprs {
+                    return self.check_expr(expr);
+                }
+                let tys = exprs
+                    .iter()
+                    .map(|e| match e {
+                        Some(e) => self.check_expr(e),
+                        None => self.unknown(),
+                    })
+                    .collect::<SmallVec<[_; 8]>>();
+                gcx.mk_ty(TyKind::Tuple(gcx.mk_tys(&tys)))
+            }
+            hir::ExprKind::TypeCall(ref ty) => gcx.type_of_hir_ty(ty).make_meta(gcx),
+            hir::ExprKind::Type(ref ty) => gcx.type_of_hir_ty(ty).make_type_type(gcx),
+            hir::ExprKind::Unary(op, inner) => {
+                let ty = self.check_expr(inner);
+                if matches!(
+                    op.kind,
+                    UnOpKind::PreInc | UnOpKind::PreDec | UnOpKind::PostInc | UnOpKind::PostDec
+                ) {
+                    self.check_lvalue(inner, false);
+                }
+                if let Some(ty) = self.check_user_defined_unop(expr, op, ty) {
+                    return ty;
+                }
+                self.check_unop(expr, op, ty)
+            }
+            hir::ExprKind::Err(guar) => gcx.mk_ty_err(guar),
+        }
+    }
+
+    fn check_tuple_assign(
+        &mut self,
+        lhs: &hir::Expr<'_>,
+        lhs_exprs: &[Option<&hir::Expr<'_>>],
+        rhs: &hir::Expr<'_>,
+    ) -> Ty<'gcx> {
+        let lhs_tys = lhs_exprs
+            .iter()
+            .map(|e| match e {
+                Some(e) => self.check_expr(e),
+                None => self.unknown(),
+            })
+            .collect::<SmallVec<[_; 8]>>();
+        let lhs_ty = self.gcx.mk_ty(TyKind::Tuple(self.gcx.mk_tys(&lhs_tys)));
+        self.gcx.set_type_of_expr(lhs.id, lhs_ty);
+        let is_lvalue = self.check_lvalue(lhs, true);
+
+        let rhs_ty = self.check_expr(rhs);
+        let rhs_tys = tuple_components(&rhs_ty);
+        if rhs_tys.len() != lhs_tys.len() {
+            if !rhs_ty.has_error() {
+                self.component_count_mismatch(lhs.span.to(rhs.span), lhs_tys.len(), rhs_tys.len());
+            }
+        } else if is_lvalue {
+            for (e, (&from, &to)) in lhs_exprs.iter().zip(rhs_tys.iter().zip(&lhs_tys)) {
+                match e {
+                    Some(e) if self.is_storage_pointer(e) => {
+                        self.check_coercible(rhs.span, from, to);
+                    }
+                    Some(_) => {
+                        self.check_copyable(rhs.span, from, to);
+                    }
+                    None => {}
+                }
+            }
+        }
+        lhs_ty
+    }
+
+    fn component_count_mismatch(&self, span: Span, lhs: usize, rhs: usize) {
+        let msg = format!(
+            "different number of components on the left hand side ({lhs}) \
+             than on the right hand side ({rhs})"
+        );
+        self.gcx.dcx().err(msg).code(error_code!(7364)).span(span).emit();
+    }
+
+    /// Checks that the expression can be assigned to, deleted, incremented or decremented.
+    ///
+    /// Tuples are only allowed on the left-hand side of assignments. Returns `false` if the
+    /// expression, or one of the components of the tuple, is not an lvalue.
+    fn check_lvalue(&mut self, expr: &hir::Expr<'_>, allow_tuple: bool) -> bool {
+        let gcx = self.gcx;
+        let expr = expr.peel_parens();
+        if gcx.type_of_expr(expr.id).is_some_and(|ty| ty.has_error()) {
+            return true;
+        }
+        let base_ty = |base: &hir::Expr<'_>| gcx.type_of_expr(base.id).unwrap_or(gcx.types.unit);
+        match expr.kind {
+            hir::ExprKind::Tuple(exprs) if allow_tuple => {
+                let mut is_lvalue = true;
+                for expr in exprs.iter().flatten() {
+                    is_lvalue &= self.check_lvalue(expr, true);
+                }
+                return is_lvalue;
+            }
+            hir::ExprKind::Ident(_) => match gcx.res_of_expr(expr.id) {
+                Some(Res::Item(hir::ItemId::Variable(id))) => self.check_assignable_var(expr, id),
+                Some(Res::Item(id)) => {
+                    let name = gcx.item_name(id);
+                    let note =
+                        format!("{} `{name}` is declared here", gcx.hir.item(id).description());
+                    self.not_lvalue(expr).span_note(name.span, note).emit();
+                    return false;
+                }
+                _ => {
+                    self.not_lvalue(expr).emit();
+                    return false;
+                }
+            },
+            hir::ExprKind::Index(base, _) => match base_ty(base).kind {
+                TyKind::Ref(_, DataLocation::Calldata) => {
+                    self.read_only_calldata(expr, base, "calldata arrays are read-only");
+                }
+                TyKind::Elementary(ElementaryType::FixedBytes(_)) => {
+                    let msg = "single bytes in fixed bytes arrays cannot be modified";
+                    gcx.dcx().err(msg).code(error_code!(4360)).span(expr.span).emit();
+                }
+                _ => {}
+            },
+            hir::ExprKind::Member(base, member) => match base_ty(base).kind {
+                TyKind::Ref(inner, loc) if matches!(inner.kind, TyKind::Struct(_)) => {
+                    if loc == DataLocation::Calldata {
+                        self.read_only_calldata(expr, base, "calldata structs are read-only");
+                    }
+                }
+                TyKind::Ref(..) if member.name == sym::length => {
+                    let msg = "member `length` is read-only and cannot be used to resize arrays";
+                    gcx.dcx().err(msg).code(error_code!(7567)).span(expr.span).emit();
+                }
+                _ => {
+                    self.not_lvalue(expr).emit();
+                    return false;
+                }
+            },
+            // `array.push()` returns a reference to the new element.
+            hir::ExprKind::Call(callee, hir::CallArgs::Unnamed([]))
+                if matches!(
+                    callee.peel_parens().kind,
+                    hir::ExprKind::Member(_, member) if member.name == sym::push
+                ) => {}
+            hir::ExprKind::Err(_) => {}
+            _ => {
+                self.not_lvalue(expr).emit();
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Checks that the variable that the expression resolves to can be assigned to.
+    fn check_assignable_var(&mut self, expr: &hir::Expr<'_>, id: hir::VariableId) {
+        let gcx = self.gcx;
+        let var = gcx.hir.variable(id);
+        let Some(mutability) = var.mutability else { return };
+        let name = var.name.map_or(kw::Empty, |name| name.name);
+        let note = format!("`{name}` is declared as `{mutability}` here");
+        match mutability {
+            hir::VarMut::Constant => {
+                let msg = "cannot assign to a constant variable";
+                gcx.dcx()
+                    .err(msg)
+                    .code(error_code!(6520))
+                    .span(expr.span)
+                    .span_note(var.span, note)
+                    .emit();
+            }
+            hir::VarMut::Immutable => {
+                let in_constructor = self.function.is_some_and(|f| {
+                    let func = gcx.hir.function(f);
+                    func.kind.is_constructor() && func.contract == var.contract
+                });
+                if !in_constructor {
+                    let msg = "cannot write to immutable variable here";
+                    let help = "immutable variables can only be assigned in the constructor of \
+                                the contract that defines them";
+                    gcx.dcx()
+                        .err(msg)
+                        .code(error_code!(1581))
+                        .span(expr.span)
+                        .span_note(var.span, note)
+                        .help(help)
+                        .emit();
+                }
+            }
+        }
+    }
+
+    /// Reports an assignment to a calldata array or struct.
+    fn read_only_calldata(&self, expr: &hir::Expr<'_>, base: &hir::Expr<'_>, msg: &'static str) {
+        let mut diag = self.gcx.dcx().err(msg).code(error_code!(6182)).span(expr.span);
+        if let Some(id) = self.root_variable(base) {
+            let var = self.gcx.hir.variable(id);
+            let name = var.name.map_or(kw::Empty, |name| name.name);
+            diag = diag.span_note(var.span, format!("`{name}` is declared in `calldata` here"));
+        }
+        diag.emit();
+    }
+
+    fn not_lvalue(&self, expr: &hir::Expr<'_>) -> DiagnosticBuilder<'_, ErrorGuaranteed> {
+        self.gcx.dcx().err("expression has to be an lvalue").code(error_code!(4247)).span(expr.span)
+    }
+
+    /// Returns the variable at the root of a chain of index and member accesses.
+    fn root_variable(&self, expr: &hir::Expr<'_>) -> Option<hir::VariableId> {
+        let mut expr = expr.peel_parens();
+        loop {
+            match expr.kind {
+                hir::ExprKind::Index(base, _)
+                | hir::ExprKind::Slice(base, ..)
+                | hir::ExprKind::Member(base, _) => expr = base.peel_parens(),
+                hir::ExprKind::Ident(_) => {
+                    let Some(Res::Item(hir::ItemId::Variable(id))) = self.gcx.res_of_expr(expr.id)
+                    else {
+                        return None;
+                    };
+                    return Some(id);
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn check_lit(&mut self, lit: &hir::Lit) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        match lit.kind {
+            LitKind::Str(_, ref s) => gcx.mk_ty_string_literal(s),
+            LitKind::Number(ref n) => {
+                gcx.mk_ty_int_literal(false, n.bits().try_into().unwrap_or(u16::MAX))
+            }
+            LitKind::Rational(ref r) => gcx.mk_ty_rational_literal(r.is_negative()),
+            LitKind::Address(_) => gcx.types.address,
+            LitKind::Bool(_) => gcx.types.bool,
+            LitKind::Err(guar) => gcx.mk_ty_err(guar),
+        }
+    }
+
+    fn check_new(&mut self, hir_ty: &hir::Type<'_>) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        let ty = gcx.type_of_hir_ty(hir_ty);
+        match ty.kind {
+            TyKind::Contract(id) => {
+                let contract = gcx.hir.contract(id);
+                let msg = match contract.kind {
+                    ContractKind::Contract => None,
+                    ContractKind::AbstractContract => {
+                        Some("cannot instantiate an abstract contract")
+                    }
+                    ContractKind::Interface => Some("cannot instantiate an interface"),
+                    ContractKind::Library => Some("cannot instantiate a library"),
+                };
+                if let Some(msg) = msg {
+                    let note = format!("`{}` is defined here", contract.name);
+                    let guar = gcx
+                        .dcx()
+                        .err(msg)
+                        .span(hir_ty.span)
+                        .span_note(contract.name.span, note)
+                        .emit();
+                    return gcx.mk_ty_err(guar);
+                }
+                let (parameters, state_mutability) = match contract.ctor {
+                    Some(ctor) => {
+                        let ctor_ty = gcx.type_of_item(ctor.into());
+                        (
+                            ctor_ty.parameters().unwrap_or_default(),
+                            ctor_ty.state_mutability().unwrap_or_default(),
+                        )
+                    }
+                    None => (&[][..], StateMutability::NonPayable),
+                };
+                gcx.mk_builtin_fn(parameters, state_mutability, &[ty])
+            }
+            TyKind::DynArray(_)
+            | TyKind::Elementary(ElementaryType::String | ElementaryType::Bytes) => {
+                let ret = ty.with_loc(gcx, DataLocation::Memory);
+                gcx.mk_builtin_fn(&[gcx.types.uint(256)], StateMutability::Pure, &[ret])
+            }
+            TyKind::Err(_) => ty,
+            _ => {
+                let msg = "contract or array type expected";
+                gcx.mk_ty_err(gcx.dcx().err(msg).span(hir_ty.span).emit())
+            }
+        }
+    }
+
+    fn check_call(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        callee: &hir::Expr<'_>,
+        args: &hir::CallArgs<'_>,
+    ) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        let candidates = self.callee_candidates(callee);
+        // The argument types are needed to resolve overloads, so they are checked first.
+        self.check_args_unknown(args);
+        let (callee_ty, res) = match candidates[..] {
+            [candidate] => candidate,
+            _ => match self.resolve_overload(callee.span, &candidates, args) {
+                Ok(candidate) => candidate,
+                Err(guar) => return gcx.mk_ty_err(guar),
+            },
+        };
+        self.record_callee(callee, callee_ty, res);
+
+        if callee_ty.has_error() {
+            return callee_ty;
+        }
+
+        // Builtins that accept a variable number of arguments.
+        if let Some(Res::Builtin(builtin)) = res {
+            if is_variadic(builtin) {
+                return self.check_variadic_call(expr, builtin, callee_ty, args);
+            }
+        }
+
+        match callee_ty.kind {
+            TyKind::FnPtr(f) => {
+                self.check_args("function call", expr.span, args, f.parameters, res);
+                return_ty(gcx, f.returns)
+            }
+            TyKind::Type(to) => match to.kind {
+                TyKind::Struct(id) => {
+                    let params = gcx.mk_ty_iter(
+                        gcx.struct_field_types(id)
+                            .iter()
+                            .map(|ty| ty.with_loc_if_ref(gcx, DataLocation::Memory)),
+                    );
+                    self.check_args("function call", expr.span, args, params, res);
+                    to.with_loc(gcx, DataLocation::Memory)
+                }
+                _ => self.check_explicit_conversion(expr, args, to),
+            },
+            TyKind::Event(..) | TyKind::Error(..) => callee_ty,
+            _ => {
+                let msg = format!("type `{}` is not callable", callee_ty.display(gcx));
+                gcx.mk_ty_err(gcx.dcx().err(msg).span(callee.span).emit())
+            }
+        }
+    }
+
+    /// Selects the candidate whose parameters match the already checked arguments.
+    fn resolve_overload(
+        &self,
+        span: Span,
+        candidates: &[Candidate<'gcx>],
+        args: &hir::CallArgs<'_>,
+    ) -> Result<Candidate<'gcx>, ErrorGuaranteed> {
+        let gcx = self.gcx;
+        if args_exprs(args).any(|arg| self.checked_type(arg).has_error()) {
+            return Err(ErrorGuaranteed::new_unchecked());
+        }
+        let matching = candidates
+            .iter()
+            .copied()
+            .filter(|&candidate| self.is_viable_candidate(candidate, args))
+            .collect::<SmallVec<[_; 4]>>();
+        let (msg, notes) = match matching[..] {
+            [candidate] => return Ok(candidate),
+            [] => ("no matching declaration found after argument-dependent lookup", candidates),
+            _ => ("no unique declaration found after argument-dependent lookup", &matching[..]),
+        };
+        let mut diag = gcx.dcx().err(msg).span(span);
+        for &(_, res) in notes {
+            if let Some(Res::Item(id)) = res {
+                diag = diag.span_note(gcx.item_span(id), "candidate");
+            }
+        }
+        Err(diag.emit())
+    }
+
+    /// Returns `true` if the already checked arguments can be passed to the given candidate.
+    fn is_viable_candidate(&self, (ty, res): Candidate<'gcx>, args: &hir::CallArgs<'_>) -> bool {
+        let Some(params) = self.callable_parameters(ty, res) else { return false };
+        if args.len() != params.len() {
+            return false;
+        }
+        let Some(indices) = self.arg_param_indices(args, params.len(), res) else { return false };
+        args_exprs(args).zip(indices).all(|(arg, index)| {
+            index.is_some_and(|i| {
+                self.is_hex_literal_of_size(arg, params[i])
+                    || self.is_implicitly_convertible(self.checked_type(arg), params[i])
+            })
+        })
+    }
+
+    /// Records the type and the resolution of the callee of a call expression.
+    fn record_callee(&self, callee: &hir::Expr<'_>, ty: Ty<'gcx>, res: Option<Res>) {
+        self.gcx.set_type_of_expr(callee.id, ty);
+        if let Some(res) = res {
+            self.gcx.set_res_of_expr(callee.id, res);
+        }
+        if let hir::ExprKind::CallOptions(inner, _) = callee.kind {
+            self.record_callee(inner, ty, res);
+        }
+    }
+
+    fn check_explicit_conversion(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        args: &hir::CallArgs<'_>,
+        to: Ty<'gcx>,
+    ) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        let hir::CallArgs::Unnamed([arg]) = args else {
+            let msg = "exactly one argument expected for explicit type conversion";
+            return gcx.mk_ty_err(gcx.dcx().err(msg).span(expr.span).emit());
+        };
+        let from = self.checked_type(arg);
+        if from.has_error() {
+            return from;
+        }
+        // Conversions keep the data location of the argument.
+        let loc = match from.kind {
+            TyKind::Ref(_, loc) => loc,
+            _ => DataLocation::Memory,
+        };
+        let to = to.with_loc_if_ref(gcx, loc);
+        if self.is_hex_literal_of_size(arg, to) {
+            return to;
+        }
+        if let Err(err) = from.is_explicitly_convertible_to(gcx, to) {
+            let msg = format!(
+                "explicit type conversion not allowed from `{}` to `{}`",
+                from.display(gcx),
+                to.display(gcx),
+            );
+            let mut diag = gcx.dcx().err(msg).span(expr.span);
+            if let Some(note) = err.note() {
+                diag = diag.note(note);
+            }
+            if err == ConversionError::UserDefinedValueType {
+                let help = if let TyKind::Udvt(..) = to.kind {
+                    format!("use `{}.wrap` to convert a value to this type", to.display(gcx))
+                } else {
+                    format!("use `{}.unwrap` to get the underlying value", from.display(gcx))
+                };
+                diag = diag.help(help);
+            }
+            diag.emit();
+        }
+        to
+    }
+
+    /// Checks the already checked call arguments against the given parameter types.
+    fn check_args(
+        &mut self,
+        kind: &str,
+        span: Span,
+        args: &hir::CallArgs<'_>,
+        params: &[Ty<'gcx>],
+        res: Option<Res>,
+    ) {
+        let gcx = self.gcx;
+        if args.len() != params.len() {
+            let msg = format!(
+                "wrong argument count for {kind}: {} arguments given but expected {}",
+                args.len(),
+                params.len()
+            );
+            gcx.dcx().err(msg).span(span).emit();
+            return;
+        }
+
+        let Some(indices) = self.arg_param_indices(args, params.len(), res) else {
+            let msg = "named arguments cannot be used for functions that take arbitrary parameters";
+            gcx.dcx().err(msg).span(span).emit();
+            return;
+        };
+        for (i, (arg, index)) in args_exprs(args).zip(indices).enumerate() {
+            match (index, args) {
+                (Some(index), _) => self.check_checked_expr_coercible(arg, params[index]),
+                (None, hir::CallArgs::Named(named)) => {
+                    let name = named[i].name;
+                    let msg =
+                        format!("named argument `{name}` does not match function declaration");
+                    gcx.dcx().err(msg).span(name.span).emit();
+                }
+                (None, hir::CallArgs::Unnamed(_)) => unreachable!(),
+            }
+        }
+    }
+
+    /// Returns the index of the parameter that each argument is passed to, or `None` for named
+    /// arguments that do not match any parameter.
+    ///
+    /// Returns `None` if named arguments are used but the parameter names are not known.
+    fn arg_param_indices(
+        &self,
+        args: &hir::CallArgs<'_>,
+        n_params: usize,
+        res: Option<Res>,
+    ) -> Option<SmallVec<[Option<usize>; 8]>> {
+        let gcx = self.gcx;
+        match args {
+            hir::CallArgs::Unnamed(args) => Some((0..args.len()).map(Some).collect()),
+            hir::CallArgs::Named(args) => {
+                let names = match res {
+                    Some(Res::Item(id)) => gcx.item_parameters_opt(id)?,
+                    _ => return None,
+                };
+                // Functions attached with `using for` are called without their first parameter.
+                let names = &names[names.len().checked_sub(n_params)?..];
+                Some(
+                    args.iter()
+                        .map(|arg| {
+                            names.iter().position(|&var| {
+                                gcx.item_name_opt(var)
+                                    .is_some_and(|name| name.name == arg.name.name)
+                            })
+                        })
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    /// Checks the already checked arguments of a call to a builtin that accepts a variable number
+    /// of arguments, returning the type of the call.
+    fn check_variadic_call(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        builtin: Builtin,
+        callee_ty: Ty<'gcx>,
+        args: &hir::CallArgs<'_>,
+    ) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        let name = variadic_builtin_name(builtin);
+        let ret = return_ty(gcx, callee_ty.returns().unwrap_or_default());
+        let hir::CallArgs::Unnamed(args) = *args else {
+            let msg = format!("named arguments cannot be used with `{name}`");
+            gcx.dcx().err(msg).span(expr.span).emit();
+            return ret;
+        };
+        let min_args = match builtin {
+            Builtin::AbiEncode
+            | Builtin::AbiEncodePacked
+            | Builtin::StringConcat
+            | Builtin::BytesConcat => 0,
+            Builtin::AbiEncodeWithSelector | Builtin::AbiEncodeWithSignature => 1,
+            Builtin::AbiEncodeCall | Builtin::AbiDecode => 2,
+            _ => unreachable!(),
+        };
+        let exact = matches!(builtin, Builtin::AbiEncodeCall | Builtin::AbiDecode);
+        if args.len() < min_args || (exact && args.len() != min_args) {
+            let expected = if exact { "exactly" } else { "at least" };
+            let msg = format!(
+                "wrong argument count for `{name}`: {} arguments given but expected {expected} \
+                 {min_args}",
+                args.len(),
+            );
+            gcx.dcx().err(msg).span(expr.span).emit();
+            return if builtin == Builtin::AbiDecode { self.unknown() } else { ret };
+        }
+
+        match builtin {
+            Builtin::AbiEncode | Builtin::AbiEncodePacked => {
+                let packed = builtin == Builtin::AbiEncodePacked;
+                for arg in args {
+                    self.check_abi_encodable(arg, packed);
+                }
+            }
+            Builtin::AbiEncodeWithSelector | Builtin::AbiEncodeWithSignature => {
+                let first = if builtin == Builtin::AbiEncodeWithSelector {
+                    gcx.types.fixed_bytes(4)
+                } else {
+                    gcx.types.string_ref.memory
+                };
+                self.check_checked_expr_coercible(&args[0], first);
+                for arg in &args[1..] {
+                    self.check_abi_encodable(arg, false);
+                }
+            }
+            Builtin::AbiEncodeCall => self.check_abi_encode_call(&args[0], &args[1]),
+            Builtin::AbiDecode => {
+                self.check_checked_expr_coercible(&args[0], gcx.types.bytes_ref.memory);
+                return self.check_abi_decode_types(&args[1]);
+            }
+            Builtin::StringConcat => {
+                for arg in args {
+                    self.check_checked_expr_coercible(arg, gcx.types.string_ref.memory);
+                }
+            }
+            Builtin::BytesConcat => {
+                for arg in args {
+                    let ty = self.checked_type(arg);
+                    let valid = ty.has_error()
+                        || is_fixed_bytes(ty)
+                        || matches!(ty.kind, TyKind::StringLiteral(..))
+                        || ty.peel_refs() == gcx.types.bytes;
+                    if !valid {
+                        let msg = format!(
+                            "invalid type for argument of `bytes.concat`: expected `bytes` or a \
+                             fixed bytes type, found `{}`",
+                            ty.display(gcx),
+                        );
+                        gcx.dcx().err(msg).span(arg.span).emit();
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+        ret
+    }
+
+    /// Checks that the already checked argument can be ABI-encoded.
+    fn check_abi_encodable(&self, arg: &hir::Expr<'_>, packed: bool) {
+        let gcx = self.gcx;
+        let ty = self.checked_type(arg);
+        if ty.has_error() {
+            return;
+        }
+        let Err(note) = abi_encodable(ty, packed) else { return };
+        let msg = if packed {
+            format!("type `{}` cannot be packed-encoded", ty.display(gcx))
+        } else {
+            format!("type `{}` cannot be ABI-encoded", ty.display(gcx))
+        };
+        let mut diag = gcx.dcx().err(msg).span(arg.span).note(note);
+        if is_number_literal(ty) {
+            diag = diag.help("convert the literal to an explicit type first");
+        }
+        diag.emit();
+    }
+
+    /// Checks the arguments of `abi.encodeCall` against the parameters of the function pointer.
+    fn check_abi_encode_call(&self, callee: &hir::Expr<'_>, args: &hir::Expr<'_>) {
+        let gcx = self.gcx;
+        let callee_ty = self.checked_type(callee);
+        if callee_ty.has_error() {
+            return;
+        }
+        let f = match callee_ty.kind {
+            TyKind::FnPtr(f) if f.visibility >= Visibility::Public => f,
+            _ => {
+                let msg = format!(
+                    "expected the first argument of `abi.encodeCall` to be an external function \
+                     pointer, found `{}`",
+                    callee_ty.display(gcx),
+                );
+                let mut diag = gcx.dcx().err(msg).span(callee.span);
+                if matches!(callee_ty.kind, TyKind::FnPtr(_)) {
+                    diag = diag.note("only `public` and `external` functions can be encoded");
+                }
+                diag.emit();
+                return;
+            }
+        };
+
+        // The arguments are given as a tuple, or as a single expression.
+        let exprs: SmallVec<[&hir::Expr<'_>; 8]> = match args.peel_parens().kind {
+            hir::ExprKind::Tuple(exprs) if exprs.len() != 1 => {
+                let Some(exprs) = exprs.iter().copied().collect() else { return };
+                exprs
+            }
+            _ => smallvec![args],
+        };
+        if exprs.len() != f.parameters.len() {
+            let msg = format!(
+                "wrong argument count for function call: {} arguments given but expected {}",
+                exprs.len(),
+                f.parameters.len(),
+            );
+            gcx.dcx().err(msg).span(args.span).emit();
+            return;
+        }
+        for (arg, &param) in exprs.into_iter().zip(f.parameters) {
+            self.check_checked_expr_coercible(arg, param);
+        }
+    }
+
+    /// Returns the type of a call to `abi.decode` from its already checked second argument, which
+    /// must be a type or a tuple of types.
+    fn check_abi_decode_types(&self, types: &hir::Expr<'_>) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        let ty = self.checked_type(types);
+        if ty.has_error() {
+            return ty;
+        }
+        let mut tys = SmallVec::<[_; 8]>::new();
+        for &ty in tuple_components(&ty) {
+            let TyKind::Type(ty) = ty.kind else {
+                let msg = "the second argument of `abi.decode` must be a type or a tuple of types";
+                return gcx.mk_ty_err(gcx.dcx().err(msg).span(types.span).emit());
+            };
+            let ty = ty.with_loc_if_ref(gcx, DataLocation::Memory);
+            if let Err(note) = abi_encodable(ty, false) {
+                let msg = format!("type `{}` cannot be ABI-decoded", ty.display(gcx));
+                return gcx.mk_ty_err(gcx.dcx().err(msg).span(types.span).note(note).emit());
+            }
+            tys.push(ty);
+        }
+        return_ty(gcx, &tys)
+    }
+
+    /// Type checks the arguments without any expected type.
+    fn check_args_unknown(&mut self, args: &hir::CallArgs<'_>) {
+        for arg in args_exprs(args) {
+            self.check_expr(arg);
+        }
+    }
+
+    fn check_call_options(&mut self, options: &[hir::NamedArg<'_>]) {
+        let gcx = self.gcx;
+        for option in options {
+            let expected = match option.name.name {
+                sym::value | kw::Gas => gcx.types.uint(256),
+                sym::salt => gcx.types.fixed_bytes(32),
+                _ => {
+                    self.check_expr(&option.value);
+                    let msg = format!(
+                        "unknown call option `{}`; valid options are `salt`, `value` and `gas`",
+                        option.name
+                    );
+                    gcx.dcx().err(msg).span(option.name.span).emit();
+                    continue;
+                }
+            };
+            self.check_expr_coercible(&option.value, expected);
+        }
+    }
+
+    /// Returns the possible callees of the given expression.
+    fn callee_candidates(&mut self, callee: &hir::Expr<'_>) -> SmallVec<[Candidate<'gcx>; 4]> {
+        match callee.kind {
+            hir::ExprKind::Ident(res) if !matches!(res, [Res::Builtin(Builtin::Super)]) => {
+                self.candidates_of_res(res)
+            }
+            hir::ExprKind::Member(base, ident) => self.member_candidates(base, ident),
+            hir::ExprKind::CallOptions(inner, options) => {
+                let candidates = self.callee_candidates(inner);
+                self.check_call_options(options);
+                if let [(ty, _)] = candidates[..] {
+                    self.gcx.set_type_of_expr(inner.id, ty);
+                }
+                candidates
+            }
+            _ => SmallVec::from_iter([(self.check_expr(callee), None)]),
+        }
+    }
+
+    fn candidates_of_res(&self, res: &[Res]) -> SmallVec<[Candidate<'gcx>; 4]> {
+        let mut candidates = SmallVec::<[Candidate<'gcx>; 4]>::new();
+        for &res in res {
+            if let Res::Item(hir::ItemId::Function(id)) = res {
+                // Getters are only accessible through the contract type.
+                if self.gcx.hir.function(id).is_getter() {
+                    continue;
+                }
+                // Inherited functions are declared after the ones that override them, so skip
+                // functions that have the same parameter types as a previous candidate.
+                let params = self.overriding_parameters(res);
+                if candidates.iter().any(|&(_, prev)| {
+                    prev.and_then(|prev| self.overriding_parameters(prev)) == params
+                }) {
+                    continue;
+                }
+            }
+            candidates.push((self.type_of_res(res), Some(res)));
+        }
+        candidates
+    }
+
+    /// Returns the external parameter types of a function or a public state variable's getter,
+    /// which are used to determine whether one overrides the other.
+    fn overriding_parameters(&self, res: Res) -> Option<&'gcx [Ty<'gcx>]> {
+        let gcx = self.gcx;
+        let id = match res {
+            Res::Item(hir::ItemId::Function(id)) => id,
+            Res::Item(hir::ItemId::Variable(id)) => gcx.hir.variable(id).getter?,
+            _ => return None,
+        };
+        gcx.type_of_item(id.into()).as_externally_callable_function(gcx).parameters()
+    }
+
+    fn type_of_res(&self, res: Res) -> Ty<'gcx> {
+        match res {
+            Res::Builtin(Builtin::This) => match self.contract {
+                Some(id) => self.gcx.type_of_item(id.into()),
+                None => self.unknown(),
+            },
+            // `super` is only valid as the base of a member access, which is checked separately.
+            Res::Builtin(Builtin::Super) => self.unknown(),
+            // Functions referenced by name are internal function pointers.
+            Res::Item(hir::ItemId::Function(id)) => {
+                let ty = self.gcx.type_of_item(id.into());
+                let TyKind::FnPtr(f) = ty.kind else { return ty };
+                if f.visibility == Visibility::External {
+                    return ty;
+                }
+                self.gcx.mk_ty_fn(f.parameters, f.state_mutability, Visibility::Internal, f.returns)
+            }
+            res => self.gcx.type_of_res(res),
+        }
+    }
+
+    /// Returns the members of the type of `base` with the given name.
+    fn member_candidates(
+        &mut self,
+        base: &hir::Expr<'_>,
+        ident: Ident,
+    ) -> SmallVec<[Candidate<'gcx>; 4]> {
+        let gcx = self.gcx;
+        if let hir::ExprKind::Ident([Res::Builtin(Builtin::Super)]) = base.kind {
+            return self.super_member_candidates(base, ident);
+        }
+        let base_ty = self.check_expr(base);
+        if base_ty.has_error() {
+            return SmallVec::from_iter([(base_ty, None)]);
+        }
+        // Public functions accessed through a contract type are internal function pointers, but
+        // they are still part of the external interface: `C.f.selector`.
+        if ident.name == sym::selector && self.is_public_function_of_contract_type(base) {
+            let builtin = Builtin::FunctionSelector;
+            return SmallVec::from_iter([(builtin.ty(gcx), Some(Res::Builtin(builtin)))]);
+        }
+        // Reference types without a location, such as struct constructors, live in memory.
+        let base_ty = if base_ty.is_ref() {
+            base_ty
+        } else {
+            base_ty.with_loc_if_ref(gcx, DataLocation::Memory)
+        };
+        let members = gcx.members_of(base_ty);
+        let mut candidates = members
+            .iter()
+            .filter(|m| m.name == ident.name)
+            .map(|m| (m.ty, m.res))
+            .collect::<SmallVec<[_; 4]>>();
+        candidates.extend(self.attached_functions(base_ty, ident.name));
+        if candidates.is_empty() {
+            let msg = format!(
+                "member `{ident}` not found or not visible after argument-dependent lookup in `{}`",
+                base_ty.display(gcx)
+            );
+            let guar = self.report_member_not_found(ident, msg, members.iter().map(|m| m.name));
+            return SmallVec::from_iter([(gcx.mk_ty_err(guar), None)]);
+        }
+        candidates
+    }
+
+    /// Returns `true` if the already checked expression is a public function accessed through a
+    /// contract type, such as `C.f`.
+    fn is_public_function_of_contract_type(&self, expr: &hir::Expr<'_>) -> bool {
+        let hir::ExprKind::Member(base, _) = expr.kind else { return false };
+        let is_contract_type = matches!(
+            self.checked_type(base).kind,
+            TyKind::Type(ty) if matches!(ty.kind, TyKind::Contract(_))
+        );
+        is_contract_type
+            && matches!(
+                self.gcx.res_of_expr(expr.id),
+                Some(Res::Item(hir::ItemId::Function(f)))
+                    if self.gcx.hir.function(f).visibility == Visibility::Public
+            )
+    }
+
+    /// Returns the functions with the given name that are attached to the type with `using for`
+    /// directives, bound to a value of that type.
+    fn attached_functions(&self, ty: Ty<'gcx>, name: Symbol) -> SmallVec<[Candidate<'gcx>; 4]> {
+        let gcx = self.gcx;
+        let mut functions = SmallVec::<[hir::FunctionId; 4]>::new();
+        for directive in self.using_directives(ty) {
+            if let Some(directive_ty) = &directive.ty {
+                if gcx.type_of_hir_ty(directive_ty) != ty.peel_refs() {
+                    continue;
+                }
+            }
+            let attached = match directive.list {
+                hir::UsingList::Library(id) => {
+                    let library = gcx.hir.contract(id).functions().filter(|&f| {
+                        let func = gcx.hir.function(f);
+                        func.is_ordinary() && func.visibility != Visibility::Private
+                    });
+                    library.collect::<SmallVec<[_; 8]>>()
+                }
+                // Functions that implement an operator are not attached as members.
+                hir::UsingList::Functions(list) => {
+                    list.iter().filter(|f| f.operator.is_none()).map(|f| f.function).collect()
+                }
+            };
+            for f in attached {
+                if gcx.item_name_opt(f).is_some_and(|n| n.name == name) && !functions.contains(&f) {
+                    functions.push(f);
+                }
+            }
+        }
+
+        let mut candidates = SmallVec::new();
+        for f in functions {
+            let res = Res::Item(f.into());
+            let TyKind::FnPtr(ptr) = self.type_of_res(res).kind else { continue };
+            let Some((&first, params)) = ptr.parameters.split_first() else { continue };
+            if !self.is_implicitly_convertible(ty, first) {
+                continue;
+            }
+            let bound = gcx.mk_ty_fn(params, ptr.state_mutability, ptr.visibility, ptr.returns);
+            candidates.push((bound, Some(res)));
+        }
+        candidates
+    }
+
+    /// Returns the `using for` directives that can apply to the given type in the current scope.
+    ///
+    /// These are the directives of the current contract and source, and the global directives of
+    /// the source in which the type is defined.
+    fn using_directives(
+        &self,
+        ty: Ty<'gcx>,
+    ) -> impl Iterator<Item = &'gcx hir::UsingDirective<'gcx>> + use<'gcx> {
+        let gcx = self.gcx;
+        let contract = self.contract.map(|id| gcx.hir.contract(id).using_directives);
+        let source = gcx.hir.source(self.source).using_directives;
+        let global = type_definition_source(gcx, ty)
+            .filter(|&id| id != self.source)
+            .map(|id| gcx.hir.source(id).using_directives);
+        contract
+            .unwrap_or_default()
+            .iter()
+            .chain(source)
+            .chain(global.unwrap_or_default().iter().filter(|d| d.global))
+    }
+
+    /// Returns the function that implements the given operator for a user-defined value type.
+    ///
+    /// Operators can only be defined in global directives, which must be in the same source as the
+    /// type definition.
+    fn user_defined_operator(
+        &self,
+        ty: Ty<'gcx>,
+        matches: impl Fn(hir::UserDefinableOperator, usize) -> bool,
+    ) -> Option<hir::FunctionId> {
+        let gcx = self.gcx;
+        let TyKind::Udvt(_, id) = ty.kind else { return None };
+        let directives = gcx.hir.source(gcx.hir.udvt(id).source).using_directives;
+        directives
+            .iter()
+            .filter(|d| {
+                d.global && d.ty.as_ref().is_some_and(|d_ty| gcx.type_of_hir_ty(d_ty) == ty)
+            })
+            .flat_map(|d| match d.list {
+                hir::UsingList::Functions(list) => list,
+                hir::UsingList::Library(_) => &[],
+            })
+            .find(|f| {
+                f.operator
+                    .is_some_and(|op| matches(op, gcx.hir.function(f.function).parameters.len()))
+            })
+            .map(|f| f.function)
+    }
+
+    /// Type checks a binary operation using a user-defined operator.
+    ///
+    /// Returns `None` if the left operand does not have a user-defined operator for `op`.
+    fn check_user_defined_binop(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        lhs_ty: Ty<'gcx>,
+        op: hir::BinOp,
+        rhs: &hir::Expr<'_>,
+    ) -> Option<Ty<'gcx>> {
+        let f = self.user_defined_operator(lhs_ty, |operator, n_params| {
+            operator.to_op().right() == Some(op.kind) && n_params == 2
+        })?;
+        self.check_checked_expr_coercible(rhs, lhs_ty);
+        Some(self.record_operator(expr, f))
+    }
+
+    /// Type checks a unary operation using a user-defined operator.
+    ///
+    /// Returns `None` if the operand does not have a user-defined operator for `op`.
+    fn check_user_defined_unop(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        op: hir::UnOp,
+        ty: Ty<'gcx>,
+    ) -> Option<Ty<'gcx>> {
+        let f = self.user_defined_operator(ty, |operator, n_params| match op.kind {
+            UnOpKind::Neg => operator == hir::UserDefinableOperator::Sub && n_params == 1,
+            kind => operator.to_op().left() == Some(kind),
+        })?;
+        Some(self.record_operator(expr, f))
+    }
+
+    /// Records the function that implements the operator of the given expression, returning the
+    /// result type.
+    fn record_operator(&self, expr: &hir::Expr<'_>, f: hir::FunctionId) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        gcx.set_res_of_expr(expr.id, Res::Item(f.into()));
+        return_ty(gcx, gcx.type_of_item(f.into()).returns().unwrap_or_default())
+    }
+
+    /// Returns the functions with the given name in the bases of the current contract.
+    fn super_member_candidates(
+        &mut self,
+        base: &hir::Expr<'_>,
+        ident: Ident,
+    ) -> SmallVec<[Candidate<'gcx>; 4]> {
+        let gcx = self.gcx;
+        let Some(contract) = self.contract else {
+            let msg = "`super` can only be used inside of a contract";
+            return SmallVec::from_iter([(
+                gcx.mk_ty_err(gcx.dcx().err(msg).span(base.span).emit()),
+                None,
+            )]);
+        };
+        let bases = &gcx.hir.contract(contract).linearized_bases[1..];
+        let functions = bases
+            .iter()
+            .flat_map(|&base| gcx.hir.contract(base).functions())
+            .filter(|&f| {
+                let func = gcx.hir.function(f);
+                func.is_ordinary() && func.visibility != Visibility::Private
+            })
+            .collect::<SmallVec<[_; 8]>>();
+        let res = functions
+            .iter()
+            .filter(|&&f| gcx.item_name(f).name == ident.name)
+            .map(|&f| Res::Item(f.into()))
+            .collect::<SmallVec<[_; 4]>>();
+        if res.is_empty() {
+            let names = functions.iter().map(|&f| gcx.item_name(f).name);
+            let msg = format!("member `{ident}` not found or not visible in `super`");
+            let guar = self.report_member_not_found(ident, msg, names);
+            return SmallVec::from_iter([(gcx.mk_ty_err(guar), None)]);
+        }
+        self.candidates_of_res(&res)
+    }
+
+    fn report_member_not_found(
+        &self,
+        ident: Ident,
+        msg: String,
+        names: impl Iterator<Item = Symbol>,
+    ) -> ErrorGuaranteed {
+        let gcx = self.gcx;
+        let mut diag = gcx.dcx().err(msg).span(ident.span);
+        if let Some(similar) = find_similar_name(ident.name, names) {
+            diag = diag.help(format!("did you mean `{similar}`?"));
+        }
+        diag.emit()
+    }
+
+    /// Returns the parameter types of the given callee, if it is callable.
+    fn callable_parameters(&self, ty: Ty<'gcx>, res: Option<Res>) -> Option<&'gcx [Ty<'gcx>]> {
+        if let Some(Res::Builtin(builtin)) = res {
+            if is_variadic(builtin) {
+                return None;
+            }
+        }
+        match ty.kind {
+            TyKind::FnPtr(f) => Some(f.parameters),
+            TyKind::Event(tys, _) | TyKind::Error(tys, _) => Some(tys),
+            _ => None,
+        }
+    }
+
+    fn check_index(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        base: &hir::Expr<'_>,
+        index: Option<&hir::Expr<'_>>,
+    ) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        let base_ty = self.check_expr(base);
+
+        // Array types: `T[]`, `T[N]`.
+        if let TyKind::Type(inner) = base_ty.kind {
+            let kind = match index {
+                Some(index) => match ConstantEvaluator::new(gcx).eval_array_len(index) {
+                    Ok(len) => TyKind::Array(inner, len),
+                    Err(guar) => TyKind::Err(guar),
+                },
+                None => TyKind::DynArray(inner),
+            };
+            return gcx.mk_ty(kind).make_type_type(gcx);
+        }
+
+        let Some(index) = index else {
+            let msg = "index expression cannot be omitted";
+            return gcx.mk_ty_err(gcx.dcx().err(msg).span(expr.span).emit());
+        };
+        if base_ty.has_error() {
+            self.check_expr(index);
+            return base_ty;
+        }
+
+        let (inner, loc) = match base_ty.kind {
+            TyKind::Ref(inner, loc) => (inner, Some(loc)),
+            _ => (base_ty, None),
+        };
+        let with_loc = |ty: Ty<'gcx>| match loc {
+            Some(loc) => ty.with_loc_if_ref(gcx, loc),
+            None => ty,
+        };
+        match inner.kind {
+            TyKind::DynArray(elem) | TyKind::Array(elem, _) => {
+                self.check_expr_coercible(index, gcx.types.uint(256));
+                with_loc(elem)
+            }
+            TyKind::Elementary(ElementaryType::Bytes | ElementaryType::FixedBytes(_)) => {
+                self.check_expr_coercible(index, gcx.types.uint(256));
+                gcx.types.fixed_bytes(1)
+            }
+            TyKind::Mapping(key, value) => {
+                self.check_expr_coercible(index, key);
+                with_loc(value)
+            }
+            _ => {
+                self.check_expr(index);
+                let msg = format!(
+                    "indexed expression has to be a type, mapping or array (is `{}`)",
+                    base_ty.display(gcx)
+                );
+                gcx.mk_ty_err(gcx.dcx().err(msg).span(base.span).emit())
+            }
+        }
+    }
+
+    fn check_unop(&mut self, expr: &hir::Expr<'_>, op: hir::UnOp, ty: Ty<'gcx>) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        if ty.has_error() {
+            return ty;
+        }
+        let valid = match op.kind {
+            UnOpKind::Not => ty.peel_refs() == gcx.types.bool,
+            UnOpKind::Neg => match ty.kind {
+                TyKind::IntLiteral(..) | TyKind::RationalLiteral(_) => {
+                    return self.fold_literal(expr).unwrap_or_else(|| self.unknown());
+                }
+                TyKind::Elementary(ElementaryType::Int(_) | ElementaryType::Fixed(..)) => true,
+                TyKind::Elementary(ElementaryType::UInt(_)) => {
+                    let msg = format!(
+                        "built-in unary operator `{}` cannot be applied to type `{}`",
+                        op.kind.to_str(),
+                        ty.display(gcx),
+                    );
+                    let note = "unary negation is only allowed for signed integers";
+                    let guar = gcx.dcx().err(msg).span(expr.span).note(note).emit();
+                    return gcx.mk_ty_err(guar);
+                }
+                _ => false,
+            },
+            UnOpKind::BitNot => match ty.kind {
+                TyKind::IntLiteral(negative, bits) => {
+                    return gcx.mk_ty_int_literal(!negative, bits);
+                }
+                _ => is_integer(ty) || is_fixed_bytes(ty),
+            },
+            UnOpKind::PreInc | UnOpKind::PreDec | UnOpKind::PostInc | UnOpKind::PostDec => {
+                is_integer(ty.peel_refs())
+            }
+        };
+        if !valid {
+            let msg = format!(
+                "built-in unary operator `{}` cannot be applied to type `{}`",
+                op.kind.to_str(),
+                ty.display(gcx),
+            );
+            return gcx.mk_ty_err(gcx.dcx().err(msg).span(expr.span).emit());
+        }
+        ty.peel_refs()
+    }
+
+    /// Folds a binary operation between two number literals.
+    ///
+    /// Returns `None` if the operation is not valid on literals.
+    fn check_literal_binop(&mut self, expr: &hir::Expr<'_>, op: hir::BinOp) -> Option<Ty<'gcx>> {
+        if is_comparison(op.kind) {
+            return Some(self.gcx.types.bool);
+        }
+        if matches!(op.kind, BinOpKind::And | BinOpKind::Or) {
+            return None;
+        }
+        self.fold_literal(expr)
+    }
+
+    /// Evaluates an operation on number literals, returning the literal type of the result.
+    ///
+    /// Returns `None` if the operation is not valid on the operands.
+    fn fold_literal(&self, expr: &hir::Expr<'_>) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+        let mut evaluator = ConstantEvaluator::new(gcx);
+        match evaluator.try_eval(expr) {
+            Ok(ConstValue::Rational(value)) => Some(if value.is_integer() {
+                let value = value.to_integer();
+                let (negative, bits) = if value.is_negative() {
+                    (true, (-value - 1u8).bits())
+                } else {
+                    (false, value.bits())
+                };
+                gcx.mk_ty_int_literal(negative, bits.try_into().unwrap_or(u16::MAX))
+            } else {
+                gcx.mk_ty_rational_literal(value.is_negative())
+            }),
+            Err(err) => match err.kind {
+                EvalErrorKind::UnsupportedUnaryOp | EvalErrorKind::UnsupportedBinaryOp => None,
+                EvalErrorKind::UnsupportedExpr => Some(self.unknown()),
+                _ => Some(gcx.mk_ty_err(evaluator.emit_eval_error(expr, err))),
+            },
+            Ok(_) => Some(self.unknown()),
+        }
+    }
+
+    fn check_binop(
+        &mut self,
+        lhs: &hir::Expr<'_>,
+        lhs_ty: Ty<'gcx>,
+        op: hir::BinOp,
+        rhs: &hir::Expr<'_>,
+        rhs_ty: Ty<'gcx>,
+        assign: bool,
+    ) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        if lhs_ty.has_error() || rhs_ty.has_error() {
+            return self.unknown();
+        }
+        let l = lhs_ty.peel_refs();
+        let r = rhs_ty.peel_refs();
+        let result = match op.kind {
+            BinOpKind::Shl | BinOpKind::Shr | BinOpKind::Sar | BinOpKind::Pow => {
+                let r_valid = match r.kind {
+                    TyKind::IntLiteral(negative, _) => !negative,
+                    TyKind::Elementary(ElementaryType::UInt(_)) => true,
+                    _ => false,
+                };
+                let l_valid = if op.kind == BinOpKind::Pow {
+                    is_integer(l)
+                } else {
+                    is_integer(l) || is_fixed_bytes(l)
+                };
+                (l_valid && r_valid).then(|| match l.kind {
+                    // Literal bases with non-literal exponents or shift amounts use 256 bits.
+                    TyKind::IntLiteral(true, _) => gcx.types.int(256),
+                    TyKind::IntLiteral(false, _) => gcx.types.uint(256),
+                    _ => l,
+                })
+            }
+            BinOpKind::And | BinOpKind::Or => {
+                (l == gcx.types.bool && r == gcx.types.bool).then_some(gcx.types.bool)
+            }
+            BinOpKind::Eq | BinOpKind::Ne => self
+                .binop_common_type(l, r)
+                .filter(|&ty| supports_equality(ty))
+                .map(|_| gcx.types.bool),
+            BinOpKind::Lt | BinOpKind::Le | BinOpKind::Gt | BinOpKind::Ge => self
+                .binop_common_type(l, r)
+                .filter(|&ty| supports_ordering(ty))
+                .map(|_| gcx.types.bool),
+            BinOpKind::BitAnd | BinOpKind::BitOr | BinOpKind::BitXor => {
+                self.binop_common_type(l, r).filter(|&ty| is_integer(ty) || is_fixed_bytes(ty))
+            }
+            BinOpKind::Add | BinOpKind::Sub | BinOpKind::Mul | BinOpKind::Div | BinOpKind::Rem => {
+                self.binop_common_type(l, r).filter(|&ty| is_integer(ty) || is_fixed_point(ty))
+            }
+        };
+        match result {
+            Some(ty) => ty,
+            None => {
+                let op_str = if assign {
+                    format!("{}=", op.kind.to_str())
+                } else {
+                    op.kind.to_str().to_string()
+                };
+                let msg = format!(
+                    "built-in binary operator `{op_str}` cannot be applied to types `{}` and `{}`",
+                    lhs_ty.display(gcx),
+                    rhs_ty.display(gcx),
+                );
+                let span = lhs.span.to(rhs.span);
+                gcx.mk_ty_err(gcx.dcx().err(msg).span(span).emit())
+            }
+        }
+    }
+
+    /// Returns the common type of the operands of a binary operator.
+    fn binop_common_type(&self, l: Ty<'gcx>, r: Ty<'gcx>) -> Option<Ty<'gcx>> {
+        match (is_number_literal(l), is_number_literal(r)) {
+            (true, true) => Some(self.mobile_type(l)).filter(|ty| !ty.has_error()),
+            (true, false) => self.is_implicitly_convertible(l, r).then_some(r),
+            (false, true) => self.is_implicitly_convertible(r, l).then_some(l),
+            (false, false) => self.common_type(l, r),
+        }
+    }
+
+    /// Returns the type that both types can be implicitly converted to, if any.
+    fn common_type(&self, a: Ty<'gcx>, b: Ty<'gcx>) -> Option<Ty<'gcx>> {
+        if a.has_error() {
+            return Some(a);
+        }
+        if b.has_error() {
+            return Some(b);
+        }
+        if self.is_implicitly_convertible(b, a) {
+            Some(a)
+        } else if self.is_implicitly_convertible(a, b) {
+            Some(b)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the type that a literal is converted to when it's used without an expected type.
+    fn mobile_type(&self, ty: Ty<'gcx>) -> Ty<'gcx> {
+        let gcx = self.gcx;
+        match ty.kind {
+            TyKind::IntLiteral(false, bits) if bits <= 256 => {
+                gcx.types.uint(bits.max(8).next_multiple_of(8))
+            }
+            TyKind::IntLiteral(true, bits) if bits < 256 => {
+                gcx.types.int((bits + 1).max(8).next_multiple_of(8))
+            }
+            TyKind::IntLiteral(..) | TyKind::RationalLiteral(_) => self.unknown(),
+            TyKind::StringLiteral(..) => gcx.types.string_ref.memory,
+            TyKind::Tuple(tys) => {
+                gcx.mk_ty(TyKind::Tuple(gcx.mk_ty_iter(tys.iter().map(|&ty| self.mobile_type(ty)))))
+            }
+            _ => ty,
+        }
+    }
+
+    /// Returns `true` if the expression is a local variable or parameter that references
+    /// storage, meaning that assigning to it changes what it points to instead of copying.
+    fn is_storage_pointer(&self, expr: &hir::Expr<'_>) -> bool {
+        let expr = expr.peel_parens();
+        if !matches!(expr.kind, hir::ExprKind::Ident(_)) {
+            return false;
+        }
+        let Some(Res::Item(hir::ItemId::Variable(id))) = self.gcx.res_of_expr(expr.id) else {
+            return false;
+        };
+        !self.gcx.hir.variable(id).is_state_variable()
+            && self.gcx.type_of_item(id.into()).is_ref_at(DataLocation::Storage)
+    }
+
+    /// Returns `true` if `from` is implicitly convertible to `to`.
+    fn is_implicitly_convertible(&self, from: Ty<'gcx>, to: Ty<'gcx>) -> bool {
+        from.is_implicitly_convertible_to(self.gcx, to).is_ok()
+    }
+
+    /// Returns `true` if the expression is a hexadecimal number literal that has exactly as many
+    /// digits as the fixed bytes type `ty`.
+    fn is_hex_literal_of_size(&self, expr: &hir::Expr<'_>, ty: Ty<'gcx>) -> bool {
+        let hir::ExprKind::Lit(lit) = expr.peel_parens().kind else { return false };
+        let TyKind::Elementary(ElementaryType::FixedBytes(size)) = ty.kind else { return false };
+        let s = lit.symbol.as_str();
+        matches!(lit.kind, LitKind::Number(_))
+            && s.starts_with("0x")
+            && s[2..].chars().filter(|&c| c != '_').count() == size.bytes() as usize * 2
+    }
+
+    /// Returns an error type that does not emit any further diagnostics.
+    ///
+    /// Used for expressions whose type cannot be computed yet.
+    fn unknown(&self) -> Ty<'gcx> {
+        self.gcx.mk_ty_err(ErrorGuaranteed::new_unchecked())
+    }
+}
+
+pub(super) fn args_exprs<'a, 'hir>(
+    args: &'a hir::CallArgs<'hir>,
+) -> impl Iterator<Item = &'a hir::Expr<'hir>> {
+    let (unnamed, named) = match args {
+        hir::CallArgs::Unnamed(args) => (&args[..], &[][..]),
+        hir::CallArgs::Named(args) => (&[][..], &args[..]),
+    };
+    unnamed.iter().chain(named.iter().map(|arg| &arg.value))
+}
+
+/// Returns the components of a tuple type, or the type itself if it is not a tuple.
+fn tuple_components<'a, 'gcx>(ty: &'a Ty<'gcx>) -> &'a [Ty<'gcx>] {
+    match ty.kind {
+        TyKind::Tuple(tys) => tys,
+        _ => std::slice::from_ref(ty),
+    }
+}
+
+fn return_ty<'gcx>(gcx: Gcx<'gcx>, returns: &[Ty<'gcx>]) -> Ty<'gcx> {
+    match *returns {
+        [ret] => ret,
+        _ => gcx.mk_ty(TyKind::Tuple(gcx.mk_tys(returns))),
+    }
+}
+
+/// Returns the name of a builtin that accepts a variable number of arguments, as it is written in
+/// the source.
+fn variadic_builtin_name(builtin: Builtin) -> &'static str {
+    match builtin {
+        Builtin::AbiEncode => "abi.encode",
+        Builtin::AbiEncodePacked => "abi.encodePacked",
+        Builtin::AbiEncodeWithSelector => "abi.encodeWithSelector",
+        Builtin::AbiEncodeCall => "abi.encodeCall",
+        Builtin::AbiEncodeWithSignature => "abi.encodeWithSignature",
+        Builtin::AbiDecode => "abi.decode",
+        Builtin::StringConcat => "string.concat",
+        Builtin::BytesConcat => "bytes.concat",
+        _ => unreachable!("{builtin:?} is not variadic"),
+    }
+}
+
+/// Returns `Ok(())` if values of the type can be ABI-encoded, otherwise returns the reason why they
+/// cannot be.
+fn abi_encodable(ty: Ty<'_>, packed: bool) -> Result<(), &'static str> {
+    let ty = ty.peel_refs();
+    match ty.kind {
+        TyKind::IntLiteral(..) if packed => {
+            Err("number literals have no fixed size, so they cannot be packed-encoded")
+        }
+        TyKind::RationalLiteral(_) => Err("fractional numbers cannot be encoded"),
+        TyKind::FnPtr(f) if f.visibility < Visibility::Public => {
+            Err("internal function pointers cannot be encoded")
+        }
+        TyKind::Struct(_) if packed => Err("structs are not supported in packed encoding"),
+        TyKind::Array(elem, _) | TyKind::DynArray(elem) if packed => {
+            let elem = elem.peel_refs();
+            let nested = match elem.kind {
+                TyKind::Elementary(elem) => !elem.is_value_type(),
+                TyKind::Array(..) | TyKind::DynArray(_) | TyKind::Struct(_) => true,
+                _ => false,
+            };
+            if nested {
+                Err("nested arrays and arrays of dynamic types are not supported in packed encoding")
+            } else {
+                abi_encodable(elem, packed)
+            }
+        }
+        _ if ty.has_mapping() => Err("types containing mappings cannot be encoded"),
+        _ if ty.is_recursive() => Err("recursive types cannot be encoded"),
+        TyKind::Mapping(..)
+        | TyKind::Tuple(_)
+        | TyKind::Type(_)
+        | TyKind::Meta(_)
+        | TyKind::Module(_)
+        | TyKind::BuiltinModule(_)
+        | TyKind::Event(..)
+        | TyKind::Error(..) => Err("only values can be encoded"),
+        _ => Ok(()),
+    }
+}
+
+fn is_variadic(builtin: Builtin) -> bool {
+    matches!(
+        builtin,
+        Builtin::AbiEncode
+            | Builtin::AbiEncodePacked
+            | Builtin::AbiEncodeWithSelector
+            | Builtin::AbiEncodeCall
+            | Builtin::AbiEncodeWithSignature
+            | Builtin::AbiDecode
+            | Builtin::StringConcat
+            | Builtin::BytesConcat
+    )
+}
+
+/// Returns the source in which the given user-defined type is defined.
+fn type_definition_source(gcx: Gcx<'_>, ty: Ty<'_>) -> Option<hir::SourceId> {
+    let id: hir::ItemId = match ty.peel_refs().kind {
+        TyKind::Contract(id) => id.into(),
+        TyKind::Struct(id) => id.into(),
+        TyKind::Enum(id) => id.into(),
+        TyKind::Udvt(_, id) => id.into(),
+        _ => return None,
+    };
+    Some(gcx.hir.item(id).source())
+}
+
+pub(super) fn is_comparison(op: BinOpKind) -> bool {
+    matches!(
+        op,
+        BinOpKind::Lt
+            | BinOpKind::Le
+            | BinOpKind::Gt
+            | BinOpKind::Ge
+            | BinOpKind::Eq
+            | BinOpKind::Ne
+    )
+}
+
+fn is_integer(ty: Ty<'_>) -> bool {
+    matches!(
+        ty.kind,
+        TyKind::IntLiteral(..)
+            | TyKind::Elementary(ElementaryType::Int(_) | ElementaryType::UInt(_))
+    )
+}
+
+fn is_fixed_point(ty: Ty<'_>) -> bool {
+    matches!(
+        ty.kind,
+        TyKind::RationalLiteral(_)
+            | TyKind::Elementary(ElementaryType::Fixed(..) | ElementaryType::UFixed(..))
+    )
+}
+
+fn is_number_literal(ty: Ty<'_>) -> bool {
+    matches!(ty.kind, TyKind::IntLiteral(..) | TyKind::RationalLiteral(_))
+}
+
+fn is_fixed_bytes(ty: Ty<'_>) -> bool {
+    matches!(ty.kind, TyKind::Elementary(ElementaryType::FixedBytes(_)))
+}
+
+fn supports_equality(ty: Ty<'_>) -> bool {
+    match ty.kind {
+        TyKind::Elementary(ty) => ty.is_value_type(),
+        TyKind::Contract(_) | TyKind::Enum(_) => true,
+        TyKind::FnPtr(f) => f.visibility >= Visibility::Internal,
+        _ => false,
+    }
+}
+
+fn supports_ordering(ty: Ty<'_>) -> bool {
+    match ty.kind {
+        TyKind::Elementary(ty) => ty.is_value_type() && ty != ElementaryType::Bool,
+        TyKind::Enum(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns the name in `names` that is most similar to `name`, if any is similar enough.
+///
+/// Names that only differ by the order of their characters are also considered similar.
+fn find_similar_name(name: Symbol, names: impl Iterator<Item = Symbol>) -> Option<Symbol> {
+    let name = name.as_str();
+    let max_dist = std::cmp::max(name.len(), 3) / 3;
+    let sorted_chars = |s: &str| {
+        let mut chars = s.chars().collect::<SmallVec<[_; 32]>>();
+        chars.sort_unstable();
+        chars
+    };
+    let name_chars = sorted_chars(name);
+    names
+        .filter_map(|candidate| {
+            let dist = edit_distance(name, candidate.as_str());
+            if dist <= max_dist {
+                Some((dist, candidate))
+            } else if sorted_chars(candidate.as_str()) == name_chars {
+                Some((max_dist + 1, candidate))
+            } else {
+                None
+            }
+        })
+        .min_by_key(|&(dist, _)| dist)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Returns the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<SmallVec<[_; 32]>>();
+    let mut prev = (0..=b.len()).collect::<SmallVec<[_; 32]>>();
+    let mut curr = SmallVec::<[usize; 32]>::from_elem(0, b.len() + 1);
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
@@ -0,0 +1,190 @@
+//! Checks the restrictions of abstract contracts, interfaces and libraries.
+//!
+//! Reference: <https://docs.soliditylang.org/en/latest/contracts.html#abstract-contracts>
+
+use super::overrides::{override_key, OverrideKey};
+use crate::{
+    hir::{self, ContractKind, Visibility},
+    ty::Gcx,
+};
+use solar_data_structures::{map::FxHashMap, smallvec::SmallVec};
+use solar_interface::{error_code, Span};
+
+/// Checks that the contract implements all of its functions unless it is marked `abstract`, and
+/// that interfaces and libraries only contain the items that they are allowed to.
+pub(super) fn check_contract(gcx: Gcx<'_>, id: hir::ContractId) {
+    let contract = gcx.hir.contract(id);
+
+    for f in contract.functions() {
+        check_function(gcx, contract, f);
+    }
+
+    if contract.kind.is_interface() {
+        check_interface(gcx, contract);
+    }
+
+    check_base_constructor_args(gcx, id);
+
+    if contract.kind.is_contract() {
+        let missing = unimplemented_functions(gcx, id);
+        if !missing.is_empty() {
+            let msg = format!("contract `{}` should be marked as abstract", contract.name);
+            let mut diag = gcx.dcx().err(msg).span(contract.name.span);
+            for f in missing {
+                diag = diag.span_note(gcx.hir.function(f).span, "missing implementation");
+            }
+            diag.emit();
+        }
+    }
+}
+
+/// Checks the implementation requirements of a contract function.
+fn check_function(gcx: Gcx<'_>, contract: &hir::Contract<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if func.body.is_some() || func.is_getter() {
+        return;
+    }
+    if let Some(modifier) = func.modifiers.first() {
+        let msg = "functions without implementation cannot have modifiers";
+        gcx.dcx().err(msg).span(modifier.span).emit();
+    }
+    match contract.kind {
+        ContractKind::Interface => {}
+        ContractKind::Library => {
+            gcx.dcx().err("library functions must be implemented").span(func.span).emit();
+        }
+        ContractKind::Contract | ContractKind::AbstractContract => {
+            if !func.marked_virtual {
+                let kind = if func.kind.is_modifier() { "modifiers" } else { "functions" };
+                let msg = format!("{kind} without implementation must be marked virtual");
+                gcx.dcx().err(msg).span(func.span).emit();
+            }
+        }
+    }
+}
+
+/// Checks the items and the bases of an interface.
+fn check_interface(gcx: Gcx<'_>, contract: &hir::Contract<'_>) {
+    for &base in contract.bases {
+        let base = gcx.hir.contract(base);
+        if !base.kind.is_interface() {
+            let msg = "interfaces can only inherit from other interfaces";
+            let note = format!("{} `{}` is defined here", base.kind, base.name);
+            gcx.dcx().err(msg).span(contract.name.span).span_note(base.name.span, note).emit();
+        }
+    }
+
+    for var in contract.variables() {
+        let var = gcx.hir.variable(var);
+        gcx.dcx().err("variables cannot be declared in interfaces").span(var.span).emit();
+    }
+
+    for f in contract.functions() {
+        let func = gcx.hir.function(f);
+        if func.is_getter() {
+            continue;
+        }
+        if func.kind.is_constructor() {
+            let msg = "constructors cannot be defined in interfaces";
+            gcx.dcx().err(msg).span(func.span).emit();
+            continue;
+        }
+        if func.kind.is_modifier() {
+            let msg = "modifiers cannot be defined or declared in interfaces";
+            gcx.dcx().err(msg).span(func.span).emit();
+            continue;
+        }
+        if func.body.is_some() {
+            let msg = "functions in interfaces cannot have an implementation";
+            gcx.dcx().err(msg).span(func.span).emit();
+        }
+        if func.visibility != Visibility::External {
+            let msg = "functions in interfaces must be declared external";
+            gcx.dcx().err(msg).span(func.span).emit();
+        }
+    }
+}
+
+/// Checks that the arguments of each base constructor are given exactly once in the inheritance
+/// hierarchy, either in an inheritance specifier or in the modifiers of a constructor.
+///
+/// Arguments may only be omitted if the contract is abstract.
+fn check_base_constructor_args(gcx: Gcx<'_>, id: hir::ContractId) {
+    let contract = gcx.hir.contract(id);
+    let mut given = FxHashMap::<hir::ContractId, (hir::ContractId, Span)>::default();
+    // Visit the most derived contract last, so that the errors point to its own specifiers.
+    for &base_id in contract.linearized_bases.iter().rev() {
+        let base = gcx.hir.contract(base_id);
+        let ctor_modifiers = base.ctor.map(|ctor| gcx.hir.function(ctor).modifiers);
+        let specifiers = base.bases_args.iter().chain(ctor_modifiers.unwrap_or_default());
+        for specifier in specifiers {
+            let hir::ItemId::Contract(target) = specifier.id else { continue };
+            let Some(&(prev_base, prev_span)) = given.get(&target) else {
+                given.insert(target, (base_id, specifier.span));
+                continue;
+            };
+            // Conflicts within the hierarchy of a direct base are reported for that base.
+            let reported = contract.bases.iter().any(|&direct| {
+                let linearized = gcx.hir.contract(direct).linearized_bases;
+                linearized.contains(&prev_base) && linearized.contains(&base_id)
+            });
+            if !reported {
+                let msg = "base constructor arguments given twice";
+                let note = "previous constructor arguments given here";
+                gcx.dcx()
+                    .err(msg)
+                    .code(error_code!(3364))
+                    .span(specifier.span)
+                    .span_note(prev_span, note)
+                    .emit();
+            }
+        }
+    }
+
+    if !contract.kind.is_contract() {
+        return;
+    }
+    for &base_id in &contract.linearized_bases[1..] {
+        let base = gcx.hir.contract(base_id);
+        let Some(ctor) = base.ctor else { continue };
+        let ctor = gcx.hir.function(ctor);
+        if ctor.parameters.is_empty() || given.contains_key(&base_id) {
+            continue;
+        }
+        let msg = format!("no arguments passed to the base constructor of `{}`", base.name);
+        let help = format!("specify the arguments or mark `{}` as abstract", contract.name);
+        gcx.dcx()
+            .err(msg)
+            .code(error_code!(3415))
+            .span(contract.name.span)
+            .span_note(ctor.span, "the constructor is declared here")
+            .help(help)
+            .emit();
+    }
+}
+
+/// Returns the functions and modifiers that the contract inherits or declares without
+/// implementing them.
+///
+/// The most derived definition of each function is considered, following the linearized bases.
+fn unimplemented_functions(gcx: Gcx<'_>, id: hir::ContractId) -> SmallVec<[hir::FunctionId; 4]> {
+    let mut seen = SmallVec::<[OverrideKey<'_>; 16]>::new();
+    let mut missing = SmallVec::new();
+    for &base in gcx.hir.contract(id).linearized_bases {
+        for f in gcx.hir.contract(base).functions() {
+            let func = gcx.hir.function(f);
+            if func.kind.is_constructor() {
+                continue;
+            }
+            let key = override_key(gcx, f);
+            if seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            if func.body.is_none() && !func.is_getter() {
+                missing.push(f);
+            }
+        }
+    }
+    missing
+}
@@ -0,0 +1,397 @@
+//! Control-flow analysis of function bodies.
+//!
+//! Reports unreachable code, return values and storage pointers that may be used without being
+//! assigned, and modifiers that may not execute the body of the modified function.
+
+use super::checker::args_exprs;
+use crate::{
+    cfg::{BasicBlock, BasicBlockId, Cfg, Terminator},
+    hir::{self, yul, BinOpKind, DataLocation},
+    ty::Gcx,
+};
+use solar_data_structures::{
+    index::IndexVec,
+    map::{FxHashMap, FxHashSet},
+};
+use solar_interface::{error_code, Span};
+
+/// Runs the control-flow checks on the body of the given function.
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if func.is_getter() {
+        return;
+    }
+    let Some(body) = func.body else { return };
+    let cfg = Cfg::new(body);
+    let reachable = cfg.reachable_blocks();
+    check_unreachable(gcx, &cfg, &reachable);
+    if func.kind.is_modifier() {
+        check_placeholder(gcx, func, &cfg);
+    }
+    UnassignedVars::new(gcx, func).check(&cfg);
+}
+
+/// Warns about code that can never be executed.
+///
+/// Overlapping unreachable blocks, such as nested blocks, are reported together.
+fn check_unreachable(gcx: Gcx<'_>, cfg: &Cfg<'_>, reachable: &IndexVec<BasicBlockId, bool>) {
+    let mut spans = cfg
+        .blocks()
+        .iter_enumerated()
+        .filter(|&(id, _)| !reachable[id])
+        .filter_map(|(_, block)| block_span(block))
+        .collect::<Vec<_>>();
+    spans.sort_unstable_by_key(|span| span.lo());
+
+    let mut spans = spans.into_iter().peekable();
+    while let Some(mut span) = spans.next() {
+        while let Some(next) = spans.next_if(|next| next.lo() <= span.hi()) {
+            span = span.to(next);
+        }
+        gcx.dcx().warn("unreachable code").code(error_code!(5740)).span(span).emit();
+    }
+}
+
+/// Returns the span of the code in a basic block, if it contains any.
+fn block_span(block: &BasicBlock<'_>) -> Option<Span> {
+    let terminator = match block.terminator {
+        Terminator::Branch { cond, .. } => Some(cond.span),
+        Terminator::Try { stmt, .. }
+        | Terminator::Return(Some(stmt))
+        | Terminator::Revert(stmt) => Some(stmt.span),
+        Terminator::Goto(_) | Terminator::Return(None) | Terminator::Exit => None,
+    };
+    block.stmts.iter().map(|stmt| stmt.span).chain(terminator).reduce(Span::to)
+}
+
+/// Checks that a modifier executes `_` on all the paths that do not revert.
+fn check_placeholder(gcx: Gcx<'_>, func: &hir::Function<'_>, cfg: &Cfg<'_>) {
+    let span = func.name.map_or(func.span, |name| name.span);
+    let has_placeholder =
+        |block: &BasicBlock<'_>| block.stmts.iter().any(|stmt| is_placeholder(stmt));
+    if !cfg.blocks().iter().any(has_placeholder) {
+        let msg = "modifier body does not contain `_`";
+        gcx.dcx().err(msg).code(error_code!(2883)).span(span).emit();
+        return;
+    }
+
+    let mut visited = IndexVec::from_vec(vec![false; cfg.blocks().len()]);
+    let mut stack = vec![Cfg::ENTRY];
+    while let Some(id) = stack.pop() {
+        if std::mem::replace(&mut visited[id], true) {
+            continue;
+        }
+        let block = cfg.block(id);
+        if has_placeholder(block) {
+            continue;
+        }
+        if let Terminator::Return(stmt) = block.terminator {
+            let mut diag = gcx.dcx().warn("modifier may return without executing `_`").span(span);
+            if let Some(stmt) = stmt {
+                diag = diag.span_note(stmt.span, "returns here");
+            }
+            diag.help("the body of the modified function is not executed on this path").emit();
+            return;
+        }
+        stack.extend(block.terminator.successors());
+    }
+}
+
+fn is_placeholder(stmt: &hir::Stmt<'_>) -> bool {
+    matches!(stmt.kind, hir::StmtKind::Placeholder)
+}
+
+/// Marks the variables that are assigned in an inline assembly block, such as `x.slot := 0`, as
+/// assigned, whether the assignment is always executed or not.
+fn visit_assembly(block: yul::Block<'_>, state: &mut State) {
+    for stmt in block {
+        match stmt.kind {
+            yul::StmtKind::Assign(targets, _) => {
+                for target in targets {
+                    if let yul::ExprKind::External(external) = target.kind {
+                        state.remove(&external.variable);
+                    }
+                }
+            }
+            yul::StmtKind::Block(block) | yul::StmtKind::If(_, block) => {
+                visit_assembly(block, state)
+            }
+            yul::StmtKind::For { init, step, body, .. } => {
+                for block in [init, step, body] {
+                    visit_assembly(block, state);
+                }
+            }
+            yul::StmtKind::Switch(switch) => {
+                for case in switch.cases {
+                    visit_assembly(case.body, state);
+                }
+                if let Some(block) = switch.default_case {
+                    visit_assembly(block, state);
+                }
+            }
+            // Functions cannot access local variables.
+            yul::StmtKind::FunctionDef(_)
+            | yul::StmtKind::Expr(_)
+            | yul::StmtKind::VarDecl(..)
+            | yul::StmtKind::Leave
+            | yul::StmtKind::Break
+            | yul::StmtKind::Continue => {}
+        }
+    }
+}
+
+/// The variables that may not have been assigned yet.
+type State = FxHashSet<hir::VariableId>;
+
+/// Reports the storage pointers and the unnamed return variables that can be used or returned
+/// without having been assigned first.
+struct UnassignedVars<'gcx> {
+    gcx: Gcx<'gcx>,
+    func: &'gcx hir::Function<'gcx>,
+    /// Whether to report the uses of unassigned variables, once the analysis has converged.
+    report: bool,
+    /// The variables that were already reported.
+    reported: FxHashSet<hir::VariableId>,
+}
+
+impl<'gcx> UnassignedVars<'gcx> {
+    fn new(gcx: Gcx<'gcx>, func: &'gcx hir::Function<'gcx>) -> Self {
+        Self { gcx, func, report: false, reported: FxHashSet::default() }
+    }
+
+    fn check(mut self, cfg: &Cfg<'_>) {
+        let initial = self
+            .func
+            .returns
+            .iter()
+            .copied()
+            .filter(|&id| {
+                let var = self.gcx.hir.variable(id);
+                var.name.is_none() || var.data_location == Some(DataLocation::Storage)
+            })
+            .collect::<State>();
+
+        let mut entry_states = FxHashMap::<BasicBlockId, State>::default();
+        entry_states.insert(Cfg::ENTRY, initial);
+        let mut worklist = vec![Cfg::ENTRY];
+        while let Some(id) = worklist.pop() {
+            let mut state = entry_states[&id].clone();
+            self.visit_block(cfg.block(id), &mut state);
+            for succ in cfg.block(id).terminator.successors() {
+                let changed = match entry_states.get_mut(&succ) {
+                    Some(succ_state) => {
+                        let len = succ_state.len();
+                        succ_state.extend(state.iter().copied());
+                        succ_state.len() != len
+                    }
+                    None => {
+                        entry_states.insert(succ, state.clone());
+                        true
+                    }
+                };
+                if changed {
+                    worklist.push(succ);
+                }
+            }
+        }
+
+        self.report = true;
+        for (id, block) in cfg.blocks().iter_enumerated() {
+            if let Some(state) = entry_states.get(&id) {
+                self.visit_block(block, &mut state.clone());
+            }
+        }
+    }
+
+    fn visit_block(&mut self, block: &BasicBlock<'_>, state: &mut State) {
+        for stmt in &block.stmts {
+            match stmt.kind {
+                hir::StmtKind::DeclSingle(id) => {
+                    let var = self.gcx.hir.variable(id);
+                    if let Some(init) = var.initializer {
+                        self.visit_expr(init, state);
+                    } else if var.data_location == Some(DataLocation::Storage) {
+                        state.insert(id);
+                    }
+                }
+                hir::StmtKind::DeclMulti(_, expr) | hir::StmtKind::Expr(expr) => {
+                    self.visit_expr(expr, state)
+                }
+                hir::StmtKind::Emit(_, ref args) => self.visit_args(args, state),
+                hir::StmtKind::Assembly(assembly) => visit_assembly(assembly.block, state),
+                // The statement could not be lowered, and may assign any variable.
+                hir::StmtKind::Err(_) => state.clear(),
+                _ => {}
+            }
+        }
+
+        match block.terminator {
+            Terminator::Branch { cond, .. } => self.visit_expr(cond, state),
+            Terminator::Try { stmt, .. } => {
+                if let hir::StmtKind::Try(try_) = stmt.kind {
+                    self.visit_expr(&try_.expr, state);
+                }
+            }
+            Terminator::Return(stmt) => {
+                if let Some(&hir::Stmt { kind: hir::StmtKind::Return(Some(expr)), .. }) = stmt {
+                    self.visit_expr(expr, state);
+                    return;
+                }
+                for &ret in self.func.returns {
+                    if state.contains(&ret) {
+                        self.report_return(ret);
+                    }
+                }
+            }
+            Terminator::Revert(stmt) => match stmt.kind {
+                hir::StmtKind::Revert(_, ref args) => self.visit_args(args, state),
+                hir::StmtKind::Expr(expr) => self.visit_expr(expr, state),
+                _ => {}
+            },
+            Terminator::Goto(_) | Terminator::Exit => {}
+        }
+    }
+
+    fn visit_args(&mut self, args: &hir::CallArgs<'_>, state: &mut State) {
+        for arg in args_exprs(args) {
+            self.visit_expr(arg, state);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &hir::Expr<'_>, state: &mut State) {
+        match expr.kind {
+            hir::ExprKind::Array(exprs) => {
+                for expr in exprs {
+                    self.visit_expr(expr, state);
+                }
+            }
+            hir::ExprKind::Assign(lhs, op, rhs) => {
+                self.visit_expr(rhs, state);
+                self.visit_assignee(lhs, op.is_none(), state);
+            }
+            hir::ExprKind::Binary(lhs, op, rhs) => {
+                self.visit_expr(lhs, state);
+                if matches!(op.kind, BinOpKind::And | BinOpKind::Or) {
+                    // The right-hand side is not always evaluated.
+                    self.visit_expr(rhs, &mut state.clone());
+                } else {
+                    self.visit_expr(rhs, state);
+                }
+            }
+            hir::ExprKind::Call(callee, ref args) => {
+                self.visit_expr(callee, state);
+                self.visit_args(args, state);
+            }
+            hir::ExprKind::CallOptions(callee, options) => {
+                self.visit_expr(callee, state);
+                for option in options {
+                    self.visit_expr(&option.value, state);
+                }
+            }
+            hir::ExprKind::Delete(inner)
+            | hir::ExprKind::Member(inner, _)
+            | hir::ExprKind::Payable(inner)
+            | hir::ExprKind::Unary(_, inner) => self.visit_expr(inner, state),
+            hir::ExprKind::Ident(_) => {
+                if let Some(id) = local_variable(expr) {
+                    if state.contains(&id) {
+                        self.report_access(id, expr);
+                    }
+                }
+            }
+            hir::ExprKind::Index(base, index) => {
+                self.visit_expr(base, state);
+                if let Some(index) = index {
+                    self.visit_expr(index, state);
+                }
+            }
+            hir::ExprKind::Slice(base, start, end) => {
+                self.visit_expr(base, state);
+                for expr in [start, end].into_iter().flatten() {
+                    self.visit_expr(expr, state);
+                }
+            }
+            hir::ExprKind::Ternary(cond, true_, false_) => {
+                self.visit_expr(cond, state);
+                let mut false_state = state.clone();
+                self.visit_expr(true_, state);
+                self.visit_expr(false_, &mut false_state);
+                state.extend(false_state);
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.visit_expr(expr, state);
+                }
+            }
+            hir::ExprKind::Lit(_)
+            | hir::ExprKind::New(_)
+            | hir::ExprKind::TypeCall(_)
+            | hir::ExprKind::Type(_)
+            | hir::ExprKind::Err(_) => {}
+        }
+    }
+
+    /// Visits the left-hand side of an assignment. `plain` is `false` for compound assignments,
+    /// which also read the variable.
+    fn visit_assignee(&mut self, expr: &hir::Expr<'_>, plain: bool, state: &mut State) {
+        match expr.kind {
+            hir::ExprKind::Ident(_) if plain => {
+                if let Some(id) = local_variable(expr) {
+                    state.remove(&id);
+                }
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.visit_assignee(expr, plain, state);
+                }
+            }
+            _ => self.visit_expr(expr, state),
+        }
+    }
+
+    fn report_access(&mut self, id: hir::VariableId, expr: &hir::Expr<'_>) {
+        if !self.report || !self.reported.insert(id) {
+            return;
+        }
+        let msg = "this variable is of storage pointer type and can be accessed without prior \
+                   assignment, which would lead to undefined behaviour";
+        let var = self.gcx.hir.variable(id);
+        self.gcx
+            .dcx()
+            .err(msg)
+            .code(error_code!(3464))
+            .span(expr.span)
+            .span_note(var.span, "the variable is declared here")
+            .emit();
+    }
+
+    fn report_return(&mut self, id: hir::VariableId) {
+        if !self.report || !self.reported.insert(id) {
+            return;
+        }
+        let var = self.gcx.hir.variable(id);
+        if var.data_location == Some(DataLocation::Storage) {
+            let msg = "this variable is of storage pointer type and can be returned without prior \
+                       assignment, which would lead to undefined behaviour";
+            self.gcx.dcx().err(msg).code(error_code!(3464)).span(var.span).emit();
+        } else {
+            let help = "add an explicit return with value to all non-reverting code paths or \
+                        name the variable";
+            self.gcx
+                .dcx()
+                .warn("unnamed return variable can remain unassigned")
+                .code(error_code!(6321))
+                .span(var.span)
+                .help(help)
+                .emit();
+        }
+    }
+}
+
+/// Returns the variable that the identifier expression resolves to, if any.
+fn local_variable(expr: &hir::Expr<'_>) -> Option<hir::VariableId> {
+    match expr.peel_parens().kind {
+        hir::ExprKind::Ident(&[hir::Res::Item(hir::ItemId::Variable(id))]) => Some(id),
+        _ => None,
+    }
+}
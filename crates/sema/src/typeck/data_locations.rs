@@ -0,0 +1,120 @@
+//! Checks the data locations of variable declarations.
+//!
+//! Reference: <https://docs.soliditylang.org/en/latest/types.html#data-location>
+
+use crate::{
+    hir::{self, DataLocation, Visibility},
+    ty::Gcx,
+};
+use solar_data_structures::smallvec::SmallVec;
+use solar_interface::error_code;
+
+/// Checks the data locations of the parameters and return values of the given function.
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if func.is_getter() {
+        return;
+    }
+    let is_library = func.contract.is_some_and(|c| gcx.hir.contract(c).kind.is_library());
+    let is_external = func.visibility == Visibility::External;
+
+    let mut allowed = SmallVec::<[_; 3]>::new();
+    if is_library || func.visibility <= Visibility::Internal {
+        allowed.push(DataLocation::Storage);
+    }
+    allowed.push(DataLocation::Memory);
+    if is_external || !func.kind.is_constructor() {
+        allowed.push(DataLocation::Calldata);
+    }
+
+    let external = if is_external { " external" } else { "" };
+    let param_desc = format!("parameter in{external} function");
+    for &param in func.parameters {
+        check_location(gcx, param, &allowed, &param_desc);
+    }
+    let ret_desc = format!("return parameter in{external} function");
+    for &ret in func.returns {
+        check_location(gcx, ret, &allowed, &ret_desc);
+    }
+}
+
+/// Checks the data location of a local variable declaration.
+pub(super) fn check_local_var(gcx: Gcx<'_>, id: hir::VariableId) {
+    let allowed = [DataLocation::Storage, DataLocation::Memory, DataLocation::Calldata];
+    check_location(gcx, id, &allowed, "variable");
+}
+
+/// Checks the data location of a `try` return parameter or a `catch` parameter.
+pub(super) fn check_try_catch_param(gcx: Gcx<'_>, id: hir::VariableId) {
+    check_location(gcx, id, &[DataLocation::Memory], "parameter in function");
+}
+
+/// Checks the `transient` data location of a state variable.
+pub(super) fn check_state_var(gcx: Gcx<'_>, id: hir::VariableId) {
+    let var = gcx.hir.variable(id);
+    if var.data_location != Some(DataLocation::Transient) {
+        return;
+    }
+    if var.mutability.is_some() {
+        let msg = "`transient` cannot be used as data location for constant or immutable variables";
+        gcx.dcx().err(msg).span(var.span).emit();
+    } else if gcx.type_of_item(id.into()).is_reference_type() {
+        let msg = "`transient` data location is only supported for value types";
+        gcx.dcx().err(msg).span(var.span).emit();
+    }
+}
+
+fn check_location(gcx: Gcx<'_>, id: hir::VariableId, allowed: &[DataLocation], desc: &str) {
+    let var = gcx.hir.variable(id);
+    let ty = gcx.type_of_item(id.into());
+    if ty.has_error() {
+        return;
+    }
+
+    let given = var.data_location;
+    if !ty.is_reference_type() {
+        if let Some(loc) = given {
+            let msg = format!(
+                "data location can only be specified for array, struct or mapping types, \
+                 but `{loc}` was given"
+            );
+            gcx.dcx().err(msg).code(error_code!(6651)).span(var.span).emit();
+        }
+        return;
+    }
+
+    if !given.is_some_and(|loc| allowed.contains(&loc)) {
+        let given = match given {
+            Some(loc) => format!("`{loc}`"),
+            None => "none".to_string(),
+        };
+        let msg = format!(
+            "data location must be {} for {desc}, but {given} was given",
+            fmt_locations(allowed)
+        );
+        gcx.dcx().err(msg).code(error_code!(6651)).span(var.span).emit();
+        return;
+    }
+
+    if ty.has_mapping() && !ty.is_ref_at(DataLocation::Storage) {
+        let msg = format!(
+            "type `{}` is only valid in storage because it contains a (nested) mapping",
+            ty.peel_refs().display(gcx)
+        );
+        gcx.dcx().err(msg).code(error_code!(4061)).span(var.span).emit();
+    }
+}
+
+/// Formats a list of data locations, e.g. "`storage`, `memory` or `calldata`".
+fn fmt_locations(locations: &[DataLocation]) -> String {
+    let mut s = String::new();
+    for (i, loc) in locations.iter().enumerate() {
+        if i > 0 {
+            s.push_str(if i == locations.len() - 1 { " or " } else { ", " });
+        }
+        s.push('`');
+        s.push_str(loc.to_str());
+        s.push('`');
+    }
+    s
+}
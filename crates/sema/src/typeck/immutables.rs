@@ -0,0 +1,666 @@
+//! Checks the initialization of immutable variables, and collects their references.
+//!
+//! Immutable variables must be initialized exactly once, either inline or in the constructor of
+//! the contract that defines them, outside of loops and `try` statements. They cannot be read
+//! before being initialized, including in the internal functions and modifiers that the
+//! constructor calls.
+//!
+//! Reference: <https://docs.soliditylang.org/en/latest/contracts.html#immutable>
+
+use super::{checker::args_exprs, overrides::override_key};
+use crate::{
+    builtins::Builtin,
+    cfg::{BasicBlockId, Cfg, Terminator},
+    hir::{self, VarMut},
+    ty::{Gcx, ImmutableReference},
+};
+use solar_data_structures::{
+    map::{FxHashMap, FxHashSet},
+    smallvec::SmallVec,
+    BumpExt,
+};
+use solar_interface::{error_code, Span};
+
+/// Checks the immutable variables declared in the given contract, and returns the references to
+/// immutable variables in its runtime code.
+pub(crate) fn check_contract(gcx: Gcx<'_>, id: hir::ContractId) -> &[ImmutableReference] {
+    let contract = gcx.hir.contract(id);
+    let immutables = contract
+        .variables()
+        .filter(|&var| gcx.hir.variable(var).mutability == Some(VarMut::Immutable))
+        .collect::<SmallVec<[_; 8]>>();
+    if !immutables.is_empty() {
+        check_initializers(gcx, id, contract);
+        check_constructor(gcx, id, contract, &immutables);
+    }
+    collect_references(gcx, contract)
+}
+
+/// Checks that the state variable initializers only read immutable variables that were
+/// initialized inline before them.
+fn check_initializers(gcx: Gcx<'_>, id: hir::ContractId, contract: &hir::Contract<'_>) {
+    let mut initialized = FxHashSet::default();
+    let mut reported = FxHashSet::default();
+    for var_id in contract.variables() {
+        let var = gcx.hir.variable(var_id);
+        let Some(init) = var.initializer else { continue };
+        for (access, expr) in accesses(gcx, init) {
+            let (reads, call) = match access {
+                Access::Read(var) => (vec![(expr, var)], None),
+                Access::Call(f, virtual_) => (called_reads(gcx, id, f, virtual_), Some(expr.span)),
+                Access::Write(_) => continue,
+            };
+            for (expr, read) in reads {
+                if gcx.hir.variable(read).contract == Some(id)
+                    && !initialized.contains(&read)
+                    && reported.insert(read)
+                {
+                    read_before_initialization(gcx, expr, read, call);
+                }
+            }
+        }
+        if var.mutability == Some(VarMut::Immutable) {
+            initialized.insert(var_id);
+        }
+    }
+}
+
+/// Checks the assignments to immutable variables in the constructor, and that all the immutable
+/// variables are initialized by the end of the construction.
+fn check_constructor(
+    gcx: Gcx<'_>,
+    id: hir::ContractId,
+    contract: &hir::Contract<'_>,
+    immutables: &[hir::VariableId],
+) {
+    let inline =
+        || immutables.iter().copied().filter(|&id| gcx.hir.variable(id).initializer.is_some());
+    let Some((ctor, body)) = contract.ctor.and_then(|ctor| {
+        let ctor = gcx.hir.function(ctor);
+        Some((ctor, ctor.body?))
+    }) else {
+        let missing = immutables.iter().copied().filter(|id| !inline().any(|i| i == *id));
+        report_uninitialized(gcx, contract.name.span, missing);
+        return;
+    };
+
+    // Don't report the same variable again if it is assigned in a loop.
+    let mut in_context = FxHashSet::default();
+    check_assignment_context(gcx, body, None, &mut in_context);
+
+    let mut checker = ConstructorChecker {
+        gcx,
+        contract: id,
+        report: true,
+        in_context,
+        reported_reads: FxHashSet::default(),
+        uninitialized: SmallVec::new(),
+    };
+    let mut initial = State::default();
+    for id in inline() {
+        initial.maybe.insert(id, gcx.hir.variable(id).initializer.unwrap().span);
+        initial.definitely.insert(id);
+    }
+
+    // The modifiers of the constructor run before its body.
+    for modifier in ctor.modifiers {
+        if let hir::ItemId::Function(f) = modifier.id {
+            checker.visit_call(f, true, modifier.span, &initial);
+        }
+    }
+    checker.report = false;
+
+    let cfg = Cfg::new(body);
+    let mut entry_states = FxHashMap::<BasicBlockId, State>::default();
+    entry_states.insert(Cfg::ENTRY, initial);
+    let mut worklist = vec![Cfg::ENTRY];
+    while let Some(id) = worklist.pop() {
+        let mut state = entry_states[&id].clone();
+        checker.visit_block(&cfg, id, &mut state);
+        for succ in cfg.block(id).terminator.successors() {
+            let changed = match entry_states.get_mut(&succ) {
+                Some(succ_state) => succ_state.merge(&state),
+                None => {
+                    entry_states.insert(succ, state.clone());
+                    true
+                }
+            };
+            if changed {
+                worklist.push(succ);
+            }
+        }
+    }
+
+    checker.report = true;
+    for id in cfg.blocks().indices() {
+        if let Some(state) = entry_states.get(&id) {
+            let mut state = state.clone();
+            checker.visit_block(&cfg, id, &mut state);
+            if let Terminator::Return(_) = cfg.block(id).terminator {
+                for &var in immutables {
+                    if !state.definitely.contains(&var)
+                        && !checker.in_context.contains(&var)
+                        && !checker.uninitialized.contains(&var)
+                    {
+                        checker.uninitialized.push(var);
+                    }
+                }
+            }
+        }
+    }
+
+    report_uninitialized(gcx, contract.name.span, checker.uninitialized.into_iter());
+}
+
+/// Reports the immutable variables that are not initialized by the end of the construction.
+fn report_uninitialized(gcx: Gcx<'_>, span: Span, vars: impl Iterator<Item = hir::VariableId>) {
+    let mut vars = vars.peekable();
+    if vars.peek().is_none() {
+        return;
+    }
+    let msg = "construction control flow ends without initializing all immutable variables";
+    let mut diag = gcx.dcx().err(msg).code(error_code!(2658)).span(span);
+    for var in vars {
+        let note = format!("`{}` is not initialized", gcx.item_name(var));
+        diag = diag.span_note(gcx.item_span(var), note);
+    }
+    diag.help("immutable variables must be initialized inline or in the constructor").emit();
+}
+
+/// Reports a read of an immutable variable before its initialization. `call` is the span of the
+/// call through which the variable is read, if it is read in another function.
+fn read_before_initialization(
+    gcx: Gcx<'_>,
+    expr: &hir::Expr<'_>,
+    id: hir::VariableId,
+    call: Option<Span>,
+) {
+    let msg = "immutable variables cannot be read before they are initialized";
+    let var = gcx.hir.variable(id);
+    let mut diag = gcx
+        .dcx()
+        .err(msg)
+        .code(error_code!(7733))
+        .span(expr.span)
+        .span_note(var.span, "the variable is declared here");
+    if let Some(call) = call {
+        diag = diag.span_note(call, "the variable is read through this call");
+    }
+    diag.emit();
+}
+
+/// Reports the assignments to immutable variables in loops and `try` statements.
+///
+/// `context` is the kind of the innermost enclosing statement that disallows assignments.
+fn check_assignment_context<'gcx>(
+    gcx: Gcx<'gcx>,
+    block: hir::Block<'gcx>,
+    context: Option<&str>,
+    reported: &mut FxHashSet<hir::VariableId>,
+) {
+    for stmt in block {
+        let (blocks, inner_context): (SmallVec<[_; 2]>, _) = match stmt.kind {
+            hir::StmtKind::Block(block) | hir::StmtKind::UncheckedBlock(block) => {
+                (smallvec_of([block]), context)
+            }
+            hir::StmtKind::Loop(block, _) => (smallvec_of([block]), Some("a loop")),
+            hir::StmtKind::If(_, then, else_) => {
+                (std::iter::once(then).chain(else_).map(std::slice::from_ref).collect(), context)
+            }
+            hir::StmtKind::Try(try_) => (
+                std::iter::once(try_.block).chain(try_.catch.iter().map(|c| c.block)).collect(),
+                Some("a `try`/`catch` statement"),
+            ),
+            _ => (SmallVec::new(), context),
+        };
+        if let Some(context) = context {
+            for expr in stmt_exprs(gcx, stmt) {
+                for (access, expr) in accesses(gcx, expr) {
+                    if let Access::Write(var) = access {
+                        reported.insert(var);
+                        let msg =
+                            format!("immutable variables cannot be initialized inside {context}");
+                        gcx.dcx().err(msg).code(error_code!(6672)).span(expr.span).emit();
+                    }
+                }
+            }
+        }
+        for block in blocks {
+            check_assignment_context(gcx, block, inner_context, reported);
+        }
+    }
+}
+
+fn smallvec_of<T, const N: usize>(items: [T; N]) -> SmallVec<[T; 2]> {
+    items.into_iter().collect()
+}
+
+/// The immutable variables that are initialized at a point of the constructor.
+#[derive(Clone, Default)]
+struct State {
+    /// The variables that may be initialized, along with the span of one of their
+    /// initializations.
+    maybe: FxHashMap<hir::VariableId, Span>,
+    /// The variables that are initialized on all paths.
+    definitely: FxHashSet<hir::VariableId>,
+}
+
+impl State {
+    /// Merges the state of another predecessor into `self`. Returns `true` if it changed.
+    fn merge(&mut self, other: &Self) -> bool {
+        let len = (self.maybe.len(), self.definitely.len());
+        for (&var, &span) in &other.maybe {
+            self.maybe.entry(var).or_insert(span);
+        }
+        self.definitely.retain(|var| other.definitely.contains(var));
+        len != (self.maybe.len(), self.definitely.len())
+    }
+}
+
+struct ConstructorChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    contract: hir::ContractId,
+    /// Whether to report errors, once the analysis has converged.
+    report: bool,
+    /// The variables that are assigned in a loop or a `try` statement, which were already
+    /// reported.
+    in_context: FxHashSet<hir::VariableId>,
+    /// The variables whose reads before initialization were already reported.
+    reported_reads: FxHashSet<hir::VariableId>,
+    /// The variables that may not be initialized when the constructor returns.
+    uninitialized: SmallVec<[hir::VariableId; 4]>,
+}
+
+impl<'gcx> ConstructorChecker<'gcx> {
+    fn visit_block(&mut self, cfg: &Cfg<'gcx>, id: BasicBlockId, state: &mut State) {
+        let gcx = self.gcx;
+        let block = cfg.block(id);
+        let terminator_exprs: SmallVec<[_; 2]> = match block.terminator {
+            Terminator::Branch { cond, .. } => smallvec_of([cond]),
+            Terminator::Try { stmt, .. }
+            | Terminator::Return(Some(stmt))
+            | Terminator::Revert(stmt) => stmt_exprs(gcx, stmt),
+            Terminator::Goto(_) | Terminator::Return(None) | Terminator::Exit => SmallVec::new(),
+        };
+        let exprs =
+            block.stmts.iter().flat_map(|stmt| stmt_exprs(gcx, stmt)).chain(terminator_exprs);
+        for expr in exprs {
+            for (access, expr) in accesses(self.gcx, expr) {
+                let var = match access {
+                    Access::Read(var) | Access::Write(var) => var,
+                    Access::Call(f, virtual_) => {
+                        self.visit_call(f, virtual_, expr.span, state);
+                        continue;
+                    }
+                };
+                if self.gcx.hir.variable(var).contract != Some(self.contract) {
+                    continue;
+                }
+                match access {
+                    Access::Read(_) => self.visit_read(expr, var, None, state),
+                    Access::Write(_) => {
+                        if let Some(&prev) = state.maybe.get(&var) {
+                            if self.report && !self.in_context.contains(&var) {
+                                let msg = "immutable state variable already initialized";
+                                self.gcx
+                                    .dcx()
+                                    .err(msg)
+                                    .code(error_code!(1574))
+                                    .span(expr.span)
+                                    .span_note(prev, "the variable may already be initialized here")
+                                    .emit();
+                            }
+                        }
+                        state.maybe.entry(var).or_insert(expr.span);
+                        state.definitely.insert(var);
+                    }
+                    Access::Call(..) => unreachable!(),
+                }
+            }
+        }
+    }
+
+    /// Checks the reads of immutable variables in the function called at `span`.
+    fn visit_call(&mut self, f: hir::FunctionId, virtual_: bool, span: Span, state: &State) {
+        for (expr, var) in called_reads(self.gcx, self.contract, f, virtual_) {
+            if self.gcx.hir.variable(var).contract == Some(self.contract) {
+                self.visit_read(expr, var, Some(span), state);
+            }
+        }
+    }
+
+    fn visit_read(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        var: hir::VariableId,
+        call: Option<Span>,
+        state: &State,
+    ) {
+        if self.report && !state.definitely.contains(&var) && self.reported_reads.insert(var) {
+            read_before_initialization(self.gcx, expr, var, call);
+        }
+    }
+}
+
+/// Returns the reads of immutable variables in the given internal function or modifier, and in
+/// the ones that it calls, transitively.
+///
+/// `contract` is the contract being constructed, which determines the implementation of the
+/// functions that are called virtually.
+fn called_reads<'gcx>(
+    gcx: Gcx<'gcx>,
+    contract: hir::ContractId,
+    f: hir::FunctionId,
+    virtual_: bool,
+) -> Vec<(&'gcx hir::Expr<'gcx>, hir::VariableId)> {
+    let mut reads = Vec::new();
+    let mut visited = FxHashSet::default();
+    let mut stack = vec![(f, virtual_)];
+    while let Some((f, virtual_)) = stack.pop() {
+        let f = if virtual_ { resolve_virtual(gcx, contract, f) } else { f };
+        if !visited.insert(f) {
+            continue;
+        }
+        let func = gcx.hir.function(f);
+        for modifier in func.modifiers {
+            if let hir::ItemId::Function(m) = modifier.id {
+                stack.push((m, true));
+            }
+            for arg in args_exprs(&modifier.args) {
+                push_reads(gcx, arg, &mut reads, &mut stack);
+            }
+        }
+        if let Some(body) = func.body {
+            for_each_expr_in_block(gcx, body, &mut |expr| {
+                push_reads(gcx, expr, &mut reads, &mut stack);
+            });
+        }
+    }
+    reads
+}
+
+fn push_reads<'gcx>(
+    gcx: Gcx<'gcx>,
+    expr: &'gcx hir::Expr<'gcx>,
+    reads: &mut Vec<(&'gcx hir::Expr<'gcx>, hir::VariableId)>,
+    calls: &mut Vec<(hir::FunctionId, bool)>,
+) {
+    for (access, expr) in accesses(gcx, expr) {
+        match access {
+            Access::Read(var) => reads.push((expr, var)),
+            Access::Call(f, virtual_) => calls.push((f, virtual_)),
+            Access::Write(_) => {}
+        }
+    }
+}
+
+/// Returns the implementation of the given function in the most derived contract.
+fn resolve_virtual(
+    gcx: Gcx<'_>,
+    contract: hir::ContractId,
+    id: hir::FunctionId,
+) -> hir::FunctionId {
+    let func = gcx.hir.function(id);
+    if !func.virtual_ || func.contract.is_none() {
+        return id;
+    }
+    let key = override_key(gcx, id);
+    gcx.hir
+        .contract(contract)
+        .linearized_bases
+        .iter()
+        .flat_map(|&base| gcx.hir.contract(base).functions())
+        .find(|&f| gcx.hir.function(f).body.is_some() && override_key(gcx, f) == key)
+        .unwrap_or(id)
+}
+
+/// Collects the reads of immutable variables in the functions and modifiers of the contract and
+/// of its bases, except for the constructors.
+fn collect_references<'gcx>(
+    gcx: Gcx<'gcx>,
+    contract: &hir::Contract<'_>,
+) -> &'gcx [ImmutableReference] {
+    let mut references = Vec::new();
+    for &base in contract.linearized_bases {
+        for f in gcx.hir.contract(base).functions() {
+            let func = gcx.hir.function(f);
+            if func.kind.is_constructor() {
+                continue;
+            }
+            let Some(body) = func.body else { continue };
+            for_each_expr_in_block(gcx, body, &mut |expr| {
+                for (access, expr) in accesses(gcx, expr) {
+                    if let Access::Read(variable) | Access::Write(variable) = access {
+                        references.push(ImmutableReference {
+                            variable,
+                            expr: expr.id,
+                            span: expr.span,
+                        });
+                    }
+                }
+            });
+        }
+    }
+    gcx.bump().alloc_vec(references)
+}
+
+/// Calls `f` on the top-level expressions of all the statements in the block, recursively.
+fn for_each_expr_in_block<'hir>(
+    gcx: Gcx<'hir>,
+    block: hir::Block<'hir>,
+    f: &mut impl FnMut(&'hir hir::Expr<'hir>),
+) {
+    for stmt in block {
+        stmt_exprs(gcx, stmt).into_iter().for_each(&mut *f);
+        match stmt.kind {
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => for_each_expr_in_block(gcx, block, f),
+            hir::StmtKind::If(_, then, else_) => {
+                for stmt in std::iter::once(then).chain(else_) {
+                    for_each_expr_in_block(gcx, std::slice::from_ref(stmt), f);
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                for_each_expr_in_block(gcx, try_.block, f);
+                for clause in try_.catch {
+                    for_each_expr_in_block(gcx, clause.block, f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the expressions that are evaluated by the statement itself, excluding the ones in
+/// nested statements.
+fn stmt_exprs<'hir>(
+    gcx: Gcx<'hir>,
+    stmt: &'hir hir::Stmt<'hir>,
+) -> SmallVec<[&'hir hir::Expr<'hir>; 2]> {
+    match stmt.kind {
+        hir::StmtKind::DeclSingle(id) => gcx.hir.variable(id).initializer.into_iter().collect(),
+        hir::StmtKind::DeclMulti(_, expr)
+        | hir::StmtKind::Expr(expr)
+        | hir::StmtKind::Return(Some(expr))
+        | hir::StmtKind::If(expr, ..) => smallvec_of([expr]),
+        hir::StmtKind::Emit(_, ref args) | hir::StmtKind::Revert(_, ref args) => {
+            args_exprs(args).collect()
+        }
+        hir::StmtKind::Try(try_) => smallvec_of([&try_.expr]),
+        hir::StmtKind::Assembly(_)
+        | hir::StmtKind::Block(_)
+        | hir::StmtKind::UncheckedBlock(_)
+        | hir::StmtKind::Return(None)
+        | hir::StmtKind::Break
+        | hir::StmtKind::Continue
+        | hir::StmtKind::Loop(..)
+        | hir::StmtKind::Placeholder
+        | hir::StmtKind::Err(_) => SmallVec::new(),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Access {
+    /// A read of an immutable variable.
+    Read(hir::VariableId),
+    /// An assignment to an immutable variable.
+    Write(hir::VariableId),
+    /// An internal call to a function, and whether the call is virtual.
+    Call(hir::FunctionId, bool),
+}
+
+/// Returns the accesses to immutable variables and the internal calls in the expression, in
+/// evaluation order.
+fn accesses<'hir>(
+    gcx: Gcx<'_>,
+    expr: &'hir hir::Expr<'hir>,
+) -> Vec<(Access, &'hir hir::Expr<'hir>)> {
+    let mut accesses = Vec::new();
+    visit_expr(gcx, expr, &mut accesses);
+    accesses
+}
+
+fn visit_expr<'hir>(
+    gcx: Gcx<'_>,
+    expr: &'hir hir::Expr<'hir>,
+    accesses: &mut Vec<(Access, &'hir hir::Expr<'hir>)>,
+) {
+    macro_rules! visit {
+        ($expr:expr) => {
+            visit_expr(gcx, $expr, accesses)
+        };
+    }
+    match expr.kind {
+        hir::ExprKind::Array(exprs) => {
+            for expr in exprs {
+                visit!(expr);
+            }
+        }
+        hir::ExprKind::Assign(lhs, op, rhs) => {
+            visit!(rhs);
+            visit_assignee(gcx, lhs, op.is_none(), accesses);
+        }
+        hir::ExprKind::Binary(lhs, _, rhs) => {
+            visit!(lhs);
+            visit!(rhs);
+        }
+        hir::ExprKind::Call(callee, ref args) => {
+            visit!(callee);
+            for arg in args_exprs(args) {
+                visit!(arg);
+            }
+            if let Some((f, virtual_)) = internal_callee(gcx, callee) {
+                accesses.push((Access::Call(f, virtual_), expr));
+            }
+        }
+        hir::ExprKind::CallOptions(callee, options) => {
+            visit!(callee);
+            for option in options {
+                visit!(&option.value);
+            }
+        }
+        hir::ExprKind::Delete(inner)
+        | hir::ExprKind::Member(inner, _)
+        | hir::ExprKind::Payable(inner)
+        | hir::ExprKind::Unary(_, inner) => visit!(inner),
+        hir::ExprKind::Ident(_) => {
+            if let Some(id) = immutable_variable(gcx, expr) {
+                accesses.push((Access::Read(id), expr));
+            }
+        }
+        hir::ExprKind::Index(base, index) => {
+            visit!(base);
+            if let Some(index) = index {
+                visit!(index);
+            }
+        }
+        hir::ExprKind::Slice(base, start, end) => {
+            visit!(base);
+            for expr in [start, end].into_iter().flatten() {
+                visit!(expr);
+            }
+        }
+        hir::ExprKind::Ternary(cond, true_, false_) => {
+            visit!(cond);
+            visit!(true_);
+            visit!(false_);
+        }
+        hir::ExprKind::Tuple(exprs) => {
+            for expr in exprs.iter().flatten() {
+                visit!(expr);
+            }
+        }
+        hir::ExprKind::Lit(_)
+        | hir::ExprKind::New(_)
+        | hir::ExprKind::TypeCall(_)
+        | hir::ExprKind::Type(_)
+        | hir::ExprKind::Err(_) => {}
+    }
+}
+
+/// Visits the left-hand side of an assignment. `plain` is `false` for compound assignments,
+/// which also read the variable.
+fn visit_assignee<'hir>(
+    gcx: Gcx<'_>,
+    expr: &'hir hir::Expr<'hir>,
+    plain: bool,
+    accesses: &mut Vec<(Access, &'hir hir::Expr<'hir>)>,
+) {
+    match expr.peel_parens().kind {
+        hir::ExprKind::Ident(_) => {
+            if let Some(id) = immutable_variable(gcx, expr) {
+                if !plain {
+                    accesses.push((Access::Read(id), expr));
+                }
+                accesses.push((Access::Write(id), expr));
+            }
+        }
+        hir::ExprKind::Tuple(exprs) => {
+            for expr in exprs.iter().flatten() {
+                visit_assignee(gcx, expr, plain, accesses);
+            }
+        }
+        _ => visit_expr(gcx, expr, accesses),
+    }
+}
+
+/// Returns the immutable variable that the identifier expression resolves to, if any.
+fn immutable_variable(gcx: Gcx<'_>, expr: &hir::Expr<'_>) -> Option<hir::VariableId> {
+    let expr = expr.peel_parens();
+    let res = match expr.kind {
+        // Overloaded identifiers are disambiguated during type checking.
+        hir::ExprKind::Ident(&[res]) => res,
+        hir::ExprKind::Ident(_) => gcx.res_of_expr(expr.id)?,
+        _ => return None,
+    };
+    match res {
+        hir::Res::Item(hir::ItemId::Variable(id))
+            if gcx.hir.variable(id).mutability == Some(VarMut::Immutable) =>
+        {
+            Some(id)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the function that the callee of an internal call refers to, if any, and whether the
+/// call is virtual.
+///
+/// Calls through `super` and through the name of a base contract or library are not virtual.
+fn internal_callee(gcx: Gcx<'_>, callee: &hir::Expr<'_>) -> Option<(hir::FunctionId, bool)> {
+    let callee = callee.peel_parens();
+    let Some(hir::Res::Item(hir::ItemId::Function(id))) = gcx.res_of_expr(callee.id) else {
+        return None;
+    };
+    match callee.kind {
+        hir::ExprKind::Ident(_) => Some((id, true)),
+        hir::ExprKind::Member(base, _) => match base.peel_parens().kind {
+            hir::ExprKind::Ident(
+                [hir::Res::Builtin(Builtin::Super) | hir::Res::Item(hir::ItemId::Contract(_))],
+            ) => Some((id, false)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -1,10 +1,21 @@
 use crate::{
     ast_lowering::resolve::{Declaration, Declarations},
     hir::{self, Res},
-    ty::{Gcx, Ty},
+    ty::{Gcx, Ty, TyKind},
 };
 use rayon::prelude::*;
-use solar_data_structures::{map::FxHashSet, parallel};
+use solar_data_structures::{
+    map::{FxHashMap, FxHashSet},
+    parallel,
+};
+
+mod checker;
+mod contracts;
+mod control_flow;
+mod data_locations;
+pub(crate) mod immutables;
+mod mutability;
+mod overrides;
 
 pub(crate) fn check(gcx: Gcx<'_>) {
     parallel!(
@@ -15,7 +26,58 @@ pub(crate) fn check(gcx: Gcx<'_>) {
         gcx.hir.par_source_ids().for_each(|id| {
             check_duplicate_definitions(gcx, &gcx.symbol_resolver.source_scopes[id]);
         }),
+        gcx.hir.par_contract_ids().for_each(|id| {
+            check_using_directives(gcx, gcx.hir.contract(id).using_directives, Some(id));
+        }),
+        gcx.hir.par_sources().for_each(|source| {
+            check_using_directives(gcx, source.using_directives, None);
+        }),
+        gcx.hir.par_event_ids().for_each(|id| {
+            check_event_parameters(gcx, id);
+        }),
+        gcx.hir.par_contract_ids().for_each(|id| {
+            overrides::check_contract(gcx, id);
+        }),
+        gcx.hir.par_contract_ids().for_each(|id| {
+            contracts::check_contract(gcx, id);
+        }),
+        gcx.hir.par_function_ids().for_each(|id| {
+            if gcx.hir.function(id).is_free() {
+                overrides::check_free_function(gcx, id);
+            }
+        }),
+        gcx.hir.par_function_ids().for_each(|id| {
+            data_locations::check_function(gcx, id);
+        }),
+        gcx.hir.par_function_ids().for_each(|id| {
+            control_flow::check_function(gcx, id);
+        }),
+        gcx.hir.par_contracts().for_each(|contract| {
+            contract.variables().for_each(|id| data_locations::check_state_var(gcx, id));
+        }),
+        gcx.hir.par_function_ids().for_each(|id| {
+            checker::check_function(gcx, id);
+        }),
+        gcx.hir.par_contract_ids().for_each(|id| {
+            checker::check_contract(gcx, id);
+        }),
+        gcx.hir.par_contracts().for_each(|contract| {
+            contract.variables().for_each(|id| checker::check_var(gcx, id));
+        }),
+        gcx.hir.par_sources().for_each(|source| {
+            source.items.iter().filter_map(hir::ItemId::as_variable).for_each(|id| {
+                checker::check_var(gcx, id);
+            });
+        }),
     );
+
+    // Requires the types and resolutions of the expressions in function bodies.
+    gcx.hir.par_function_ids().for_each(|id| {
+        mutability::check_function(gcx, id);
+    });
+    gcx.hir.par_contract_ids().for_each(|id| {
+        let _ = gcx.immutable_references(id);
+    });
 }
 
 /// Checks for definitions that have the same name and parameter types in the given scope.
@@ -76,6 +138,130 @@ fn check_duplicate_definitions(gcx: Gcx<'_>, scope: &Declarations) {
     }
 }
 
+/// Warns about `indexed` event parameters of dynamic types, which are logged as the hash of their
+/// value instead of the value itself.
+fn check_event_parameters(gcx: Gcx<'_>, id: hir::EventId) {
+    for &param in gcx.hir.event(id).parameters {
+        let var = gcx.hir.variable(param);
+        if !var.indexed {
+            continue;
+        }
+        let ty = gcx.type_of_item(param.into());
+        if !is_dynamically_sized(gcx, ty) {
+            continue;
+        }
+        let msg = format!(
+            "only the hash of `indexed` parameters of type `{}` is logged",
+            ty.display(gcx)
+        );
+        gcx.dcx()
+            .warn(msg)
+            .span(var.span)
+            .note("the value itself cannot be recovered from the event log")
+            .help("remove `indexed` to log the value")
+            .emit();
+    }
+}
+
+/// Returns `true` if the ABI encoding of the type has a dynamic size.
+fn is_dynamically_sized<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> bool {
+    match ty.kind {
+        TyKind::Elementary(hir::ElementaryType::String | hir::ElementaryType::Bytes)
+        | TyKind::DynArray(_) => true,
+        TyKind::Ref(ty, _) | TyKind::Array(ty, _) => is_dynamically_sized(gcx, ty),
+        TyKind::Struct(id) => {
+            ty.is_recursive()
+                || gcx.struct_field_types(id).iter().any(|&ty| is_dynamically_sized(gcx, ty))
+        }
+        _ => false,
+    }
+}
+
+/// Checks that the functions attached with `using for` directives can be bound to the type, and
+/// that the user-defined operators have the correct signature and are defined only once.
+fn check_using_directives(
+    gcx: Gcx<'_>,
+    directives: &[hir::UsingDirective<'_>],
+    contract: Option<hir::ContractId>,
+) {
+    let mut operators = FxHashMap::default();
+    for directive in directives {
+        let hir::UsingList::Functions(functions) = directive.list else { continue };
+        let ty = directive.ty.as_ref().map(|ty| gcx.type_of_hir_ty(ty));
+        for f in functions {
+            let func = gcx.hir.function(f.function);
+            let name = gcx.item_canonical_name(f.function);
+            if func.visibility == hir::Visibility::Private && func.contract != contract {
+                let msg = format!(
+                    "function `{name}` is private and therefore cannot be attached to a type outside of the library where it is defined"
+                );
+                gcx.dcx().err(msg).span(f.span).emit();
+                continue;
+            }
+            let Some(ty) = ty.filter(|ty| !ty.has_error()) else { continue };
+            let f_ty = gcx.type_of_item(f.function.into());
+            let params = f_ty.parameters().unwrap_or_default();
+            let returns = f_ty.returns().unwrap_or_default();
+            let Some(operator) = f.operator else {
+                let Some(&first) = params.first() else {
+                    let msg = format!(
+                        "function `{name}` does not have any parameters and cannot be attached to the type `{}`",
+                        ty.display(gcx)
+                    );
+                    gcx.dcx().err(msg).span(f.span).emit();
+                    continue;
+                };
+                if ty.is_implicitly_convertible_to(gcx, first).is_err() {
+                    let msg = format!(
+                        "function `{name}` cannot be attached to the type `{}` because the type cannot be implicitly converted to the first parameter of the function (`{}`)",
+                        ty.display(gcx),
+                        first.display(gcx),
+                    );
+                    gcx.dcx().err(msg).span(f.span).emit();
+                }
+                continue;
+            };
+
+            if !matches!(ty.kind, TyKind::Udvt(..)) {
+                continue;
+            }
+            let op = operator.to_op();
+            let op_str = op.either(|op| op.to_str(), |op| op.to_str());
+            let unary =
+                op.is_left() || (operator == hir::UserDefinableOperator::Sub && params.len() == 1);
+            let (n_params, n_params_str) =
+                if unary { (1, "one parameter") } else { (2, "two parameters") };
+            if params.len() != n_params || params.iter().any(|&param| param != ty) {
+                let msg = format!(
+                    "wrong parameters in operator definition: the function `{name}` needs to have {n_params_str} of type `{}` to be used for the operator `{op_str}`",
+                    ty.display(gcx)
+                );
+                gcx.dcx().err(msg).span(f.span).emit();
+                continue;
+            }
+            let ret =
+                if op.right().is_some_and(checker::is_comparison) { gcx.types.bool } else { ty };
+            if returns != [ret] {
+                let msg = format!(
+                    "wrong return parameters in operator definition: the function `{name}` needs to return exactly one value of type `{}` to be used for the operator `{op_str}`",
+                    ret.display(gcx)
+                );
+                gcx.dcx().err(msg).span(f.span).emit();
+                continue;
+            }
+            if let Some(&prev) = operators.get(&(ty, operator, unary)) {
+                let msg = format!(
+                    "user-defined operator `{op_str}` for type `{}` is defined multiple times",
+                    ty.display(gcx)
+                );
+                gcx.dcx().err(msg).span(f.span).span_note(prev, "previous definition here").emit();
+                continue;
+            }
+            operators.insert((ty, operator, unary), f.span);
+        }
+    }
+}
+
 fn same_external_params<'gcx>(gcx: Gcx<'gcx>, a: Ty<'gcx>, b: Ty<'gcx>) -> bool {
     let key = |ty: Ty<'gcx>| ty.as_externally_callable_function(gcx).parameters().unwrap();
     key(a) == key(b)
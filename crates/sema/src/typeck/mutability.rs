@@ -0,0 +1,482 @@
+//! Infers the state mutability that function bodies require, and checks it against the declared
+//! state mutability.
+//!
+//! Reference: <https://docs.soliditylang.org/en/latest/contracts.html#state-mutability>
+
+use super::checker::args_exprs;
+use crate::{
+    builtins::Builtin,
+    hir::{self, yul, ContractKind, Res, StateMutability, UnOpKind, Visibility},
+    ty::{Gcx, Ty, TyKind},
+};
+use solar_ast::ast::DataLocation;
+use solar_interface::{error_code, sym, Span};
+
+/// Checks the body of the given function against its declared state mutability, and warns if the
+/// declared state mutability is less restrictive than necessary.
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    if func.is_getter() || func.kind.is_modifier() {
+        return;
+    }
+    check_declaration(gcx, func);
+    let Some(body) = func.body else { return };
+
+    let mut checker = MutabilityChecker::new(gcx, Some(func));
+    checker.visit_block(body);
+    for modifier in func.modifiers {
+        checker.visit_args(&modifier.args);
+        let Some(id) = modifier.id.as_function() else { continue };
+        let id = func.contract.map_or(id, |c| gcx.hir.resolve_modifier(id, c));
+        checker.visit_modifier(id);
+    }
+
+    let required = checker.required;
+    let declared = func.state_mutability;
+    let can_be_restricted = !checker.errored
+        && !checker.incomplete
+        && !body.is_empty()
+        && rank(required) < rank(declared)
+        && declared != StateMutability::Payable
+        && func.kind.is_function()
+        && !func.virtual_;
+    if can_be_restricted {
+        let msg = format!("function state mutability can be restricted to `{required}`");
+        let span = func.name.map_or(func.span, |name| name.span);
+        gcx.dcx().warn(msg).code(error_code!(2018)).span(span).emit();
+    }
+}
+
+/// Checks that the declared state mutability is allowed for the function.
+fn check_declaration(gcx: Gcx<'_>, func: &hir::Function<'_>) {
+    if func.state_mutability != StateMutability::Payable {
+        return;
+    }
+    let msg = if func.contract.is_some_and(|c| gcx.hir.contract(c).kind == ContractKind::Library) {
+        "library functions cannot be payable"
+    } else if func.visibility <= Visibility::Internal {
+        "`internal` and `private` functions cannot be payable"
+    } else {
+        return;
+    };
+    gcx.dcx().err(msg).span(func.span).emit();
+}
+
+struct MutabilityChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The function being checked, if its declared state mutability should be enforced.
+    function: Option<&'gcx hir::Function<'gcx>>,
+    /// The least restrictive state mutability required so far.
+    required: StateMutability,
+    /// Whether an error was reported.
+    errored: bool,
+    /// Whether the body contains code that could not be analyzed, such as code with errors.
+    incomplete: bool,
+}
+
+impl<'gcx> MutabilityChecker<'gcx> {
+    fn new(gcx: Gcx<'gcx>, function: Option<&'gcx hir::Function<'gcx>>) -> Self {
+        Self { gcx, function, required: StateMutability::Pure, errored: false, incomplete: false }
+    }
+
+    /// Records that the expression at `span` requires `mutability`.
+    fn require(&mut self, mutability: StateMutability, span: Span) {
+        let mutability = if mutability == StateMutability::Payable {
+            StateMutability::NonPayable
+        } else {
+            mutability
+        };
+        if rank(mutability) > rank(self.required) {
+            self.required = mutability;
+        }
+        let Some(func) = self.function else { return };
+        let declared = func.state_mutability;
+        if rank(mutability) <= rank(declared) {
+            return;
+        }
+        let (msg, code) = if mutability == StateMutability::View {
+            let msg = "function declared as `pure`, but this expression (potentially) reads the \
+                       blockchain state and requires `view`";
+            (msg.to_string(), error_code!(2527))
+        } else {
+            let msg = format!(
+                "function cannot be declared as `{declared}` because this expression \
+                 (potentially) modifies the state"
+            );
+            (msg, error_code!(8961))
+        };
+        self.gcx.dcx().err(msg).code(code).span(span).emit();
+        self.errored = true;
+    }
+
+    /// Records the state mutability required by a modifier invocation.
+    fn visit_modifier(&mut self, id: hir::FunctionId) {
+        let gcx = self.gcx;
+        let modifier = gcx.hir.function(id);
+        let Some(body) = modifier.body else { return };
+        let mut checker = MutabilityChecker::new(gcx, None);
+        checker.visit_block(body);
+        let Some(func) = self.function else { return };
+        if rank(checker.required) > rank(func.state_mutability) {
+            let msg = format!(
+                "function declared as `{}`, but modifier `{}` requires `{}`",
+                func.state_mutability,
+                gcx.item_name(id),
+                checker.required,
+            );
+            let span = func.name.map_or(func.span, |name| name.span);
+            let note_span = modifier.name.map_or(modifier.span, |name| name.span);
+            gcx.dcx().err(msg).span(span).span_note(note_span, "modifier defined here").emit();
+            self.errored = true;
+        }
+        if rank(checker.required) > rank(self.required) {
+            self.required = checker.required;
+        }
+        self.incomplete |= checker.incomplete;
+    }
+
+    fn visit_block(&mut self, block: hir::Block<'_>) {
+        for stmt in block {
+            self.visit_stmt(stmt);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &hir::Stmt<'_>) {
+        match stmt.kind {
+            hir::StmtKind::DeclSingle(id) => {
+                if let Some(init) = self.gcx.hir.variable(id).initializer {
+                    self.visit_expr(init);
+                }
+            }
+            hir::StmtKind::DeclMulti(_, expr) => self.visit_expr(expr),
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => self.visit_block(block),
+            hir::StmtKind::Emit(_, ref args) => {
+                self.visit_args(args);
+                self.require(StateMutability::NonPayable, stmt.span);
+            }
+            hir::StmtKind::Revert(_, ref args) => self.visit_args(args),
+            hir::StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::StmtKind::If(cond, then, else_) => {
+                self.visit_expr(cond);
+                self.visit_stmt(then);
+                if let Some(else_) = else_ {
+                    self.visit_stmt(else_);
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                self.visit_expr(&try_.expr);
+                self.visit_block(try_.block);
+                for catch in try_.catch {
+                    self.visit_block(catch.block);
+                }
+            }
+            hir::StmtKind::Expr(expr) => self.visit_expr(expr),
+            hir::StmtKind::Assembly(assembly) => self.visit_yul_block(assembly.block),
+            hir::StmtKind::Err(_) => self.incomplete = true,
+            hir::StmtKind::Break | hir::StmtKind::Continue | hir::StmtKind::Placeholder => {}
+        }
+    }
+
+    fn visit_yul_block(&mut self, block: yul::Block<'_>) {
+        for stmt in block {
+            self.visit_yul_stmt(stmt);
+        }
+    }
+
+    fn visit_yul_stmt(&mut self, stmt: &yul::Stmt<'_>) {
+        match stmt.kind {
+            yul::StmtKind::Block(block) => self.visit_yul_block(block),
+            yul::StmtKind::Assign(_, expr) | yul::StmtKind::Expr(expr) => self.visit_yul_expr(expr),
+            yul::StmtKind::VarDecl(_, expr) => {
+                if let Some(expr) = expr {
+                    self.visit_yul_expr(expr);
+                }
+            }
+            yul::StmtKind::If(cond, block) => {
+                self.visit_yul_expr(cond);
+                self.visit_yul_block(block);
+            }
+            yul::StmtKind::For { init, cond, step, body } => {
+                self.visit_yul_block(init);
+                self.visit_yul_expr(cond);
+                self.visit_yul_block(step);
+                self.visit_yul_block(body);
+            }
+            yul::StmtKind::Switch(switch) => {
+                self.visit_yul_expr(&switch.selector);
+                for case in switch.cases {
+                    self.visit_yul_block(case.body);
+                }
+                if let Some(block) = switch.default_case {
+                    self.visit_yul_block(block);
+                }
+            }
+            // Functions are analyzed where they are defined, whether they are called or not.
+            yul::StmtKind::FunctionDef(f) => self.visit_yul_block(f.body),
+            yul::StmtKind::Leave | yul::StmtKind::Break | yul::StmtKind::Continue => {}
+        }
+    }
+
+    /// Records the state mutability of the builtins called in inline assembly. Solidity
+    /// variables can only be accessed by their storage slot, which is known at compile time.
+    fn visit_yul_expr(&mut self, expr: &yul::Expr<'_>) {
+        match expr.kind {
+            yul::ExprKind::Call(_, args) => {
+                for arg in args {
+                    self.visit_yul_expr(arg);
+                }
+                if let Some(builtin) = expr.builtin() {
+                    self.require(builtin.state_mutability(), expr.span);
+                }
+            }
+            yul::ExprKind::Err(_) => self.incomplete = true,
+            yul::ExprKind::Var(_) | yul::ExprKind::External(_) | yul::ExprKind::Lit(_) => {}
+        }
+    }
+
+    fn visit_args(&mut self, args: &hir::CallArgs<'_>) {
+        for arg in args_exprs(args) {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &hir::Expr<'_>) {
+        match expr.kind {
+            hir::ExprKind::Array(exprs) => {
+                for expr in exprs {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Assign(lhs, _, rhs) => {
+                self.visit_lvalue(lhs);
+                self.visit_expr(rhs);
+            }
+            hir::ExprKind::Binary(lhs, _, rhs) => {
+                self.visit_expr(lhs);
+                self.visit_expr(rhs);
+                self.visit_user_defined_operator(expr);
+            }
+            hir::ExprKind::Call(callee, ref args) => {
+                self.visit_expr(callee);
+                self.visit_args(args);
+                if let Some(ty) = self.gcx.type_of_expr(callee.id) {
+                    self.visit_call(expr, ty);
+                }
+            }
+            hir::ExprKind::CallOptions(callee, options) => {
+                self.visit_expr(callee);
+                for option in options {
+                    self.visit_expr(&option.value);
+                }
+            }
+            hir::ExprKind::Delete(inner) => self.visit_lvalue(inner),
+            hir::ExprKind::Ident(_) => self.visit_res(expr),
+            hir::ExprKind::Index(base, index) => {
+                self.visit_expr(base);
+                if let Some(index) = index {
+                    self.visit_expr(index);
+                }
+                if self.is_storage(base) {
+                    self.require(StateMutability::View, expr.span);
+                }
+            }
+            hir::ExprKind::Slice(base, start, end) => {
+                self.visit_expr(base);
+                for index in [start, end].into_iter().flatten() {
+                    self.visit_expr(index);
+                }
+            }
+            hir::ExprKind::Member(base, member) => {
+                // `this.f.selector` is known at compile time, and does not read `this`.
+                if member.name == sym::selector && self.is_this_member(base) {
+                    return;
+                }
+                self.visit_expr(base);
+                self.visit_res(expr);
+                // Attached and builtin functions are accounted for when they are called.
+                let is_function = self
+                    .gcx
+                    .type_of_expr(expr.id)
+                    .is_some_and(|ty| matches!(ty.kind, TyKind::FnPtr(_)));
+                if self.is_storage(base) && !is_function {
+                    self.require(StateMutability::View, expr.span);
+                }
+            }
+            hir::ExprKind::Payable(inner) => self.visit_expr(inner),
+            hir::ExprKind::Ternary(cond, true_, false_) => {
+                self.visit_expr(cond);
+                self.visit_expr(true_);
+                self.visit_expr(false_);
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Unary(op, inner) => {
+                match op.kind {
+                    UnOpKind::PreInc | UnOpKind::PreDec | UnOpKind::PostInc | UnOpKind::PostDec => {
+                        self.visit_lvalue(inner)
+                    }
+                    UnOpKind::Not | UnOpKind::Neg | UnOpKind::BitNot => self.visit_expr(inner),
+                }
+                self.visit_user_defined_operator(expr);
+            }
+            hir::ExprKind::Err(_) => self.incomplete = true,
+            // Contract creations are accounted for when the constructor is called.
+            hir::ExprKind::New(_)
+            | hir::ExprKind::Lit(_)
+            | hir::ExprKind::TypeCall(_)
+            | hir::ExprKind::Type(_) => {}
+        }
+    }
+
+    /// Visits an expression that is being assigned to.
+    fn visit_lvalue(&mut self, expr: &hir::Expr<'_>) {
+        let expr = expr.peel_parens();
+        match expr.kind {
+            hir::ExprKind::Ident(_) => {
+                if self.state_variable(expr).is_some() {
+                    self.require(StateMutability::NonPayable, expr.span);
+                }
+            }
+            hir::ExprKind::Index(base, _) | hir::ExprKind::Member(base, _) => {
+                if self.is_storage(base) {
+                    self.require(StateMutability::NonPayable, expr.span);
+                    self.visit_storage_path(expr);
+                } else {
+                    self.visit_expr(expr);
+                }
+            }
+            hir::ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    self.visit_lvalue(expr);
+                }
+            }
+            _ => self.visit_expr(expr),
+        }
+    }
+
+    /// Visits the indices of a storage location that is being assigned to, without requiring
+    /// `view` for reading the location itself.
+    fn visit_storage_path(&mut self, expr: &hir::Expr<'_>) {
+        let expr = expr.peel_parens();
+        match expr.kind {
+            hir::ExprKind::Ident(_) => {}
+            hir::ExprKind::Index(base, index) if self.is_storage(base) => {
+                if let Some(index) = index {
+                    self.visit_expr(index);
+                }
+                self.visit_storage_path(base);
+            }
+            hir::ExprKind::Member(base, _) if self.is_storage(base) => {
+                self.visit_storage_path(base)
+            }
+            _ => self.visit_expr(expr),
+        }
+    }
+
+    /// Returns `true` if the expression is a member of `this`, such as `this.f`.
+    fn is_this_member(&self, expr: &hir::Expr<'_>) -> bool {
+        let hir::ExprKind::Member(base, _) = expr.peel_parens().kind else { return false };
+        matches!(self.gcx.res_of_expr(base.peel_parens().id), Some(Res::Builtin(Builtin::This)))
+    }
+
+    /// Visits the declaration that an identifier or member access refers to.
+    fn visit_res(&mut self, expr: &hir::Expr<'_>) {
+        let gcx = self.gcx;
+        match gcx.res_of_expr(expr.id) {
+            Some(Res::Item(hir::ItemId::Variable(_))) => {
+                let Some(var) = self.state_variable(expr) else { return };
+                let is_pure = match var.mutability {
+                    Some(m) if m.is_constant() => true,
+                    // Immutables that are assigned literals are pure.
+                    Some(m) if m.is_immutable() => var.initializer.is_some_and(|init| {
+                        matches!(init.peel_parens().kind, hir::ExprKind::Lit(_))
+                    }),
+                    _ => false,
+                };
+                if !is_pure {
+                    self.require(StateMutability::View, expr.span);
+                }
+            }
+            Some(Res::Builtin(builtin)) => self.visit_builtin(expr, builtin),
+            _ => {}
+        }
+    }
+
+    fn visit_builtin(&mut self, expr: &hir::Expr<'_>, builtin: Builtin) {
+        use Builtin::*;
+        match builtin {
+            This | BlockCoinbase | BlockTimestamp | BlockDifficulty | BlockPrevrandao
+            | BlockNumber | BlockGaslimit | BlockChainid | BlockBasefee | BlockBlobbasefee
+            | MsgSender | MsgGas | TxOrigin | TxGasPrice | AddressBalance | AddressCode
+            | AddressCodehash => self.require(StateMutability::View, expr.span),
+            MsgValue => {
+                self.require(StateMutability::View, expr.span);
+                self.check_msg_value(expr.span);
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks that `msg.value` is only used in payable or non-public functions.
+    fn check_msg_value(&mut self, span: Span) {
+        let Some(func) = self.function else { return };
+        if func.state_mutability == StateMutability::Payable || func.visibility < Visibility::Public
+        {
+            return;
+        }
+        let msg = "`msg.value` can only be used in payable public functions";
+        let help = "make the function `payable` or use an internal function to avoid this error";
+        self.gcx.dcx().err(msg).code(error_code!(5887)).span(span).help(help).emit();
+        self.errored = true;
+    }
+
+    /// Records the state mutability of the called function.
+    fn visit_call(&mut self, expr: &hir::Expr<'_>, callee_ty: Ty<'gcx>) {
+        if let TyKind::FnPtr(f) = callee_ty.kind {
+            self.require(f.state_mutability, expr.span);
+        }
+    }
+
+    /// Records the state mutability of a user-defined operator, if `expr` uses one.
+    fn visit_user_defined_operator(&mut self, expr: &hir::Expr<'_>) {
+        if let Some(Res::Item(hir::ItemId::Function(f))) = self.gcx.res_of_expr(expr.id) {
+            self.require(self.gcx.hir.function(f).state_mutability, expr.span);
+        }
+    }
+
+    /// Returns the state variable that the expression refers to, if any.
+    fn state_variable(&self, expr: &hir::Expr<'_>) -> Option<&'gcx hir::Variable<'gcx>> {
+        match self.gcx.res_of_expr(expr.id) {
+            Some(Res::Item(hir::ItemId::Variable(id))) => {
+                Some(self.gcx.hir.variable(id)).filter(|var| var.is_state_variable())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the expression is a reference to storage.
+    fn is_storage(&self, expr: &hir::Expr<'_>) -> bool {
+        self.gcx.type_of_expr(expr.id).is_some_and(|ty| {
+            ty.is_ref_at(DataLocation::Storage) || ty.is_ref_at(DataLocation::Transient)
+        })
+    }
+}
+
+/// Returns the order of the state mutability from the most to the least restrictive.
+fn rank(mutability: StateMutability) -> u8 {
+    match mutability {
+        StateMutability::Pure => 0,
+        StateMutability::View => 1,
+        StateMutability::NonPayable => 2,
+        StateMutability::Payable => 3,
+    }
+}
@@ -0,0 +1,389 @@
+//! Checks the overriding rules of functions, modifiers and public state variables.
+//!
+//! Reference: <https://docs.soliditylang.org/en/latest/contracts.html#function-overriding>
+
+use crate::{
+    hir::{self, FunctionKind, StateMutability, Visibility},
+    ty::{Gcx, Ty},
+};
+use solar_data_structures::{
+    map::{FxHashMap, FxHashSet},
+    smallvec::SmallVec,
+};
+use solar_interface::Symbol;
+
+/// Checks the overriding rules of the items of the given contract, and that the contract
+/// overrides the functions that are defined in more than one of its bases.
+pub(super) fn check_contract(gcx: Gcx<'_>, id: hir::ContractId) {
+    OverrideChecker::new(gcx).check_contract(id);
+}
+
+/// Checks that a free function is neither `virtual` nor `override`.
+pub(super) fn check_free_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let func = gcx.hir.function(id);
+    debug_assert!(func.is_free());
+    if func.marked_virtual {
+        gcx.dcx().err("free functions cannot be virtual").span(func.span).emit();
+    }
+    if func.override_ {
+        gcx.dcx().err("free functions cannot override").span(func.span).emit();
+    }
+}
+
+/// The part of a function's signature that determines whether it overrides another function.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) struct OverrideKey<'gcx> {
+    kind: FunctionKind,
+    name: Option<Symbol>,
+    parameters: Option<&'gcx [Ty<'gcx>]>,
+}
+
+struct OverrideChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The functions that each contract inherits, without the ones that it defines itself.
+    inherited: FxHashMap<hir::ContractId, SmallVec<[hir::FunctionId; 16]>>,
+}
+
+impl<'gcx> OverrideChecker<'gcx> {
+    fn new(gcx: Gcx<'gcx>) -> Self {
+        Self { gcx, inherited: FxHashMap::default() }
+    }
+
+    fn check_contract(&mut self, id: hir::ContractId) {
+        let gcx = self.gcx;
+        let contract = gcx.hir.contract(id);
+
+        for f in contract.functions() {
+            self.check_specifiers(contract, f);
+        }
+        for var in contract.variables() {
+            let v = gcx.hir.variable(var);
+            if v.override_ && v.getter.is_none() {
+                let msg = "only public state variables can override";
+                gcx.dcx().err(msg).span(v.span).emit();
+            }
+        }
+
+        for f in contract.functions().filter(|&f| can_override(gcx, f)) {
+            let overridden = self.overridden_functions(f);
+            self.check_overrides(f, &overridden);
+        }
+
+        self.check_ambiguous_overrides(id);
+    }
+
+    /// Checks the `virtual` specifier of a contract function.
+    fn check_specifiers(&self, contract: &hir::Contract<'_>, id: hir::FunctionId) {
+        let gcx = self.gcx;
+        let func = gcx.hir.function(id);
+        if !func.marked_virtual {
+            return;
+        }
+        let msg = if contract.kind.is_library() {
+            "library functions cannot be `virtual`"
+        } else if func.visibility == Visibility::Private {
+            "`virtual` and `private` cannot be used together"
+        } else if func.kind.is_constructor() {
+            "constructors cannot be `virtual`"
+        } else {
+            return;
+        };
+        gcx.dcx().err(msg).span(func.span).emit();
+    }
+
+    /// Checks an item against the base functions that it overrides.
+    fn check_overrides(&self, id: hir::FunctionId, overridden: &[hir::FunctionId]) {
+        let gcx = self.gcx;
+        let func = gcx.hir.function(id);
+        let desc = description(func);
+
+        if overridden.is_empty() {
+            if func.override_ {
+                let msg = format!("{desc} has override specified but does not override anything");
+                gcx.dcx().err(msg).span(func.span).emit();
+            }
+            return;
+        }
+
+        // Since 0.8.8, `override` is not required when only a single interface function is
+        // implemented.
+        let implements_interface = matches!(overridden, [base] if is_interface_function(gcx, *base))
+            && !func.kind.is_modifier();
+        if !func.override_ && !implements_interface {
+            let msg = format!("overriding {desc} is missing `override` specifier");
+            let mut diag = gcx.dcx().err(msg).span(func.span);
+            for &base in overridden {
+                diag = diag.span_note(gcx.hir.function(base).span, "overridden function is here");
+            }
+            diag.emit();
+        }
+
+        for &base in overridden {
+            self.check_override(id, base);
+        }
+
+        if func.override_ {
+            self.check_override_list(func, overridden);
+        }
+    }
+
+    /// Checks that `func` can override `base`.
+    fn check_override(&self, func_id: hir::FunctionId, base_id: hir::FunctionId) {
+        let gcx = self.gcx;
+        let func = gcx.hir.function(func_id);
+        let base = gcx.hir.function(base_id);
+        let note = "overridden function is here";
+        let err = |msg: String| gcx.dcx().err(msg).span(func.span).span_note(base.span, note);
+
+        if base.is_getter() {
+            err("cannot override public state variable".to_string()).emit();
+            return;
+        }
+        if !base.virtual_ {
+            err(format!("trying to override non-virtual {}", base.kind))
+                .help("did you forget to add `virtual`?")
+                .emit();
+        }
+
+        if func.kind.is_modifier() {
+            return;
+        }
+
+        if func.is_getter() {
+            if base.visibility != Visibility::External {
+                let msg =
+                    "public state variables can only override functions with external visibility";
+                err(msg.to_string()).emit();
+            }
+        } else if func.visibility != base.visibility
+            && !(func.visibility == Visibility::Public && base.visibility == Visibility::External)
+        {
+            err(format!(
+                "overriding function visibility differs from `{}` to `{}`",
+                base.visibility, func.visibility
+            ))
+            .emit();
+        }
+
+        if !is_mutability_overridable(base.state_mutability, func.state_mutability) {
+            err(format!(
+                "overriding {} changes state mutability from `{}` to `{}`",
+                description(func),
+                base.state_mutability,
+                func.state_mutability,
+            ))
+            .emit();
+        }
+
+        let returns = |id: hir::FunctionId| {
+            let ty = gcx.type_of_item(id.into());
+            let ty = if gcx.hir.function(id).is_part_of_external_interface() {
+                ty.as_externally_callable_function(gcx)
+            } else {
+                ty
+            };
+            ty.returns().unwrap_or_default()
+        };
+        if returns(func_id) != returns(base_id) {
+            err(format!("overriding {} return types differ", description(func))).emit();
+        }
+    }
+
+    /// Checks the contracts listed in `override(...)` against the contracts that define the
+    /// overridden functions.
+    fn check_override_list(&self, func: &hir::Function<'_>, overridden: &[hir::FunctionId]) {
+        let gcx = self.gcx;
+        let mut expected = SmallVec::<[hir::ContractId; 4]>::new();
+        for &base in overridden {
+            let c = gcx.hir.function(base).contract.unwrap();
+            if !expected.contains(&c) {
+                expected.push(c);
+            }
+        }
+
+        if func.overrides.is_empty() {
+            if expected.len() > 1 {
+                self.report_missing_overrides(func, &expected);
+            }
+            return;
+        }
+
+        let mut seen = FxHashSet::default();
+        for &c in func.overrides {
+            let name = gcx.item_name(c);
+            if !seen.insert(c) {
+                let msg = format!("duplicate contract `{name}` found in override list");
+                gcx.dcx().err(msg).span(func.span).emit();
+            } else if !expected.contains(&c) {
+                let msg = format!("invalid contract specified in override list: `{name}`");
+                gcx.dcx().err(msg).span(func.span).emit();
+            }
+        }
+        let missing = expected
+            .iter()
+            .copied()
+            .filter(|c| !func.overrides.contains(c))
+            .collect::<SmallVec<[_; 4]>>();
+        if !missing.is_empty() {
+            self.report_missing_overrides(func, &missing);
+        }
+    }
+
+    fn report_missing_overrides(&self, func: &hir::Function<'_>, contracts: &[hir::ContractId]) {
+        let gcx = self.gcx;
+        let names =
+            contracts.iter().map(|&c| format!("`{}`", gcx.item_name(c))).collect::<Vec<_>>();
+        let msg = match &names[..] {
+            [name] => format!("{} needs to specify overridden contract {name}", description(func)),
+            [rest @ .., last] => format!(
+                "{} needs to specify overridden contracts {} and {last}",
+                description(func),
+                rest.join(", ")
+            ),
+            [] => unreachable!(),
+        };
+        gcx.dcx().err(msg).span(func.span).emit();
+    }
+
+    /// Checks that the contract overrides the functions that it inherits from more than one base
+    /// when none of them overrides the others.
+    fn check_ambiguous_overrides(&mut self, id: hir::ContractId) {
+        let gcx = self.gcx;
+        let contract = gcx.hir.contract(id);
+        let own = contract
+            .functions()
+            .filter(|&f| can_override(gcx, f))
+            .map(|f| self.key(f))
+            .collect::<SmallVec<[_; 16]>>();
+
+        let inherited = self.inherited_functions(id).clone();
+        let mut reported = FxHashSet::default();
+        for (i, &f) in inherited.iter().enumerate() {
+            let key = self.key(f);
+            if own.contains(&key) || reported.contains(&i) {
+                continue;
+            }
+            let group = inherited
+                .iter()
+                .enumerate()
+                .skip(i)
+                .filter(|&(_, &g)| self.key(g) == key)
+                .map(|(j, &g)| {
+                    reported.insert(j);
+                    g
+                })
+                .collect::<SmallVec<[_; 4]>>();
+            // Functions that are overridden by another function of the group are not ambiguous.
+            let conflicting = group
+                .iter()
+                .copied()
+                .filter(|&g| !group.iter().any(|&h| h != g && self.overrides_transitively(h, g)))
+                .collect::<SmallVec<[_; 4]>>();
+            if conflicting.len() <= 1 {
+                continue;
+            }
+
+            let func = gcx.hir.function(f);
+            let what = if func.kind.is_modifier() { "modifier" } else { "function" };
+            let msg = match func.name {
+                Some(name) => format!("derived contract must override {what} `{name}`"),
+                None => format!("derived contract must override {} function", func.kind),
+            };
+            let mut diag = gcx.dcx().err(msg).span(contract.name.span).note(format!(
+                "two or more base classes define a {what} with the same name and parameter types"
+            ));
+            for &g in &conflicting {
+                diag = diag.span_note(gcx.hir.function(g).span, "definition here");
+            }
+            diag.emit();
+        }
+    }
+
+    /// Returns the inherited functions that the given contract function overrides.
+    fn overridden_functions(&mut self, id: hir::FunctionId) -> SmallVec<[hir::FunctionId; 4]> {
+        let contract = self.gcx.hir.function(id).contract.unwrap();
+        let key = self.key(id);
+        let inherited = self.inherited_functions(contract).clone();
+        inherited.into_iter().filter(|&f| self.key(f) == key).collect()
+    }
+
+    /// Returns `true` if `f` overrides `base`, directly or through other functions.
+    fn overrides_transitively(&mut self, f: hir::FunctionId, base: hir::FunctionId) -> bool {
+        let overridden = self.overridden_functions(f);
+        overridden.contains(&base)
+            || overridden.iter().any(|&g| self.overrides_transitively(g, base))
+    }
+
+    /// Returns the functions that the contract inherits from its direct bases.
+    ///
+    /// For each base, these are the functions that the base defines, and the functions that it
+    /// inherits without overriding them.
+    fn inherited_functions(&mut self, id: hir::ContractId) -> &SmallVec<[hir::FunctionId; 16]> {
+        if !self.inherited.contains_key(&id) {
+            let gcx = self.gcx;
+            let mut inherited = SmallVec::<[_; 16]>::new();
+            for &base in gcx.hir.contract(id).bases {
+                let own = gcx
+                    .hir
+                    .contract(base)
+                    .functions()
+                    .filter(|&f| can_override(gcx, f) && !is_private(gcx, f))
+                    .collect::<SmallVec<[_; 16]>>();
+                let own_keys = own.iter().map(|&f| self.key(f)).collect::<SmallVec<[_; 16]>>();
+                let base_inherited = self.inherited_functions(base).clone();
+                let candidates = own.into_iter().chain(
+                    base_inherited.into_iter().filter(|&f| !own_keys.contains(&self.key(f))),
+                );
+                for f in candidates {
+                    if !inherited.contains(&f) {
+                        inherited.push(f);
+                    }
+                }
+            }
+            self.inherited.insert(id, inherited);
+        }
+        &self.inherited[&id]
+    }
+
+    fn key(&self, id: hir::FunctionId) -> OverrideKey<'gcx> {
+        override_key(self.gcx, id)
+    }
+}
+
+/// Returns the key that determines which functions the given function overrides.
+pub(super) fn override_key(gcx: Gcx<'_>, id: hir::FunctionId) -> OverrideKey<'_> {
+    let func = gcx.hir.function(id);
+    OverrideKey {
+        kind: func.kind,
+        name: func.name.map(|name| name.name),
+        parameters: gcx.type_of_item(id.into()).as_externally_callable_function(gcx).parameters(),
+    }
+}
+
+/// Returns `true` if the function can override or be overridden.
+fn can_override(gcx: Gcx<'_>, id: hir::FunctionId) -> bool {
+    !gcx.hir.function(id).kind.is_constructor()
+}
+
+fn is_private(gcx: Gcx<'_>, id: hir::FunctionId) -> bool {
+    gcx.hir.function(id).visibility == Visibility::Private
+}
+
+fn is_interface_function(gcx: Gcx<'_>, id: hir::FunctionId) -> bool {
+    gcx.hir.function(id).contract.is_some_and(|c| gcx.hir.contract(c).kind.is_interface())
+}
+
+fn description(func: &hir::Function<'_>) -> &'static str {
+    if func.is_getter() {
+        "public state variable"
+    } else {
+        func.kind.to_str()
+    }
+}
+
+/// Returns `true` if a function with mutability `base` can be overridden by one with mutability
+/// `func`.
+fn is_mutability_overridable(base: StateMutability, func: StateMutability) -> bool {
+    use StateMutability::*;
+    base == func || matches!((base, func), (NonPayable, View | Pure) | (View, Pure))
+}
@@ -0,0 +1,248 @@
+use solar_ast::ast::StateMutability;
+use solar_interface::{config::EvmVersion, kw, Symbol};
+
+/// A builtin function of the Yul EVM dialect.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/yul.html#evm-dialect>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuiltinFunction {
+    /// The name of the builtin.
+    pub name: Symbol,
+    /// The number of arguments.
+    pub parameters: usize,
+    /// The number of return values.
+    pub returns: usize,
+    /// The first EVM version in which the builtin is available, if it was added after Homestead.
+    pub since: Option<EvmVersion>,
+    /// The first EVM version in which the builtin is no longer available, if any.
+    pub until: Option<EvmVersion>,
+    /// The index of the argument that must be a literal, if any.
+    pub literal_argument: Option<usize>,
+    /// Whether the builtin can only be used in Yul objects, and not in inline assembly.
+    pub object_only: bool,
+}
+
+impl BuiltinFunction {
+    const fn new(name: Symbol, parameters: usize, returns: usize) -> Self {
+        Self {
+            name,
+            parameters,
+            returns,
+            since: None,
+            until: None,
+            literal_argument: None,
+            object_only: false,
+        }
+    }
+
+    const fn since(mut self, evm_version: EvmVersion) -> Self {
+        self.since = Some(evm_version);
+        self
+    }
+
+    const fn object_only(mut self, literal_argument: Option<usize>) -> Self {
+        self.object_only = true;
+        self.literal_argument = literal_argument;
+        self
+    }
+
+    /// Returns the builtin function with the given name, if any.
+    ///
+    /// This includes the `verbatim_<n>i_<m>o` family of builtins.
+    pub fn from_name(name: Symbol) -> Option<Self> {
+        use EvmVersion::*;
+
+        let builtin = match name {
+            kw::Stop | kw::Invalid => Self::new(name, 0, 0),
+
+            kw::Add
+            | kw::Sub
+            | kw::Mul
+            | kw::Div
+            | kw::Sdiv
+            | kw::Mod
+            | kw::Smod
+            | kw::Exp
+            | kw::Signextend
+            | kw::Lt
+            | kw::Gt
+            | kw::Slt
+            | kw::Sgt
+            | kw::Eq
+            | kw::And
+            | kw::Or
+            | kw::Xor
+            | kw::Byte
+            | kw::Keccak256 => Self::new(name, 2, 1),
+            kw::Shl | kw::Shr | kw::Sar => Self::new(name, 2, 1).since(Constantinople),
+            kw::Addmod | kw::Mulmod => Self::new(name, 3, 1),
+            kw::Not | kw::Iszero => Self::new(name, 1, 1),
+
+            kw::Address
+            | kw::Caller
+            | kw::Callvalue
+            | kw::Calldatasize
+            | kw::Codesize
+            | kw::Gasprice
+            | kw::Origin
+            | kw::Coinbase
+            | kw::Timestamp
+            | kw::Number
+            | kw::Gaslimit
+            | kw::Gas
+            | kw::Msize => Self::new(name, 0, 1),
+            kw::Balance | kw::Calldataload | kw::Extcodesize | kw::Blockhash => {
+                Self::new(name, 1, 1)
+            }
+            kw::Calldatacopy | kw::Codecopy => Self::new(name, 3, 0),
+            kw::Extcodecopy => Self::new(name, 4, 0),
+            kw::Returndatasize => Self::new(name, 0, 1).since(Byzantium),
+            kw::Returndatacopy => Self::new(name, 3, 0).since(Byzantium),
+            kw::Extcodehash => Self::new(name, 1, 1).since(Constantinople),
+            kw::Chainid | kw::Selfbalance => Self::new(name, 0, 1).since(Istanbul),
+            kw::Basefee => Self::new(name, 0, 1).since(London),
+            kw::Difficulty => Self { until: Some(Paris), ..Self::new(name, 0, 1) },
+            kw::Prevrandao => Self::new(name, 0, 1).since(Paris),
+            kw::Blobbasefee => Self::new(name, 0, 1).since(Cancun),
+            kw::Blobhash => Self::new(name, 1, 1).since(Cancun),
+
+            kw::Pop => Self::new(name, 1, 0),
+            kw::Mload | kw::Sload => Self::new(name, 1, 1),
+            kw::Mstore | kw::Mstore8 | kw::Sstore => Self::new(name, 2, 0),
+            kw::Tload => Self::new(name, 1, 1).since(Cancun),
+            kw::Tstore => Self::new(name, 2, 0).since(Cancun),
+            kw::Mcopy => Self::new(name, 3, 0).since(Cancun),
+
+            kw::Log0 => Self::new(name, 2, 0),
+            kw::Log1 => Self::new(name, 3, 0),
+            kw::Log2 => Self::new(name, 4, 0),
+            kw::Log3 => Self::new(name, 5, 0),
+            kw::Log4 => Self::new(name, 6, 0),
+
+            kw::Create => Self::new(name, 3, 1),
+            kw::Create2 => Self::new(name, 4, 1).since(Constantinople),
+            kw::Call | kw::Callcode => Self::new(name, 7, 1),
+            kw::Delegatecall => Self::new(name, 6, 1),
+            kw::Staticcall => Self::new(name, 6, 1).since(Byzantium),
+            kw::Return => Self::new(name, 2, 0),
+            kw::Revert => Self::new(name, 2, 0).since(Byzantium),
+            kw::Selfdestruct => Self::new(name, 1, 0),
+
+            kw::Datasize | kw::Dataoffset | kw::Loadimmutable | kw::Linkersymbol => {
+                Self::new(name, 1, 1).object_only(Some(0))
+            }
+            kw::Memoryguard => Self::new(name, 1, 1).object_only(Some(0)),
+            kw::Datacopy => Self::new(name, 3, 0).object_only(None),
+            kw::Setimmutable => Self::new(name, 3, 0).object_only(Some(1)),
+
+            _ => return Self::verbatim(name),
+        };
+        Some(builtin)
+    }
+
+    /// Parses a `verbatim_<n>i_<m>o` builtin, which takes the bytecode to insert followed by `n`
+    /// arguments, and returns `m` values.
+    fn verbatim(name: Symbol) -> Option<Self> {
+        let rest = name.as_str().strip_prefix("verbatim_")?;
+        let (inputs, outputs) = rest.strip_suffix('o')?.split_once("i_")?;
+        let parse = |s: &str| -> Option<usize> {
+            // Disallow leading zeros so that each builtin has exactly one name.
+            if !s.bytes().all(|b| b.is_ascii_digit()) || (s.len() > 1 && s.starts_with('0')) {
+                return None;
+            }
+            s.parse().ok()
+        };
+        let (inputs, outputs) = (parse(inputs)?, parse(outputs)?);
+        if inputs > 99 || outputs > 99 {
+            return None;
+        }
+        Some(Self::new(name, inputs + 1, outputs).object_only(Some(0)))
+    }
+
+    /// Returns `true` if the builtin can be used when targeting the given EVM version.
+    pub fn is_available(&self, evm_version: EvmVersion) -> bool {
+        self.since.is_none_or(|since| evm_version >= since)
+            && self.until.is_none_or(|until| evm_version < until)
+    }
+
+    /// Returns `true` if the argument at the given index must be a string literal.
+    ///
+    /// The only builtin with a non-string literal argument is `memoryguard`.
+    pub fn has_string_literal_argument(&self, index: usize) -> bool {
+        self.literal_argument == Some(index) && self.name != kw::Memoryguard
+    }
+
+    /// Returns `true` if the builtin always ends the execution of the current call.
+    pub fn terminates(&self) -> bool {
+        matches!(self.name, kw::Return | kw::Revert | kw::Stop | kw::Invalid | kw::Selfdestruct)
+    }
+
+    /// Returns the state mutability that a function calling the builtin requires.
+    pub fn state_mutability(&self) -> StateMutability {
+        match self.name {
+            kw::Sstore
+            | kw::Tstore
+            | kw::Log0
+            | kw::Log1
+            | kw::Log2
+            | kw::Log3
+            | kw::Log4
+            | kw::Create
+            | kw::Create2
+            | kw::Call
+            | kw::Callcode
+            | kw::Delegatecall
+            | kw::Selfdestruct => StateMutability::NonPayable,
+
+            kw::Sload
+            | kw::Tload
+            | kw::Address
+            | kw::Balance
+            | kw::Selfbalance
+            | kw::Origin
+            | kw::Caller
+            | kw::Callvalue
+            | kw::Gasprice
+            | kw::Gas
+            | kw::Extcodesize
+            | kw::Extcodecopy
+            | kw::Extcodehash
+            | kw::Blockhash
+            | kw::Blobhash
+            | kw::Coinbase
+            | kw::Timestamp
+            | kw::Number
+            | kw::Difficulty
+            | kw::Prevrandao
+            | kw::Gaslimit
+            | kw::Chainid
+            | kw::Basefee
+            | kw::Blobbasefee
+            | kw::Staticcall => StateMutability::View,
+
+            _ => StateMutability::Pure,
+        }
+    }
+}
+
+/// Returns a help message if `name` is an EVM instruction that cannot be called from Yul, since
+/// the stack and the control flow are managed by the compiler.
+pub(super) fn low_level_instruction_help(name: &str) -> Option<&'static str> {
+    let numbered = |prefix: &str, max: usize| {
+        name.strip_prefix(prefix)
+            .filter(|n| !n.starts_with('0'))
+            .and_then(|n| n.parse::<usize>().ok())
+            .is_some_and(|n| (1..=max).contains(&n))
+    };
+    Some(match name {
+        "push0" => {
+            "use the literal `0` instead, which is pushed with `PUSH0` when targeting EVM version \
+             `shanghai` or later"
+        }
+        "jump" | "jumpi" | "jumpdest" => "use `if`, `switch`, `for` or functions instead",
+        "pc" => "the position of instructions in the bytecode is determined by the compiler",
+        _ if numbered("push", 32) => "use a literal instead",
+        _ if numbered("dup", 16) || numbered("swap", 16) => "use variables instead",
+        _ => return None,
+    })
+}
@@ -0,0 +1,579 @@
+//! Yul semantic analysis.
+//!
+//! Resolves the identifiers of standalone Yul objects and inline assembly blocks, and checks
+//! function calls against the builtins of the EVM dialect.
+//!
+//! Yul does not allow shadowing: an identifier cannot be declared if another identifier with the
+//! same name is visible, even if it was declared outside of the current function and therefore
+//! cannot be accessed.
+
+use alloy_primitives::U256;
+use solar_ast::ast::{self, yul, LitKind};
+use solar_data_structures::map::{FxHashMap, FxHashSet};
+use solar_interface::{
+    config::EvmVersion, diagnostics::DiagCtxt, kw, pluralize, Ident, Session, Span, Symbol,
+};
+
+mod builtins;
+pub use builtins::BuiltinFunction;
+
+/// Resolves identifiers that are not declared in Yul, such as the Solidity declarations that
+/// are referenced from inline assembly.
+pub(crate) trait ResolveExternal {
+    /// Returns the span of the external declaration with the given name, if any.
+    fn lookup(&mut self, name: Ident) -> Option<Span>;
+
+    /// Resolves a path that is not declared in Yul. `in_function` is `true` if the path is used
+    /// inside of a Yul function, and `assign` is `true` if it is assigned to.
+    ///
+    /// Returns `false` if the path could not be resolved. Any other error must be emitted by the
+    /// implementation.
+    fn resolve(&mut self, path: &ast::PathSlice, in_function: bool, assign: bool) -> bool;
+}
+
+/// Analyzes a Yul object and its sub-objects.
+#[instrument(level = "debug", skip_all, fields(object = %object.name.value))]
+pub(crate) fn analyze_object(sess: &Session, object: &yul::Object<'_>) {
+    for child in object.children.iter() {
+        analyze_object(sess, child);
+    }
+
+    // The names that can be referenced by `datasize`, `dataoffset` and `datacopy`.
+    let mut data = FxHashSet::default();
+    data.insert(object.name.value);
+    collect_data_names(object, None, &mut data);
+
+    let mut analyzer = Analyzer::new(sess, Some(&data), None);
+    analyzer.block(object.code.code);
+}
+
+/// Analyzes an inline assembly block.
+#[instrument(level = "debug", skip_all)]
+pub(crate) fn analyze_inline_assembly(
+    sess: &Session,
+    block: &[yul::Stmt<'_>],
+    external: &mut dyn ResolveExternal,
+) {
+    let mut analyzer = Analyzer::new(sess, None, Some(external));
+    analyzer.block(block);
+}
+
+/// Collects the names of the sub-objects and data segments of `object`. Nested sub-objects are
+/// referenced by joining their names with `.`.
+fn collect_data_names(
+    object: &yul::Object<'_>,
+    prefix: Option<&str>,
+    names: &mut FxHashSet<Symbol>,
+) {
+    let path = |name: Symbol| match prefix {
+        Some(prefix) => Symbol::intern(&format!("{prefix}.{name}")),
+        None => name,
+    };
+    for data in object.data.iter() {
+        names.insert(path(data.name.value));
+    }
+    for child in object.children.iter() {
+        let name = path(child.name.value);
+        names.insert(name);
+        collect_data_names(child, Some(name.as_str()), names);
+    }
+}
+
+struct Analyzer<'a> {
+    dcx: &'a DiagCtxt,
+    evm_version: EvmVersion,
+    /// The names that can be referenced by the object builtins. `None` in inline assembly, where
+    /// the object builtins are not available.
+    data: Option<&'a FxHashSet<Symbol>>,
+    external: Option<&'a mut dyn ResolveExternal>,
+    scopes: Vec<Scope>,
+    /// The function that is currently being analyzed, if any.
+    function: Option<Ident>,
+    /// Whether we are inside of the body of a `for` loop, in the current function.
+    in_loop: bool,
+}
+
+#[derive(Default)]
+struct Scope {
+    declarations: FxHashMap<Symbol, Declaration>,
+    /// Whether this is the outermost scope of a function. Variables declared outside of it
+    /// cannot be accessed.
+    function: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Declaration {
+    kind: DeclarationKind,
+    span: Span,
+}
+
+#[derive(Clone, Copy)]
+enum DeclarationKind {
+    Variable,
+    Function { parameters: usize, returns: usize },
+}
+
+impl<'a> Analyzer<'a> {
+    fn new(
+        sess: &'a Session,
+        data: Option<&'a FxHashSet<Symbol>>,
+        external: Option<&'a mut dyn ResolveExternal>,
+    ) -> Self {
+        Self {
+            dcx: &sess.dcx,
+            evm_version: sess.evm_version,
+            data,
+            external,
+            scopes: Vec::new(),
+            function: None,
+            in_loop: false,
+        }
+    }
+
+    fn block(&mut self, block: &[yul::Stmt<'_>]) {
+        self.scopes.push(Scope::default());
+        self.stmts(block);
+        self.scopes.pop();
+    }
+
+    /// Analyzes the statements of a block in the current scope.
+    fn stmts(&mut self, stmts: &[yul::Stmt<'_>]) {
+        // Functions are visible in the whole block in which they are declared.
+        for stmt in stmts {
+            if let yul::StmtKind::FunctionDef(f) = &stmt.kind {
+                let kind = DeclarationKind::Function {
+                    parameters: f.parameters.len(),
+                    returns: f.returns.len(),
+                };
+                self.declare(f.name, kind);
+            }
+        }
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &yul::Stmt<'_>) {
+        match &stmt.kind {
+            yul::StmtKind::Block(block) => self.block(block),
+            yul::StmtKind::AssignSingle(path, expr) => {
+                self.expr(expr);
+                self.assign(path);
+            }
+            yul::StmtKind::AssignMulti(paths, call) => {
+                self.call_with_values(call, stmt.span, paths.len());
+                let mut seen = FxHashSet::default();
+                for path in paths.iter() {
+                    if !seen.insert(path_name(path)) {
+                        let msg = format!(
+                            "variable `{path}` occurs multiple times on the left-hand side of the \
+                             assignment"
+                        );
+                        self.dcx.err(msg).span(path.span()).emit();
+                    }
+                    self.assign(path);
+                }
+            }
+            yul::StmtKind::Expr(call) => self.call_with_values(call, stmt.span, 0),
+            yul::StmtKind::If(cond, body) => {
+                self.expr(cond);
+                self.block(body);
+            }
+            yul::StmtKind::For { init, cond, step, body } => {
+                for stmt in init.iter() {
+                    if let yul::StmtKind::FunctionDef(f) = &stmt.kind {
+                        let msg = "functions cannot be defined in the initializer of a `for` loop";
+                        self.dcx.err(msg).span(f.name.span).emit();
+                    }
+                }
+                // Variables declared in the initializer are visible in the rest of the loop.
+                self.scopes.push(Scope::default());
+                self.stmts(init);
+                self.expr(cond);
+                let in_loop = std::mem::replace(&mut self.in_loop, false);
+                self.block(step);
+                self.in_loop = true;
+                self.block(body);
+                self.in_loop = in_loop;
+                self.scopes.pop();
+            }
+            yul::StmtKind::Switch(switch) => {
+                self.expr(&switch.selector);
+                let mut cases = FxHashMap::<U256, Span>::default();
+                for case in switch.branches.iter() {
+                    self.lit(case.constant);
+                    if let Some(value) = lit_value(&case.constant.kind) {
+                        if let Some(&previous) = cases.get(&value) {
+                            self.dcx
+                                .err("duplicate case value")
+                                .span(case.constant.span)
+                                .span_note(previous, "previous case defined here")
+                                .emit();
+                        } else {
+                            cases.insert(value, case.constant.span);
+                        }
+                    }
+                    self.block(case.body);
+                }
+                if let Some(default) = &switch.default_case {
+                    self.block(default);
+                }
+            }
+            yul::StmtKind::Leave => {
+                if self.function.is_none() {
+                    self.dcx.err("`leave` outside of a function").span(stmt.span).emit();
+                }
+            }
+            yul::StmtKind::Break | yul::StmtKind::Continue => {
+                if !self.in_loop {
+                    let keyword = if matches!(stmt.kind, yul::StmtKind::Break) {
+                        "break"
+                    } else {
+                        "continue"
+                    };
+                    let msg = format!("`{keyword}` outside of the body of a `for` loop");
+                    self.dcx.err(msg).span(stmt.span).emit();
+                }
+            }
+            yul::StmtKind::FunctionDef(f) => self.function_def(f),
+            yul::StmtKind::VarDecl(names, value) => {
+                if let Some(value) = value {
+                    self.values(value, names.len());
+                }
+                for &name in names.iter() {
+                    self.check_external_shadowing(name);
+                    self.declare(name, DeclarationKind::Variable);
+                }
+            }
+        }
+    }
+
+    fn function_def(&mut self, f: &yul::Function<'_>) {
+        let function = self.function.replace(f.name);
+        let in_loop = std::mem::replace(&mut self.in_loop, false);
+        self.scopes.push(Scope { function: true, ..Default::default() });
+        for &name in f.parameters.iter().chain(f.returns.iter()) {
+            self.declare(name, DeclarationKind::Variable);
+        }
+        self.stmts(f.body);
+        self.scopes.pop();
+        self.in_loop = in_loop;
+        self.function = function;
+    }
+
+    /// Declares a name in the current scope.
+    fn declare(&mut self, name: Ident, kind: DeclarationKind) {
+        if let Some((previous, _)) = self.lookup(name.name) {
+            let same_scope = self.scopes.last().unwrap().declarations.contains_key(&name.name);
+            let msg = if same_scope {
+                format!("identifier `{name}` already declared")
+            } else {
+                format!("declaration of `{name}` shadows a previous declaration")
+            };
+            let mut err = self.dcx.err(msg).span(name.span);
+            if !previous.span.is_dummy() {
+                err = err.span_note(previous.span, "previous declaration declared here");
+            }
+            if !same_scope {
+                err = err.note("shadowing is not allowed in Yul");
+            }
+            err.emit();
+            return;
+        }
+
+        // Reserved builtins are already rejected by the parser.
+        if !name.is_reserved(true) && self.builtin(name.name).is_some() {
+            let msg =
+                format!("cannot use the name of the builtin function `{name}` as an identifier");
+            self.dcx.err(msg).span(name.span).emit();
+            return;
+        }
+
+        let decl = Declaration { kind, span: name.span };
+        self.scopes.last_mut().unwrap().declarations.insert(name.name, decl);
+    }
+
+    /// Inline assembly variables cannot shadow the Solidity declarations that are visible from
+    /// the assembly block.
+    fn check_external_shadowing(&mut self, name: Ident) {
+        let Some(external) = &mut self.external else { return };
+        if let Some(span) = external.lookup(name) {
+            self.dcx
+                .err(format!("declaration of `{name}` shadows a declaration outside of the inline assembly block"))
+                .span(name.span)
+                .span_note(span, "the shadowed declaration is here")
+                .emit();
+        }
+    }
+
+    /// Looks up a name, returning its declaration and whether it was declared outside of the
+    /// current function.
+    fn lookup(&self, name: Symbol) -> Option<(Declaration, bool)> {
+        let mut outside_function = false;
+        for scope in self.scopes.iter().rev() {
+            if let Some(&decl) = scope.declarations.get(&name) {
+                return Some((decl, outside_function));
+            }
+            outside_function |= scope.function;
+        }
+        None
+    }
+
+    /// Returns the builtin function with the given name, if it is available in the current
+    /// dialect.
+    fn builtin(&self, name: Symbol) -> Option<BuiltinFunction> {
+        BuiltinFunction::from_name(name).filter(|b| !b.object_only || self.data.is_some())
+    }
+
+    /// Checks that `path` refers to a variable that can be assigned to.
+    fn assign(&mut self, path: &ast::PathSlice) {
+        self.path(path, true);
+    }
+
+    fn path(&mut self, path: &ast::PathSlice, assign: bool) {
+        let name = path_name(path);
+        let span = path.span();
+        match self.lookup(name) {
+            Some((Declaration { kind: DeclarationKind::Variable, span: decl_span }, outside)) => {
+                if outside {
+                    let f = self.function.unwrap();
+                    self.dcx
+                        .err(format!("cannot find variable `{name}` in this scope"))
+                        .span(span)
+                        .span_note(
+                            decl_span,
+                            format!("`{name}` is declared outside of function `{f}`"),
+                        )
+                        .note("functions cannot access variables declared outside of them")
+                        .emit();
+                }
+            }
+            Some((Declaration { kind: DeclarationKind::Function { .. }, .. }, _)) => {
+                let msg = if assign {
+                    format!("cannot assign to function `{name}`")
+                } else {
+                    format!("expected variable, found function `{name}`")
+                };
+                self.dcx.err(msg).span(span).emit();
+            }
+            None if self.builtin(name).is_some() => {
+                // Reserved builtins are already rejected by the parser.
+                if !path.first().is_reserved(true) {
+                    let msg = if assign {
+                        format!("cannot assign to builtin function `{name}`")
+                    } else {
+                        format!("expected variable, found builtin function `{name}`")
+                    };
+                    self.dcx.err(msg).span(span).emit();
+                }
+            }
+            None => {
+                let in_function = self.function.is_some();
+                let resolved = match &mut self.external {
+                    Some(external) => external.resolve(path, in_function, assign),
+                    None => false,
+                };
+                if !resolved {
+                    let msg = format!("cannot find variable `{name}` in this scope");
+                    self.dcx.err(msg).span(span).emit();
+                }
+            }
+        }
+    }
+
+    /// Analyzes an expression that must evaluate to exactly one value.
+    fn expr(&mut self, expr: &yul::Expr<'_>) {
+        match &expr.kind {
+            yul::ExprKind::Path(path) => self.path(path, false),
+            yul::ExprKind::Call(call) => self.call_with_values(call, expr.span, 1),
+            yul::ExprKind::Lit(lit) => self.lit(lit),
+        }
+    }
+
+    /// Analyzes an expression that must evaluate to `n` values.
+    fn values(&mut self, expr: &yul::Expr<'_>, n: usize) {
+        match &expr.kind {
+            yul::ExprKind::Call(call) => self.call_with_values(call, expr.span, n),
+            _ if n == 1 => self.expr(expr),
+            _ => {
+                self.expr(expr);
+                let msg = format!("expected {n} values, found 1");
+                self.dcx.err(msg).span(expr.span).help("use a function call instead").emit();
+            }
+        }
+    }
+
+    fn lit(&mut self, lit: &ast::Lit) {
+        if let LitKind::Str(_, bytes) = &lit.kind {
+            if bytes.len() > 32 {
+                self.dcx
+                    .err("string literal is too long")
+                    .span(lit.span)
+                    .note(format!(
+                        "string literals are limited to 32 bytes, but this one is {} bytes long",
+                        bytes.len()
+                    ))
+                    .emit();
+            }
+        }
+    }
+
+    /// Analyzes a function call whose result must be exactly `expected` values.
+    fn call_with_values(&mut self, call: &yul::ExprCall<'_>, span: Span, expected: usize) {
+        let Some(returns) = self.call(call, span) else { return };
+        if returns != expected {
+            let s = if returns == 1 { "" } else { "s" };
+            let msg =
+                format!("function `{}` returns {returns} value{s}, expected {expected}", call.name);
+            let mut err = self.dcx.err(msg).span(span);
+            if expected == 0 {
+                err = err.help("use `pop` to discard the values, or assign them to variables");
+            }
+            err.emit();
+        }
+    }
+
+    /// Analyzes a function call, returning the number of values it returns, if known.
+    fn call(&mut self, call: &yul::ExprCall<'_>, span: Span) -> Option<usize> {
+        let name = call.name;
+        let (parameters, returns) = match self.lookup(name.name) {
+            Some((
+                Declaration { kind: DeclarationKind::Function { parameters, returns }, .. },
+                _,
+            )) => (parameters, returns),
+            Some((Declaration { kind: DeclarationKind::Variable, .. }, _)) => {
+                let msg = format!("expected function, found variable `{name}`");
+                self.dcx.err(msg).span(name.span).emit();
+                self.args(call, None);
+                return None;
+            }
+            None => {
+                let Some(builtin) = self.builtin(name.name) else {
+                    let mut err = match builtins::low_level_instruction_help(name.as_str()) {
+                        Some(help) => self
+                            .dcx
+                            .err(format!("the `{name}` instruction cannot be used in Yul"))
+                            .help(help),
+                        None => {
+                            self.dcx.err(format!("cannot find function `{name}` in this scope"))
+                        }
+                    };
+                    err = err.span(name.span);
+                    err.emit();
+                    self.args(call, None);
+                    return None;
+                };
+                self.check_availability(&builtin, name.span);
+                self.args(call, Some(&builtin));
+                (builtin.parameters, builtin.returns)
+            }
+        };
+
+        let found = call.arguments.len();
+        if found != parameters {
+            let msg = format!(
+                "function `{name}` takes {parameters} argument{}, but {found} {} supplied",
+                pluralize!(parameters),
+                pluralize!("was", found),
+            );
+            self.dcx.err(msg).span(span).emit();
+        }
+        Some(returns)
+    }
+
+    fn args(&mut self, call: &yul::ExprCall<'_>, builtin: Option<&BuiltinFunction>) {
+        for (i, arg) in call.arguments.iter().enumerate() {
+            let Some(builtin) = builtin.filter(|b| b.literal_argument == Some(i)) else {
+                self.expr(arg);
+                continue;
+            };
+            self.literal_arg(builtin, i, arg);
+        }
+    }
+
+    /// Checks an argument of a builtin that must be a literal.
+    fn literal_arg(&mut self, builtin: &BuiltinFunction, i: usize, arg: &yul::Expr<'_>) {
+        let string = builtin.has_string_literal_argument(i);
+        let value = match &arg.kind {
+            yul::ExprKind::Lit(lit) => match &lit.kind {
+                LitKind::Str(_, bytes) if string => Some(bytes),
+                LitKind::Str(..) => None,
+                LitKind::Err(_) => return,
+                _ if string => None,
+                _ => return,
+            },
+            _ => None,
+        };
+        let Some(value) = value else {
+            let ordinal = ["first", "second"][i];
+            let kind = if string { "string literal" } else { "number literal" };
+            let msg = format!("the {ordinal} argument of `{}` must be a {kind}", builtin.name);
+            self.dcx.err(msg).span(arg.span).emit();
+            return;
+        };
+
+        if matches!(builtin.name, kw::Datasize | kw::Dataoffset) {
+            let data = self.data.unwrap();
+            let name = std::str::from_utf8(value).ok().map(Symbol::intern);
+            if !name.is_some_and(|name| data.contains(&name)) {
+                let name = String::from_utf8_lossy(value);
+                let msg = format!("unknown object or data `{name}`");
+                self.dcx.err(msg).span(arg.span).emit();
+            }
+        }
+    }
+
+    fn check_availability(&mut self, builtin: &BuiltinFunction, span: Span) {
+        if builtin.is_available(self.evm_version) {
+            return;
+        }
+        let name = builtin.name;
+        let msg =
+            format!("the `{name}` builtin is not available in EVM version `{}`", self.evm_version);
+        let mut err = self.dcx.err(msg).span(span);
+        if let Some(since) = builtin.since.filter(|&since| self.evm_version < since) {
+            err = err.note(format!("`{name}` is available since EVM version `{since}`"));
+        }
+        if let Some(until) = builtin.until.filter(|&until| self.evm_version >= until) {
+            err = err.note(format!("`{name}` was removed in EVM version `{until}`"));
+        }
+        match name {
+            kw::Difficulty => err = err.help("use `prevrandao` instead"),
+            kw::Prevrandao => err = err.help("use `difficulty` instead"),
+            _ => {}
+        }
+        err.emit();
+    }
+}
+
+/// Returns the name of a Yul path. Paths with multiple segments are joined with `.`.
+pub(crate) fn path_name(path: &ast::PathSlice) -> Symbol {
+    match path.segments() {
+        [ident] => ident.name,
+        segments => {
+            let names = segments.iter().map(|ident| ident.as_str()).collect::<Vec<_>>();
+            Symbol::intern(&names.join("."))
+        }
+    }
+}
+
+/// Returns the value of a literal, if it is known.
+fn lit_value(lit: &LitKind) -> Option<U256> {
+    match lit {
+        LitKind::Number(n) => {
+            let (sign, bytes) = n.to_bytes_be();
+            if sign == num_bigint::Sign::Minus {
+                return None;
+            }
+            U256::try_from_be_slice(&bytes)
+        }
+        LitKind::Str(_, bytes) if bytes.len() <= 32 => {
+            let mut word = [0u8; 32];
+            word[..bytes.len()].copy_from_slice(bytes);
+            Some(U256::from_be_bytes(word))
+        }
+        LitKind::Bool(b) => Some(U256::from(*b as u8)),
+        LitKind::Address(address) => Some(U256::from_be_bytes(address.into_word().0)),
+        _ => None,
+    }
+}
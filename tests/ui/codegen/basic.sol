@@ -0,0 +1,26 @@
+//@ignore-host: windows
+//@compile-flags: --emit=ir
+
+contract Counter {
+    uint256 public count;
+    mapping(address => uint256) public balances;
+    event Incremented(address indexed by, uint256 value);
+
+    modifier positive(uint256 x) {
+        require(x > 0, "zero");
+        _;
+    }
+
+    function increment(uint256 by) external positive(by) returns (uint256) {
+        count += by;
+        balances[msg.sender] = balances[msg.sender] + by;
+        emit Incremented(msg.sender, count);
+        return count;
+    }
+
+    function sum(uint256 n) public pure returns (uint256 s) {
+        for (uint256 i = 0; i < n; i++) {
+            s += i;
+        }
+    }
+}
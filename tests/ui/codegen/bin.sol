@@ -0,0 +1,43 @@
+//@ignore-host: windows
+//@compile-flags: --emit=bin,bin-runtime
+
+contract Counter {
+    uint256 public count;
+
+    event Incremented(uint256 count);
+
+    function increment(uint256 by) external {
+        require(by != 0, "zero");
+        count += by;
+        emit Incremented(count);
+    }
+}
+
+interface I {
+    function f() external;
+}
+
+contract Base {
+    uint256 internal immutable start;
+
+    constructor(uint256 start_) {
+        start = start_;
+    }
+}
+
+contract Child is Base {
+    address public owner;
+
+    constructor(address owner_, uint256 start_) Base(start_) {
+        owner = owner_;
+    }
+}
+
+contract Nested {
+    mapping(uint256 => mapping(uint256 => uint256)) internal nested;
+
+    function nest(uint256 a, uint256 b, uint256 v) public returns (uint256) {
+        nested[a][b] = v;
+        return nested[a][b] + nested[b][a];
+    }
+}
@@ -0,0 +1,43 @@
+//@ignore-host: windows
+//@compile-flags: --emit=ir
+
+abstract contract Base {
+    uint8 internal immutable decimals;
+    address public owner;
+
+    constructor(uint8 d) {
+        decimals = d;
+        owner = msg.sender;
+    }
+
+    function scale(uint256 x) internal view virtual returns (uint256) {
+        return x * 10 ** decimals;
+    }
+}
+
+contract Token is Base(18) {
+    struct Info { uint128 a; uint128 b; }
+    Info public info;
+    uint256[] public list;
+    enum State { A, B }
+    State public state;
+
+    function set(uint128 a, uint128 b) external {
+        info.a = a;
+        info.b = b;
+        list.push(a);
+        state = State.B;
+    }
+
+    function scale(uint256 x) internal view override returns (uint256) {
+        return super.scale(x) + 1;
+    }
+
+    function get(uint256 x) external view returns (uint256, bool) {
+        return (scale(x), state == State.B);
+    }
+
+    function hash(bytes calldata data) external pure returns (bytes32) {
+        return keccak256(data);
+    }
+}
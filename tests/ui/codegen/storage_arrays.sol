@@ -0,0 +1,20 @@
+//@ignore-host: windows
+//@compile-flags: --emit=ir
+
+contract C {
+    struct S {
+        uint256 a;
+        uint256 b;
+    }
+
+    S[] ss;
+    uint256[] xs;
+
+    function f(uint256 v) external returns (uint256, uint256) {
+        S storage s = ss.push();
+        s.b = v;
+        xs.push() = v + 1;
+        uint256 z = xs.push();
+        return (ss[ss.length - 1].b + z, xs[0]);
+    }
+}
@@ -0,0 +1,72 @@
+//@compile-flags: --emit=ir
+
+contract C {
+    struct S {
+        uint256 a;
+    }
+
+    S s;
+
+    function f(uint256 a) external {
+        s = S(a); //~ ERROR: the code generator does not support struct constructors yet
+    }
+}
+
+contract D {}
+
+contract Creation {
+    function f() external returns (address) {
+        return address(new D()); //~ ERROR: the code generator does not support contract creation yet
+    }
+
+    function g() external pure returns (uint256) {
+        uint256[] memory a = new uint256[](2); //~ ERROR: the code generator does not support allocation of memory arrays yet
+        return a.length;
+    }
+
+    function h() external pure returns (bytes memory) {
+        return type(D).creationCode; //~ ERROR: the code generator does not support `creationCode` yet
+    }
+}
+
+contract DynamicArrays {
+    uint256[] public arr;
+
+    function f(uint256[] calldata a) external pure returns (uint256) { //~ ERROR: the code generator does not support ABI coding of `uint256[] memory` yet
+        return a.length;
+    }
+
+    function g(bytes memory data) external pure returns (uint256) {
+        return abi.decode(data, (uint256[])).length; //~ ERROR: the code generator does not support ABI coding of `uint256[] memory` yet
+    }
+
+    function h() external view returns (bytes memory) {
+        return abi.encode(arr); //~ ERROR: the code generator does not support ABI coding of `uint256[] storage` yet
+    }
+}
+
+contract StorageBytes {
+    bytes b;
+
+    function f() external {
+        b.push(0x01); //~ ERROR: the code generator does not support `push` and `pop` on storage `bytes` yet
+    }
+
+    function g() external {
+        b.pop(); //~ ERROR: the code generator does not support `push` and `pop` on storage `bytes` yet
+    }
+}
+
+contract Slices {
+    function f(bytes calldata a) external pure returns (bytes calldata) {
+        return a[1:]; //~ ERROR: the code generator does not support slices yet
+    }
+}
+
+contract Assembly {
+    function f() external pure returns (uint256 r) {
+        assembly { //~ ERROR: the code generator does not support inline assembly yet
+            r := 1
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//@ignore-host: windows
+//@compile-flags: --emit=storage-layout --pretty-json
+
+struct S {
+    uint128 a;
+    uint128 b;
+    uint8[3] c;
+    mapping(address => uint256) d;
+}
+
+struct Node {
+    uint256 value;
+    Node[] children;
+}
+
+contract Base {
+    uint8 a;
+    bool b;
+    uint256 c;
+    uint256 constant CONSTANT = 1;
+    uint256 immutable IMMUTABLE = 2;
+}
+
+contract C is Base {
+    enum E {
+        A,
+        B
+    }
+
+    type U is uint64;
+
+    address d;
+    E e;
+    U u;
+    S s;
+    uint16 f;
+    uint256[] g;
+    bytes32[2] h;
+    string i;
+    mapping(string => mapping(uint256 => S[])) j;
+    function() internal k;
+    function() external view l;
+    C m;
+    Node n;
+
+    uint256 transient t1;
+    bool transient t2;
+}
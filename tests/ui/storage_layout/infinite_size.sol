@@ -0,0 +1,17 @@
+//@compile-flags: --emit=storage-layout
+
+contract C {
+    struct A {
+        B b;
+    }
+    struct B {
+        A[2] a;
+    }
+    struct D {
+        D[] d;
+        mapping(uint256 => D) m;
+    }
+
+    A a; //~ ERROR: type `struct C.A` has an infinite size in storage
+    D d;
+}
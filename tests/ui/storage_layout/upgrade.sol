@@ -0,0 +1,49 @@
+//@compile-flags: --storage-layout-base ../../tests/ui/storage_layout/auxiliary/upgrade_base.json
+
+contract GapOk {
+    uint256 a;
+    uint128 b;
+    uint128 c;
+    uint256[9] __gap;
+}
+
+contract GapBad {
+    uint256 a;
+    uint256 b;
+    uint256 c;
+    uint256[9] __gap; //~ ERROR: storage gap `__gap` ends at slot 12 instead of slot 11
+}
+
+contract Token { //~ ERROR: state variable `removed` was removed
+    struct Account {
+        uint256 balance;
+        uint64 nonce;
+        bool frozen;
+    }
+
+    uint256 supply;
+    mapping(address => Account) accounts;
+    uint8 decimals;
+    uint64 small; //~ WARN: the type of state variable `small` changed from `int64` to `uint64`
+    bool paused;
+    uint256 newName; //~ WARN: state variable `oldName` was renamed to `newName`
+    mapping(bytes32 => uint256) allowance; //~ ERROR: the key type of the mapping in state variable `allowance` changed
+    uint256 price; //~ ERROR: the type of state variable `price` changed from `uint128` to `uint256`
+
+    bool transient lock; //~ ERROR: the type of state variable `lock` changed from `uint256` to `bool`
+}
+
+contract Reordered {
+    uint256 b; //~ ERROR: state variable `b` was moved from slot 1 at offset 0 to slot 0 at offset 0
+    uint256 a; //~ ERROR: state variable `a` was moved from slot 0 at offset 0 to slot 1 at offset 0
+}
+
+contract Inserted {
+    uint256 a;
+    uint256 x;
+    uint256 b; //~ ERROR: state variable `b` was moved from slot 1 at offset 0 to slot 2 at offset 0
+}
+
+contract New {
+    uint256 a;
+}
@@ -0,0 +1,69 @@
+interface I {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function ping() external;
+}
+
+contract C {
+    struct S {
+        uint256 a;
+    }
+
+    mapping(uint256 => uint256) map;
+    uint256[][] nested;
+
+    function internalFn() internal {}
+
+    function encode(address to, S memory s, uint256[] memory arr) public view {
+        bytes memory b;
+        b = abi.encode(1, to, s, arr, "hello");
+        b = abi.encode(map); //~ ERROR: type `mapping(uint256 => uint256) storage` cannot be ABI-encoded
+        b = abi.encode(internalFn); //~ ERROR: type `function ()` cannot be ABI-encoded
+        b = abi.encode(1.5); //~ ERROR: type `rational_const` cannot be ABI-encoded
+        b = abi.encode(I); //~ ERROR: type `type(contract I)` cannot be ABI-encoded
+
+        b = abi.encodePacked(uint8(1), to, arr, "hello");
+        b = abi.encodePacked(1); //~ ERROR: type `int_const` cannot be packed-encoded
+        b = abi.encodePacked(s); //~ ERROR: type `struct C.S memory` cannot be packed-encoded
+        b = abi.encodePacked(nested); //~ ERROR: type `uint256[][] storage` cannot be packed-encoded
+
+        b = abi.encodeWithSelector(I.transfer.selector, to, 1);
+        b = abi.encodeWithSelector(0x12345678, to);
+        b = abi.encodeWithSelector(1); //~ ERROR: type `int_const` is not implicitly convertible to expected type `bytes4`
+        b = abi.encodeWithSelector(); //~ ERROR: wrong argument count for `abi.encodeWithSelector`
+        b = abi.encodeWithSignature("transfer(address,uint256)", to, 1);
+        b = abi.encodeWithSignature(1); //~ ERROR: type `int_const` is not implicitly convertible to expected type `string memory`
+    }
+
+    function encodeCall(address to) public pure {
+        bytes memory b;
+        b = abi.encodeCall(I.transfer, (to, 1));
+        b = abi.encodeCall(I.ping, ());
+        b = abi.encodeCall(I.transfer, (to)); //~ ERROR: wrong argument count for function call
+        b = abi.encodeCall(I.transfer, (1, to)); //~ ERROR: type `int_const` is not implicitly convertible to expected type `address`
+        //~^ ERROR: type `address` is not implicitly convertible to expected type `uint256`
+        b = abi.encodeCall(internalFn, ()); //~ ERROR: expected the first argument of `abi.encodeCall` to be an external function pointer, found `function ()`
+        b = abi.encodeCall(to, ()); //~ ERROR: expected the first argument of `abi.encodeCall` to be an external function pointer, found `address`
+        b = abi.encodeCall(I.transfer); //~ ERROR: wrong argument count for `abi.encodeCall`
+    }
+
+    function decode(bytes memory data, bytes calldata cdata) public pure {
+        (uint256 a, bytes memory b) = abi.decode(data, (uint256, bytes));
+        uint256 c = abi.decode(cdata, (uint256));
+        S memory s = abi.decode(data, (S));
+        (uint256 d, uint256 e) = abi.decode(data, (uint256)); //~ ERROR: different number of components on the left hand side (2) than on the right hand side (1)
+        string memory f = abi.decode(data, (bytes)); //~ ERROR: type `bytes memory` is not implicitly convertible to expected type `string memory`
+        abi.decode(data, (1)); //~ ERROR: the second argument of `abi.decode` must be a type or a tuple of types
+        abi.decode(1, (uint256)); //~ ERROR: type `int_const` is not implicitly convertible to expected type `bytes memory`
+        uint256 g = abi.decode(data); //~ ERROR: wrong argument count for `abi.decode`
+    }
+
+    function concat(string memory s, bytes memory b, bytes32 h) public pure {
+        string memory s2 = string.concat(s, "a", s);
+        bytes memory b2 = bytes.concat(b, h, "a", hex"00");
+        s2 = string.concat(s, b); //~ ERROR: type `bytes memory` is not implicitly convertible to expected type `string memory`
+        b2 = bytes.concat(b, s); //~ ERROR: invalid type for argument of `bytes.concat`: expected `bytes` or a fixed bytes type, found `string memory`
+        b2 = bytes.concat(1); //~ ERROR: invalid type for argument of `bytes.concat`: expected `bytes` or a fixed bytes type, found `int_const`
+        s2 = string.concat();
+        b2 = bytes.concat(b, b);
+    }
+}
@@ -0,0 +1,50 @@
+abstract contract A {
+    function f() public virtual;
+    function g() public virtual returns (uint256);
+    modifier m() virtual;
+}
+
+abstract contract B is A {
+    function f() public override {}
+}
+
+contract C is B { //~ ERROR: contract `C` should be marked as abstract
+    modifier m() override { _; }
+}
+
+contract D is B {
+    function g() public pure override returns (uint256) { return 1; }
+    modifier m() override { _; }
+}
+
+interface I {
+    function h() external;
+    function v() external view returns (uint256);
+}
+
+contract E is I { //~ ERROR: contract `E` should be marked as abstract
+    function h() external {}
+}
+
+contract F is I {
+    uint256 public v;
+    function h() external {}
+}
+
+contract G { //~ ERROR: contract `G` should be marked as abstract
+    function f() public; //~ ERROR: functions without implementation must be marked virtual
+    modifier m(); //~ ERROR: modifiers without implementation must be marked virtual
+}
+
+library L {
+    function f() internal; //~ ERROR: library functions must be implemented
+}
+
+contract New {
+    function f() public {
+        new A(); //~ ERROR: cannot instantiate an abstract contract
+        new I(); //~ ERROR: cannot instantiate an interface
+        new L(); //~ ERROR: cannot instantiate a library
+        new D();
+    }
+}
@@ -0,0 +1,23 @@
+type Fixed is int256;
+
+using { add as +, sub as -, neg as -, eq as ==, unwrapped } for Fixed global;
+
+function add(Fixed a, Fixed b) pure returns (Fixed) {
+    return Fixed.wrap(Fixed.unwrap(a) + Fixed.unwrap(b));
+}
+
+function sub(Fixed a, Fixed b) pure returns (Fixed) {
+    return Fixed.wrap(Fixed.unwrap(a) - Fixed.unwrap(b));
+}
+
+function neg(Fixed a) pure returns (Fixed) {
+    return Fixed.wrap(-Fixed.unwrap(a));
+}
+
+function eq(Fixed a, Fixed b) pure returns (bool) {
+    return Fixed.unwrap(a) == Fixed.unwrap(b);
+}
+
+function unwrapped(Fixed a) pure returns (int256) {
+    return Fixed.unwrap(a);
+}
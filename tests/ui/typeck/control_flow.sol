@@ -0,0 +1,190 @@
+struct S {
+    uint256 x;
+}
+
+contract C {
+    S s;
+    uint256 v;
+    bool flag;
+
+    function afterReturn() public pure returns (uint256) {
+        return 1;
+        uint256 x = 2; //~ WARN: unreachable code
+        x += 1;
+    }
+
+    function afterRevert(bool c) public pure {
+        if (c) {
+            revert("no");
+            c = false; //~ WARN: unreachable code
+        } else {
+            revert();
+        }
+        c = true; //~ WARN: unreachable code
+    }
+
+    function afterLoop() public pure returns (uint256 i) {
+        while (true) {
+            i++;
+            continue;
+            i--; //~ WARN: unreachable code
+        }
+    }
+
+    function forNext() public pure {
+        for (uint256 i = 0; i < 10; i++) { //~ WARN: unreachable code
+            return;
+        }
+    }
+
+    function unnamed(bool c) public pure returns (uint256) { //~ WARN: unnamed return variable can remain unassigned
+        if (c) {
+            return 1;
+        }
+    }
+
+    function unnamedReturn(bool c) public pure returns (uint256, uint256) {
+    //~^ WARN: unnamed return variable can remain unassigned
+    //~| WARN: unnamed return variable can remain unassigned
+        if (c) {
+            return;
+        }
+        return (1, 2);
+    }
+
+    function unnamedOk(bool c) public pure returns (uint256) {
+        if (c) {
+            return 1;
+        }
+        revert();
+    }
+
+    function named(bool c) public pure returns (uint256 r) {
+        if (c) {
+            r = 1;
+        }
+    }
+
+    function storagePtr(bool c) internal view returns (uint256) {
+        S storage p;
+        if (c) {
+            p = s;
+        }
+        return p.x; //~ ERROR: this variable is of storage pointer type and can be accessed without prior assignment
+    }
+
+    function storagePtrOk(bool c) internal view returns (uint256) {
+        S storage p;
+        S storage q;
+        if (c) {
+            p = s;
+        } else {
+            p = s;
+        }
+        (q) = (p);
+        return p.x + q.x;
+    }
+
+    function storagePtrTernary(bool c) internal view returns (uint256) {
+        S storage p;
+        c ? p = s : s;
+        return p.x; //~ ERROR: this variable is of storage pointer type and can be accessed without prior assignment
+    }
+
+    function storageReturn(bool c) internal view returns (S storage r) { //~ ERROR: this variable is of storage pointer type and can be returned without prior assignment
+        if (c) {
+            r = s;
+        }
+    }
+
+    function storageReturnOk() internal view returns (S storage r, S storage) {
+        r = s;
+        return (r, s);
+    }
+
+    function storageReturnAssembly() internal pure returns (S storage r) { //~ ERROR: this variable is of storage pointer type and can be returned without prior assignment
+        assembly {
+            let slot := r.slot
+        }
+    }
+
+    function storageReturnAssemblyOk(bool c) internal pure returns (S storage r) {
+        assembly {
+            if c {
+                r.slot := 0
+            }
+        }
+    }
+
+    modifier noPlaceholder() { //~ ERROR: modifier body does not contain `_`
+        require(flag);
+    }
+
+    modifier conditional() { //~ WARN: modifier may return without executing `_`
+        if (flag) {
+            return;
+        }
+        _;
+    }
+
+    modifier fallthrough() { //~ WARN: modifier may return without executing `_`
+        if (flag) {
+            _;
+        }
+    }
+
+    modifier reverting() {
+        if (!flag) {
+            revert();
+        }
+        _;
+        v = 0;
+    }
+}
+
+contract Loops {
+    function forContinue(uint256 n) public pure returns (uint256) {
+        for (uint256 i = 0; i < n; i++) {
+            if (i == 2) continue;
+            return i;
+        }
+        return n;
+    }
+
+    function doWhileContinue(bool x, bool c) public pure returns (uint256 r) {
+        do {
+            if (x) continue;
+            r++;
+        } while (c);
+    }
+
+    function doWhileReturn(bool c) public pure returns (uint256) {
+        do {
+            return 1;
+        } while (c); //~ WARN: unreachable code
+    }
+}
+
+contract Assembly {
+    function returns_(uint256 a) public pure returns (uint256) {
+        assembly {
+            mstore(0, a)
+            return(0, 32)
+        }
+    }
+
+    function reverts() public pure returns (uint256) {
+        assembly {
+            switch calldatasize()
+            case 0 { revert(0, 0) }
+            default { invalid() }
+        }
+        return 1; //~ WARN: unreachable code
+    }
+
+    function conditional(uint256 a) public pure returns (uint256) { //~ WARN: unnamed return variable can remain unassigned
+        assembly {
+            if a { stop() }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+contract C {
+    uint8 small = 255;
+    uint8 tooBig = 256; //~ ERROR: is not implicitly convertible to expected type `uint8`
+    int8 negative = -128;
+    uint8 unsigned = -1; //~ ERROR: is not implicitly convertible to expected type `uint8`
+    bytes4 sel = 0x12345678;
+    bytes4 notSel = 0x123456; //~ ERROR: is not implicitly convertible to expected type `bytes4`
+    string s = "hello";
+    bool b = 1; //~ ERROR: is not implicitly convertible to expected type `bool`
+
+    function f(uint16 x, int16 y) public pure returns (uint32, int32) {
+        uint32 a = x;
+        int32 c = y;
+        int32 d = x;
+        uint8 e = x; //~ ERROR: is not implicitly convertible to expected type `uint8`
+        uint8 g = uint8(x);
+        address h = address(0);
+        a;
+        c;
+        d;
+        e;
+        g;
+        h;
+        return (x, y);
+    }
+
+    function g() public pure returns (uint) {
+        return (1, 2); //~ ERROR: different number of arguments in return statement than in returns declaration
+    }
+
+    function h(uint x) public pure returns (uint) {
+        if (x) {} //~ ERROR: is not implicitly convertible to expected type `bool`
+        return h(1, 2); //~ ERROR: wrong argument count for function call: 2 arguments given but expected 1
+    }
+}
@@ -0,0 +1,43 @@
+struct S {
+    uint256 x;
+    mapping(uint256 => uint256) m;
+}
+
+struct T {
+    uint256 x;
+}
+
+library L {
+    function ext(uint256[] storage a) external {}
+    function pub(uint256[] storage a) public {}
+}
+
+contract C {
+    uint256 transient t;
+    uint256[] transient arr; //~ ERROR: `transient` data location is only supported for value types
+
+    constructor(uint256[] calldata a) {} //~ ERROR: data location must be `memory` for parameter in function, but `calldata` was given
+
+    function ext(uint256[] storage a) external {} //~ ERROR: data location must be `memory` or `calldata` for parameter in external function, but `storage` was given
+    function extRet() external returns (uint256[] storage) {} //~ ERROR: data location must be `memory` or `calldata` for return parameter in external function, but `storage` was given
+    //~^ ERROR: this variable is of storage pointer type and can be returned without prior assignment
+    function pub(uint256[] storage a) public {} //~ ERROR: data location must be `memory` or `calldata` for parameter in function, but `storage` was given
+    function none(uint256[] a) internal {} //~ ERROR: data location must be `storage`, `memory` or `calldata` for parameter in function, but none was given
+    function value(uint256 memory a) internal {} //~ ERROR: data location can only be specified for array, struct or mapping types, but `memory` was given
+    function ok(uint256[] storage a, bytes calldata b, T memory c) internal pure returns (T storage) { revert(); }
+
+    function locals() internal { //~ WARN: function state mutability can be restricted to `pure`
+        uint256[] x; //~ ERROR: data location must be `storage`, `memory` or `calldata` for variable, but none was given
+        uint256 memory y; //~ ERROR: data location can only be specified for array, struct or mapping types, but `memory` was given
+        S memory s; //~ ERROR: type `struct S` is only valid in storage because it contains a (nested) mapping
+        (uint256[] z, uint256 w) = (new uint256[](0), 1); //~ ERROR: data location must be `storage`, `memory` or `calldata` for variable, but none was given
+        mapping(uint256 => uint256) storage m;
+        T memory tm;
+    }
+
+    function tryCatch() external {
+        try this.extRet() returns (uint256[] calldata r) { //~ ERROR: data location must be `memory` for parameter in function, but `calldata` was given
+        } catch (bytes storage reason) { //~ ERROR: data location must be `memory` for parameter in function, but `storage` was given
+        }
+    }
+}
@@ -0,0 +1,47 @@
+interface I {
+    event E(uint256 a, bool b);
+}
+
+contract C {
+    event E(uint256 a, bool b);
+    event O(uint256 a);
+    event O(string s);
+    event S(string indexed s); //~ WARN: only the hash of `indexed` parameters of type `string` is logged
+    event A(uint256[] indexed a, uint256[2] indexed b, bytes32 indexed c); //~ WARN: only the hash of `indexed` parameters of type `uint256[]` is logged
+
+    error Err(uint256 code);
+    error Empty();
+
+    struct T {
+        uint256 x;
+    }
+
+    function f() public {
+        emit E(1, true);
+        emit E({b: true, a: 1});
+        emit I.E(1, true);
+        emit O(1);
+        emit O("a");
+        emit E(1); //~ ERROR: wrong argument count for event invocation: 1 arguments given but expected 2
+        emit E(true, 1); //~ ERROR: is not implicitly convertible to expected type
+        //~^ ERROR: is not implicitly convertible to expected type
+        emit E({a: 1, c: true}); //~ ERROR: named argument `c` does not match function declaration
+        emit O(true); //~ ERROR: no matching declaration found after argument-dependent lookup
+        emit Err(1); //~ ERROR: expression has to be an event invocation
+        emit T(1); //~ ERROR: expression has to be an event invocation
+    }
+
+    function g(uint256 x) public pure {
+        if (x == 0) revert Empty();
+        if (x == 1) revert Err({code: 1});
+        if (x == 2) revert Err(); //~ ERROR: wrong argument count for error call: 0 arguments given but expected 1
+        if (x == 3) revert Err("a"); //~ ERROR: is not implicitly convertible to expected type
+        revert E(1, true); //~ ERROR: expression has to be an error
+    }
+
+    function h() public pure returns (uint256) {
+        revert T(1); //~ ERROR: expression has to be an error
+        uint256 x = 1;
+        return x;
+    }
+}
@@ -0,0 +1,8 @@
+contract C {
+    // `-` is left-associative: (1 - 1) - 1
+    function a(uint[1 - 1 - 1] memory) public {} //~ ERROR: evaluation of constant value failed
+    // `**` is right-associative: 2 ** (3 ** 0)
+    function b(uint[2 ** 3 ** 0] memory) public {}
+    function c(uint[8 / 4 / 2] memory) public {}
+    function d(uint[2 ** 2 * 2] memory) public {}
+}
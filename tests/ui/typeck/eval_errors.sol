@@ -0,0 +1,11 @@
+contract C {
+    uint8 constant small = 200;
+
+    function f(uint[type(int8).min] memory) public {} //~ ERROR: evaluation of constant value failed
+    function g(uint[small + 100] memory) public {} //~ ERROR: evaluation of constant value failed
+    function j(uint[1 / 2] memory) public {} //~ ERROR: evaluation of constant value failed
+    function m(uint[uint8(1) * 2 ** 8] memory) public {} //~ ERROR: evaluation of constant value failed
+    function n(uint[int8(-128) / -1] memory) public {} //~ ERROR: evaluation of constant value failed
+    function o(uint[uint8(300)] memory) public {} //~ ERROR: evaluation of constant value failed
+    function p(uint[uint8(-1) + 2] memory) public {} //~ ERROR: evaluation of constant value failed
+}
@@ -0,0 +1,39 @@
+contract C {
+    bytes32 constant hash = keccak256("");
+
+    // Explicit conversions truncate and extend.
+    function a(uint[uint8(uint16(300))] memory x) public pure {
+        uint[1] memory y = x; //~ ERROR: is not implicitly convertible to expected type
+    }
+    function b(uint[uint8(int8(-1))] memory x) public pure {
+        uint[1] memory y = x; //~ ERROR: is not implicitly convertible to expected type
+    }
+    function c(uint[uint8(bytes1("a"))] memory x) public pure {
+        uint[1] memory y = x; //~ ERROR: is not implicitly convertible to expected type
+    }
+    function d(uint[uint8(uint256(hash))] memory x) public pure {
+        uint[1] memory y = x; //~ ERROR: is not implicitly convertible to expected type
+    }
+
+    // Type bounds.
+    function e(uint[type(uint8).max] memory x) public pure {
+        uint[1] memory y = x; //~ ERROR: is not implicitly convertible to expected type
+    }
+
+    // Arithmetic is checked according to the type of the operands.
+    function h(uint[uint8(3) << 7] memory x) public pure {
+        uint[1] memory y = x; //~ ERROR: is not implicitly convertible to expected type
+    }
+
+    // Number literals have arbitrary precision.
+    function i(uint[2.5 * 2] memory x) public pure {
+        uint[1] memory y = x; //~ ERROR: is not implicitly convertible to expected type
+    }
+    function k(uint[2 ** 300 / 2 ** 299] memory x) public pure {
+        uint[1] memory y = x; //~ ERROR: is not implicitly convertible to expected type
+    }
+
+    function l(uint[1 < 2 ? 3 : 4] memory x) public pure {
+        uint[1] memory y = x; //~ ERROR: is not implicitly convertible to expected type
+    }
+}
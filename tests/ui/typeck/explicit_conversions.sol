@@ -0,0 +1,81 @@
+type U is uint256;
+type V is uint256;
+
+enum E { A, B }
+
+contract Base {}
+
+contract Payable {
+    receive() external payable {}
+}
+
+contract C is Base {
+    function f(uint16 u16, int16 i16, int8 i8, bytes2 b2, address a, address payable ap) public pure {
+        uint8(u16);
+        int16(u16);
+        uint16(i16);
+        uint32(i8); //~ ERROR: explicit type conversion not allowed
+        bytes2(u16);
+        bytes4(u16); //~ ERROR: explicit type conversion not allowed
+        uint16(b2);
+        bytes1(b2);
+        bytes4(b2);
+        address(uint160(0));
+        address(uint256(0)); //~ ERROR: explicit type conversion not allowed
+        address(bytes20(0));
+        uint160(a);
+        uint160(ap);
+        bytes20(ap);
+        uint256(ap); //~ ERROR: explicit type conversion not allowed
+        payable(a);
+        address(ap);
+        E(u16);
+        uint8(E.A);
+        bool(u16); //~ ERROR: explicit type conversion not allowed
+        uint8(256); //~ ERROR: explicit type conversion not allowed
+        uint8(-1); //~ ERROR: explicit type conversion not allowed
+        bytes4(0x12345678);
+        bytes4(1); //~ ERROR: explicit type conversion not allowed
+        address(0);
+    }
+
+    function g(bytes memory b, string memory s, Payable p, C c) public pure {
+        string(b);
+        bytes(s);
+        bytes4(b);
+        bytes32(s); //~ ERROR: explicit type conversion not allowed
+        address(c);
+        payable(address(p));
+        Base(c);
+        C(address(0));
+        address payable x = payable(address(c));
+        x;
+    }
+
+    function h(uint256 x, U u) public pure {
+        U v = u;
+        uint256 y = x;
+        v = y; //~ ERROR: is not implicitly convertible
+        y = v; //~ ERROR: is not implicitly convertible
+    }
+
+    function j(uint256 x, U u, V v) public pure {
+        U(x); //~ ERROR: explicit type conversion not allowed
+        uint256(u); //~ ERROR: explicit type conversion not allowed
+        U(v); //~ ERROR: explicit type conversion not allowed
+        U(u);
+        U.wrap(x);
+        U.unwrap(u);
+    }
+
+    function i(uint16 x, address a) public pure {
+        uint8 y = x; //~ ERROR: is not implicitly convertible
+        address payable b = a; //~ ERROR: is not implicitly convertible
+        string memory s = hex"ff"; //~ ERROR: is not implicitly convertible
+        bytes2 t = "abc"; //~ ERROR: is not implicitly convertible
+        y;
+        b;
+        s;
+        t;
+    }
+}
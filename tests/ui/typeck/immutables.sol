@@ -0,0 +1,136 @@
+contract Ok {
+    uint256 immutable a = 1;
+    uint256 immutable b = a + 1;
+    uint256 immutable c;
+    uint256 immutable d;
+
+    constructor(bool x) {
+        c = b;
+        if (x) {
+            d = 1;
+        } else {
+            d = c;
+        }
+    }
+
+    function get() public view returns (uint256) {
+        return a + b + c + d;
+    }
+}
+
+contract Reads {
+    uint256 immutable a;
+    uint256 b = a; //~ ERROR: immutable variables cannot be read before they are initialized
+    uint256 immutable c = d; //~ ERROR: immutable variables cannot be read before they are initialized
+    uint256 immutable d = 1;
+
+    constructor() {
+        uint256 x = a + 1; //~ ERROR: immutable variables cannot be read before they are initialized
+        a = x;
+    }
+}
+
+contract Twice { //~ ERROR: construction control flow ends without initializing all immutable variables
+    uint256 immutable a = 1;
+    uint256 immutable b;
+    uint256 immutable c;
+
+    constructor(bool x) {
+        a = 2; //~ ERROR: immutable state variable already initialized
+        if (x) {
+            b = 1;
+        }
+        b = 2; //~ ERROR: immutable state variable already initialized
+        if (x) {
+            c = 1;
+        }
+    }
+}
+
+contract Contexts {
+    uint256 immutable a;
+    uint256 immutable b;
+
+    constructor() {
+        for (uint256 i = 0; i < 1; i++) {
+            a = i; //~ ERROR: immutable variables cannot be initialized inside a loop
+        }
+        try this.f() {
+            b = 1; //~ ERROR: immutable variables cannot be initialized inside a `try`/`catch` statement
+        } catch {
+            b = 2; //~ ERROR: immutable variables cannot be initialized inside a `try`/`catch` statement
+        }
+    }
+
+    function f() external {}
+}
+
+contract NoConstructor { //~ ERROR: construction control flow ends without initializing all immutable variables
+    uint256 immutable a;
+    uint256 immutable b = 1;
+}
+
+contract Reverting {
+    uint256 immutable a;
+
+    constructor(bool x) {
+        if (x) {
+            revert();
+        }
+        a = 1;
+    }
+}
+
+contract Calls {
+    uint256 immutable a;
+    uint256 immutable b;
+    uint256 immutable c;
+
+    modifier check() {
+        require(c > 0); //~ ERROR: immutable variables cannot be read before they are initialized
+        _;
+    }
+
+    constructor() check {
+        uint256 t = get();
+        a = t;
+        b = twice();
+        c = 1;
+    }
+
+    function get() internal view returns (uint256) {
+        return a; //~ ERROR: immutable variables cannot be read before they are initialized
+    }
+
+    function twice() internal view returns (uint256) {
+        return get() * 2;
+    }
+
+    function read() public view returns (uint256) {
+        return a + b + c;
+    }
+}
+
+contract CallsAfterInitialization {
+    uint256 immutable a;
+    uint256 immutable b;
+
+    constructor() {
+        a = 1;
+        b = get();
+    }
+
+    function get() internal view returns (uint256) {
+        return a;
+    }
+}
+
+contract ErrorThenTwice {
+    uint256 immutable a;
+
+    constructor() {
+        uint256 x = a; //~ ERROR: immutable variables cannot be read before they are initialized
+        a = x;
+        a = 2; //~ ERROR: immutable state variable already initialized
+    }
+}
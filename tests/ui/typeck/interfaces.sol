@@ -0,0 +1,19 @@
+interface I {
+    function f() external;
+}
+
+contract C {}
+
+interface J is I, C { //~ ERROR: interfaces can only inherit from other interfaces
+    uint256 constant X = 1; //~ ERROR: variables cannot be declared in interfaces
+    uint256 y; //~ ERROR: variables cannot be declared in interfaces
+
+    constructor() {} //~ ERROR: constructors cannot be defined in interfaces
+    modifier m(); //~ ERROR: modifiers cannot be defined or declared in interfaces
+
+    function g() public; //~ ERROR: functions in interfaces must be declared external
+    function h() external {} //~ ERROR: functions in interfaces cannot have an implementation
+    function i() external view returns (uint256);
+    fallback() external;
+    receive() external payable;
+}
@@ -0,0 +1,67 @@
+uint256 constant FILE_CONST = 1;
+
+struct S {
+    uint256 x;
+    uint256[] arr;
+    mapping(uint256 => uint256) m;
+}
+
+struct T {
+    uint256 x;
+}
+
+contract B {
+    uint256 immutable baseImm;
+
+    constructor() {
+        baseImm = 1;
+    }
+}
+
+contract C is B {
+    uint256 constant CONST = 1;
+    uint256 immutable imm;
+    uint256[] arr;
+    mapping(uint256 => uint256) m;
+    S s;
+
+    event Ev();
+
+    constructor() {
+        imm = 1;
+        baseImm = 2; //~ ERROR: cannot write to immutable variable here
+    }
+
+    function assignments(uint256[] calldata cd, T calldata cs, bytes32 b) external {
+        CONST = 2; //~ ERROR: cannot assign to a constant variable
+        FILE_CONST++; //~ ERROR: cannot assign to a constant variable
+        imm = 2; //~ ERROR: cannot write to immutable variable here
+        cd[0] = 1; //~ ERROR: calldata arrays are read-only
+        cs.x = 1; //~ ERROR: calldata structs are read-only
+        b[0] = 0; //~ ERROR: single bytes in fixed bytes arrays cannot be modified
+        arr.length = 0; //~ ERROR: member `length` is read-only and cannot be used to resize arrays
+        true = false; //~ ERROR: expression has to be an lvalue
+        1 = 2; //~ ERROR: expression has to be an lvalue
+        1 += 2; //~ ERROR: expression has to be an lvalue
+        assignments = assignments; //~ ERROR: expression has to be an lvalue
+        delete msg.sender; //~ ERROR: expression has to be an lvalue
+        delete m; //~ ERROR: built-in unary operator `delete` cannot be applied to type `mapping(uint256 => uint256)`
+        delete s;
+        delete m[0];
+        arr.push() = 1;
+        cd = cd;
+    }
+
+    function tuples() public {
+        uint256 a;
+        uint256 b;
+        (a, CONST) = (1, 2); //~ ERROR: cannot assign to a constant variable
+        (a, b) = (1, 2, 3); //~ ERROR: different number of components on the left hand side (2) than on the right hand side (3)
+        (a, , b) = (1, 2, 3);
+        (uint256 c, uint256 d) = (1, 2, 3); //~ ERROR: different number of components on the left hand side (2) than on the right hand side (3)
+        (uint256 e, ) = tuples(); //~ ERROR: different number of components on the left hand side (2) than on the right hand side (0)
+        (a, b) = (b, a);
+        (a, 1) = (1, 2); //~ ERROR: expression has to be an lvalue
+        (a, (b, c)) = (1, (2, 3));
+    }
+}
@@ -0,0 +1,77 @@
+library L {
+    uint256 constant C = 1;
+    struct S { uint256 a; }
+    function f(uint256 x) internal pure returns (uint256) { return x; }
+    function g() public pure {}
+}
+
+interface I {
+    function h(uint256) external;
+}
+
+enum E { A, B }
+
+contract Base {
+    function v() public virtual returns (uint256) { return 1; }
+    function priv() private {}
+    function o(uint256) public {}
+    function o(bool) public {}
+}
+
+contract C is Base {
+    struct T { uint256 value; uint256[] arr; }
+
+    T t;
+    uint256[] arr;
+    uint256[3] fixedArr;
+
+    function v() public override returns (uint256) {
+        return super.v();
+    }
+
+    function test(I i, T memory m) public {
+        t.value;
+        t.arr.push(1);
+        t.arr.push() = 2;
+        t.arr.pop();
+        arr.length;
+        fixedArr.length;
+        fixedArr.push(1); //~ ERROR: member `push` not found
+        m.arr.push(1); //~ ERROR: member `push` not found
+        m.value;
+        m.valeu; //~ ERROR: member `valeu` not found
+        L.f(L.C);
+        L.g();
+        L.S memory s = L.S(1);
+        s.a;
+        E.A;
+        E.C; //~ ERROR: member `C` not found
+        type(E).min;
+        type(uint8).max;
+        type(C).name;
+        type(I).interfaceId;
+        I.h.selector;
+        i.h.selector;
+        i.h.address;
+        i.h(1);
+        i.g(1); //~ ERROR: member `g` not found
+        this.v();
+        this.test.selector;
+        C.test.selector;
+        Base.v.selector;
+        v.selector; //~ ERROR: member `selector` not found
+        this.o(1);
+        this.o.selector; //~ ERROR: member `o` not unique after argument-dependent lookup
+        super.o.selector; //~ ERROR: member `o` not unique after argument-dependent lookup
+        msg.sender;
+        msg.sendr; //~ ERROR: member `sendr` not found
+        block.timestamp;
+        address(this).balance;
+        payable(address(this)).transfer(1);
+        address(this).transfer(1); //~ ERROR: member `transfer` not found
+        bytes32(0).length;
+        super.priv(); //~ ERROR: member `priv` not found
+        super; //~ ERROR: `super` can only be used to access members of the base contracts
+        super(); //~ ERROR: `super` can only be used to access members of the base contracts
+    }
+}
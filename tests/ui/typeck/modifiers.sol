@@ -0,0 +1,61 @@
+contract A {
+    constructor(uint256 x) {}
+
+    modifier m(uint256 x) virtual {
+        _;
+    }
+
+    modifier n() {
+        _;
+    }
+
+    function f(uint256 x) public m(x) n {}
+
+    function g() public m(1) m(2) {} //~ ERROR: modifier `m` is applied more than once
+
+    function h() public m(1, 2) {} //~ ERROR: wrong argument count for modifier invocation: 2 arguments given but expected 1
+
+    function i() public m(true) {} //~ ERROR: is not implicitly convertible to expected type `uint256`
+
+    function j() public m({x: 1}) {}
+
+    function k() public m({y: 1}) {} //~ ERROR: named argument `y` does not match function declaration
+}
+
+contract B is A {
+    constructor() A(1) {}
+
+    modifier m(uint256 x) override {
+        _;
+    }
+
+    function l() public m(1) A.m(2) {} //~ ERROR: modifier `m` is applied more than once
+}
+
+contract C is A(1) {
+    constructor() A(2) {} //~ ERROR: base constructor arguments given twice
+}
+
+contract D is A {} //~ ERROR: no arguments passed to the base constructor of `A`
+
+abstract contract E is A {}
+
+contract F is E {} //~ ERROR: no arguments passed to the base constructor of `A`
+
+contract G is A(1), B {} //~ ERROR: base constructor arguments given twice
+
+contract H is A("a") {} //~ ERROR: is not implicitly convertible to expected type `uint256`
+
+contract I {}
+
+contract J is I {
+    constructor() I(1) {} //~ ERROR: wrong argument count for constructor call: 1 arguments given but expected 0
+}
+
+abstract contract K {
+    function f() public virtual n; //~ ERROR: functions without implementation cannot have modifiers
+
+    modifier n() {
+        _;
+    }
+}
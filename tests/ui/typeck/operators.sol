@@ -0,0 +1,22 @@
+contract C {
+    function f(uint a, int b, bool c, bytes32 d, address e) public pure {
+        a + a;
+        a + b; //~ ERROR: built-in binary operator `+` cannot be applied to types `uint256` and `int256`
+        c && c;
+        c + c; //~ ERROR: built-in binary operator `+` cannot be applied to types `bool` and `bool`
+        a && a; //~ ERROR: built-in binary operator `&&` cannot be applied to types `uint256` and `uint256`
+        d & d;
+        d << a;
+        e < e;
+        e + e; //~ ERROR: built-in binary operator `+` cannot be applied to types `address` and `address`
+        a += b; //~ ERROR: built-in binary operator `+=` cannot be applied to types `uint256` and `int256`
+        -a; //~ ERROR: built-in unary operator `-` cannot be applied to type `uint256`
+        -b;
+        !c;
+        !a; //~ ERROR: built-in unary operator `!` cannot be applied to type `uint256`
+        ~d;
+        a ** a;
+        b ** a;
+        a ** b; //~ ERROR: built-in binary operator `**` cannot be applied to types `uint256` and `int256`
+    }
+}
@@ -0,0 +1,47 @@
+function free(uint) {}
+function free(string memory) {}
+
+contract Base {
+    function f(uint) public virtual {}
+    function f(bytes32) public virtual {}
+}
+
+contract C is Base {
+    function f(uint) public override {}
+
+    function g(uint8) public {}
+    function g(uint16) public {}
+
+    function h(uint a, bool b) public {}
+    function h(bool b, uint a) public {}
+
+    function k(uint a) public {}
+    function k(bool b) public {}
+
+    function test() public {
+        free(1);
+        free("a");
+        free(true); //~ ERROR: no matching declaration found after argument-dependent lookup
+
+        f(1);
+        f(bytes32(0));
+        f(address(0)); //~ ERROR: no matching declaration found after argument-dependent lookup
+
+        uint8 x8;
+        uint16 x16;
+        g(x8); //~ ERROR: no unique declaration found after argument-dependent lookup
+        g(x16);
+        g(1); //~ ERROR: no unique declaration found after argument-dependent lookup
+        g(256);
+
+        h(1, true);
+        h(true, 1);
+        h({a: 1, b: true}); //~ ERROR: no unique declaration found after argument-dependent lookup
+        k({a: 1});
+        k({b: true});
+        k({a: true}); //~ ERROR: no matching declaration found after argument-dependent lookup
+        k({c: 1}); //~ ERROR: no matching declaration found after argument-dependent lookup
+
+        g; //~ ERROR: no matching declaration found after variable lookup
+    }
+}
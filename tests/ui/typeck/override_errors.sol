@@ -0,0 +1,41 @@
+contract A {
+    function f() public virtual {}
+    function g() public {}
+    function h() external virtual {}
+    function i() public view virtual returns (uint256) { return 1; }
+    function j() internal virtual {}
+    function k() public virtual returns (uint256) { return 1; }
+    function l() public payable virtual {}
+    function n(uint256) public virtual {}
+    function w() public virtual returns (uint256) {} //~ WARN: unnamed return variable can remain unassigned
+    modifier m() virtual { _; }
+}
+
+contract B is A {
+    uint256 public override w; //~ ERROR: public state variables can only override functions with external visibility
+    uint256 internal override x; //~ ERROR: only public state variables can override
+
+    function f() public {} //~ ERROR: overriding function is missing `override` specifier
+    function g() public override {} //~ ERROR: trying to override non-virtual function
+    function h() public override {}
+    function i() public pure override returns (uint256) { return 2; }
+    function j() public override {} //~ ERROR: overriding function visibility differs from `internal` to `public`
+    function k() public override returns (int256) { return 1; } //~ ERROR: overriding function return types differ
+    //~^ WARN: function state mutability can be restricted to `pure`
+    function l() public override {} //~ ERROR: overriding function changes state mutability from `payable` to `nonpayable`
+    function n(int256) public override {} //~ ERROR: function has override specified but does not override anything
+    modifier m() override { _; }
+}
+
+interface I {
+    function a() external;
+    function b() external view returns (uint256);
+    function c() external;
+}
+
+contract C is I {
+    uint256 public override(I) b;
+
+    function a() external {}
+    function c() external view override {}
+}
@@ -0,0 +1,53 @@
+contract X {
+    function f() public virtual {}
+}
+
+contract Y {
+    function f() public virtual {}
+}
+
+contract Z1 is X, Y {
+    function f() public override {} //~ ERROR: function needs to specify overridden contracts `X` and `Y`
+}
+
+contract Z2 is X, Y {
+    function f() public override(X) {} //~ ERROR: function needs to specify overridden contract `Y`
+}
+
+contract Z3 is X, Y {
+    function f() public override(X, Y, Z1) {} //~ ERROR: invalid contract specified in override list: `Z1`
+}
+
+contract Z4 is X, Y {
+    function f() public override(X, Y, X) {} //~ ERROR: duplicate contract `X` found in override list
+}
+
+contract Z5 is X, Y {
+    function f() public override(Y, X) {}
+}
+
+contract D is X, Y {} //~ ERROR: derived contract must override function `f`
+
+contract P is X {
+    function f() public virtual override {}
+}
+
+contract Q is X, P {
+    function f() public override(X, P) {}
+}
+
+contract R is X, P {}
+
+interface I1 {
+    function g() external;
+}
+
+interface I2 {
+    function g() external;
+}
+
+abstract contract S is I1, I2 {} //~ ERROR: derived contract must override function `g`
+
+abstract contract T is I1, I2 {
+    function g() external virtual override(I1, I2);
+}
@@ -0,0 +1,10 @@
+function free() virtual {} //~ ERROR: free functions cannot be virtual
+function free2() override {} //~ ERROR: free functions cannot override
+
+library L {
+    function f() public virtual {} //~ ERROR: library functions cannot be `virtual`
+}
+
+contract C {
+    function f() private virtual {} //~ ERROR: `virtual` and `private` cannot be used together
+}
@@ -0,0 +1,47 @@
+contract C {
+    function f() public pure {
+        uint a = 1.5 ether;
+        uint b = 2.5e3 * 2;
+        uint c = 0.5 * 4;
+        uint d = 5 / 2 * 2;
+        int e = -2.5 * 2;
+        uint f = 2 ** -1 * 8;
+        uint g = 1 / 3 + 2 / 3;
+
+        uint h = 0.5 * 3; //~ ERROR: is not implicitly convertible
+        uint i = 5 / 2; //~ ERROR: is not implicitly convertible
+        int j = -1.5; //~ ERROR: is not implicitly convertible
+        uint k = uint(2.5); //~ ERROR: explicit type conversion not allowed
+        uint l = 1 / 0; //~ ERROR: evaluation of constant value failed
+        uint m = 2.5 << 1; //~ ERROR: cannot be applied to types
+        uint n = ~0.5; //~ ERROR: cannot be applied to type
+    }
+
+    function fixedPoint(fixed x, ufixed y) public pure returns (fixed) {
+        fixed a = 1.5;
+        ufixed b = -1.5; //~ ERROR: is not implicitly convertible
+        fixed128x18 c = x;
+        fixed64x10 d = x; //~ ERROR: is not implicitly convertible
+        fixed e = y; //~ ERROR: is not implicitly convertible
+        fixed f = fixed(y);
+        uint g = uint(x);
+        bool h = x < 2.5;
+        return -x + x * 1.5;
+    }
+
+    function fixedPointRange() public pure {
+        ufixed8x1 a = 25.5;
+        ufixed8x1 b = 1000; //~ ERROR: is not implicitly convertible
+        ufixed8x1 c = 25.6; //~ ERROR: is not implicitly convertible
+        ufixed8x1 d = 0.15; //~ ERROR: is not implicitly convertible
+        fixed8x1 e = -12.8;
+        fixed8x1 f = -12.9; //~ ERROR: is not implicitly convertible
+        fixed g = 1e20;
+        fixed h = 1e21; //~ ERROR: is not implicitly convertible
+    }
+
+    function mixed(uint x) public pure {
+        x * 1.5; //~ ERROR: cannot be applied to types
+        x + 2.5 * 2;
+    }
+}
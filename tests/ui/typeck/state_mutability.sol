@@ -0,0 +1,105 @@
+struct S {
+    uint256 x;
+}
+
+library L {
+    function get(S storage s) internal view returns (uint256) {
+        return s.x;
+    }
+
+    function pay() public payable {} //~ ERROR: library functions cannot be payable
+}
+
+contract C {
+    using L for S;
+
+    uint256 x;
+    uint256 constant C1 = 1;
+    uint256 immutable I1 = 2;
+    uint256 immutable I2 = block.number;
+    S s;
+    uint256[] arr;
+
+    event E();
+
+    modifier writes() {
+        x = 1;
+        _;
+    }
+
+    function readsInPure() public pure returns (uint256) {
+        return x; //~ ERROR: function declared as `pure`, but this expression (potentially) reads the blockchain state and requires `view`
+    }
+
+    function blockInPure() public pure returns (uint256) {
+        return block.timestamp; //~ ERROR: function declared as `pure`, but this expression (potentially) reads the blockchain state and requires `view`
+    }
+
+    function senderInPure() public pure returns (address) {
+        return msg.sender; //~ ERROR: function declared as `pure`, but this expression (potentially) reads the blockchain state and requires `view`
+    }
+
+    function writesInView() public view {
+        x = 1; //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        s.x = 2; //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        arr.push(1); //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        delete arr; //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        x++; //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        emit E(); //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        new D(); //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        payable(msg.sender).transfer(1); //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        nonPayable(); //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+    }
+
+    function modifierInView() public view writes {} //~ ERROR: function declared as `view`, but modifier `writes` requires `nonpayable`
+
+    function value() public returns (uint256) {
+        return msg.value; //~ ERROR: `msg.value` can only be used in payable public functions
+    }
+
+    function valuePayable() public payable returns (uint256) {
+        return msg.value;
+    }
+
+    function valueInternal() internal view returns (uint256) {
+        return msg.value;
+    }
+
+    function payInternal() internal payable {} //~ ERROR: `internal` and `private` functions cannot be payable
+
+    function pureOk(S memory m, uint256 a) public pure returns (uint256) {
+        uint256[] memory local = new uint256[](a);
+        local[0] = m.x + C1 + I1;
+        m.x = 1;
+        return keccak256(abi.encode(local)).length;
+    }
+
+    function selectorOk() public pure returns (bytes4, bytes4) {
+        return (this.pureOk.selector, (this).viewOk.selector);
+    }
+
+    function viewOk() public view returns (uint256) {
+        S storage p = s;
+        return p.get() + I2 + address(this).balance + arr.length;
+    }
+
+    function nonPayable() public {
+        x = 2;
+    }
+
+    function canBeView() public returns (uint256) { //~ WARN: function state mutability can be restricted to `view`
+        return x;
+    }
+
+    function canBePure(uint256 a) public view returns (uint256) { //~ WARN: function state mutability can be restricted to `pure`
+        return a + 1;
+    }
+
+    function canBePureVirtual(uint256 a) public view virtual returns (uint256) {
+        return a + 1;
+    }
+
+    function empty() public {}
+}
+
+contract D {}
@@ -0,0 +1,41 @@
+contract C {
+    uint256 x;
+
+    function readsInPure() public pure returns (uint256 r) {
+        assembly {
+            r := sload(x.slot) //~ ERROR: function declared as `pure`, but this expression (potentially) reads the blockchain state and requires `view`
+        }
+    }
+
+    function writesInView() public view {
+        assembly {
+            sstore(x.slot, 1) //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+        }
+    }
+
+    function slotInPure() public pure returns (uint256 r) {
+        assembly {
+            r := x.slot
+        }
+    }
+
+    function inFunction() public view {
+        assembly {
+            function f() {
+                log0(0, 0) //~ ERROR: function cannot be declared as `view` because this expression (potentially) modifies the state
+            }
+        }
+    }
+
+    function canBeView() public returns (uint256 r) { //~ WARN: function state mutability can be restricted to `view`
+        assembly {
+            r := caller()
+        }
+    }
+
+    function canBePure() public view returns (uint256 r) { //~ WARN: function state mutability can be restricted to `pure`
+        assembly {
+            r := add(1, 2)
+        }
+    }
+}
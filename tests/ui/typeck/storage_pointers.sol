@@ -0,0 +1,40 @@
+struct S {
+    uint256 x;
+}
+
+contract C {
+    S s;
+    S[] ss;
+    uint256[] arr = [1, 2, 3]; //~ ERROR: is not implicitly convertible
+    uint256[3] fixedArr = [uint256(1), 2, 3];
+    string constant NAME = "name";
+    string name = NAME;
+
+    function copies(S memory m, S calldata c, uint256[] memory a) external {
+        s = m;
+        s = c;
+        ss[0] = m;
+        arr = a;
+        (s, arr) = (m, a);
+        name = NAME;
+
+        S storage p = s;
+        p = ss[1];
+        p.x = m.x;
+    }
+
+    function pointers(S memory m, uint256[] memory a) internal {
+        S storage p = m; //~ ERROR: type `struct S memory` is not implicitly convertible to expected type `struct S storage`
+        p = m; //~ ERROR: type `struct S memory` is not implicitly convertible to expected type `struct S storage`
+        uint256[] storage q;
+        (p, q) = (s, a); //~ ERROR: type `uint256[] memory` is not implicitly convertible to expected type `uint256[] storage`
+        takesPointer(m); //~ ERROR: type `struct S memory` is not implicitly convertible to expected type `struct S storage`
+        takesPointer(s);
+    }
+
+    function returnsPointer(S memory m) internal view returns (S storage) { //~ WARN: function state mutability can be restricted to `pure`
+        return m; //~ ERROR: type `struct S memory` is not implicitly convertible to expected type `struct S storage`
+    }
+
+    function takesPointer(S storage p) internal {}
+}
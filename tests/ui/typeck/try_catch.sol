@@ -0,0 +1,65 @@
+interface I {
+    function f() external returns (uint256, string memory);
+    function g() external;
+}
+
+library L {
+    function h() public pure returns (uint256) {
+        return 1;
+    }
+}
+
+contract D {
+    constructor(uint256) {}
+}
+
+contract C {
+    function internalFn() internal pure returns (uint256) {
+        return 1;
+    }
+
+    function ok(I i) public {
+        try i.f() returns (uint256 a, string memory b) {
+            a;
+            b;
+        } catch Error(string memory reason) {
+            reason;
+        } catch Panic(uint256 code) {
+            code;
+        } catch (bytes memory reason) {
+            reason;
+        }
+
+        try i.g() {} catch {}
+        try i.f() {} catch {}
+        try this.ok(i) {} catch {}
+        try new D(1) returns (D d) {
+            d;
+        } catch {}
+        try new D{salt: 0}(1) {} catch {}
+    }
+
+    function notExternal(I i) public pure {
+        try internalFn() {} catch {} //~ ERROR: try can only be used with external function calls and contract creation calls
+        try L.h() {} catch {} //~ ERROR: try can only be used with external function calls and contract creation calls
+        try i.g {} catch {} //~ ERROR: try can only be used with external function calls and contract creation calls
+    }
+
+    function returnsClause(I i) public {
+        try i.f() returns (uint256 a) {} catch {} //~ ERROR: function returns 2 values, but the `returns` clause has 1 variable
+        try i.f() returns (uint8 a, string memory b) {} catch {} //~ ERROR: invalid type in the `returns` clause: expected `uint256`, found `uint8`
+        try new D(1) returns (D d, uint256 x) {} catch {} //~ ERROR: function returns 1 value, but the `returns` clause has 2 variables
+        try new D(1) returns (C c) {} catch {} //~ ERROR: invalid type in the `returns` clause: expected `contract D`, found `contract C`
+    }
+
+    function catchClauses(I i) public {
+        try i.g() {} catch Error(bytes memory) {} //~ ERROR: expected `catch Error(string memory ...) { ... }`
+        try i.g() {} catch Panic(uint8) {} //~ ERROR: expected `catch Panic(uint256 ...) { ... }`
+        try i.g() {} catch Panic {} //~ ERROR: expected `catch Panic(uint256 ...) { ... }`
+        try i.g() {} catch (string memory) {} //~ ERROR: expected `catch (bytes memory ...) { ... }` or `catch { ... }`
+        try i.g() {} catch Other(bytes memory) {} //~ ERROR: invalid catch clause name `Other`
+        try i.g() {} catch Error(string memory) {} catch Error(string memory) {} //~ ERROR: this `try` statement already has an `Error` catch clause
+        try i.g() {} catch Panic(uint256) {} catch Panic(uint256) {} //~ ERROR: this `try` statement already has a `Panic` catch clause
+        try i.g() {} catch {} catch (bytes memory) {} //~ ERROR: this `try` statement already has a low-level catch clause
+    }
+}
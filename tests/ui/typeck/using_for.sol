@@ -0,0 +1,56 @@
+import { Fixed } from "./auxiliary/fixed.sol";
+
+library Math {
+    struct Point { uint256 x; uint256 y; }
+
+    function double(uint256 x) internal pure returns (uint256) { return x * 2; }
+    function add(uint256 x, uint256 y) internal pure returns (uint256) { return x + y; }
+    function push2(uint256[] storage arr, uint256 a, uint256 b) internal {
+        arr.push(a);
+        arr.push(b);
+    }
+    function norm(Point memory p) internal pure returns (uint256) { return p.x + p.y; }
+    function hidden(uint256 x) private pure returns (uint256) { return x; }
+}
+
+function triple(uint256 x) pure returns (uint256) {
+    return x * 3;
+}
+
+using { triple } for uint256;
+
+contract C {
+    using Math for uint256;
+    using Math for uint256[];
+    using Math for Math.Point;
+
+    uint256[] arr;
+    Math.Point point;
+
+    function f(uint256 x, Fixed a, Fixed b) public returns (uint256) {
+        arr.push2(1, 2);
+        arr.push2({ a: 1, b: 2 });
+        uint256 n = point.norm();
+
+        Fixed c = a + b;
+        c = -c;
+        c = c - a;
+        bool e = a == b;
+        int256 u = c.unwrapped();
+
+        a * b; //~ ERROR: built-in binary operator `*` cannot be applied to types `Fixed` and `Fixed`
+        a != b; //~ ERROR: built-in binary operator `!=` cannot be applied to types `Fixed` and `Fixed`
+        x.hidden(); //~ ERROR: member `hidden` not found
+        x.add(1, 2); //~ ERROR: wrong argument count for function call: 2 arguments given but expected 1
+        x.push2(1, 2); //~ ERROR: member `push2` not found
+
+        return x.double().add(1).triple();
+    }
+}
+
+contract D {
+    function g(uint256 x) public pure returns (uint256) {
+        x.double(); //~ ERROR: member `double` not found
+        return x.triple();
+    }
+}
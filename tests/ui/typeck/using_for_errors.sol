@@ -0,0 +1,37 @@
+import { Fixed as OtherFixed } from "./auxiliary/fixed.sol";
+
+type Fixed is int256;
+
+struct S { uint256 a; }
+
+function takesS(S memory s) pure returns (uint256) { return s.a; }
+function takesUint(uint256 x) pure returns (uint256) { return x; }
+function takesOther(OtherFixed x) pure returns (OtherFixed) { return x; }
+function add(Fixed a, Fixed b) pure returns (Fixed) { return a; }
+function viewMul(Fixed a, Fixed b) view returns (Fixed) { return a; }
+
+library Lib {}
+
+contract C {
+    using Lib for *;
+    using { takesUint } for uint256 global; //~ ERROR: `global` can only be used at file level
+
+    function m(uint256 x) public {}
+}
+
+interface I {
+    using Lib for uint256; //~ ERROR: the `using for` directive is not allowed inside interfaces
+}
+
+using { takesS } for S global;
+using { takesUint } for *; //~ ERROR: the type has to be specified explicitly at file level
+using Lib for *; //~ ERROR: the type has to be specified explicitly at file level
+using { takesUint } for uint256 global; //~ ERROR: `global` can only be used with user-defined types
+using { takesOther } for OtherFixed global; //~ ERROR: `global` can only be used with types defined in the same source unit
+using C for uint256; //~ ERROR: expected library, found contract
+using { C } for uint256; //~ ERROR: expected function, found contract
+using { C.m } for uint256; //~ ERROR: only file-level functions and library functions can be attached to a type
+
+using { add as + } for Fixed; //~ ERROR: operators can only be defined in a global `using for` directive
+using { viewMul as * } for Fixed global; //~ ERROR: only pure free functions can be used to define operators
+using { takesS as + } for S global; //~ ERROR: operators can only be implemented for user-defined value types
@@ -0,0 +1,28 @@
+type Fixed is int256;
+
+struct S { uint256 a; }
+
+function noParams() pure {}
+function takesS(S memory s) pure returns (uint256) { return s.a; }
+function add(Fixed a, Fixed b) pure returns (Fixed) { return a; }
+function badAdd(Fixed a, int256 b) pure returns (Fixed) { return a; }
+function badNot(Fixed a, Fixed b) pure returns (Fixed) { return a; }
+function badEq(Fixed a, Fixed b) pure returns (Fixed) { return a; }
+function badLt(Fixed a, Fixed b) pure returns (bool, bool) { return (true, true); }
+
+library Lib {
+    using { p } for uint256;
+
+    function p(uint256 x) private pure returns (uint256) { return x; }
+}
+
+using { noParams } for uint256; //~ ERROR: function `noParams` does not have any parameters and cannot be attached to the type `uint256`
+using { takesS } for uint256; //~ ERROR: function `takesS` cannot be attached to the type `uint256` because the type cannot be implicitly converted to the first parameter of the function (`struct S memory`)
+using { Lib.p } for uint256; //~ ERROR: function `Lib.p` is private and therefore cannot be attached to a type outside of the library where it is defined
+
+using { add as + } for Fixed global;
+using { add as + } for Fixed global; //~ ERROR: user-defined operator `+` for type `Fixed` is defined multiple times
+using { badAdd as - } for Fixed global; //~ ERROR: wrong parameters in operator definition: the function `badAdd` needs to have two parameters of type `Fixed` to be used for the operator `-`
+using { badNot as ~ } for Fixed global; //~ ERROR: wrong parameters in operator definition: the function `badNot` needs to have one parameter of type `Fixed` to be used for the operator `~`
+using { badEq as == } for Fixed global; //~ ERROR: wrong return parameters in operator definition: the function `badEq` needs to return exactly one value of type `bool` to be used for the operator `==`
+using { badLt as < } for Fixed global; //~ ERROR: wrong return parameters in operator definition: the function `badLt` needs to return exactly one value of type `bool` to be used for the operator `<`
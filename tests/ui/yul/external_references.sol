@@ -0,0 +1,67 @@
+struct S {
+    uint256 x;
+}
+
+contract C {
+    uint256 x;
+    uint128 y;
+    uint256 constant C = 1;
+    uint256 immutable I = 2;
+    S s;
+    function(uint256) external e;
+
+    function f(
+        uint256 a,
+        uint256[] calldata arr,
+        uint256[2] calldata fixedArr,
+        bytes memory m,
+        function() external g
+    ) public {
+        S storage p = s;
+        assembly {
+            let v := sload(x.slot)
+            v := y.offset
+            v := arr.offset
+            v := arr.length
+            v := fixedArr
+            v := m
+            v := g.selector
+            v := g.address
+            g.selector := 0
+            p.slot := 0
+            v := p.offset
+            arr.offset := 0
+            arr.length := 0
+            v := C
+            sstore(x.slot, v)
+
+            v := x //~ ERROR: cannot access `x` without a suffix
+            v := p //~ ERROR: cannot access `p` without a suffix
+            v := arr //~ ERROR: cannot access `arr` without a suffix
+            v := I //~ ERROR: immutable variables cannot be accessed from inline assembly
+            C := 0 //~ ERROR: cannot assign to constant `C`
+            v := C.slot //~ ERROR: the `.slot` suffix can only be used with storage variables
+            v := a.offset //~ ERROR: the `.offset` suffix can only be used with storage variables and dynamic calldata arrays
+            v := m.length //~ ERROR: the `.length` suffix can only be used with dynamic calldata arrays
+            v := a.selector //~ ERROR: the `.selector` suffix can only be used with local variables of external function type
+            v := e.address //~ ERROR: the `.address` suffix can only be used with local variables of external function type
+            v := x.foo //~ ERROR: unknown suffix `.foo`
+            v := x.slot.slot //~ ERROR: unknown suffix `.slot.slot`
+            x.slot := 0 //~ ERROR: cannot assign to the `.slot` of state variable `x`
+            p.offset := 0 //~ ERROR: cannot assign to the `.offset` of storage pointer `p`
+        }
+    }
+
+    function storageReturn() internal view returns (S storage r) {
+        assembly {
+            r.slot := s.slot
+        }
+    }
+
+    function flags() public pure {
+        assembly "evmasm" ("memory-safe") {}
+        assembly "other" {} //~ ERROR: unknown inline assembly dialect `other`
+        assembly ("memory-safe", "memory-safe") {} //~ ERROR: inline assembly block is marked as `"memory-safe"` more than once
+        assembly ("unknown") {} //~ ERROR: unknown inline assembly flag `unknown`
+    }
+}
@@ -0,0 +1,24 @@
+contract C {
+    uint256 s;
+    uint256 constant C = 1;
+
+    function f(uint256 a) public view returns (uint256 r) {
+        uint256 b = 1;
+        assembly {
+            r := add(a, b)
+            let c := sload(s.slot)
+            let d := C
+
+            function g() -> v {
+                v := a //~ ERROR: cannot access local variables from inside of an inline assembly function
+                v := C
+            }
+
+            let b := 2 //~ ERROR: declaration of `b` shadows a declaration outside of the inline assembly block
+            let e := undefined //~ ERROR: cannot find variable `undefined` in this scope
+            let h := f //~ ERROR: expected variable, found function `f`
+            pop(datasize("C")) //~ ERROR: cannot find function `datasize` in this scope
+            let datasize := 1
+        }
+    }
+}